@@ -0,0 +1,3 @@
+ALTER TABLE "dropbox" ADD COLUMN IF NOT EXISTS "list_folder_cursor" text;--> statement-breakpoint
+ALTER TABLE "dropbox_paths" ADD COLUMN IF NOT EXISTS "content_hash" text;--> statement-breakpoint
+ALTER TABLE "dropbox_paths" ADD COLUMN IF NOT EXISTS "rev" text;
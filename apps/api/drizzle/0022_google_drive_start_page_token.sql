@@ -0,0 +1,2 @@
+ALTER TABLE "google_drive" ADD COLUMN IF NOT EXISTS "start_page_token" text;--> statement-breakpoint
+ALTER TABLE "google_drive_paths" ADD COLUMN IF NOT EXISTS "md5_checksum" text;
@@ -0,0 +1,30 @@
+CREATE TABLE IF NOT EXISTS "library_files" (
+	"xata_id" text PRIMARY KEY DEFAULT xata_id() NOT NULL,
+	"user_id" text NOT NULL,
+	"track_id" text NOT NULL,
+	"path" text NOT NULL,
+	"sha256" text NOT NULL,
+	"file_size" bigint NOT NULL,
+	"modified_at" timestamp with time zone NOT NULL,
+	"xata_createdat" timestamp with time zone DEFAULT now() NOT NULL,
+	"xata_updatedat" timestamp with time zone DEFAULT now() NOT NULL,
+	CONSTRAINT "library_files_user_id_path_unique" UNIQUE("user_id","path")
+);
+--> statement-breakpoint
+CREATE TABLE IF NOT EXISTS "library_scans" (
+	"xata_id" text PRIMARY KEY DEFAULT xata_id() NOT NULL,
+	"user_id" text NOT NULL,
+	"scanning" boolean DEFAULT false NOT NULL,
+	"count" integer DEFAULT 0 NOT NULL,
+	"folder_count" integer DEFAULT 0 NOT NULL,
+	"started_at" timestamp with time zone,
+	"finished_at" timestamp with time zone,
+	"xata_createdat" timestamp with time zone DEFAULT now() NOT NULL,
+	"xata_updatedat" timestamp with time zone DEFAULT now() NOT NULL,
+	CONSTRAINT "library_scans_user_id_unique" UNIQUE("user_id")
+);
+--> statement-breakpoint
+CREATE INDEX IF NOT EXISTS "library_files_user_id_sha256_idx" ON "library_files" USING btree ("user_id","sha256");--> statement-breakpoint
+ALTER TABLE "library_files" ADD CONSTRAINT "library_files_user_id_users_xata_id_fk" FOREIGN KEY ("user_id") REFERENCES "public"."users"("xata_id") ON DELETE no action ON UPDATE no action;--> statement-breakpoint
+ALTER TABLE "library_files" ADD CONSTRAINT "library_files_track_id_tracks_xata_id_fk" FOREIGN KEY ("track_id") REFERENCES "public"."tracks"("xata_id") ON DELETE no action ON UPDATE no action;--> statement-breakpoint
+ALTER TABLE "library_scans" ADD CONSTRAINT "library_scans_user_id_users_xata_id_fk" FOREIGN KEY ("user_id") REFERENCES "public"."users"("xata_id") ON DELETE no action ON UPDATE no action;
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS "radio_stations" (
+	"xata_id" text PRIMARY KEY DEFAULT xata_id() NOT NULL,
+	"user_id" text NOT NULL,
+	"name" text NOT NULL,
+	"stream_url" text NOT NULL,
+	"homepage_url" text,
+	"xata_createdat" timestamp with time zone DEFAULT now() NOT NULL,
+	"xata_updatedat" timestamp with time zone DEFAULT now() NOT NULL
+);
+--> statement-breakpoint
+CREATE INDEX IF NOT EXISTS "radio_stations_user_id_idx" ON "radio_stations" USING btree ("user_id");--> statement-breakpoint
+ALTER TABLE "radio_stations" ADD CONSTRAINT "radio_stations_user_id_users_xata_id_fk" FOREIGN KEY ("user_id") REFERENCES "public"."users"("xata_id") ON DELETE no action ON UPDATE no action;
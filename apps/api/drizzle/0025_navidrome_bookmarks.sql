@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS "navidrome_bookmarks" (
+	"xata_id" text PRIMARY KEY DEFAULT xata_id() NOT NULL,
+	"user_id" text NOT NULL,
+	"track_id" text NOT NULL,
+	"position_ms" bigint DEFAULT 0 NOT NULL,
+	"comment" text,
+	"xata_createdat" timestamp with time zone DEFAULT now() NOT NULL,
+	"xata_updatedat" timestamp with time zone DEFAULT now() NOT NULL,
+	CONSTRAINT "navidrome_bookmarks_user_id_track_id_unique" UNIQUE("user_id","track_id")
+);
+--> statement-breakpoint
+ALTER TABLE "navidrome_bookmarks" ADD CONSTRAINT "navidrome_bookmarks_user_id_users_xata_id_fk" FOREIGN KEY ("user_id") REFERENCES "public"."users"("xata_id") ON DELETE no action ON UPDATE no action;--> statement-breakpoint
+ALTER TABLE "navidrome_bookmarks" ADD CONSTRAINT "navidrome_bookmarks_track_id_tracks_xata_id_fk" FOREIGN KEY ("track_id") REFERENCES "public"."tracks"("xata_id") ON DELETE no action ON UPDATE no action;
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS "navidrome_shares" (
+	"xata_id" text PRIMARY KEY DEFAULT xata_id() NOT NULL,
+	"user_id" text NOT NULL,
+	"description" text,
+	"track_ids" text[] DEFAULT '{}' NOT NULL,
+	"expires_at" timestamp with time zone,
+	"last_visited_at" timestamp with time zone,
+	"visit_count" integer DEFAULT 0 NOT NULL,
+	"xata_createdat" timestamp with time zone DEFAULT now() NOT NULL,
+	"xata_updatedat" timestamp with time zone DEFAULT now() NOT NULL
+);
+--> statement-breakpoint
+CREATE INDEX IF NOT EXISTS "navidrome_shares_user_id_idx" ON "navidrome_shares" USING btree ("user_id");--> statement-breakpoint
+ALTER TABLE "navidrome_shares" ADD CONSTRAINT "navidrome_shares_user_id_users_xata_id_fk" FOREIGN KEY ("user_id") REFERENCES "public"."users"("xata_id") ON DELETE no action ON UPDATE no action;
@@ -0,0 +1,3 @@
+ALTER TABLE "playlist_tracks" ADD COLUMN IF NOT EXISTS "uri" text;--> statement-breakpoint
+ALTER TABLE "playlist_tracks" ADD COLUMN IF NOT EXISTS "position" integer;--> statement-breakpoint
+ALTER TABLE "playlist_tracks" ADD CONSTRAINT "playlist_tracks_uri_unique" UNIQUE("uri");
@@ -0,0 +1,2 @@
+DELETE FROM "user_playlists" a USING "user_playlists" b WHERE a."user_id" = b."user_id" AND a."playlist_id" = b."playlist_id" AND a."xata_id" > b."xata_id";--> statement-breakpoint
+CREATE UNIQUE INDEX IF NOT EXISTS "user_playlists_unique_index" ON "user_playlists" USING btree ("user_id","playlist_id");
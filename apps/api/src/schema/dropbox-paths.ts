@@ -10,6 +10,8 @@ const dropboxPaths = pgTable("dropbox_paths", {
   trackId: text("track_id").notNull(),
   directoryId: text("directory_id").references(() => dropboxDirectories.id),
   fileId: text("file_id").notNull().unique(),
+  contentHash: text("content_hash"),
+  rev: text("rev"),
   xataVersion: text("xata_version"),
   createdAt: timestamp("xata_createdat").defaultNow().notNull(),
   updatedAt: timestamp("xata_updatedat").defaultNow().notNull(),
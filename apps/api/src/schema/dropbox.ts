@@ -11,6 +11,7 @@ const dropbox = pgTable("dropbox", {
   dropboxTokenId: text("dropbox_token_id")
     .notNull()
     .references(() => dropboxTokens.id),
+  listFolderCursor: text("list_folder_cursor"),
   xataVersion: text("xata_version"),
   createdAt: timestamp("xata_createdat").defaultNow().notNull(),
   updatedAt: timestamp("xata_updatedat").defaultNow().notNull(),
@@ -11,6 +11,7 @@ const googleDrive = pgTable("google_drive", {
   userId: text("user_id")
     .notNull()
     .references(() => users.id),
+  startPageToken: text("start_page_token"),
   xataVersion: text("xata_version"),
   createdAt: timestamp("xata_createdat").defaultNow().notNull(),
   updatedAt: timestamp("xata_updatedat").defaultNow().notNull(),
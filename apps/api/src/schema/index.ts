@@ -17,15 +17,20 @@ import googleDriveAccounts from "./google-drive-accounts";
 import googleDriveDirectories from "./google-drive-directories";
 import googleDrivePaths from "./google-drive-paths";
 import googleDrive from "./googledrive";
+import libraryFiles from "./library-files";
+import libraryScans from "./library-scans";
 import lovedTracks from "./loved-tracks";
 import mirrorSources from "./mirror-sources";
+import navidromeBookmarks from "./navidrome-bookmarks";
 import navidromePlaylistTracks from "./navidrome-playlist-tracks";
 import navidromePlaylists from "./navidrome-playlists";
+import navidromeShares from "./navidrome-shares";
 import notifications from "./notifications";
 import playlistTracks from "./playlist-tracks";
 import playlists from "./playlists";
 import profileShouts from "./profile-shouts";
 import queueTracks from "./queue-tracks";
+import radioStations from "./radio-stations";
 import scrobbles from "./scrobbles";
 import shoutLikes from "./shout-likes";
 import shoutReports from "./shout-reports";
@@ -90,4 +95,9 @@ export default {
   feeds,
   follows,
   mirrorSources,
+  libraryFiles,
+  libraryScans,
+  radioStations,
+  navidromeBookmarks,
+  navidromeShares,
 };
@@ -0,0 +1,47 @@
+import { type InferInsertModel, type InferSelectModel, sql } from "drizzle-orm";
+import {
+  bigint,
+  index,
+  pgTable,
+  text,
+  timestamp,
+  unique,
+} from "drizzle-orm/pg-core";
+import tracks from "./tracks";
+import users from "./users";
+
+// Files of a local music directory indexed by `rockskyd library`, which keeps
+// them in sync with the tracks of the user's library.
+const libraryFiles = pgTable(
+  "library_files",
+  {
+    id: text("xata_id").primaryKey().default(sql`xata_id()`),
+    userId: text("user_id")
+      .notNull()
+      .references(() => users.id),
+    trackId: text("track_id")
+      .notNull()
+      .references(() => tracks.id),
+    path: text("path").notNull(),
+    /* Identity hash of the track the file was tagged as, matched when a file
+       is renamed or moved. */
+    sha256: text("sha256").notNull(),
+    fileSize: bigint("file_size", { mode: "number" }).notNull(),
+    modifiedAt: timestamp("modified_at", { withTimezone: true }).notNull(),
+    createdAt: timestamp("xata_createdat", { withTimezone: true })
+      .defaultNow()
+      .notNull(),
+    updatedAt: timestamp("xata_updatedat", { withTimezone: true })
+      .defaultNow()
+      .notNull(),
+  },
+  (t) => [
+    unique("library_files_user_id_path_unique").on(t.userId, t.path),
+    index("library_files_user_id_sha256_idx").on(t.userId, t.sha256),
+  ],
+);
+
+export type SelectLibraryFile = InferSelectModel<typeof libraryFiles>;
+export type InsertLibraryFile = InferInsertModel<typeof libraryFiles>;
+
+export default libraryFiles;
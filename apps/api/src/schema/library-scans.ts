@@ -0,0 +1,35 @@
+import { type InferInsertModel, type InferSelectModel, sql } from "drizzle-orm";
+import {
+  boolean,
+  integer,
+  pgTable,
+  text,
+  timestamp,
+} from "drizzle-orm/pg-core";
+import users from "./users";
+
+// Progress of the library scans of a user, reported by the Subsonic
+// `getScanStatus` endpoint.
+const libraryScans = pgTable("library_scans", {
+  id: text("xata_id").primaryKey().default(sql`xata_id()`),
+  userId: text("user_id")
+    .notNull()
+    .unique()
+    .references(() => users.id),
+  scanning: boolean("scanning").notNull().default(false),
+  count: integer("count").notNull().default(0),
+  folderCount: integer("folder_count").notNull().default(0),
+  startedAt: timestamp("started_at", { withTimezone: true }),
+  finishedAt: timestamp("finished_at", { withTimezone: true }),
+  createdAt: timestamp("xata_createdat", { withTimezone: true })
+    .defaultNow()
+    .notNull(),
+  updatedAt: timestamp("xata_updatedat", { withTimezone: true })
+    .defaultNow()
+    .notNull(),
+});
+
+export type SelectLibraryScan = InferSelectModel<typeof libraryScans>;
+export type InsertLibraryScan = InferInsertModel<typeof libraryScans>;
+
+export default libraryScans;
@@ -0,0 +1,48 @@
+import { type InferInsertModel, type InferSelectModel, sql } from "drizzle-orm";
+import {
+  bigint,
+  pgTable,
+  text,
+  timestamp,
+  unique,
+} from "drizzle-orm/pg-core";
+import tracks from "./tracks";
+import users from "./users";
+
+// Positions saved within tracks through the Subsonic bookmark endpoints, one
+// per user and track.
+const navidromeBookmarks = pgTable(
+  "navidrome_bookmarks",
+  {
+    id: text("xata_id").primaryKey().default(sql`xata_id()`),
+    userId: text("user_id")
+      .notNull()
+      .references(() => users.id),
+    trackId: text("track_id")
+      .notNull()
+      .references(() => tracks.id),
+    positionMs: bigint("position_ms", { mode: "number" }).notNull().default(0),
+    comment: text("comment"),
+    createdAt: timestamp("xata_createdat", { withTimezone: true })
+      .defaultNow()
+      .notNull(),
+    updatedAt: timestamp("xata_updatedat", { withTimezone: true })
+      .defaultNow()
+      .notNull(),
+  },
+  (t) => [
+    unique("navidrome_bookmarks_user_id_track_id_unique").on(
+      t.userId,
+      t.trackId,
+    ),
+  ],
+);
+
+export type SelectNavidromeBookmark = InferSelectModel<
+  typeof navidromeBookmarks
+>;
+export type InsertNavidromeBookmark = InferInsertModel<
+  typeof navidromeBookmarks
+>;
+
+export default navidromeBookmarks;
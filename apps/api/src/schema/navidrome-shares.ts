@@ -0,0 +1,33 @@
+import { type InferInsertModel, type InferSelectModel, sql } from "drizzle-orm";
+import { index, integer, pgTable, text, timestamp } from "drizzle-orm/pg-core";
+import users from "./users";
+
+// Public links to tracks of a user's library, created through the Subsonic
+// share endpoints. Albums and playlists are resolved to their tracks when
+// shared.
+const navidromeShares = pgTable(
+  "navidrome_shares",
+  {
+    id: text("xata_id").primaryKey().default(sql`xata_id()`),
+    userId: text("user_id")
+      .notNull()
+      .references(() => users.id),
+    description: text("description"),
+    trackIds: text("track_ids").array().notNull().default(sql`'{}'`),
+    expiresAt: timestamp("expires_at", { withTimezone: true }),
+    lastVisitedAt: timestamp("last_visited_at", { withTimezone: true }),
+    visitCount: integer("visit_count").notNull().default(0),
+    createdAt: timestamp("xata_createdat", { withTimezone: true })
+      .defaultNow()
+      .notNull(),
+    updatedAt: timestamp("xata_updatedat", { withTimezone: true })
+      .defaultNow()
+      .notNull(),
+  },
+  (t) => [index("navidrome_shares_user_id_idx").on(t.userId)],
+);
+
+export type SelectNavidromeShare = InferSelectModel<typeof navidromeShares>;
+export type InsertNavidromeShare = InferInsertModel<typeof navidromeShares>;
+
+export default navidromeShares;
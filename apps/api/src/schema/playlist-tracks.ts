@@ -1,5 +1,5 @@
 import { type InferInsertModel, type InferSelectModel, sql } from "drizzle-orm";
-import { pgTable, text, timestamp } from "drizzle-orm/pg-core";
+import { integer, pgTable, text, timestamp } from "drizzle-orm/pg-core";
 import playlists from "./playlists";
 import tracks from "./tracks";
 
@@ -11,6 +11,8 @@ const playlistTracks = pgTable("playlist_tracks", {
   trackId: text("track_id")
     .notNull()
     .references(() => tracks.id),
+  uri: text("uri").unique(),
+  position: integer("position"),
   createdAt: timestamp("xata_createdat").defaultNow().notNull(),
 });
 
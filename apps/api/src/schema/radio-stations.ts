@@ -0,0 +1,30 @@
+import { type InferInsertModel, type InferSelectModel, sql } from "drizzle-orm";
+import { index, pgTable, text, timestamp } from "drizzle-orm/pg-core";
+import users from "./users";
+
+// Internet radio stations of a user, managed through the Subsonic
+// `*InternetRadioStation*` endpoints.
+const radioStations = pgTable(
+  "radio_stations",
+  {
+    id: text("xata_id").primaryKey().default(sql`xata_id()`),
+    userId: text("user_id")
+      .notNull()
+      .references(() => users.id),
+    name: text("name").notNull(),
+    streamUrl: text("stream_url").notNull(),
+    homepageUrl: text("homepage_url"),
+    createdAt: timestamp("xata_createdat", { withTimezone: true })
+      .defaultNow()
+      .notNull(),
+    updatedAt: timestamp("xata_updatedat", { withTimezone: true })
+      .defaultNow()
+      .notNull(),
+  },
+  (t) => [index("radio_stations_user_id_idx").on(t.userId)],
+);
+
+export type SelectRadioStation = InferSelectModel<typeof radioStations>;
+export type InsertRadioStation = InferInsertModel<typeof radioStations>;
+
+export default radioStations;
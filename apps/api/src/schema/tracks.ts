@@ -21,6 +21,7 @@ const tracks = pgTable(
     sha256: text("sha256").unique().notNull(),
     discNumber: integer("disc_number"),
     lyrics: text("lyrics"),
+    syncedLyrics: text("synced_lyrics"),
     composer: text("composer"),
     genre: text("genre"),
     label: text("label"),
@@ -79,7 +79,10 @@ const retrieve = ({
             eq(tables.playlistTracks.playlistId, tables.playlists.id),
           )
           .where(eq(tables.playlists.uri, params.uri))
-          .orderBy(asc(tables.playlistTracks.createdAt))
+          .orderBy(
+            asc(tables.playlistTracks.position),
+            asc(tables.playlistTracks.createdAt),
+          )
           .execute()
           .then((rows) => rows.map((row) => row.tracks)),
       ]),
@@ -1,41 +1,345 @@
+use std::{env, time::Duration};
+
 use super::Player;
 use async_trait::async_trait;
 
-use anyhow::Error;
+use anyhow::{Error, anyhow};
+use futures_util::{SinkExt, StreamExt};
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
+use serde_json::{Value, json};
 use tokio::sync::mpsc::Sender;
-pub struct JellyfinPlayer {}
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+const DEVICE_ID: &str = "rocksky-connect";
+// Interval at which Jellyfin pushes the sessions over the WebSocket, which
+// also refreshes the progress of now playing.
+const SESSIONS_INTERVAL_MS: u64 = 3000;
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+const TICKS_PER_MS: u64 = 10_000;
+
+pub struct JellyfinPlayer {
+    client: reqwest::Client,
+    url: String,
+    api_key: String,
+    user: Option<String>,
+    device: Option<String>,
+}
+
+/// The Jellyfin server at `JELLYFIN_URL`, authenticated with
+/// `JELLYFIN_API_KEY`. The session to bridge is the active one of
+/// `JELLYFIN_USER`, optionally on the device named `JELLYFIN_DEVICE`.
+pub fn new() -> Result<JellyfinPlayer, Error> {
+    let api_key = env::var("JELLYFIN_API_KEY")?;
+    let url = env::var("JELLYFIN_URL").unwrap_or_else(|_| "http://localhost:8096".to_string());
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        AUTHORIZATION,
+        HeaderValue::from_str(&format!(
+            r#"MediaBrowser Client="Rocksky Connect", Device="{}", DeviceId="{}", Version="{}", Token="{}""#,
+            DEVICE_ID,
+            DEVICE_ID,
+            env!("CARGO_PKG_VERSION"),
+            api_key
+        ))?,
+    );
+
+    Ok(JellyfinPlayer {
+        client: reqwest::Client::builder()
+            .default_headers(headers)
+            .build()?,
+        url: url.trim_end_matches('/').to_string(),
+        api_key,
+        user: env::var("JELLYFIN_USER").ok().filter(|u| !u.is_empty()),
+        device: env::var("JELLYFIN_DEVICE").ok().filter(|d| !d.is_empty()),
+    })
+}
+
+impl JellyfinPlayer {
+    async fn sessions(&self) -> Result<Value, Error> {
+        let sessions = self
+            .client
+            .get(format!("{}/Sessions", self.url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Value>()
+            .await?;
+        Ok(sessions)
+    }
+
+    async fn command(&self, command: &str, query: &[(&str, String)]) -> Result<(), Error> {
+        let sessions = self.sessions().await?;
+        let session = select_session(&sessions, self.user.as_deref(), self.device.as_deref())
+            .ok_or_else(|| anyhow!("No active Jellyfin session"))?;
+        let id = session["Id"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Jellyfin session without id"))?;
+
+        self.client
+            .post(format!("{}/Sessions/{}/Playing/{}", self.url, id, command))
+            .query(query)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Sends what `on_change` makes of the selected session, first from the
+    /// Sessions API then on every update pushed over the WebSocket. Returns
+    /// once `tx` is closed.
+    async fn watch<F>(&self, tx: &Sender<String>, on_change: F) -> Result<(), Error>
+    where
+        F: Fn(Option<&Value>) -> Option<Value>,
+    {
+        let ws_url = format!(
+            "{}/socket?api_key={}&deviceId={}",
+            self.url
+                .replacen("https://", "wss://", 1)
+                .replacen("http://", "ws://", 1),
+            self.api_key,
+            DEVICE_ID
+        );
+
+        while !tx.is_closed() {
+            let result: Result<(), Error> = async {
+                let mut last = None;
+                let mut send = async |sessions: &Value| -> Result<(), Error> {
+                    let session =
+                        select_session(sessions, self.user.as_deref(), self.device.as_deref());
+                    if let Some(message) = on_change(session).map(|m| m.to_string())
+                        && last.as_ref() != Some(&message)
+                    {
+                        tx.send(message.clone()).await?;
+                        last = Some(message);
+                    }
+                    Ok(())
+                };
+
+                send(&self.sessions().await?).await?;
+
+                let (mut socket, _) = connect_async(&ws_url).await?;
+                socket
+                    .send(Message::text(
+                        json!({
+                            "MessageType": "SessionsStart",
+                            "Data": format!("0,{}", SESSIONS_INTERVAL_MS),
+                        })
+                        .to_string(),
+                    ))
+                    .await?;
 
-pub fn new() -> JellyfinPlayer {
-    JellyfinPlayer {}
+                // Jellyfin drops clients which don't send keep alives at the
+                // interval it asks for.
+                let mut keep_alive = tokio::time::interval(Duration::from_secs(30));
+
+                loop {
+                    tokio::select! {
+                        message = socket.next() => {
+                            let message = match message {
+                                Some(message) => message?,
+                                None => return Err(anyhow!("Jellyfin closed the connection")),
+                            };
+                            let Ok(text) = message.to_text() else { continue };
+                            let message: Value = serde_json::from_str(text).unwrap_or_default();
+                            match message["MessageType"].as_str() {
+                                Some("Sessions") => send(&message["Data"]).await?,
+                                Some("ForceKeepAlive") => {
+                                    let seconds = message["Data"].as_u64().unwrap_or(60).max(2);
+                                    keep_alive =
+                                        tokio::time::interval(Duration::from_secs(seconds / 2));
+                                }
+                                _ => {}
+                            }
+                        }
+                        _ = keep_alive.tick() => {
+                            socket
+                                .send(Message::text(json!({ "MessageType": "KeepAlive" }).to_string()))
+                                .await?;
+                        }
+                    }
+                }
+            }
+            .await;
+
+            if let Err(err) = result {
+                if tx.is_closed() {
+                    break;
+                }
+                eprintln!("Jellyfin error: {}", err);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl Player for JellyfinPlayer {
     async fn play(&self) -> Result<(), Error> {
-        Ok(())
+        self.command("Unpause", &[]).await
     }
 
     async fn pause(&self) -> Result<(), Error> {
-        Ok(())
+        self.command("Pause", &[]).await
     }
 
     async fn next(&self) -> Result<(), Error> {
-        Ok(())
+        self.command("NextTrack", &[]).await
     }
 
     async fn previous(&self) -> Result<(), Error> {
-        Ok(())
+        self.command("PreviousTrack", &[]).await
     }
 
-    async fn seek(&self, _position: u64) -> Result<(), Error> {
-        Ok(())
+    async fn seek(&self, position: u64) -> Result<(), Error> {
+        // Rocksky sends milliseconds, Jellyfin expects ticks (100ns).
+        self.command(
+            "Seek",
+            &[("seekPositionTicks", (position * TICKS_PER_MS).to_string())],
+        )
+        .await
     }
 
-    async fn broadcast_now_playing(&self, _tx: Sender<String>) -> Result<(), Error> {
-        Ok(())
+    async fn broadcast_now_playing(&self, tx: Sender<String>) -> Result<(), Error> {
+        self.watch(&tx, |session| session.and_then(track_message))
+            .await
     }
 
-    async fn broadcast_status(&self, _tx: Sender<String>) -> Result<(), Error> {
-        Ok(())
+    async fn broadcast_status(&self, tx: Sender<String>) -> Result<(), Error> {
+        self.watch(&tx, |session| {
+            Some(json!({ "type": "status", "status": status_code(session) }))
+        })
+        .await
+    }
+}
+
+/// The session to bridge: one of `user` playing music (on `device` when
+/// set), preferring sessions which aren't paused.
+fn select_session<'a>(
+    sessions: &'a Value,
+    user: Option<&str>,
+    device: Option<&str>,
+) -> Option<&'a Value> {
+    let candidates: Vec<&Value> = sessions
+        .as_array()?
+        .iter()
+        .filter(|session| session["NowPlayingItem"]["Type"].as_str() == Some("Audio"))
+        .filter(|session| {
+            user.is_none_or(|user| {
+                session["UserName"]
+                    .as_str()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(user))
+            })
+        })
+        .filter(|session| {
+            device.is_none_or(|device| {
+                session["DeviceName"]
+                    .as_str()
+                    .is_some_and(|name| name.to_lowercase().contains(&device.to_lowercase()))
+            })
+        })
+        .collect();
+
+    candidates
+        .iter()
+        .find(|session| session["PlayState"]["IsPaused"].as_bool() != Some(true))
+        .or_else(|| candidates.first())
+        .copied()
+}
+
+/// Rocksky status codes: 1 playing, 2 paused, 0 stopped.
+fn status_code(session: Option<&Value>) -> u8 {
+    match session {
+        Some(session) if session["PlayState"]["IsPaused"].as_bool() == Some(true) => 2,
+        Some(_) => 1,
+        None => 0,
+    }
+}
+
+/// The now playing message from a session playing music.
+fn track_message(session: &Value) -> Option<Value> {
+    let item = &session["NowPlayingItem"];
+    let title = item["Name"].as_str()?;
+
+    let artists: Vec<&str> = item["Artists"]
+        .as_array()
+        .map(|artists| artists.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let artist = if artists.is_empty() {
+        "Unknown Artist".to_string()
+    } else {
+        artists.join(", ")
+    };
+
+    Some(json!({
+        "type": "track",
+        "title": title,
+        "album_artist": item["AlbumArtist"].as_str().unwrap_or(&artist),
+        "artist": artist,
+        "album": item["Album"].as_str().unwrap_or("Unknown Album"),
+        "length": item["RunTimeTicks"].as_u64().unwrap_or(0) / TICKS_PER_MS,
+        "elapsed": session["PlayState"]["PositionTicks"].as_u64().unwrap_or(0) / TICKS_PER_MS,
+        "is_playing": session["PlayState"]["IsPaused"].as_bool() != Some(true),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(user: &str, device: &str, paused: bool) -> Value {
+        json!({
+            "Id": format!("{}-{}", user, device),
+            "UserName": user,
+            "DeviceName": device,
+            "PlayState": { "PositionTicks": 123_450_000u64, "IsPaused": paused },
+            "NowPlayingItem": {
+                "Type": "Audio",
+                "Name": "Song",
+                "Artists": ["Artist"],
+                "AlbumArtist": "Album Artist",
+                "Album": "Album",
+                "RunTimeTicks": 2_010_000_000u64,
+            },
+        })
+    }
+
+    #[test]
+    fn test_select_session() {
+        let sessions = json!([
+            { "Id": "idle", "UserName": "alice", "DeviceName": "TV" },
+            session("alice", "Phone", true),
+            session("alice", "Desktop", false),
+            session("bob", "Desktop", false),
+        ]);
+
+        let id = |session: Option<&Value>| session.map(|s| s["Id"].as_str().unwrap().to_string());
+        assert_eq!(
+            id(select_session(&sessions, Some("Alice"), None)),
+            Some("alice-Desktop".into())
+        );
+        assert_eq!(
+            id(select_session(&sessions, Some("alice"), Some("phone"))),
+            Some("alice-Phone".into())
+        );
+        assert_eq!(id(select_session(&sessions, Some("carol"), None)), None);
+    }
+
+    #[test]
+    fn test_track_message() {
+        assert_eq!(
+            track_message(&session("alice", "Desktop", true)),
+            Some(json!({
+                "type": "track",
+                "title": "Song",
+                "artist": "Artist",
+                "album_artist": "Album Artist",
+                "album": "Album",
+                "length": 201_000,
+                "elapsed": 12_345,
+                "is_playing": false,
+            }))
+        );
     }
 }
@@ -35,9 +35,9 @@ pub fn get_current_player() -> Result<Box<dyn Player + Send + Sync>, Error> {
     let player_type = player_type.unwrap();
 
     match player_type.as_str() {
-        "jellyfin" => Ok(Box::new(jellyfin::new())),
+        "jellyfin" => Ok(Box::new(jellyfin::new()?)),
         "kodi" => Ok(Box::new(kodi::new()?)),
-        "mopidy" => Ok(Box::new(mopidy::new())),
+        "mopidy" => Ok(Box::new(mopidy::new()?)),
         "mpd" => Ok(Box::new(mpd::new())),
         "mpris" => Ok(Box::new(mpris::new())),
         "vlc" => Ok(Box::new(vlc::new())),
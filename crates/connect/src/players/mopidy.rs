@@ -1,41 +1,292 @@
+use std::{env, time::Duration};
+
 use super::Player;
-use anyhow::Error;
+use anyhow::{Error, anyhow};
 use async_trait::async_trait;
+use futures_util::StreamExt;
+use jsonrpsee::{
+    core::{
+        client::ClientT,
+        params::{ArrayParams, ObjectParams},
+    },
+    http_client::{HttpClient, HttpClientBuilder},
+    rpc_params,
+};
+use serde_json::{Value, json};
 use tokio::sync::mpsc::Sender;
+use tokio_tungstenite::connect_async;
+
+// Mopidy has no event for the progress of a track, now playing is refreshed
+// at this interval while playing.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(3);
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+// Events after which now playing and the status are refreshed.
+const PLAYBACK_EVENTS: [&str; 7] = [
+    "track_playback_started",
+    "track_playback_paused",
+    "track_playback_resumed",
+    "track_playback_ended",
+    "playback_state_changed",
+    "seeked",
+    "stream_title_changed",
+];
+
+pub struct MopidyPlayer {
+    client: HttpClient,
+    ws_url: String,
+}
+
+/// Mopidy's HTTP frontend at `MOPIDY_URL`: JSON-RPC for commands, the
+/// WebSocket for events.
+pub fn new() -> Result<MopidyPlayer, Error> {
+    let url = env::var("MOPIDY_URL").unwrap_or_else(|_| "http://localhost:6680".to_string());
+    let url = url.trim_end_matches('/');
+
+    let client = HttpClientBuilder::default().build(format!("{}/mopidy/rpc", url))?;
+    let ws_url = format!(
+        "{}/mopidy/ws",
+        url.replacen("https://", "wss://", 1)
+            .replacen("http://", "ws://", 1)
+    );
+
+    Ok(MopidyPlayer { client, ws_url })
+}
+
+impl MopidyPlayer {
+    async fn call(&self, method: &str) -> Result<Value, Error> {
+        let response = self
+            .client
+            .request::<Value, ArrayParams>(method, rpc_params![])
+            .await?;
+        Ok(response)
+    }
+
+    async fn state(&self) -> Result<String, Error> {
+        let state = self.call("core.playback.get_state").await?;
+        state
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Unexpected playback state: {}", state))
+    }
+
+    /// An http(s) image of the track, Mopidy-Local serves relative paths
+    /// which mean nothing to Rocksky.
+    async fn image(&self, uri: &str) -> Result<Option<String>, Error> {
+        let mut params = ObjectParams::new();
+        params.insert("uris", vec![uri])?;
+        let images = self
+            .client
+            .request::<Value, ObjectParams>("core.library.get_images", params)
+            .await?;
+
+        Ok(images[uri].as_array().and_then(|images| {
+            images
+                .iter()
+                .filter_map(|image| image["uri"].as_str())
+                .find(|image| image.starts_with("http"))
+                .map(str::to_string)
+        }))
+    }
+
+    /// Sends what `on_change` makes of the current state, track and position
+    /// on connection and after every playback event. Returns once `tx` is
+    /// closed.
+    async fn watch<F>(&self, tx: &Sender<String>, periodic: bool, on_change: F) -> Result<(), Error>
+    where
+        F: Fn(&str, &Value, u64, Option<&str>) -> Option<Value>,
+    {
+        while !tx.is_closed() {
+            let result: Result<(), Error> = async {
+                let (mut events, _) = connect_async(&self.ws_url).await?;
+                let mut progress = tokio::time::interval(PROGRESS_INTERVAL);
+                let mut last = None;
+
+                loop {
+                    let state = self.state().await?;
+                    let track = self.call("core.playback.get_current_track").await?;
+                    let position = self
+                        .call("core.playback.get_time_position")
+                        .await?
+                        .as_u64()
+                        .unwrap_or(0);
+                    let image = match track["uri"].as_str() {
+                        Some(uri) if periodic => self.image(uri).await.unwrap_or(None),
+                        _ => None,
+                    };
 
-pub struct MopidyPlayer {}
+                    if let Some(message) = on_change(&state, &track, position, image.as_deref()) {
+                        let message = message.to_string();
+                        if last.as_ref() != Some(&message) {
+                            tx.send(message.clone()).await?;
+                            last = Some(message);
+                        }
+                    }
 
-pub fn new() -> MopidyPlayer {
-    MopidyPlayer {}
+                    let playing = state == "playing";
+                    progress.reset();
+                    loop {
+                        tokio::select! {
+                            event = events.next() => {
+                                let event = match event {
+                                    Some(event) => event?,
+                                    None => return Err(anyhow!("Mopidy closed the connection")),
+                                };
+                                let event: Value =
+                                    serde_json::from_str(event.to_text().unwrap_or("{}"))
+                                        .unwrap_or_default();
+                                if event["event"]
+                                    .as_str()
+                                    .is_some_and(|event| PLAYBACK_EVENTS.contains(&event))
+                                {
+                                    break;
+                                }
+                            }
+                            _ = progress.tick(), if periodic && playing => break,
+                        }
+                    }
+                }
+            }
+            .await;
+
+            if let Err(err) = result {
+                if tx.is_closed() {
+                    break;
+                }
+                eprintln!("Mopidy error: {}", err);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl Player for MopidyPlayer {
     async fn play(&self) -> Result<(), Error> {
+        match self.state().await?.as_str() {
+            "paused" => self.call("core.playback.resume").await?,
+            "stopped" => self.call("core.playback.play").await?,
+            _ => Value::Null,
+        };
         Ok(())
     }
 
     async fn pause(&self) -> Result<(), Error> {
+        self.call("core.playback.pause").await?;
         Ok(())
     }
 
     async fn next(&self) -> Result<(), Error> {
+        self.call("core.playback.next").await?;
         Ok(())
     }
 
     async fn previous(&self) -> Result<(), Error> {
+        self.call("core.playback.previous").await?;
         Ok(())
     }
 
-    async fn seek(&self, _position: u64) -> Result<(), Error> {
+    async fn seek(&self, position: u64) -> Result<(), Error> {
+        let mut params = ObjectParams::new();
+        params.insert("time_position", position)?;
+        self.client
+            .request::<Value, ObjectParams>("core.playback.seek", params)
+            .await?;
         Ok(())
     }
 
-    async fn broadcast_now_playing(&self, _tx: Sender<String>) -> Result<(), Error> {
-        Ok(())
+    async fn broadcast_now_playing(&self, tx: Sender<String>) -> Result<(), Error> {
+        self.watch(&tx, true, track_message).await
     }
 
-    async fn broadcast_status(&self, _tx: Sender<String>) -> Result<(), Error> {
-        Ok(())
+    async fn broadcast_status(&self, tx: Sender<String>) -> Result<(), Error> {
+        self.watch(&tx, false, |state, _, _, _| {
+            Some(json!({ "type": "status", "status": status_code(state) }))
+        })
+        .await
+    }
+}
+
+/// Rocksky status codes: 1 playing, 2 paused, 0 stopped.
+fn status_code(state: &str) -> u8 {
+    match state {
+        "playing" => 1,
+        "paused" => 2,
+        _ => 0,
+    }
+}
+
+fn artist_names(artists: &Value) -> Option<String> {
+    let names: Vec<&str> = artists
+        .as_array()?
+        .iter()
+        .filter_map(|artist| artist["name"].as_str())
+        .collect();
+    (!names.is_empty()).then(|| names.join(", "))
+}
+
+/// The now playing message from a Mopidy `Track` model, `None` when nothing
+/// is playing.
+fn track_message(state: &str, track: &Value, position: u64, image: Option<&str>) -> Option<Value> {
+    if state == "stopped" {
+        return None;
+    }
+    let title = track["name"].as_str()?;
+    let artist = artist_names(&track["artists"]).unwrap_or_else(|| "Unknown Artist".into());
+
+    let mut message = json!({
+        "type": "track",
+        "title": title,
+        "album_artist": artist_names(&track["album"]["artists"]).unwrap_or_else(|| artist.clone()),
+        "artist": artist,
+        "album": track["album"]["name"].as_str().unwrap_or("Unknown Album"),
+        "length": track["length"].as_u64().unwrap_or(0),
+        "elapsed": position,
+        "is_playing": state == "playing",
+    });
+
+    if let Some(image) = image {
+        message["album_art"] = json!(image);
+    }
+
+    Some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_message() {
+        let track = json!({
+            "__model__": "Track",
+            "uri": "local:track:Artist/Album/01%20Song.flac",
+            "name": "Song",
+            "artists": [{ "__model__": "Artist", "name": "Artist" }, { "name": "Guest" }],
+            "album": {
+                "__model__": "Album",
+                "name": "Album",
+                "artists": [{ "name": "Artist" }],
+            },
+            "length": 201_000,
+        });
+        assert_eq!(
+            track_message("playing", &track, 12_345, Some("https://i.scdn.co/image/1")),
+            Some(json!({
+                "type": "track",
+                "title": "Song",
+                "artist": "Artist, Guest",
+                "album_artist": "Artist",
+                "album": "Album",
+                "length": 201_000,
+                "elapsed": 12_345,
+                "is_playing": true,
+                "album_art": "https://i.scdn.co/image/1",
+            }))
+        );
+
+        assert_eq!(track_message("stopped", &track, 0, None), None);
+        assert_eq!(track_message("playing", &Value::Null, 0, None), None);
     }
 }
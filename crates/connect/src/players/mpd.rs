@@ -1,41 +1,471 @@
+use std::{collections::HashMap, env, time::Duration};
+
 use super::Player;
-use anyhow::Error;
+use anyhow::{Error, anyhow};
 use async_trait::async_trait;
-use tokio::sync::mpsc::Sender;
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    sync::mpsc::Sender,
+};
+
+// While playing, now playing is re-sent at this interval even without player
+// events so that controllers show a moving progress bar.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(3);
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
 
-pub struct MpdPlayer {}
+pub struct MpdPlayer {
+    host: String,
+    port: u16,
+    password: Option<String>,
+}
 
+/// Reads `MPD_HOST` (`[password@]host`, or the path of a unix socket),
+/// `MPD_PORT` and `MPD_PASSWORD`, like `mpc` does.
 pub fn new() -> MpdPlayer {
-    MpdPlayer {}
+    let (host, password) =
+        parse_host(&env::var("MPD_HOST").unwrap_or_else(|_| "localhost".to_string()));
+    let port = env::var("MPD_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(6600);
+
+    MpdPlayer {
+        host,
+        port,
+        password: password.or_else(|| env::var("MPD_PASSWORD").ok()),
+    }
+}
+
+fn parse_host(host: &str) -> (String, Option<String>) {
+    match host.rsplit_once('@') {
+        Some((password, host)) if !host.is_empty() && !host.starts_with('/') => {
+            (host.to_string(), Some(password.to_string()))
+        }
+        _ => (host.to_string(), None),
+    }
+}
+
+struct MpdConnection {
+    reader: Box<dyn AsyncBufRead + Unpin + Send>,
+    writer: Box<dyn AsyncWrite + Unpin + Send>,
+    // Kept across reads so that a read interrupted by a timeout resumes where
+    // it stopped instead of losing half a line.
+    line: Vec<u8>,
+}
+
+impl MpdConnection {
+    async fn send(&mut self, command: &str) -> Result<(), Error> {
+        self.writer.write_all(command.as_bytes()).await?;
+        self.writer.write_all(b"\n").await?;
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    async fn read_line(&mut self) -> Result<String, Error> {
+        self.reader.read_until(b'\n', &mut self.line).await?;
+        if !self.line.ends_with(b"\n") {
+            return Err(anyhow!("MPD closed the connection"));
+        }
+        let line = String::from_utf8_lossy(&self.line).trim_end().to_string();
+        self.line.clear();
+        Ok(line)
+    }
+
+    /// Reads the `key: value` pairs of a response up to its `OK`.
+    async fn read_response(&mut self) -> Result<Vec<(String, String)>, Error> {
+        let mut pairs = vec![];
+        loop {
+            let line = self.read_line().await?;
+            if line == "OK" {
+                return Ok(pairs);
+            }
+            if let Some(error) = line.strip_prefix("ACK ") {
+                return Err(anyhow!("MPD error: {}", error));
+            }
+            if let Some((key, value)) = line.split_once(": ") {
+                pairs.push((key.to_string(), value.to_string()));
+            }
+        }
+    }
+
+    async fn command(&mut self, command: &str) -> Result<HashMap<String, String>, Error> {
+        self.send(command).await?;
+        Ok(self.read_response().await?.into_iter().collect())
+    }
+
+    /// Waits for a change of the player (song, state, seek), at most
+    /// `timeout`.
+    async fn idle_player(&mut self, timeout: Duration) -> Result<(), Error> {
+        self.send("idle player").await?;
+        match tokio::time::timeout(timeout, self.read_response()).await {
+            Ok(response) => response.map(|_| ()),
+            Err(_) => {
+                self.send("noidle").await?;
+                self.read_response().await.map(|_| ())
+            }
+        }
+    }
+}
+
+impl MpdPlayer {
+    async fn connect(&self) -> Result<MpdConnection, Error> {
+        let (reader, writer): (
+            Box<dyn AsyncBufRead + Unpin + Send>,
+            Box<dyn AsyncWrite + Unpin + Send>,
+        ) = if self.host.starts_with('/') {
+            #[cfg(unix)]
+            {
+                let (reader, writer) = tokio::net::UnixStream::connect(&self.host)
+                    .await?
+                    .into_split();
+                (Box::new(BufReader::new(reader)), Box::new(writer))
+            }
+            #[cfg(not(unix))]
+            return Err(anyhow!("unix sockets are not supported on this platform"));
+        } else {
+            let (reader, writer) = TcpStream::connect((self.host.as_str(), self.port))
+                .await?
+                .into_split();
+            (Box::new(BufReader::new(reader)), Box::new(writer))
+        };
+
+        let mut connection = MpdConnection {
+            reader,
+            writer,
+            line: vec![],
+        };
+
+        let greeting = connection.read_line().await?;
+        if !greeting.starts_with("OK MPD ") {
+            return Err(anyhow!("Unexpected MPD greeting: {}", greeting));
+        }
+
+        if let Some(password) = &self.password {
+            connection
+                .command(&format!("password {}", quote(password)))
+                .await?;
+        }
+
+        Ok(connection)
+    }
+
+    async fn command(&self, command: &str) -> Result<(), Error> {
+        self.connect().await?.command(command).await?;
+        Ok(())
+    }
+
+    /// Runs `on_change` with the current song and status on connection and
+    /// after every player event, reconnecting when MPD goes away. Returns
+    /// once `tx` is closed.
+    async fn watch<F>(&self, tx: &Sender<String>, periodic: bool, on_change: F) -> Result<(), Error>
+    where
+        F: Fn(&HashMap<String, String>, &HashMap<String, String>) -> Option<Value>,
+    {
+        while !tx.is_closed() {
+            let result: Result<(), Error> = async {
+                let mut connection = self.connect().await?;
+                loop {
+                    let status = connection.command("status").await?;
+                    let song = connection.command("currentsong").await?;
+                    if let Some(message) = on_change(&song, &status) {
+                        tx.send(message.to_string()).await?;
+                    }
+
+                    let playing = status.get("state").map(String::as_str) == Some("play");
+                    if periodic && playing {
+                        connection.idle_player(PROGRESS_INTERVAL).await?;
+                    } else {
+                        connection.idle_player(Duration::MAX).await?;
+                    }
+                }
+            }
+            .await;
+
+            if let Err(err) = result {
+                if tx.is_closed() {
+                    break;
+                }
+                eprintln!("MPD connection error: {}", err);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl Player for MpdPlayer {
     async fn play(&self) -> Result<(), Error> {
+        let mut connection = self.connect().await?;
+        let status = connection.command("status").await?;
+        // `pause 0` only resumes a paused song, `play` starts a stopped queue.
+        match status.get("state").map(String::as_str) {
+            Some("stop") => connection.command("play").await?,
+            _ => connection.command("pause 0").await?,
+        };
         Ok(())
     }
 
     async fn pause(&self) -> Result<(), Error> {
-        Ok(())
+        self.command("pause 1").await
     }
 
     async fn next(&self) -> Result<(), Error> {
-        Ok(())
+        self.command("next").await
     }
 
     async fn previous(&self) -> Result<(), Error> {
-        Ok(())
+        self.command("previous").await
     }
 
-    async fn seek(&self, _position: u64) -> Result<(), Error> {
-        Ok(())
+    async fn seek(&self, position: u64) -> Result<(), Error> {
+        // Rocksky sends milliseconds, MPD expects seconds.
+        self.command(&format!("seekcur {}", position as f64 / 1000.0))
+            .await
     }
 
-    async fn broadcast_now_playing(&self, _tx: Sender<String>) -> Result<(), Error> {
-        Ok(())
+    async fn broadcast_now_playing(&self, tx: Sender<String>) -> Result<(), Error> {
+        self.watch(&tx, true, track_message).await
     }
 
-    async fn broadcast_status(&self, _tx: Sender<String>) -> Result<(), Error> {
-        Ok(())
+    async fn broadcast_status(&self, tx: Sender<String>) -> Result<(), Error> {
+        self.watch(&tx, false, |_, status| {
+            Some(json!({
+                "type": "status",
+                "status": status_code(status.get("state").map(String::as_str)),
+            }))
+        })
+        .await
+    }
+}
+
+fn quote(argument: &str) -> String {
+    format!(
+        "\"{}\"",
+        argument.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+/// Rocksky status codes: 1 playing, 2 paused, 0 stopped.
+fn status_code(state: Option<&str>) -> u8 {
+    match state {
+        Some("play") => 1,
+        Some("pause") => 2,
+        _ => 0,
+    }
+}
+
+/// The now playing message from the `currentsong` and `status` responses,
+/// `None` when nothing is playing.
+fn track_message(
+    song: &HashMap<String, String>,
+    status: &HashMap<String, String>,
+) -> Option<Value> {
+    let state = status.get("state").map(String::as_str);
+    if state == Some("stop") || !song.contains_key("file") {
+        return None;
+    }
+
+    let seconds = |value: Option<&String>| {
+        value
+            .and_then(|value| value.parse::<f64>().ok())
+            .map(|seconds| (seconds * 1000.0).round() as u64)
+    };
+    let file_name = song["file"]
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.').map(|(stem, _)| stem).or(Some(name)))
+        .unwrap_or_default();
+    let artist = song
+        .get("Artist")
+        .cloned()
+        .unwrap_or_else(|| "Unknown Artist".into());
+
+    let mut message = json!({
+        "type": "track",
+        "title": song.get("Title").map(String::as_str).unwrap_or(file_name),
+        "artist": artist,
+        "album_artist": song.get("AlbumArtist").unwrap_or(&artist),
+        "album": song.get("Album").map(String::as_str).unwrap_or("Unknown Album"),
+        "length": seconds(status.get("duration"))
+            .or_else(|| seconds(song.get("duration")))
+            .or_else(|| seconds(song.get("Time")))
+            .unwrap_or(0),
+        "elapsed": seconds(status.get("elapsed")).unwrap_or(0),
+        "is_playing": state == Some("play"),
+    });
+
+    // `samplerate:bits:channels`
+    if let Some(sample_rate) = status
+        .get("audio")
+        .and_then(|audio| audio.split(':').next())
+        .and_then(|rate| rate.parse::<u32>().ok())
+    {
+        message["sample_rate"] = json!(sample_rate);
+    }
+
+    Some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::net::TcpListener;
+
+    /// A fake MPD server answering from a canned paused/playing state, which
+    /// records the commands it receives.
+    async fn fake_mpd(state: &'static str) -> (MpdPlayer, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let commands = Arc::new(Mutex::new(vec![]));
+
+        let received = commands.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let received = received.clone();
+                tokio::spawn(async move {
+                    let (reader, mut writer) = stream.into_split();
+                    let mut lines = BufReader::new(reader).lines();
+                    writer.write_all(b"OK MPD 0.23.5\n").await.unwrap();
+                    while let Ok(Some(command)) = lines.next_line().await {
+                        received.lock().unwrap().push(command.clone());
+                        let response = match command.as_str() {
+                            "status" => format!(
+                                "volume: 100\nstate: {}\nelapsed: 12.345\nduration: 201.000\naudio: 44100:16:2\nOK\n",
+                                state
+                            ),
+                            "currentsong" => "file: Artist/Album/01 Song.flac\nTitle: Song\nArtist: Artist\nAlbumArtist: Album Artist\nAlbum: Album\nTime: 201\nOK\n".to_string(),
+                            // Answered when the client gives up waiting.
+                            "idle player" => continue,
+                            "bogus" => "ACK [5@0] {bogus} unknown command \"bogus\"\n".to_string(),
+                            _ => "OK\n".to_string(),
+                        };
+                        writer.write_all(response.as_bytes()).await.unwrap();
+                    }
+                });
+            }
+        });
+
+        let player = MpdPlayer {
+            host: "127.0.0.1".into(),
+            port,
+            password: Some("secret".into()),
+        };
+        (player, commands)
+    }
+
+    #[tokio::test]
+    async fn test_commands() {
+        let (player, commands) = fake_mpd("pause").await;
+
+        player.play().await.unwrap();
+        player.pause().await.unwrap();
+        player.next().await.unwrap();
+        player.previous().await.unwrap();
+        player.seek(90_500).await.unwrap();
+
+        let commands: Vec<String> = commands
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|command| !command.starts_with("password"))
+            .cloned()
+            .collect();
+        assert_eq!(
+            commands,
+            vec![
+                "status",
+                "pause 0",
+                "pause 1",
+                "next",
+                "previous",
+                "seekcur 90.5"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_play_stopped() {
+        let (player, commands) = fake_mpd("stop").await;
+        player.play().await.unwrap();
+        assert_eq!(commands.lock().unwrap().last().unwrap(), "play");
+    }
+
+    #[tokio::test]
+    async fn test_authentication() {
+        let (player, commands) = fake_mpd("play").await;
+        player.next().await.unwrap();
+        assert_eq!(commands.lock().unwrap()[0], "password \"secret\"");
+    }
+
+    #[tokio::test]
+    async fn test_error() {
+        let (player, _) = fake_mpd("play").await;
+        let err = player.command("bogus").await.unwrap_err();
+        assert!(err.to_string().contains("unknown command"));
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_now_playing() {
+        let (player, _) = fake_mpd("play").await;
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let task = tokio::spawn(async move { player.broadcast_now_playing(tx).await });
+
+        let message: Value = serde_json::from_str(&rx.recv().await.unwrap()).unwrap();
+        assert_eq!(
+            message,
+            json!({
+                "type": "track",
+                "title": "Song",
+                "artist": "Artist",
+                "album_artist": "Album Artist",
+                "album": "Album",
+                "length": 201_000,
+                "elapsed": 12_345,
+                "is_playing": true,
+                "sample_rate": 44100,
+            })
+        );
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_status() {
+        let (player, _) = fake_mpd("pause").await;
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let task = tokio::spawn(async move { player.broadcast_status(tx).await });
+
+        let message: Value = serde_json::from_str(&rx.recv().await.unwrap()).unwrap();
+        assert_eq!(message, json!({ "type": "status", "status": 2 }));
+        task.abort();
+    }
+
+    #[test]
+    fn test_parse_host() {
+        assert_eq!(parse_host("localhost"), ("localhost".into(), None));
+        assert_eq!(
+            parse_host("secret@mpd.lan"),
+            ("mpd.lan".into(), Some("secret".into()))
+        );
+        assert_eq!(
+            parse_host("/run/mpd/socket"),
+            ("/run/mpd/socket".into(), None)
+        );
+    }
+
+    #[test]
+    fn test_track_message_stopped() {
+        let song = HashMap::from([("file".to_string(), "song.mp3".to_string())]);
+        let status = HashMap::from([("state".to_string(), "stop".to_string())]);
+        assert_eq!(track_message(&song, &status), None);
+
+        let status = HashMap::from([("state".to_string(), "pause".to_string())]);
+        let message = track_message(&song, &status).unwrap();
+        assert_eq!(message["title"], "song");
+        assert_eq!(message["is_playing"], false);
     }
 }
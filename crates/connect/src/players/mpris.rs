@@ -1,41 +1,628 @@
+use std::{collections::HashMap, env, time::Duration};
+
 use super::Player;
-use anyhow::Error;
+use anyhow::{Error, anyhow};
 use async_trait::async_trait;
+use futures_util::StreamExt;
+use serde_json::{Value, json};
 use tokio::sync::mpsc::Sender;
+use zbus::{
+    Connection, connection, fdo::DBusProxy, proxy, zvariant::ObjectPath, zvariant::OwnedValue,
+    zvariant::Value as DBusValue,
+};
+
+const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+// Players don't signal Position changes, it is polled at this interval while
+// playing so that controllers show a moving progress bar.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(3);
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+
+#[proxy(
+    interface = "org.mpris.MediaPlayer2.Player",
+    default_path = "/org/mpris/MediaPlayer2"
+)]
+trait MediaPlayer2Player {
+    fn play(&self) -> zbus::Result<()>;
+    fn pause(&self) -> zbus::Result<()>;
+    fn next(&self) -> zbus::Result<()>;
+    fn previous(&self) -> zbus::Result<()>;
+    fn seek(&self, offset: i64) -> zbus::Result<()>;
+    fn set_position(&self, track_id: &ObjectPath<'_>, position: i64) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn playback_status(&self) -> zbus::Result<String>;
+    #[zbus(property)]
+    fn metadata(&self) -> zbus::Result<HashMap<String, OwnedValue>>;
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn position(&self) -> zbus::Result<i64>;
 
-pub struct MprisPlayer {}
+    #[zbus(signal)]
+    fn seeked(&self, position: i64) -> zbus::Result<()>;
+}
+
+pub struct MprisPlayer {
+    // Session bus when not set.
+    address: Option<String>,
+    preferred: Option<String>,
+}
 
+/// `MPRIS_PLAYER` selects a player by (part of) its bus name, e.g. `spotify`
+/// or `mpv`. Otherwise the first playing player is used.
 pub fn new() -> MprisPlayer {
-    MprisPlayer {}
+    MprisPlayer {
+        address: None,
+        preferred: env::var("MPRIS_PLAYER").ok().filter(|p| !p.is_empty()),
+    }
+}
+
+impl MprisPlayer {
+    async fn connection(&self) -> Result<Connection, Error> {
+        let connection = match &self.address {
+            Some(address) => {
+                connection::Builder::address(address.as_str())?
+                    .build()
+                    .await?
+            }
+            None => Connection::session().await?,
+        };
+        Ok(connection)
+    }
+
+    /// Finds the player to bridge among the `org.mpris.MediaPlayer2.*` names
+    /// on the bus.
+    async fn player<'a>(
+        &self,
+        connection: &'a Connection,
+    ) -> Result<MediaPlayer2PlayerProxy<'a>, Error> {
+        let names = DBusProxy::new(connection).await?.list_names().await?;
+        let names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+        let candidates = candidates(&names, self.preferred.as_deref());
+
+        let mut first = None;
+        for name in candidates {
+            let player = MediaPlayer2PlayerProxy::builder(connection)
+                .destination(name.to_string())?
+                .build()
+                .await?;
+            if player.playback_status().await.ok().as_deref() == Some("Playing") {
+                return Ok(player);
+            }
+            first.get_or_insert(player);
+        }
+
+        first.ok_or_else(|| anyhow!("No MPRIS player found"))
+    }
+
+    /// Runs `on_change` with the current metadata, status and position of the
+    /// player and again on every change, rediscovering players when the
+    /// current one goes away. Returns once `tx` is closed.
+    async fn watch<F>(&self, tx: &Sender<String>, periodic: bool, on_change: F) -> Result<(), Error>
+    where
+        F: Fn(&HashMap<String, OwnedValue>, &str, i64) -> Option<Value>,
+    {
+        while !tx.is_closed() {
+            let result: Result<(), Error> = async {
+                let connection = self.connection().await?;
+                let player = self.player(&connection).await?;
+                let name = player.inner().destination().to_string();
+
+                let mut metadata_changes = player.receive_metadata_changed().await;
+                let mut status_changes = player.receive_playback_status_changed().await;
+                let mut seeks = player.receive_seeked().await?;
+                let mut owner_changes = DBusProxy::new(&connection)
+                    .await?
+                    .receive_name_owner_changed()
+                    .await?;
+                let mut progress = tokio::time::interval(PROGRESS_INTERVAL);
+                // Property streams start with the current value, which would
+                // repeat the message just sent.
+                let mut last = None;
+
+                loop {
+                    let status = player.playback_status().await?;
+                    let metadata = player.metadata().await?;
+                    let position = player.position().await.unwrap_or(0);
+                    if let Some(message) = on_change(&metadata, &status, position) {
+                        let message = message.to_string();
+                        if last.as_ref() != Some(&message) {
+                            tx.send(message.clone()).await?;
+                            last = Some(message);
+                        }
+                    }
+
+                    let playing = status == "Playing";
+                    progress.reset();
+                    tokio::select! {
+                        Some(_) = metadata_changes.next() => {}
+                        Some(_) = status_changes.next() => {}
+                        Some(_) = seeks.next() => {}
+                        _ = progress.tick(), if periodic && playing => {}
+                        Some(change) = owner_changes.next() => {
+                            let args = change.args()?;
+                            if args.name().as_str() == name && args.new_owner().is_none() {
+                                return Err(anyhow!("{} left the bus", name));
+                            }
+                        }
+                        else => return Err(anyhow!("D-Bus connection closed")),
+                    }
+                }
+            }
+            .await;
+
+            if let Err(err) = result {
+                if tx.is_closed() {
+                    break;
+                }
+                eprintln!("MPRIS error: {}", err);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl Player for MprisPlayer {
     async fn play(&self) -> Result<(), Error> {
+        let connection = self.connection().await?;
+        self.player(&connection).await?.play().await?;
         Ok(())
     }
 
     async fn pause(&self) -> Result<(), Error> {
+        let connection = self.connection().await?;
+        self.player(&connection).await?.pause().await?;
         Ok(())
     }
 
     async fn next(&self) -> Result<(), Error> {
+        let connection = self.connection().await?;
+        self.player(&connection).await?.next().await?;
         Ok(())
     }
 
     async fn previous(&self) -> Result<(), Error> {
+        let connection = self.connection().await?;
+        self.player(&connection).await?.previous().await?;
         Ok(())
     }
 
-    async fn seek(&self, _position: u64) -> Result<(), Error> {
+    async fn seek(&self, position: u64) -> Result<(), Error> {
+        let connection = self.connection().await?;
+        let player = self.player(&connection).await?;
+        // Rocksky sends milliseconds, MPRIS expects microseconds.
+        let position = position as i64 * 1000;
+
+        // SetPosition needs the current track id, players without one can
+        // only seek relatively.
+        let metadata = player.metadata().await?;
+        match string(&metadata, "mpris:trackid").filter(|id| id != NO_TRACK) {
+            Some(track_id) => {
+                player
+                    .set_position(&ObjectPath::try_from(track_id.as_str())?, position)
+                    .await?
+            }
+            None => player.seek(position - player.position().await?).await?,
+        }
         Ok(())
     }
 
-    async fn broadcast_now_playing(&self, _tx: Sender<String>) -> Result<(), Error> {
-        Ok(())
+    async fn broadcast_now_playing(&self, tx: Sender<String>) -> Result<(), Error> {
+        self.watch(&tx, true, track_message).await
     }
 
-    async fn broadcast_status(&self, _tx: Sender<String>) -> Result<(), Error> {
-        Ok(())
+    async fn broadcast_status(&self, tx: Sender<String>) -> Result<(), Error> {
+        self.watch(&tx, false, |_, status, _| {
+            Some(json!({ "type": "status", "status": status_code(status) }))
+        })
+        .await
+    }
+}
+
+/// MPRIS bus names in the order they are tried: the ones matching
+/// `preferred` only when set.
+fn candidates<'a>(names: &'a [String], preferred: Option<&str>) -> Vec<&'a str> {
+    names
+        .iter()
+        .filter(|name| name.starts_with(MPRIS_PREFIX))
+        .filter(|name| {
+            preferred.is_none_or(|preferred| {
+                name[MPRIS_PREFIX.len()..]
+                    .to_lowercase()
+                    .contains(&preferred.to_lowercase())
+            })
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+/// Rocksky status codes: 1 playing, 2 paused, 0 stopped.
+fn status_code(status: &str) -> u8 {
+    match status {
+        "Playing" => 1,
+        "Paused" => 2,
+        _ => 0,
+    }
+}
+
+// Some players wrap metadata values in another variant.
+fn unwrap_variant<'a>(value: &'a DBusValue<'a>) -> &'a DBusValue<'a> {
+    match value {
+        DBusValue::Value(inner) => unwrap_variant(inner),
+        value => value,
+    }
+}
+
+fn string(metadata: &HashMap<String, OwnedValue>, key: &str) -> Option<String> {
+    match unwrap_variant(metadata.get(key)?) {
+        DBusValue::Str(s) => Some(s.to_string()),
+        DBusValue::ObjectPath(path) => Some(path.to_string()),
+        _ => None,
+    }
+    .filter(|s| !s.is_empty())
+}
+
+// `xesam:artist` and `xesam:albumArtist` are lists, some players send a
+// single string anyway.
+fn strings(metadata: &HashMap<String, OwnedValue>, key: &str) -> Vec<String> {
+    match metadata.get(key).map(|value| unwrap_variant(value)) {
+        Some(DBusValue::Array(values)) => values
+            .iter()
+            .filter_map(|value| match unwrap_variant(value) {
+                DBusValue::Str(s) if !s.is_empty() => Some(s.to_string()),
+                _ => None,
+            })
+            .collect(),
+        Some(DBusValue::Str(s)) if !s.is_empty() => vec![s.to_string()],
+        _ => vec![],
+    }
+}
+
+fn integer(metadata: &HashMap<String, OwnedValue>, key: &str) -> Option<i64> {
+    match unwrap_variant(metadata.get(key)?) {
+        DBusValue::I64(n) => Some(*n),
+        DBusValue::U64(n) => i64::try_from(*n).ok(),
+        DBusValue::I32(n) => Some(*n as i64),
+        DBusValue::U32(n) => Some(*n as i64),
+        DBusValue::F64(n) => Some(*n as i64),
+        _ => None,
+    }
+}
+
+/// The now playing message from the player's metadata, status and position
+/// (in microseconds), `None` when nothing is playing.
+fn track_message(
+    metadata: &HashMap<String, OwnedValue>,
+    status: &str,
+    position: i64,
+) -> Option<Value> {
+    if status == "Stopped" {
+        return None;
+    }
+    let title = string(metadata, "xesam:title")?;
+
+    let artists = strings(metadata, "xesam:artist");
+    let artist = if artists.is_empty() {
+        "Unknown Artist".to_string()
+    } else {
+        artists.join(", ")
+    };
+    let album_artists = strings(metadata, "xesam:albumArtist");
+    let album_artist = if album_artists.is_empty() {
+        artist.clone()
+    } else {
+        album_artists.join(", ")
+    };
+
+    let mut message = json!({
+        "type": "track",
+        "title": title,
+        "artist": artist,
+        "album_artist": album_artist,
+        "album": string(metadata, "xesam:album").unwrap_or_else(|| "Unknown Album".into()),
+        "length": integer(metadata, "mpris:length").unwrap_or(0).max(0) / 1000,
+        "elapsed": position.max(0) / 1000,
+        "is_playing": status == "Playing",
+    });
+
+    // Local files (file://) mean nothing to Rocksky.
+    if let Some(art) = string(metadata, "mpris:artUrl").filter(|url| url.starts_with("http")) {
+        message["album_art"] = json!(art);
+    }
+
+    Some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader},
+        process::{Child, Command, Stdio},
+        sync::{Arc, Mutex},
+    };
+    use zbus::object_server::SignalEmitter;
+
+    /// A private session bus, killed on drop.
+    struct Bus {
+        daemon: Child,
+        address: String,
+    }
+
+    impl Drop for Bus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+        }
+    }
+
+    fn start_bus() -> Bus {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("dbus-daemon must be installed to run the D-Bus tests");
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap())
+            .read_line(&mut address)
+            .unwrap();
+        Bus {
+            daemon,
+            address: address.trim().to_string(),
+        }
+    }
+
+    struct FakePlayer {
+        status: String,
+        commands: Arc<Mutex<Vec<String>>>,
+    }
+
+    fn owned(value: DBusValue<'_>) -> OwnedValue {
+        value.try_to_owned().unwrap()
+    }
+
+    fn metadata() -> HashMap<String, OwnedValue> {
+        HashMap::from([
+            (
+                "mpris:trackid".to_string(),
+                owned(ObjectPath::try_from("/org/rocksky/track/1").unwrap().into()),
+            ),
+            ("xesam:title".to_string(), owned("Song".into())),
+            ("xesam:artist".to_string(), owned(vec!["Artist"].into())),
+            ("xesam:album".to_string(), owned("Album".into())),
+            ("mpris:length".to_string(), owned(201_000_000i64.into())),
+            (
+                "mpris:artUrl".to_string(),
+                owned("https://cdn.rocksky.app/cover.jpg".into()),
+            ),
+        ])
+    }
+
+    #[zbus::interface(name = "org.mpris.MediaPlayer2.Player")]
+    impl FakePlayer {
+        fn play(&self) {
+            self.commands.lock().unwrap().push("Play".into());
+        }
+
+        fn pause(&self) {
+            self.commands.lock().unwrap().push("Pause".into());
+        }
+
+        fn next(&self) {
+            self.commands.lock().unwrap().push("Next".into());
+        }
+
+        fn previous(&self) {
+            self.commands.lock().unwrap().push("Previous".into());
+        }
+
+        fn seek(&self, offset: i64) {
+            self.commands
+                .lock()
+                .unwrap()
+                .push(format!("Seek {}", offset));
+        }
+
+        fn set_position(&self, track_id: ObjectPath<'_>, position: i64) {
+            self.commands
+                .lock()
+                .unwrap()
+                .push(format!("SetPosition {} {}", track_id, position));
+        }
+
+        #[zbus(property)]
+        fn playback_status(&self) -> String {
+            self.status.clone()
+        }
+
+        #[zbus(property)]
+        fn metadata(&self) -> HashMap<String, OwnedValue> {
+            metadata()
+        }
+
+        #[zbus(property(emits_changed_signal = "false"))]
+        fn position(&self) -> i64 {
+            12_345_000
+        }
+    }
+
+    async fn fake_player(bus: &Bus) -> (Connection, Arc<Mutex<Vec<String>>>) {
+        let commands = Arc::new(Mutex::new(vec![]));
+        let connection = connection::Builder::address(bus.address.as_str())
+            .unwrap()
+            .name("org.mpris.MediaPlayer2.fake")
+            .unwrap()
+            .serve_at(
+                "/org/mpris/MediaPlayer2",
+                FakePlayer {
+                    status: "Playing".into(),
+                    commands: commands.clone(),
+                },
+            )
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        (connection, commands)
+    }
+
+    fn player(bus: &Bus) -> MprisPlayer {
+        MprisPlayer {
+            address: Some(bus.address.clone()),
+            preferred: None,
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs dbus-daemon"]
+    async fn test_commands() {
+        let bus = start_bus();
+        let (_connection, commands) = fake_player(&bus).await;
+        let player = player(&bus);
+
+        player.play().await.unwrap();
+        player.pause().await.unwrap();
+        player.next().await.unwrap();
+        player.previous().await.unwrap();
+        player.seek(90_500).await.unwrap();
+
+        assert_eq!(
+            *commands.lock().unwrap(),
+            vec![
+                "Play",
+                "Pause",
+                "Next",
+                "Previous",
+                "SetPosition /org/rocksky/track/1 90500000"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs dbus-daemon"]
+    async fn test_broadcast_now_playing() {
+        let bus = start_bus();
+        let (_connection, _) = fake_player(&bus).await;
+        let player = player(&bus);
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let task = tokio::spawn(async move { player.broadcast_now_playing(tx).await });
+
+        let message: Value = serde_json::from_str(&rx.recv().await.unwrap()).unwrap();
+        assert_eq!(
+            message,
+            json!({
+                "type": "track",
+                "title": "Song",
+                "artist": "Artist",
+                "album_artist": "Artist",
+                "album": "Album",
+                "length": 201_000,
+                "elapsed": 12_345,
+                "is_playing": true,
+                "album_art": "https://cdn.rocksky.app/cover.jpg",
+            })
+        );
+        task.abort();
+    }
+
+    #[tokio::test]
+    #[ignore = "needs dbus-daemon"]
+    async fn test_broadcast_status_changes() {
+        let bus = start_bus();
+        let (connection, _) = fake_player(&bus).await;
+        let player = player(&bus);
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let task = tokio::spawn(async move { player.broadcast_status(tx).await });
+
+        let message: Value = serde_json::from_str(&rx.recv().await.unwrap()).unwrap();
+        assert_eq!(message, json!({ "type": "status", "status": 1 }));
+
+        let iface = connection
+            .object_server()
+            .interface::<_, FakePlayer>("/org/mpris/MediaPlayer2")
+            .await
+            .unwrap();
+        iface.get_mut().await.status = "Paused".into();
+        let emitter: &SignalEmitter<'_> = iface.signal_emitter();
+        iface
+            .get()
+            .await
+            .playback_status_changed(emitter)
+            .await
+            .unwrap();
+
+        let message: Value = serde_json::from_str(&rx.recv().await.unwrap()).unwrap();
+        assert_eq!(message, json!({ "type": "status", "status": 2 }));
+        task.abort();
+    }
+
+    #[test]
+    fn test_track_message() {
+        assert_eq!(
+            track_message(&metadata(), "Paused", 12_345_000),
+            Some(json!({
+                "type": "track",
+                "title": "Song",
+                "artist": "Artist",
+                "album_artist": "Artist",
+                "album": "Album",
+                "length": 201_000,
+                "elapsed": 12_345,
+                "is_playing": false,
+                "album_art": "https://cdn.rocksky.app/cover.jpg",
+            }))
+        );
+
+        let metadata = HashMap::from([
+            ("xesam:title".to_string(), owned("Song".into())),
+            ("xesam:artist".to_string(), owned(vec!["A", "B"].into())),
+            (
+                "xesam:albumArtist".to_string(),
+                owned("Various Artists".into()),
+            ),
+            ("mpris:length".to_string(), owned(201_000_000u64.into())),
+            (
+                "mpris:artUrl".to_string(),
+                owned("file:///tmp/cover.jpg".into()),
+            ),
+        ]);
+        assert_eq!(
+            track_message(&metadata, "Playing", -1),
+            Some(json!({
+                "type": "track",
+                "title": "Song",
+                "artist": "A, B",
+                "album_artist": "Various Artists",
+                "album": "Unknown Album",
+                "length": 201_000,
+                "elapsed": 0,
+                "is_playing": true,
+            }))
+        );
+
+        assert_eq!(track_message(&metadata, "Stopped", 0), None);
+        assert_eq!(track_message(&HashMap::new(), "Playing", 0), None);
+    }
+
+    #[test]
+    fn test_candidates() {
+        let names = vec![
+            "org.freedesktop.DBus".to_string(),
+            "org.mpris.MediaPlayer2.spotify".to_string(),
+            "org.mpris.MediaPlayer2.mpv.instance42".to_string(),
+        ];
+        assert_eq!(
+            candidates(&names, None),
+            vec![
+                "org.mpris.MediaPlayer2.spotify",
+                "org.mpris.MediaPlayer2.mpv.instance42"
+            ]
+        );
+        assert_eq!(
+            candidates(&names, Some("MPV")),
+            vec!["org.mpris.MediaPlayer2.mpv.instance42"]
+        );
     }
 }
@@ -1,41 +1,197 @@
+use std::{env, time::Duration};
+
 use super::Player;
 use anyhow::Error;
 use async_trait::async_trait;
+use serde_json::{Value, json};
 use tokio::sync::mpsc::Sender;
 
-pub struct VlcPlayer {}
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+pub struct VlcPlayer {
+    client: reqwest::Client,
+    url: String,
+    password: String,
+}
 
+/// VLC's web interface (`vlc --extraintf http --http-password ...`), at
+/// `VLC_URL` with `VLC_PASSWORD`.
 pub fn new() -> VlcPlayer {
-    VlcPlayer {}
+    let url = env::var("VLC_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    VlcPlayer {
+        client: reqwest::Client::new(),
+        url: url.trim_end_matches('/').to_string(),
+        password: env::var("VLC_PASSWORD").unwrap_or_default(),
+    }
+}
+
+impl VlcPlayer {
+    /// Fetches `status.json`, running `command` first when given.
+    async fn status(&self, command: &[(&str, &str)]) -> Result<Value, Error> {
+        let status = self
+            .client
+            .get(format!("{}/requests/status.json", self.url))
+            // VLC only checks the password.
+            .basic_auth("", Some(&self.password))
+            .query(command)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Value>()
+            .await?;
+        Ok(status)
+    }
+
+    /// Polls the status and sends what `on_change` makes of it. Returns once
+    /// `tx` is closed.
+    async fn poll<F>(&self, tx: &Sender<String>, dedup: bool, on_change: F) -> Result<(), Error>
+    where
+        F: Fn(&Value) -> Option<Value>,
+    {
+        let mut last = None;
+        while !tx.is_closed() {
+            match self.status(&[]).await {
+                Ok(status) => {
+                    if let Some(message) = on_change(&status).map(|m| m.to_string())
+                        && (!dedup || last.as_ref() != Some(&message))
+                    {
+                        tx.send(message.clone()).await?;
+                        last = Some(message);
+                    }
+                }
+                Err(err) => eprintln!("VLC error: {}", err),
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl Player for VlcPlayer {
     async fn play(&self) -> Result<(), Error> {
+        self.status(&[("command", "pl_forceresume")]).await?;
         Ok(())
     }
 
     async fn pause(&self) -> Result<(), Error> {
+        self.status(&[("command", "pl_forcepause")]).await?;
         Ok(())
     }
 
     async fn next(&self) -> Result<(), Error> {
+        self.status(&[("command", "pl_next")]).await?;
         Ok(())
     }
 
     async fn previous(&self) -> Result<(), Error> {
+        self.status(&[("command", "pl_previous")]).await?;
         Ok(())
     }
 
-    async fn seek(&self, _position: u64) -> Result<(), Error> {
+    async fn seek(&self, position: u64) -> Result<(), Error> {
+        // Rocksky sends milliseconds, VLC expects seconds.
+        let seconds = (position / 1000).to_string();
+        self.status(&[("command", "seek"), ("val", &seconds)])
+            .await?;
         Ok(())
     }
 
-    async fn broadcast_now_playing(&self, _tx: Sender<String>) -> Result<(), Error> {
-        Ok(())
+    async fn broadcast_now_playing(&self, tx: Sender<String>) -> Result<(), Error> {
+        self.poll(&tx, false, track_message).await
     }
 
-    async fn broadcast_status(&self, _tx: Sender<String>) -> Result<(), Error> {
-        Ok(())
+    async fn broadcast_status(&self, tx: Sender<String>) -> Result<(), Error> {
+        self.poll(&tx, true, |status| {
+            Some(json!({
+                "type": "status",
+                "status": status_code(status["state"].as_str()),
+            }))
+        })
+        .await
+    }
+}
+
+/// Rocksky status codes: 1 playing, 2 paused, 0 stopped.
+fn status_code(state: Option<&str>) -> u8 {
+    match state {
+        Some("playing") => 1,
+        Some("paused") => 2,
+        _ => 0,
+    }
+}
+
+/// The now playing message from VLC's `status.json`, `None` when nothing is
+/// playing.
+fn track_message(status: &Value) -> Option<Value> {
+    let state = status["state"].as_str();
+    if state == Some("stopped") {
+        return None;
+    }
+
+    let meta = &status["information"]["category"]["meta"];
+    let text = |key: &str| meta[key].as_str().filter(|s| !s.is_empty());
+    // Streams and untagged files only have a file name.
+    let title = text("title").or_else(|| text("filename"))?;
+    let artist = text("artist").unwrap_or("Unknown Artist");
+
+    let mut message = json!({
+        "type": "track",
+        "title": title,
+        "artist": artist,
+        "album_artist": text("album_artist")
+            .or_else(|| text("ALBUMARTIST"))
+            .unwrap_or(artist),
+        "album": text("album").unwrap_or("Unknown Album"),
+        "length": status["length"].as_u64().unwrap_or(0) * 1000,
+        "elapsed": status["time"].as_u64().unwrap_or(0) * 1000,
+        "is_playing": state == Some("playing"),
+    });
+
+    // Local files (file://) mean nothing to Rocksky.
+    if let Some(art) = text("artwork_url").filter(|url| url.starts_with("http")) {
+        message["album_art"] = json!(art);
+    }
+
+    Some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_message() {
+        let status = json!({
+            "state": "paused",
+            "time": 12,
+            "length": 201,
+            "information": {
+                "category": {
+                    "meta": {
+                        "title": "Song",
+                        "artist": "Artist",
+                        "album": "Album",
+                        "filename": "01 Song.flac",
+                        "artwork_url": "file:///tmp/art.jpg",
+                    }
+                }
+            }
+        });
+        assert_eq!(
+            track_message(&status),
+            Some(json!({
+                "type": "track",
+                "title": "Song",
+                "artist": "Artist",
+                "album_artist": "Artist",
+                "album": "Album",
+                "length": 201_000,
+                "elapsed": 12_000,
+                "is_playing": false,
+            }))
+        );
+
+        assert_eq!(track_message(&json!({ "state": "stopped" })), None);
     }
 }
@@ -86,10 +86,54 @@ impl DropboxClient {
         Ok(res.json::<EntryList>().await?)
     }
 
+    /// Lists everything below `path`. Deleted entries are left out, there is
+    /// nothing to remove yet: handing the cursor of the last page to
+    /// `list_folder_continue` gets what changed since, deletions included.
+    /// Returns `None` when `path` doesn't exist.
+    pub async fn list_folder_recursive(&self, path: &str) -> Result<Option<EntryList>, Error> {
+        let client = Client::new();
+        let res = client
+            .post(format!("{}/files/list_folder", BASE_URL))
+            .bearer_auth(&self.access_token)
+            .json(&json!({
+              "path": path,
+              "recursive": true,
+              "include_deleted": false,
+              "include_mounted_folders": true,
+              "include_non_downloadable_files": false,
+            }))
+            .send()
+            .await?;
+
+        if res.status().as_u16() == 409 {
+            return Ok(None);
+        }
+
+        Ok(Some(res.error_for_status()?.json::<EntryList>().await?))
+    }
+
+    /// Returns `None` when Dropbox reset the cursor, the folder must then be
+    /// listed again from scratch.
+    pub async fn list_folder_continue(&self, cursor: &str) -> Result<Option<EntryList>, Error> {
+        let client = Client::new();
+        let res = client
+            .post(format!("{}/files/list_folder/continue", BASE_URL))
+            .bearer_auth(&self.access_token)
+            .json(&json!({ "cursor": cursor }))
+            .send()
+            .await?;
+
+        if res.status().as_u16() == 409 {
+            return Ok(None);
+        }
+
+        Ok(Some(res.error_for_status()?.json::<EntryList>().await?))
+    }
+
     pub async fn download_file(&self, path: &str) -> Result<HttpResponse, Error> {
         let client = Client::new();
         let res = client
-            .post(&format!("{}/files/download", CONTENT_URL))
+            .post(format!("{}/files/download", CONTENT_URL))
             .bearer_auth(&self.access_token)
             .header("Dropbox-API-Arg", &json!({ "path": path }).to_string())
             .send()
@@ -0,0 +1,34 @@
+use sqlx::{Pool, Postgres};
+
+pub async fn get_list_folder_cursor(
+    pool: &Pool<Postgres>,
+    dropbox_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    let cursor: Option<Option<String>> =
+        sqlx::query_scalar("SELECT list_folder_cursor FROM dropbox WHERE xata_id = $1")
+            .bind(dropbox_id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(cursor.flatten())
+}
+
+pub async fn save_list_folder_cursor(
+    pool: &Pool<Postgres>,
+    dropbox_id: &str,
+    cursor: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+    UPDATE dropbox
+    SET list_folder_cursor = $2,
+        xata_updatedat = NOW()
+    WHERE xata_id = $1
+    "#,
+    )
+    .bind(dropbox_id)
+    .bind(cursor)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use crate::{types::file::Entry, xata::dropbox_diretory::DropboxDirectory};
+use rocksky_media::path::descendants_pattern;
 use sqlx::{Pool, Postgres};
 
 pub async fn create_dropbox_directory(
@@ -46,3 +47,25 @@ pub async fn create_dropbox_directory(
 
     Ok(())
 }
+
+/// Removes the folder at `path_lower` and every folder below it.
+pub async fn delete_dropbox_directories(
+    pool: &Pool<Postgres>,
+    dropbox_id: &str,
+    path_lower: &str,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+      DELETE FROM dropbox_directories
+      WHERE dropbox_id = $1
+        AND (lower(path) = $2 OR lower(path) LIKE $3)
+      "#,
+    )
+    .bind(dropbox_id)
+    .bind(path_lower)
+    .bind(descendants_pattern(path_lower))
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
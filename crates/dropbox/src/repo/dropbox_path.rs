@@ -1,3 +1,4 @@
+use rocksky_media::path::descendants_pattern;
 use sqlx::{Pool, Postgres};
 
 use crate::types::file::Entry;
@@ -30,11 +31,28 @@ pub async fn create_dropbox_path(
         None => None,
     };
 
+    // file_id survives renames and moves, the row follows the file.
     sqlx::query(
         r#"
-    INSERT INTO dropbox_paths (dropbox_id, path, file_id, track_id, name, directory_id)
-    VALUES ($1, $2, $3, $4, $5, $6)
-    ON CONFLICT DO NOTHING
+    INSERT INTO dropbox_paths (
+      dropbox_id,
+      path,
+      file_id,
+      track_id,
+      name,
+      directory_id,
+      content_hash,
+      rev
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+    ON CONFLICT (file_id) DO UPDATE SET
+      path = EXCLUDED.path,
+      track_id = EXCLUDED.track_id,
+      name = EXCLUDED.name,
+      directory_id = EXCLUDED.directory_id,
+      content_hash = EXCLUDED.content_hash,
+      rev = EXCLUDED.rev,
+      xata_updatedat = NOW()
   "#,
     )
     .bind(dropbox_id)
@@ -43,20 +61,79 @@ pub async fn create_dropbox_path(
     .bind(&track.xata_id)
     .bind(&file.name)
     .bind(&parent_dir)
+    .bind(&file.content_hash)
+    .bind(&file.rev)
     .execute(pool)
     .await?;
 
+    Ok(())
+}
+
+/// Path, content hash and rev recorded for a file at the last scan.
+pub async fn find_dropbox_path(
+    pool: &Pool<Postgres>,
+    file_id: &str,
+) -> Result<Option<(String, Option<String>, Option<String>)>, sqlx::Error> {
+    sqlx::query_as("SELECT path, content_hash, rev FROM dropbox_paths WHERE file_id = $1")
+        .bind(file_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Records the new location of a file which was renamed or moved without
+/// its content changing.
+pub async fn move_dropbox_path(
+    pool: &Pool<Postgres>,
+    file: &Entry,
+    dropbox_id: &str,
+    parent_path: Option<String>,
+) -> Result<(), sqlx::Error> {
     sqlx::query(
         r#"
-        UPDATE dropbox_paths
-        SET directory_id = $1
-        WHERE file_id = $2
-        "#,
+    UPDATE dropbox_paths
+    SET path = $2,
+        name = $3,
+        rev = $4,
+        directory_id = (
+          SELECT xata_id FROM dropbox_directories
+          WHERE dropbox_id = $5 AND path = $6
+          LIMIT 1
+        ),
+        xata_updatedat = NOW()
+    WHERE file_id = $1
+    "#,
     )
-    .bind(&parent_dir)
     .bind(&file.id)
+    .bind(&file.path_display)
+    .bind(&file.name)
+    .bind(&file.rev)
+    .bind(dropbox_id)
+    .bind(parent_path)
     .execute(pool)
     .await?;
 
     Ok(())
 }
+
+/// Removes the file at `path_lower`, or every file below it when it was a
+/// folder, from the library.
+pub async fn delete_dropbox_paths(
+    pool: &Pool<Postgres>,
+    dropbox_id: &str,
+    path_lower: &str,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+    DELETE FROM dropbox_paths
+    WHERE dropbox_id = $1
+      AND (lower(path) = $2 OR lower(path) LIKE $3)
+    "#,
+    )
+    .bind(dropbox_id)
+    .bind(path_lower)
+    .bind(descendants_pattern(path_lower))
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
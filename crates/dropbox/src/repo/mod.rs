@@ -1,3 +1,4 @@
+pub mod dropbox;
 pub mod dropbox_directory;
 pub mod dropbox_path;
 pub mod dropbox_token;
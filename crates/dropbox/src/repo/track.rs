@@ -1,6 +1,8 @@
 use anyhow::Error;
 use sqlx::{Pool, Postgres};
 
+use rocksky_media::lyrics::Lyrics;
+
 use crate::xata::track::Track;
 
 pub async fn get_track_by_hash(
@@ -22,3 +24,32 @@ pub async fn get_track_by_hash(
 
     Ok(Some(results[0].clone()))
 }
+
+/// Stores the lyrics found in a scanned file, without overwriting lyrics
+/// the track already has.
+pub async fn update_track_lyrics(
+    pool: &Pool<Postgres>,
+    track_id: &str,
+    lyrics: &Lyrics,
+) -> Result<(), Error> {
+    if lyrics.plain.is_none() && lyrics.synced.is_none() {
+        return Ok(());
+    }
+
+    sqlx::query(
+        r#"
+    UPDATE tracks
+    SET lyrics = COALESCE(lyrics, $2),
+        synced_lyrics = COALESCE(synced_lyrics, $3),
+        xata_updatedat = NOW()
+    WHERE xata_id = $1
+    "#,
+    )
+    .bind(track_id)
+    .bind(&lyrics.plain)
+    .bind(&lyrics.synced)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
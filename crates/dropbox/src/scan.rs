@@ -1,31 +1,30 @@
-use std::{env, fs::File, io::Write, path::Path, sync::Arc};
+use std::{env, path::Path, sync::Arc};
 
-use anyhow::Error;
+use anyhow::{anyhow, Error};
 use futures::future::BoxFuture;
-use lofty::{
-    file::TaggedFileExt,
-    picture::{MimeType, Picture},
-    probe::Probe,
-    tag::Accessor,
-};
+use lofty::{file::TaggedFileExt, probe::Probe, tag::Accessor};
 use owo_colors::OwoColorize;
-use reqwest::{multipart, Client};
+use reqwest::Client;
+use rocksky_media::{
+    cover::upload_album_cover, duration::get_track_duration, lyrics::read_lyrics,
+    partial::download_for_tags,
+};
 use serde_json::json;
 use sqlx::{Pool, Postgres};
-use symphonia::core::{
-    formats::FormatOptions, io::MediaSourceStream, meta::MetadataOptions, probe::Hint,
-};
 use tempfile::TempDir;
 
 use crate::{
-    client::{get_access_token, BASE_URL, CONTENT_URL},
+    client::{get_access_token, DropboxClient, BASE_URL, CONTENT_URL},
     consts::AUDIO_EXTENSIONS,
     crypto::decrypt_aes_256_ctr,
     repo::{
-        dropbox_directory::create_dropbox_directory,
-        dropbox_path::create_dropbox_path,
+        dropbox::{get_list_folder_cursor, save_list_folder_cursor},
+        dropbox_directory::{create_dropbox_directory, delete_dropbox_directories},
+        dropbox_path::{
+            create_dropbox_path, delete_dropbox_paths, find_dropbox_path, move_dropbox_path,
+        },
         dropbox_token::{find_dropbox_refresh_token, find_dropbox_refresh_tokens},
-        track::get_track_by_hash,
+        track::{get_track_by_hash, update_track_lyrics},
     },
     token::generate_token,
     types::file::{Entry, EntryList},
@@ -38,14 +37,104 @@ pub async fn scan_dropbox(pool: Arc<Pool<Postgres>>) -> Result<(), Error> {
             &token.refresh_token,
             &hex::decode(env::var("SPOTIFY_ENCRYPTION_KEY")?)?,
         )?;
-        scan_audio_files(
-            pool.clone(),
-            "/Music".to_string(),
-            refresh_token,
-            token.did,
-            token.xata_id,
-        )
-        .await?;
+        sync_music_folder(&pool, &refresh_token, &token.did, &token.xata_id).await?;
+    }
+    Ok(())
+}
+
+/// Syncs the Dropbox of the user `did`, returns false when they didn't
+/// link one.
+pub async fn scan_user_dropbox(pool: &Pool<Postgres>, did: &str) -> Result<bool, Error> {
+    let Some((refresh_token, dropbox_id)) = find_dropbox_refresh_token(pool, did).await? else {
+        return Ok(false);
+    };
+    let refresh_token = decrypt_aes_256_ctr(
+        &refresh_token,
+        &hex::decode(env::var("SPOTIFY_ENCRYPTION_KEY")?)?,
+    )?;
+    sync_music_folder(pool, &refresh_token, did, &dropbox_id).await?;
+    Ok(true)
+}
+
+/// Brings the library in line with `/Music`. The first run lists the whole
+/// folder, later runs only fetch what changed since the cursor saved by the
+/// previous one. Once an entry fails to sync the cursor is no longer
+/// advanced, the next run lists that entry again and retries it.
+async fn sync_music_folder(
+    pool: &Pool<Postgres>,
+    refresh_token: &str,
+    did: &str,
+    dropbox_id: &str,
+) -> Result<(), Error> {
+    let mut client = DropboxClient::new(refresh_token).await?;
+
+    let mut page = match get_list_folder_cursor(pool, dropbox_id).await? {
+        Some(cursor) => client.list_folder_continue(&cursor).await?,
+        None => None,
+    };
+    if page.is_none() {
+        tracing::info!(did = %did, "Listing /Music");
+        page = client.list_folder_recursive("/Music").await?;
+    }
+    let Some(mut page) = page else {
+        tracing::error!(path = %"/Music".bright_red(), "Path not found");
+        return Ok(());
+    };
+
+    let mut failed = 0;
+    loop {
+        for entry in &page.entries {
+            if let Err(e) = sync_entry(pool, &client.access_token, entry, did, dropbox_id).await {
+                tracing::error!(path = %entry.path_display.bright_red(), "Error syncing entry: {}", e);
+                failed += 1;
+            }
+        }
+
+        // Entries already synced are skipped cheaply when listed again.
+        if failed == 0 {
+            save_list_folder_cursor(pool, dropbox_id, &page.cursor).await?;
+        }
+
+        if !page.has_more {
+            if failed > 0 {
+                tracing::warn!(did = %did, failed, "Entries failed to sync, retrying them next run");
+            }
+            return Ok(());
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+        // Downloads can outlast the access token, get a fresh one per page.
+        client = DropboxClient::new(refresh_token).await?;
+        page = client
+            .list_folder_continue(&page.cursor)
+            .await?
+            .ok_or_else(|| anyhow!("list_folder cursor reset while paginating"))?;
+    }
+}
+
+async fn sync_entry(
+    pool: &Pool<Postgres>,
+    access_token: &str,
+    entry: &Entry,
+    did: &str,
+    dropbox_id: &str,
+) -> Result<(), Error> {
+    match entry.tag.as_deref() {
+        Some("folder") => {
+            let parent_path = parent_path(&entry.path_display).unwrap_or_default();
+            create_dropbox_directory(pool, entry, dropbox_id, &parent_path).await?;
+        }
+        Some("file") => scan_file(pool, access_token, entry, did, dropbox_id).await?,
+        Some("deleted") => {
+            // Files first, they reference their directory.
+            let files = delete_dropbox_paths(pool, dropbox_id, &entry.path_lower).await?;
+            let folders = delete_dropbox_directories(pool, dropbox_id, &entry.path_lower).await?;
+            if files + folders > 0 {
+                tracing::info!(path = %entry.path_display.bright_yellow(), files, folders, "Removed");
+            }
+        }
+        _ => {}
     }
     Ok(())
 }
@@ -151,290 +240,254 @@ pub fn scan_audio_files(
             return Ok(());
         }
 
-        if !AUDIO_EXTENSIONS
-            .into_iter()
-            .any(|ext| path.ends_with(&format!(".{}", ext)))
-        {
-            return Ok(());
-        }
-
-        let client = Client::new();
+        scan_file(&pool, &access_token, &entry, &did, &dropbox_id).await
+    })
+}
 
-        tracing::info!(path = %path.bright_green(), "Downloading file");
+/// Creates the track of an audio file and links the file to it, unless the
+/// file is still the one recorded by a previous scan.
+async fn scan_file(
+    pool: &Pool<Postgres>,
+    access_token: &str,
+    entry: &Entry,
+    did: &str,
+    dropbox_id: &str,
+) -> Result<(), Error> {
+    let path = &entry.path_display;
+
+    if !AUDIO_EXTENSIONS
+        .into_iter()
+        .any(|ext| path.ends_with(&format!(".{}", ext)))
+    {
+        return Ok(());
+    }
 
-        let res = client
-            .post(&format!("{}/files/download", CONTENT_URL))
-            .bearer_auth(&access_token)
-            .header("Dropbox-API-Arg", &json!({ "path": path }).to_string())
-            .send()
-            .await?;
+    if let Some((known_path, content_hash, rev)) = find_dropbox_path(pool, &entry.id).await? {
+        if is_unchanged(content_hash.as_deref(), rev.as_deref(), entry) {
+            if known_path != *path {
+                tracing::info!(from = %known_path, to = %path.bright_green(), "File moved");
+                move_dropbox_path(pool, entry, dropbox_id, parent_path(path)).await?;
+            }
+            return Ok(());
+        }
+    }
 
-        let bytes = res.bytes().await?;
+    let client = Client::new();
 
-        let temp_dir = TempDir::new()?;
-        let tmppath = temp_dir.path().join(&format!("{}", entry.name));
-        let mut tmpfile = File::create(&tmppath)?;
-        tmpfile.write_all(&bytes)?;
+    tracing::info!(path = %path.bright_green(), "Downloading tags");
+
+    let temp_dir = TempDir::new()?;
+    let tmppath = temp_dir.path().join(&entry.name);
+    download_for_tags(
+        || {
+            client
+                .post(format!("{}/files/download", CONTENT_URL))
+                .bearer_auth(access_token)
+                .header("Dropbox-API-Arg", json!({ "path": path }).to_string())
+        },
+        &tmppath,
+    )
+    .await?;
+
+    tracing::info!(path = %tmppath.clone().display().to_string().bright_green(), "Reading file");
+
+    let tagged_file = match Probe::open(&tmppath)?.read() {
+        Ok(tagged_file) => tagged_file,
+        Err(e) => {
+            tracing::error!(path = %tmppath.clone().display().to_string().bright_red(), "Error reading file: {}", e);
+            return Ok(());
+        }
+    };
 
-        tracing::info!(path = %tmppath.clone().display().to_string().bright_green(), "Reading file");
+    let primary_tag = tagged_file.primary_tag();
+    let tag = match primary_tag {
+        Some(tag) => tag,
+        None => {
+            tracing::error!(path = %tmppath.clone().display().to_string().bright_red(), "No tag found in file");
+            return Ok(());
+        }
+    };
 
-        let tagged_file = match Probe::open(&tmppath)?.read() {
-            Ok(tagged_file) => tagged_file,
-            Err(e) => {
-                tracing::error!(path = %tmppath.clone().display().to_string().bright_red(), "Error reading file: {}", e);
-                return Ok(());
-            }
-        };
+    let pictures = tag.pictures();
 
-        let primary_tag = tagged_file.primary_tag();
-        let tag = match primary_tag {
-            Some(tag) => tag,
-            None => {
-                tracing::error!(path = %tmppath.clone().display().to_string().bright_red(), "No tag found in file");
-                return Ok(());
-            }
-        };
-
-        let pictures = tag.pictures();
-
-        tracing::info!(
-            title = %tag
-                .get_string(&lofty::tag::ItemKey::TrackTitle)
-                .unwrap_or_default(),
-        );
-        tracing::info!(
-            artist = %tag
-                .get_string(&lofty::tag::ItemKey::TrackArtist)
-                .unwrap_or_default(),
-        );
-        tracing::info!(
-            album = %tag
-                .get_string(&lofty::tag::ItemKey::AlbumTitle)
-                .unwrap_or_default(),
-        );
-        tracing::info!(
-            album_artist = %tag
-                .get_string(&lofty::tag::ItemKey::AlbumArtist)
-                .unwrap_or_default(),
-        );
-        tracing::info!(
-            lyrics = %tag
-                .get_string(&lofty::tag::ItemKey::Lyrics)
-                .unwrap_or_default(),
-        );
-        tracing::info!(year = %tag.year().unwrap_or_default());
-        tracing::info!(track_number = %tag.track().unwrap_or_default());
-        tracing::info!(track_total = %tag.track_total().unwrap_or_default());
-        tracing::info!(
-            release_date = %tag
-                .get_string(&lofty::tag::ItemKey::OriginalReleaseDate)
-                .unwrap_or_default(),
-        );
-        tracing::info!(
-            recording_date = %tag
-                .get_string(&lofty::tag::ItemKey::RecordingDate)
-                .unwrap_or_default(),
-        );
-        tracing::info!(
-            copyright_message = %tag
-                .get_string(&lofty::tag::ItemKey::CopyrightMessage)
-                .unwrap_or_default(),
-        );
-        tracing::info!(pictures = ?pictures);
-
-        let title = tag
+    tracing::info!(
+        title = %tag
             .get_string(&lofty::tag::ItemKey::TrackTitle)
-            .unwrap_or_default();
-        let artist = tag
+            .unwrap_or_default(),
+    );
+    tracing::info!(
+        artist = %tag
             .get_string(&lofty::tag::ItemKey::TrackArtist)
-            .unwrap_or_default();
-        let album = tag
+            .unwrap_or_default(),
+    );
+    tracing::info!(
+        album = %tag
             .get_string(&lofty::tag::ItemKey::AlbumTitle)
-            .unwrap_or_default();
-        let album_artist = tag
+            .unwrap_or_default(),
+    );
+    tracing::info!(
+        album_artist = %tag
             .get_string(&lofty::tag::ItemKey::AlbumArtist)
-            .unwrap_or_default();
-
-        let access_token = generate_token(&did)?;
-
-        // check if track exists
-        //
-        // if not, create track
-        // upload album art
-        //
-        // link path to track
-
-        let hash = sha256::digest(format!("{} - {} - {}", title, artist, album).to_lowercase());
-
-        let track = get_track_by_hash(&pool, &hash).await?;
-        let duration = get_track_duration(&tmppath).await?;
-        let albumart_id = md5::compute(&format!("{} - {}", album_artist, album).to_lowercase());
-        let albumart_id = format!("{:x}", albumart_id);
-
-        match track {
-            Some(track) => {
-                tracing::info!(title = %title.bright_green(), "Track exists");
-                let parent_path = Path::new(&path)
-                    .parent()
-                    .map(|p| p.to_string_lossy().to_string());
-                let status =
-                    create_dropbox_path(&pool, &entry, &track, &dropbox_id, parent_path).await;
-                tracing::info!(status = ?status);
-
-                // TODO: publish file metadata to nats
-            }
-            None => {
-                tracing::info!(title = %title.bright_green(), "Creating track");
-                let album_art =
-                    upload_album_cover(albumart_id.into(), pictures, &access_token).await?;
-                let client = Client::new();
-                const URL: &str = "https://api.rocksky.app/tracks";
-                let response = client
-                    .post(URL)
-                    .header("Authorization", format!("Bearer {}", access_token))
-                    .json(&serde_json::json!({
-                        "title": tag.get_string(&lofty::tag::ItemKey::TrackTitle),
-                        "album": tag.get_string(&lofty::tag::ItemKey::AlbumTitle),
-                        "artist": tag.get_string(&lofty::tag::ItemKey::TrackArtist),
-                        "albumArtist": match tag.get_string(&lofty::tag::ItemKey::AlbumArtist) {
-                            Some(album_artist) => Some(album_artist),
-                            None => Some(tag.get_string(&lofty::tag::ItemKey::TrackArtist).unwrap_or_default()),
-                        },
-                        "duration": duration,
-                        "trackNumber": tag.track(),
-                        "releaseDate": tag.get_string(&lofty::tag::ItemKey::OriginalReleaseDate).map(|date| match date.contains("-") {
-                            true => Some(date),
-                            false => None,
-                        }),
-                        "year": tag.year(),
-                        "discNumber": tag.disk().map(|disc| match disc {
-                            0 => Some(1),
-                            _ => Some(disc),
-                        }).unwrap_or(Some(1)),
-                        "composer": tag.get_string(&lofty::tag::ItemKey::Composer),
-                        "albumArt": match album_art{
-                            Some(album_art) => Some(format!("https://cdn.rocksky.app/covers/{}", album_art)),
-                            None => None
-                        },
-                        "lyrics": tag.get_string(&lofty::tag::ItemKey::Lyrics),
-                        "copyrightMessage": tag.get_string(&lofty::tag::ItemKey::CopyrightMessage),
-                    }))
-                    .send()
-                    .await?;
-                tracing::info!(title = title, status = %response.status(), "Track saved");
-                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-
-                let track = get_track_by_hash(&pool, &hash).await?;
-                if let Some(track) = track {
-                    let parent_path = Path::new(&path)
-                        .parent()
-                        .map(|p| p.to_string_lossy().to_string());
-                    create_dropbox_path(&pool, &entry, &track, &dropbox_id, parent_path).await?;
+            .unwrap_or_default(),
+    );
+    let lyrics = read_lyrics(&tmppath, tag);
+    tracing::info!(
+        lyrics = %lyrics.plain.as_deref().unwrap_or_default(),
+        synced = lyrics.synced.is_some(),
+    );
+    tracing::info!(year = %tag.year().unwrap_or_default());
+    tracing::info!(track_number = %tag.track().unwrap_or_default());
+    tracing::info!(track_total = %tag.track_total().unwrap_or_default());
+    tracing::info!(
+        release_date = %tag
+            .get_string(&lofty::tag::ItemKey::OriginalReleaseDate)
+            .unwrap_or_default(),
+    );
+    tracing::info!(
+        recording_date = %tag
+            .get_string(&lofty::tag::ItemKey::RecordingDate)
+            .unwrap_or_default(),
+    );
+    tracing::info!(
+        copyright_message = %tag
+            .get_string(&lofty::tag::ItemKey::CopyrightMessage)
+            .unwrap_or_default(),
+    );
+    tracing::info!(pictures = ?pictures);
+
+    let title = tag
+        .get_string(&lofty::tag::ItemKey::TrackTitle)
+        .unwrap_or_default();
+    let artist = tag
+        .get_string(&lofty::tag::ItemKey::TrackArtist)
+        .unwrap_or_default();
+    let album = tag
+        .get_string(&lofty::tag::ItemKey::AlbumTitle)
+        .unwrap_or_default();
+    let album_artist = tag
+        .get_string(&lofty::tag::ItemKey::AlbumArtist)
+        .unwrap_or_default();
+
+    let access_token = generate_token(did)?;
+
+    // check if track exists
+    //
+    // if not, create track
+    // upload album art
+    //
+    // link path to track
+
+    let hash = sha256::digest(format!("{} - {} - {}", title, artist, album).to_lowercase());
+
+    let track = get_track_by_hash(pool, &hash).await?;
+    let duration = get_track_duration(&tmppath).await?;
+    let albumart_id = md5::compute(&format!("{} - {}", album_artist, album).to_lowercase());
+    let albumart_id = format!("{:x}", albumart_id);
+
+    match track {
+        Some(track) => {
+            tracing::info!(title = %title.bright_green(), "Track exists");
+            update_track_lyrics(pool, &track.xata_id, &lyrics).await?;
+            let status =
+                create_dropbox_path(pool, entry, &track, dropbox_id, parent_path(path)).await;
+            tracing::info!(status = ?status);
+
+            // TODO: publish file metadata to nats
+        }
+        None => {
+            tracing::info!(title = %title.bright_green(), "Creating track");
+            let album_art = upload_album_cover(albumart_id.into(), pictures, &access_token).await?;
+            let client = Client::new();
+            const URL: &str = "https://api.rocksky.app/tracks";
+            let response = client
+                .post(URL)
+                .header("Authorization", format!("Bearer {}", access_token))
+                .json(&serde_json::json!({
+                    "title": tag.get_string(&lofty::tag::ItemKey::TrackTitle),
+                    "album": tag.get_string(&lofty::tag::ItemKey::AlbumTitle),
+                    "artist": tag.get_string(&lofty::tag::ItemKey::TrackArtist),
+                    "albumArtist": match tag.get_string(&lofty::tag::ItemKey::AlbumArtist) {
+                        Some(album_artist) => Some(album_artist),
+                        None => Some(tag.get_string(&lofty::tag::ItemKey::TrackArtist).unwrap_or_default()),
+                    },
+                    "duration": duration,
+                    "trackNumber": tag.track(),
+                    "releaseDate": tag.get_string(&lofty::tag::ItemKey::OriginalReleaseDate).map(|date| match date.contains("-") {
+                        true => Some(date),
+                        false => None,
+                    }),
+                    "year": tag.year(),
+                    "discNumber": tag.disk().map(|disc| match disc {
+                        0 => Some(1),
+                        _ => Some(disc),
+                    }).unwrap_or(Some(1)),
+                    "composer": tag.get_string(&lofty::tag::ItemKey::Composer),
+                    "albumArt": match album_art{
+                        Some(album_art) => Some(format!("https://cdn.rocksky.app/covers/{}", album_art)),
+                        None => None
+                    },
+                    "lyrics": lyrics.plain,
+                    "copyrightMessage": tag.get_string(&lofty::tag::ItemKey::CopyrightMessage),
+                }))
+                .send()
+                .await?;
+            tracing::info!(title = title, status = %response.status(), "Track saved");
+            tokio::time::sleep(std::time::Duration::from_secs(3)).await;
 
-                    // TODO: publish file metadata to nats
+            let track = get_track_by_hash(pool, &hash).await?;
+            if let Some(track) = track {
+                update_track_lyrics(pool, &track.xata_id, &lyrics).await?;
+                create_dropbox_path(pool, entry, &track, dropbox_id, parent_path(path)).await?;
 
-                    return Ok(());
-                }
+                // TODO: publish file metadata to nats
 
-                tracing::error!(title = %title.bright_red(), "Failed to create track");
+                return Ok(());
             }
-        }
-
-        Ok(())
-    })
-}
 
-pub async fn upload_album_cover(
-    name: String,
-    pictures: &[Picture],
-    token: &str,
-) -> Result<Option<String>, Error> {
-    if pictures.is_empty() {
-        return Ok(None);
+            tracing::error!(title = %title.bright_red(), "Failed to create track");
+        }
     }
 
-    let picture = &pictures[0];
-
-    let buffer = match picture.mime_type() {
-        Some(MimeType::Jpeg) => Some(picture.data().to_vec()),
-        Some(MimeType::Png) => Some(picture.data().to_vec()),
-        Some(MimeType::Gif) => Some(picture.data().to_vec()),
-        Some(MimeType::Bmp) => Some(picture.data().to_vec()),
-        Some(MimeType::Tiff) => Some(picture.data().to_vec()),
-        _ => None,
-    };
+    Ok(())
+}
 
-    if buffer.is_none() {
-        return Ok(None);
+/// Whether a file is still the one recorded by a previous scan. Content
+/// hashes survive renames and moves, revs are the fallback for rows scanned
+/// before hashes were stored.
+fn is_unchanged(content_hash: Option<&str>, rev: Option<&str>, entry: &Entry) -> bool {
+    match (content_hash, entry.content_hash.as_deref()) {
+        (Some(known), Some(current)) => known == current,
+        _ => rev.is_some() && rev == entry.rev.as_deref(),
     }
+}
 
-    let buffer = buffer.unwrap();
-
-    let ext = match picture.mime_type() {
-        Some(MimeType::Jpeg) => "jpg",
-        Some(MimeType::Png) => "png",
-        Some(MimeType::Gif) => "gif",
-        Some(MimeType::Bmp) => "bmp",
-        Some(MimeType::Tiff) => "tiff",
-        _ => {
-            return Ok(None);
-        }
-    };
-
-    let name = format!("{}.{}", name, ext);
-
-    let part = multipart::Part::bytes(buffer).file_name(name.clone());
-    let form = multipart::Form::new().part("file", part);
-    let client = Client::new();
-
-    const URL: &str = "https://uploads.rocksky.app";
-
-    let response = client
-        .post(URL)
-        .header("Authorization", format!("Bearer {}", token))
-        .multipart(form)
-        .send()
-        .await?;
-
-    tracing::info!(status = %response.status(), "Cover uploaded");
-
-    Ok(Some(name))
+fn parent_path(path: &str) -> Option<String> {
+    Path::new(path)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
 }
 
-pub async fn get_track_duration(path: &Path) -> Result<u64, Error> {
-    let duration = 0;
-    let media_source =
-        MediaSourceStream::new(Box::new(std::fs::File::open(path)?), Default::default());
-    let mut hint = Hint::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if let Some(extension) = path.extension() {
-        if let Some(extension) = extension.to_str() {
-            hint.with_extension(extension);
+    fn entry(content_hash: Option<&str>, rev: Option<&str>) -> Entry {
+        Entry {
+            tag: Some("file".into()),
+            content_hash: content_hash.map(Into::into),
+            rev: rev.map(Into::into),
+            ..Default::default()
         }
     }
 
-    let meta_opts = MetadataOptions::default();
-    let format_opts = FormatOptions::default();
-
-    let probed = match symphonia::default::get_probe().format(
-        &hint,
-        media_source,
-        &format_opts,
-        &meta_opts,
-    ) {
-        Ok(probed) => probed,
-        Err(e) => {
-            tracing::error!(path = %path.display().to_string().bright_red(), "Error probing file: {}", e);
-            return Ok(duration);
-        }
-    };
-
-    if let Some(track) = probed.format.tracks().first() {
-        if let Some(duration) = track.codec_params.n_frames {
-            if let Some(sample_rate) = track.codec_params.sample_rate {
-                return Ok((duration as f64 / sample_rate as f64) as u64 * 1000);
-            }
-        }
+    #[test]
+    fn test_is_unchanged() {
+        let file = entry(Some("abc"), Some("015f"));
+        assert!(is_unchanged(Some("abc"), Some("0100"), &file));
+        assert!(!is_unchanged(Some("def"), Some("015f"), &file));
+        assert!(is_unchanged(None, Some("015f"), &file));
+        assert!(!is_unchanged(None, Some("0100"), &file));
+        assert!(!is_unchanged(None, None, &file));
+        assert!(!is_unchanged(None, None, &entry(None, None)));
     }
-    Ok(duration)
 }
@@ -20,6 +20,8 @@ pub struct Entry {
     pub content_hash: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_downloadable: Option<bool>,
+    // Deleted entries have no id.
+    #[serde(default)]
     pub id: String,
 }
 
@@ -6,6 +6,7 @@ pub struct Dropbox {
     pub xata_id: String,
     pub dropbox_token_id: String,
     pub user_id: String,
+    pub list_folder_cursor: Option<String>,
     pub xata_version: i32,
     #[serde(with = "chrono::serde::ts_seconds")]
     pub xata_createdat: DateTime<Utc>,
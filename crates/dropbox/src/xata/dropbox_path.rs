@@ -10,6 +10,8 @@ pub struct DropboxPath {
     pub file_id: String,
     pub directory_id: Option<String>,
     pub track_id: String,
+    pub content_hash: Option<String>,
+    pub rev: Option<String>,
     pub xata_version: i32,
     #[serde(with = "chrono::serde::ts_seconds")]
     pub xata_createdat: DateTime<Utc>,
@@ -6,11 +6,12 @@ use reqwest::Client;
 use serde_json::json;
 
 use crate::types::{
-    file::{File, FileList},
+    file::{ChangeList, File, FileList, StartPageToken},
     token::AccessToken,
 };
 
 pub const BASE_URL: &str = "https://www.googleapis.com/drive/v3";
+pub const FILE_FIELDS: &str = "id, name, mimeType, parents, md5Checksum, trashed";
 
 pub async fn get_access_token(refresh_token: &str) -> Result<AccessToken, Error> {
     let client = Client::new();
@@ -141,6 +142,48 @@ impl GoogleDriveClient {
         Ok(res.json::<File>().await?)
     }
 
+    /// Token to pass to `get_changes` to only get what changes from now on.
+    pub async fn get_start_page_token(&self) -> Result<String, Error> {
+        let client = Client::new();
+        let url = format!("{}/changes/startPageToken", BASE_URL);
+        let res = client
+            .get(&url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(res.json::<StartPageToken>().await?.start_page_token)
+    }
+
+    /// A page of the changes made to the drive since `page_token`. The last
+    /// page carries the token to start from next time.
+    pub async fn get_changes(&self, page_token: &str) -> Result<ChangeList, Error> {
+        let client = Client::new();
+        let url = format!("{}/changes", BASE_URL);
+        let res = client
+            .get(&url)
+            .bearer_auth(&self.access_token)
+            .query(&[
+                ("pageToken", page_token),
+                ("pageSize", "1000"),
+                ("includeRemoved", "true"),
+                ("spaces", "drive"),
+                (
+                    "fields",
+                    &format!(
+                        "nextPageToken, newStartPageToken, changes(fileId, removed, file({}))",
+                        FILE_FIELDS
+                    ),
+                ),
+            ])
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(res.json::<ChangeList>().await?)
+    }
+
     pub async fn download_file(&self, file_id: &str) -> Result<HttpResponse, Error> {
         let client = Client::new();
         let url = format!("{}/files/{}", BASE_URL, file_id);
@@ -0,0 +1,34 @@
+use sqlx::{Pool, Postgres};
+
+pub async fn get_start_page_token(
+    pool: &Pool<Postgres>,
+    google_drive_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    let token: Option<Option<String>> =
+        sqlx::query_scalar("SELECT start_page_token FROM google_drive WHERE xata_id = $1")
+            .bind(google_drive_id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(token.flatten())
+}
+
+pub async fn save_start_page_token(
+    pool: &Pool<Postgres>,
+    google_drive_id: &str,
+    page_token: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+    UPDATE google_drive
+    SET start_page_token = $2,
+        xata_updatedat = NOW()
+    WHERE xata_id = $1
+    "#,
+    )
+    .bind(google_drive_id)
+    .bind(page_token)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use crate::{types::file::File, xata::google_drive_directory::GoogleDriveDirectory};
+use rocksky_media::path::descendants_pattern;
 use sqlx::{Pool, Postgres};
 
 pub async fn create_google_drive_directory(
@@ -61,3 +62,113 @@ pub async fn create_google_drive_directory(
 
     Ok(())
 }
+
+pub async fn find_google_drive_directory(
+    pool: &Pool<Postgres>,
+    google_drive_id: &str,
+    file_id: &str,
+) -> Result<Option<GoogleDriveDirectory>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT *
+        FROM google_drive_directories
+        WHERE google_drive_id = $1
+          AND file_id = $2
+        LIMIT 1
+        "#,
+    )
+    .bind(google_drive_id)
+    .bind(file_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Renames `directory` or moves it under `parent`, along with the paths of
+/// the folders below it.
+pub async fn move_google_drive_directory(
+    pool: &Pool<Postgres>,
+    directory: &GoogleDriveDirectory,
+    file: &File,
+    parent: &GoogleDriveDirectory,
+) -> Result<(), sqlx::Error> {
+    let path = format!("{}/{}", parent.path.trim_end_matches('/'), file.name);
+    if path == directory.path && directory.parent_id.as_deref() == Some(parent.xata_id.as_str()) {
+        return Ok(());
+    }
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        UPDATE google_drive_directories
+        SET name = $2,
+            path = $3,
+            parent_id = $4,
+            xata_updatedat = NOW()
+        WHERE xata_id = $1
+        "#,
+    )
+    .bind(&directory.xata_id)
+    .bind(&file.name)
+    .bind(&path)
+    .bind(&parent.xata_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE google_drive_directories
+        SET path = $3 || substr(path, length($2) + 1),
+            xata_updatedat = NOW()
+        WHERE google_drive_id = $1
+          AND path LIKE $4
+        "#,
+    )
+    .bind(&directory.google_drive_id)
+    .bind(&directory.path)
+    .bind(&path)
+    .bind(descendants_pattern(&directory.path))
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Removes `directory`, the folders below it and the files they contain.
+/// Returns the number of files removed.
+pub async fn delete_google_drive_directory(
+    pool: &Pool<Postgres>,
+    directory: &GoogleDriveDirectory,
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let ids: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT xata_id
+        FROM google_drive_directories
+        WHERE google_drive_id = $1
+          AND (xata_id = $2 OR path LIKE $3)
+        "#,
+    )
+    .bind(&directory.google_drive_id)
+    .bind(&directory.xata_id)
+    .bind(descendants_pattern(&directory.path))
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let files = sqlx::query("DELETE FROM google_drive_paths WHERE directory_id = ANY($1)")
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM google_drive_directories WHERE xata_id = ANY($1)")
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(files.rows_affected())
+}
@@ -33,11 +33,25 @@ pub async fn create_google_drive_path(
         parent_dirs.first().map(|d| d.xata_id.clone())
     };
 
+    // A file modified in place is linked again to the track read from its
+    // new tags.
     let result = sqlx::query(
         r#"
-    INSERT INTO google_drive_paths (google_drive_id, file_id, track_id, name, directory_id)
-    VALUES ($1, $2, $3, $4, $5)
-    ON CONFLICT DO NOTHING
+    INSERT INTO google_drive_paths (
+      google_drive_id,
+      file_id,
+      track_id,
+      name,
+      directory_id,
+      md5_checksum
+    )
+    VALUES ($1, $2, $3, $4, $5, $6)
+    ON CONFLICT (file_id) DO UPDATE SET
+      track_id = EXCLUDED.track_id,
+      name = EXCLUDED.name,
+      directory_id = EXCLUDED.directory_id,
+      md5_checksum = EXCLUDED.md5_checksum,
+      xata_updatedat = NOW()
   "#,
     )
     .bind(google_drive_id)
@@ -45,6 +59,7 @@ pub async fn create_google_drive_path(
     .bind(&track.xata_id)
     .bind(&file.name)
     .bind(&parent_dir)
+    .bind(&file.md5_checksum)
     .execute(pool)
     .await?;
 
@@ -54,17 +69,68 @@ pub async fn create_google_drive_path(
         "Google Drive path created"
     );
 
+    Ok(())
+}
+
+/// The checksum recorded for a file at the last scan, `None` when the file
+/// isn't in the library.
+pub async fn find_google_drive_path(
+    pool: &Pool<Postgres>,
+    google_drive_id: &str,
+    file_id: &str,
+) -> Result<Option<Option<String>>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+    SELECT md5_checksum
+    FROM google_drive_paths
+    WHERE google_drive_id = $1
+      AND file_id = $2
+    "#,
+    )
+    .bind(google_drive_id)
+    .bind(file_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Records the new name and folder of a file whose content didn't change.
+pub async fn move_google_drive_path(
+    pool: &Pool<Postgres>,
+    file: &File,
+    google_drive_id: &str,
+    directory_id: &str,
+) -> Result<(), sqlx::Error> {
     sqlx::query(
         r#"
-        UPDATE google_drive_paths
-        SET directory_id = $1
-        WHERE file_id = $2
-        "#,
+    UPDATE google_drive_paths
+    SET name = $3,
+        directory_id = $4,
+        xata_updatedat = NOW()
+    WHERE google_drive_id = $1
+      AND file_id = $2
+    "#,
     )
-    .bind(&parent_dir)
+    .bind(google_drive_id)
     .bind(&file.id)
+    .bind(&file.name)
+    .bind(directory_id)
     .execute(pool)
     .await?;
 
     Ok(())
 }
+
+pub async fn delete_google_drive_path(
+    pool: &Pool<Postgres>,
+    google_drive_id: &str,
+    file_id: &str,
+) -> Result<u64, sqlx::Error> {
+    let result =
+        sqlx::query("DELETE FROM google_drive_paths WHERE google_drive_id = $1 AND file_id = $2")
+            .bind(google_drive_id)
+            .bind(file_id)
+            .execute(pool)
+            .await?;
+
+    Ok(result.rows_affected())
+}
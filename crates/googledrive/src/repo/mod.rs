@@ -1,3 +1,4 @@
+pub mod google_drive;
 pub mod google_drive_directory;
 pub mod google_drive_path;
 pub mod google_drive_token;
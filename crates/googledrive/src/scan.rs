@@ -1,35 +1,40 @@
-use std::{env, io::Write, path::Path, sync::Arc};
+use std::{env, sync::Arc};
 
-use anyhow::Error;
+use anyhow::{anyhow, Error};
 use futures::future::BoxFuture;
-use lofty::{
-    file::TaggedFileExt,
-    picture::{MimeType, Picture},
-    probe::Probe,
-    tag::Accessor,
-};
+use lofty::{file::TaggedFileExt, probe::Probe, tag::Accessor};
 use owo_colors::OwoColorize;
-use reqwest::{multipart, Client};
-use sqlx::{Pool, Postgres};
-use symphonia::core::{
-    formats::FormatOptions, io::MediaSourceStream, meta::MetadataOptions, probe::Hint,
+use reqwest::Client;
+use rocksky_media::{
+    cover::upload_album_cover, duration::get_track_duration, lyrics::read_lyrics,
+    partial::download_for_tags,
 };
+use sqlx::{Pool, Postgres};
 use tempfile::TempDir;
 
 use crate::{
-    client::{GoogleDriveClient, BASE_URL},
+    client::{GoogleDriveClient, BASE_URL, FILE_FIELDS},
     consts::AUDIO_EXTENSIONS,
     crypto::decrypt_aes_256_ctr,
     repo::{
-        google_drive_directory::create_google_drive_directory,
-        google_drive_path::create_google_drive_path,
+        google_drive::{get_start_page_token, save_start_page_token},
+        google_drive_directory::{
+            create_google_drive_directory, delete_google_drive_directory,
+            find_google_drive_directory, move_google_drive_directory,
+        },
+        google_drive_path::{
+            create_google_drive_path, delete_google_drive_path, find_google_drive_path,
+            move_google_drive_path,
+        },
         google_drive_token::{find_google_drive_refresh_token, find_google_drive_refresh_tokens},
-        track::get_track_by_hash,
+        track::{get_track_by_hash, update_track_lyrics},
     },
     token::generate_token,
-    types::file::{File, FileList},
+    types::file::{Change, File, FileList},
 };
 
+const FOLDER_MIME_TYPE: &str = "application/vnd.google-apps.folder";
+
 pub async fn scan_googledrive(pool: Arc<Pool<Postgres>>) -> Result<(), Error> {
     let refresh_tokens = find_google_drive_refresh_tokens(&pool).await?;
     for token in refresh_tokens {
@@ -38,22 +43,221 @@ pub async fn scan_googledrive(pool: Arc<Pool<Postgres>>) -> Result<(), Error> {
             &hex::decode(env::var("SPOTIFY_ENCRYPTION_KEY")?)?,
         )?;
 
+        sync_googledrive(pool.clone(), refresh_token, token.did, token.xata_id).await?;
+    }
+    Ok(())
+}
+
+/// Syncs the Google Drive of the user `did`, returns false when they didn't
+/// link one or it has no Music folder.
+pub async fn scan_user_googledrive(pool: Arc<Pool<Postgres>>, did: &str) -> Result<bool, Error> {
+    let Some((refresh_token, google_drive_id)) =
+        find_google_drive_refresh_token(&pool, did).await?
+    else {
+        return Ok(false);
+    };
+    let refresh_token = decrypt_aes_256_ctr(
+        &refresh_token,
+        &hex::decode(env::var("SPOTIFY_ENCRYPTION_KEY")?)?,
+    )?;
+    sync_googledrive(pool, refresh_token, did.to_string(), google_drive_id).await
+}
+
+/// Scans the whole Music folder the first time, then only applies the
+/// changes made to the drive since the page token saved by the previous run.
+/// Returns false when the drive has no Music folder to scan.
+async fn sync_googledrive(
+    pool: Arc<Pool<Postgres>>,
+    refresh_token: String,
+    did: String,
+    google_drive_id: String,
+) -> Result<bool, Error> {
+    let Some(mut page_token) = get_start_page_token(&pool, &google_drive_id).await? else {
         let client = GoogleDriveClient::new(&refresh_token).await?;
+        // Taken before the scan, so what changes while it runs is picked up
+        // by the next one.
+        let start_page_token = client.get_start_page_token().await?;
         let filelist = client.get_music_directory().await?;
-        let music_dir = filelist.files.first().unwrap();
+        let Some(music_dir) = filelist.files.first() else {
+            tracing::warn!(did = %did, "No Music folder in Google Drive");
+            return Ok(false);
+        };
         scan_audio_files(
             pool.clone(),
             music_dir.id.clone(),
             refresh_token.clone(),
-            token.did.clone(),
-            token.xata_id.clone(),
+            did.clone(),
+            google_drive_id.clone(),
             None,
         )
         .await?;
+        save_start_page_token(&pool, &google_drive_id, &start_page_token).await?;
+        return Ok(true);
+    };
+
+    let mut failed = 0;
+    loop {
+        let client = GoogleDriveClient::new(&refresh_token).await?;
+        let changes = client.get_changes(&page_token).await?;
+
+        for change in changes.changes {
+            let file_id = change.file_id.clone();
+            if let Err(e) = apply_change(
+                pool.clone(),
+                change,
+                refresh_token.clone(),
+                did.clone(),
+                google_drive_id.clone(),
+            )
+            .await
+            {
+                tracing::error!(file_id = %file_id.bright_red(), error = %e, "Failed to apply change");
+                failed += 1;
+            }
+        }
+
+        let progress = changes_progress(
+            changes.next_page_token,
+            changes.new_start_page_token,
+            failed > 0,
+        )?;
+        if let Some(start_page_token) = &progress.save {
+            save_start_page_token(&pool, &google_drive_id, start_page_token).await?;
+        }
+        match progress.next {
+            Some(next_page_token) => page_token = next_page_token,
+            None => {
+                if failed > 0 {
+                    tracing::warn!(did = %did, failed, "Changes failed to apply, retrying them next run");
+                }
+                return Ok(true);
+            }
+        }
+    }
+}
+
+/// Where a changes.list run stands after applying a page.
+#[derive(Debug, PartialEq)]
+struct ChangesProgress {
+    /// Page to fetch next, `None` once caught up.
+    next: Option<String>,
+    /// Token for the next run to start from.
+    save: Option<String>,
+}
+
+/// Nothing is saved once a change failed to apply: the next run starts from
+/// the last token saved before it, lists the change again and retries it.
+fn changes_progress(
+    next_page_token: Option<String>,
+    new_start_page_token: Option<String>,
+    failed: bool,
+) -> Result<ChangesProgress, Error> {
+    let (next, save) = match (next_page_token, new_start_page_token) {
+        (Some(next_page_token), _) => (Some(next_page_token.clone()), next_page_token),
+        (None, Some(new_start_page_token)) => (None, new_start_page_token),
+        (None, None) => return Err(anyhow!("changes.list returned no page token")),
+    };
+    Ok(ChangesProgress {
+        next,
+        save: Some(save).filter(|_| !failed),
+    })
+}
+
+/// Brings the library in line with a change to a file anywhere in the drive,
+/// only files inside known directories belong to the library.
+async fn apply_change(
+    pool: Arc<Pool<Postgres>>,
+    change: Change,
+    refresh_token: String,
+    did: String,
+    google_drive_id: String,
+) -> Result<(), Error> {
+    let file = match change.file {
+        Some(file) if !change.removed && file.trashed != Some(true) => file,
+        _ => return remove_file(&pool, &google_drive_id, &change.file_id).await,
+    };
+
+    let parent = match file.parents.as_ref().and_then(|parents| parents.first()) {
+        Some(parent_id) => find_google_drive_directory(&pool, &google_drive_id, parent_id).await?,
+        None => None,
+    };
+
+    if file.mime_type == FOLDER_MIME_TYPE {
+        let directory = find_google_drive_directory(&pool, &google_drive_id, &file.id).await?;
+        match (directory, parent) {
+            (Some(directory), Some(parent)) => {
+                move_google_drive_directory(&pool, &directory, &file, &parent).await?;
+            }
+            // The Music folder itself, its parent is the drive root.
+            (Some(directory), None) if directory.parent_id.is_none() => {}
+            (Some(_), None) => {
+                tracing::info!(folder = %file.name.bright_yellow(), "Folder moved out of Music");
+                remove_file(&pool, &google_drive_id, &file.id).await?;
+            }
+            (None, Some(parent)) => {
+                // Moved in with its content, which gets no changes of its own.
+                scan_audio_files(
+                    pool.clone(),
+                    file.id,
+                    refresh_token,
+                    did,
+                    google_drive_id,
+                    Some(parent.file_id),
+                )
+                .await?;
+            }
+            (None, None) => {}
+        }
+        return Ok(());
+    }
+
+    let parent = match parent {
+        Some(parent) if is_audio_file(&file.name) => parent,
+        _ => return remove_file(&pool, &google_drive_id, &file.id).await,
+    };
+
+    match find_google_drive_path(&pool, &google_drive_id, &file.id).await? {
+        Some(Some(md5_checksum)) if file.md5_checksum.as_ref() == Some(&md5_checksum) => {
+            move_google_drive_path(&pool, &file, &google_drive_id, &parent.xata_id).await?;
+        }
+        _ => {
+            scan_audio_files(
+                pool.clone(),
+                file.id,
+                refresh_token,
+                did,
+                google_drive_id,
+                Some(parent.file_id),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes a file, or a folder and everything below it, from the library.
+async fn remove_file(
+    pool: &Pool<Postgres>,
+    google_drive_id: &str,
+    file_id: &str,
+) -> Result<(), Error> {
+    let mut files = delete_google_drive_path(pool, google_drive_id, file_id).await?;
+    if let Some(directory) = find_google_drive_directory(pool, google_drive_id, file_id).await? {
+        files += delete_google_drive_directory(pool, &directory).await?;
+    }
+    if files > 0 {
+        tracing::info!(file_id = %file_id.bright_yellow(), files, "Removed from library");
     }
     Ok(())
 }
 
+fn is_audio_file(name: &str) -> bool {
+    AUDIO_EXTENSIONS
+        .into_iter()
+        .any(|ext| name.ends_with(&format!(".{}", ext)))
+}
+
 pub async fn scan_folder(
     pool: Arc<Pool<Postgres>>,
     did: &str,
@@ -97,13 +301,13 @@ pub fn scan_audio_files(
         let res = client
             .get(&url)
             .bearer_auth(&access_token)
-            .query(&[("fields", "id, name, mimeType, parents")])
+            .query(&[("fields", FILE_FIELDS)])
             .send()
             .await?;
 
         let file = res.json::<File>().await?;
 
-        if file.mime_type == "application/vnd.google-apps.folder" {
+        if file.mime_type == FOLDER_MIME_TYPE {
             tracing::info!(folder = %file.name.bright_green(), "Scanning folder");
 
             create_google_drive_directory(
@@ -165,31 +369,27 @@ pub fn scan_audio_files(
             return Ok(());
         }
 
-        if !AUDIO_EXTENSIONS
-            .into_iter()
-            .any(|ext| file.name.ends_with(&format!(".{}", ext)))
-        {
+        if !is_audio_file(&file.name) {
             return Ok(());
         }
 
-        tracing::info!(file = %file.name.bright_green(), "Downloading file");
+        tracing::info!(file = %file.name.bright_green(), "Downloading tags");
 
         let client = Client::new();
 
         let url = format!("{}/files/{}", BASE_URL, file_id);
-        let res = client
-            .get(&url)
-            .bearer_auth(&access_token)
-            .query(&[("alt", "media")])
-            .send()
-            .await?;
-
-        let bytes = res.bytes().await?;
-
         let temp_dir = TempDir::new()?;
-        let tmppath = temp_dir.path().join(&format!("{}", file.name));
-        let mut tmpfile = std::fs::File::create(&tmppath)?;
-        tmpfile.write_all(&bytes)?;
+        let tmppath = temp_dir.path().join(&file.name);
+        download_for_tags(
+            || {
+                client
+                    .get(&url)
+                    .bearer_auth(&access_token)
+                    .query(&[("alt", "media")])
+            },
+            &tmppath,
+        )
+        .await?;
 
         tracing::info!(path = %tmppath.display(), "Reading file");
 
@@ -216,7 +416,8 @@ pub fn scan_audio_files(
         tracing::info!(artist = %tag.get_string(&lofty::tag::ItemKey::TrackArtist).unwrap_or_default(), "Artist");
         tracing::info!(album_artist = %tag.get_string(&lofty::tag::ItemKey::AlbumArtist).unwrap_or_default(), "Album artist");
         tracing::info!(album = %tag.get_string(&lofty::tag::ItemKey::AlbumTitle).unwrap_or_default(), "Album");
-        tracing::info!(lyrics = %tag.get_string(&lofty::tag::ItemKey::Lyrics).unwrap_or_default(), "Lyrics");
+        let lyrics = read_lyrics(&tmppath, tag);
+        tracing::info!(lyrics = %lyrics.plain.as_deref().unwrap_or_default(), synced = lyrics.synced.is_some(), "Lyrics");
         tracing::info!(year = %tag.year().unwrap_or_default(), "Year");
         tracing::info!(track_number = %tag.track().unwrap_or_default(), "Track number");
         tracing::info!(track_total = %tag.track_total().unwrap_or_default(), "Track total");
@@ -256,6 +457,7 @@ pub fn scan_audio_files(
         match track {
             Some(track) => {
                 tracing::info!(title = %title.bright_green(), "Track exists");
+                update_track_lyrics(&pool, &track.xata_id, &lyrics).await?;
                 let parent_drive_id = parent_drive_file_id.as_deref();
                 create_google_drive_path(
                     &pool,
@@ -303,7 +505,7 @@ pub fn scan_audio_files(
                             Some(albumart) => Some(format!("https://cdn.rocksky.app/covers/{}", albumart)),
                             None => None
                         },
-                        "lyrics": tag.get_string(&lofty::tag::ItemKey::Lyrics),
+                        "lyrics": lyrics.plain,
                         "copyrightMessage": tag.get_string(&lofty::tag::ItemKey::CopyrightMessage),
                     }))
                     .send()
@@ -313,6 +515,7 @@ pub fn scan_audio_files(
 
                 let track = get_track_by_hash(&pool, &hash).await?;
                 if let Some(track) = track {
+                    update_track_lyrics(&pool, &track.xata_id, &lyrics).await?;
                     let parent_drive_id = parent_drive_file_id.as_deref();
                     create_google_drive_path(
                         &pool,
@@ -338,94 +541,59 @@ pub fn scan_audio_files(
     })
 }
 
-pub async fn upload_album_cover(
-    name: String,
-    pictures: &[Picture],
-    token: &str,
-) -> Result<Option<String>, Error> {
-    if pictures.is_empty() {
-        return Ok(None);
-    }
-
-    let picture = &pictures[0];
-
-    let buffer = match picture.mime_type() {
-        Some(MimeType::Jpeg) => Some(picture.data().to_vec()),
-        Some(MimeType::Png) => Some(picture.data().to_vec()),
-        Some(MimeType::Gif) => Some(picture.data().to_vec()),
-        Some(MimeType::Bmp) => Some(picture.data().to_vec()),
-        Some(MimeType::Tiff) => Some(picture.data().to_vec()),
-        _ => None,
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if buffer.is_none() {
-        return Ok(None);
+    fn token(token: &str) -> Option<String> {
+        Some(token.to_string())
     }
 
-    let buffer = buffer.unwrap();
-
-    let ext = match picture.mime_type() {
-        Some(MimeType::Jpeg) => "jpg",
-        Some(MimeType::Png) => "png",
-        Some(MimeType::Gif) => "gif",
-        Some(MimeType::Bmp) => "bmp",
-        Some(MimeType::Tiff) => "tiff",
-        _ => {
-            return Ok(None);
-        }
-    };
-
-    let name = format!("{}.{}", name, ext);
-
-    let part = multipart::Part::bytes(buffer).file_name(name.clone());
-    let form = multipart::Form::new().part("file", part);
-    let client = Client::new();
-
-    const URL: &str = "https://uploads.rocksky.app";
-
-    let response = client
-        .post(URL)
-        .header("Authorization", format!("Bearer {}", token))
-        .multipart(form)
-        .send()
-        .await?;
-
-    tracing::info!(status = %response.status(), "Cover uploaded");
-
-    Ok(Some(name))
-}
-
-pub async fn get_track_duration(path: &Path) -> Result<u64, Error> {
-    let duration = 0;
-    let media_source =
-        MediaSourceStream::new(Box::new(std::fs::File::open(path)?), Default::default());
-    let mut hint = Hint::new();
-
-    if let Some(extension) = path.extension() {
-        if let Some(extension) = extension.to_str() {
-            hint.with_extension(extension);
-        }
+    #[test]
+    fn changes_progress_follows_pages_then_saves_the_new_start() {
+        assert_eq!(
+            changes_progress(token("page-2"), None, false).unwrap(),
+            ChangesProgress {
+                next: token("page-2"),
+                save: token("page-2"),
+            }
+        );
+        assert_eq!(
+            changes_progress(None, token("start-9"), false).unwrap(),
+            ChangesProgress {
+                next: None,
+                save: token("start-9"),
+            }
+        );
+        // Only the last page carries the new start token.
+        assert_eq!(
+            changes_progress(token("page-2"), token("start-9"), false)
+                .unwrap()
+                .next,
+            token("page-2")
+        );
     }
 
-    let meta_opts = MetadataOptions::default();
-    let format_opts = FormatOptions::default();
-
-    let probed =
-        match symphonia::default::get_probe().format(&hint, media_source, &format_opts, &meta_opts)
-        {
-            Ok(probed) => probed,
-            Err(e) => {
-                tracing::warn!(path = %path.display(), error = %e, "Failed to probe media");
-                return Ok(duration);
+    #[test]
+    fn changes_progress_saves_nothing_after_a_failure() {
+        assert_eq!(
+            changes_progress(token("page-2"), None, true).unwrap(),
+            ChangesProgress {
+                next: token("page-2"),
+                save: None,
             }
-        };
-
-    if let Some(track) = probed.format.tracks().first() {
-        if let Some(duration) = track.codec_params.n_frames {
-            if let Some(sample_rate) = track.codec_params.sample_rate {
-                return Ok((duration as f64 / sample_rate as f64) as u64 * 1000);
+        );
+        assert_eq!(
+            changes_progress(None, token("start-9"), true).unwrap(),
+            ChangesProgress {
+                next: None,
+                save: None,
             }
-        }
+        );
+    }
+
+    #[test]
+    fn changes_progress_rejects_a_page_without_token() {
+        assert!(changes_progress(None, None, false).is_err());
     }
-    Ok(duration)
 }
@@ -10,6 +10,10 @@ pub struct File {
     pub parents: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub kind: Option<String>,
+    #[serde(rename = "md5Checksum", skip_serializing_if = "Option::is_none")]
+    pub md5_checksum: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trashed: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,6 +22,31 @@ pub struct FileList {
     pub next_page_token: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StartPageToken {
+    #[serde(rename = "startPageToken")]
+    pub start_page_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Change {
+    #[serde(rename = "fileId")]
+    pub file_id: String,
+    #[serde(default)]
+    pub removed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<File>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangeList {
+    pub changes: Vec<Change>,
+    #[serde(rename = "nextPageToken")]
+    pub next_page_token: Option<String>,
+    #[serde(rename = "newStartPageToken")]
+    pub new_start_page_token: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetFilesParams {
     pub did: String,
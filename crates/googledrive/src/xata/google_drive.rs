@@ -6,6 +6,7 @@ pub struct GoogleDrive {
     pub xata_id: String,
     pub user_id: String,
     pub google_drive_token_id: String,
+    pub start_page_token: Option<String>,
     pub xata_version: i32,
     #[serde(with = "chrono::serde::ts_seconds")]
     pub xata_createdat: DateTime<Utc>,
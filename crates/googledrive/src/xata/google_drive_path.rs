@@ -7,6 +7,7 @@ pub struct GoogleDrivePath {
     pub google_drive_id: String,
     pub track_id: String,
     pub directory_id: Option<String>,
+    pub md5_checksum: Option<String>,
     pub xata_version: i32,
     #[serde(with = "chrono::serde::ts_seconds")]
     pub xata_createdat: DateTime<Utc>,
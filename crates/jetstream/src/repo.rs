@@ -4,7 +4,7 @@ use std::sync::{Arc, OnceLock, RwLock};
 use anyhow::Error;
 use chrono::DateTime;
 use owo_colors::OwoColorize;
-use serde_json::json;
+use serde_json::{json, Value};
 use sqlx::{Pool, Postgres};
 use tokio::sync::Mutex;
 
@@ -22,10 +22,12 @@ fn user_id_cache() -> &'static RwLock<HashMap<String, String>> {
 use crate::{
     profile::did_to_profile,
     subscriber::{
-        ALBUM_NSID, ARTIST_NSID, FEED_GENERATOR_NSID, FOLLOW_NSID, SCROBBLE_NSID, SONG_NSID,
+        ALBUM_NSID, ARTIST_NSID, FEED_GENERATOR_NSID, FOLLOW_NSID, LIKE_NSID, PLAYLIST_ITEM_NSID,
+        PLAYLIST_NSID, SCROBBLE_NSID, SHOUT_NSID, SONG_NSID,
     },
     types::{
-        AlbumRecord, ArtistRecord, Commit, FeedGeneratorRecord, FollowRecord, ScrobbleRecord,
+        AlbumRecord, ArtistRecord, Commit, FeedGeneratorRecord, FollowRecord, LikeRecord,
+        PlaylistItemRecord, PlaylistItemTrack, PlaylistRecord, ScrobbleRecord, ShoutRecord,
         SongRecord,
     },
     webhook::discord::{
@@ -35,8 +37,8 @@ use crate::{
     webhook_worker::{push_to_queue, AppState},
     xata::{
         album::Album, album_track::AlbumTrack, artist::Artist, artist_album::ArtistAlbum,
-        artist_track::ArtistTrack, track::Track, user::User, user_album::UserAlbum,
-        user_artist::UserArtist, user_track::UserTrack,
+        artist_track::ArtistTrack, loved_track::LovedTrack, track::Track, user::User,
+        user_album::UserAlbum, user_artist::UserArtist, user_track::UserTrack,
     },
 };
 
@@ -55,6 +57,10 @@ pub async fn save_scrobble(
         SONG_NSID,
         FEED_GENERATOR_NSID,
         FOLLOW_NSID,
+        PLAYLIST_NSID,
+        PLAYLIST_ITEM_NSID,
+        LIKE_NSID,
+        SHOUT_NSID,
     ]
     .contains(&commit.collection.as_str())
     {
@@ -246,6 +252,154 @@ pub async fn save_scrobble(
                 publish_user(&nc, &pool, &user_id).await?;
                 publish_user(&nc, &pool, &subject_user_id).await?;
             }
+
+            if commit.collection == PLAYLIST_NSID {
+                let playlist_record: PlaylistRecord = serde_json::from_value(record.clone())?;
+                let user_id = save_user(&pool, did).await?;
+                let mut tx = pool.begin().await?;
+                let uri = format!("at://{}/app.rocksky.playlist/{}", did, commit.rkey);
+
+                save_playlist(&mut tx, &user_id, playlist_record, &uri).await?;
+
+                tx.commit().await?;
+                publish_user(&nc, &pool, &user_id).await?;
+            }
+
+            if commit.collection == PLAYLIST_ITEM_NSID {
+                let item_record: PlaylistItemRecord = serde_json::from_value(record.clone())?;
+                let mut tx = pool.begin().await?;
+                let uri = format!("at://{}/app.rocksky.playlistItem/{}", did, commit.rkey);
+
+                save_playlist_item(&mut tx, item_record, &uri).await?;
+
+                tx.commit().await?;
+            }
+
+            if commit.collection == LIKE_NSID {
+                let like_record: LikeRecord = serde_json::from_value(record.clone())?;
+                let user_id = save_user(&pool, did).await?;
+                let mut tx = pool.begin().await?;
+                let uri = format!("at://{}/app.rocksky.like/{}", did, commit.rkey);
+
+                let loved_track = save_like(&mut tx, &user_id, like_record, &uri).await?;
+
+                tx.commit().await?;
+                if let Some(loved_track) = loved_track {
+                    publish_loved_track(&nc, "rocksky.like", &loved_track, &uri).await?;
+                }
+                publish_user(&nc, &pool, &user_id).await?;
+            }
+
+            if commit.collection == SHOUT_NSID {
+                let shout_record: ShoutRecord = serde_json::from_value(record.clone())?;
+                let user_id = save_user(&pool, did).await?;
+                let mut tx = pool.begin().await?;
+                let uri = format!("at://{}/app.rocksky.shout/{}", did, commit.rkey);
+
+                save_shout(&mut tx, &user_id, shout_record, &uri).await?;
+
+                tx.commit().await?;
+                publish_user(&nc, &pool, &user_id).await?;
+            }
+        }
+        "update" => {
+            let record = commit.record.unwrap();
+            let uri = format!("at://{}/{}/{}", did, commit.collection, commit.rkey);
+
+            if commit.collection == PLAYLIST_NSID {
+                let playlist_record: PlaylistRecord = serde_json::from_value(record)?;
+                let user_id = save_user(&pool, did).await?;
+                let mut tx = pool.begin().await?;
+
+                save_playlist(&mut tx, &user_id, playlist_record, &uri).await?;
+
+                tx.commit().await?;
+            } else if commit.collection == PLAYLIST_ITEM_NSID {
+                let item_record: PlaylistItemRecord = serde_json::from_value(record)?;
+                let mut tx = pool.begin().await?;
+
+                save_playlist_item(&mut tx, item_record, &uri).await?;
+
+                tx.commit().await?;
+            } else if commit.collection == LIKE_NSID {
+                // A like has nothing to edit but its subject: drop whatever
+                // the previous version pointed at and index the new one.
+                let like_record: LikeRecord = serde_json::from_value(record)?;
+                let user_id = save_user(&pool, did).await?;
+                let mut tx = pool.begin().await?;
+
+                let unloved_track = delete_like(&mut tx, &uri).await?;
+                let loved_track = save_like(&mut tx, &user_id, like_record, &uri).await?;
+
+                tx.commit().await?;
+                if let Some(unloved_track) = unloved_track {
+                    publish_loved_track(&nc, "rocksky.unlike", &unloved_track, &uri).await?;
+                }
+                if let Some(loved_track) = loved_track {
+                    publish_loved_track(&nc, "rocksky.like", &loved_track, &uri).await?;
+                }
+            } else if commit.collection == SHOUT_NSID {
+                let shout_record: ShoutRecord = serde_json::from_value(record)?;
+                let user_id = save_user(&pool, did).await?;
+                let mut tx = pool.begin().await?;
+
+                save_shout(&mut tx, &user_id, shout_record, &uri).await?;
+
+                tx.commit().await?;
+            } else if commit.collection == SONG_NSID {
+                let song_record: SongRecord = serde_json::from_value(record)?;
+                let user_id = save_user(&pool, did).await?;
+                let mut tx = pool.begin().await?;
+
+                update_user_track(&mut tx, &user_id, song_record, &uri).await?;
+
+                tx.commit().await?;
+                publish_user(&nc, &pool, &user_id).await?;
+            } else if commit.collection == ALBUM_NSID {
+                let album_record: AlbumRecord = serde_json::from_value(record)?;
+                let user_id = save_user(&pool, did).await?;
+                let mut tx = pool.begin().await?;
+
+                update_user_album(&mut tx, &user_id, album_record, &uri).await?;
+
+                tx.commit().await?;
+                publish_user(&nc, &pool, &user_id).await?;
+            } else if commit.collection == ARTIST_NSID {
+                let artist_record: ArtistRecord = serde_json::from_value(record)?;
+                let user_id = save_user(&pool, did).await?;
+                let mut tx = pool.begin().await?;
+
+                update_user_artist(&mut tx, &user_id, artist_record, &uri).await?;
+
+                tx.commit().await?;
+                publish_user(&nc, &pool, &user_id).await?;
+            } else if commit.collection == FEED_GENERATOR_NSID {
+                let feed_generator_record: FeedGeneratorRecord = serde_json::from_value(record)?;
+                let user_id = save_user(&pool, did).await?;
+                let mut tx = pool.begin().await?;
+
+                save_feed_generator(&mut tx, &user_id, feed_generator_record, &uri).await?;
+
+                tx.commit().await?;
+            } else if commit.collection == FOLLOW_NSID {
+                let follow_record: FollowRecord = serde_json::from_value(record)?;
+                let user_id = save_user(&pool, did).await?;
+                let subject_user_id = save_user(&pool, &follow_record.subject).await?;
+                let mut tx = pool.begin().await?;
+
+                let previous_subject = delete_follow(&mut tx, &uri).await?;
+                save_follow(&mut tx, did, follow_record, &uri).await?;
+
+                tx.commit().await?;
+                publish_user(&nc, &pool, &user_id).await?;
+                publish_user(&nc, &pool, &subject_user_id).await?;
+                if let Some(previous_subject) = previous_subject {
+                    let previous_user_id = save_user(&pool, &previous_subject).await?;
+                    publish_user(&nc, &pool, &previous_user_id).await?;
+                }
+            } else {
+                tracing::warn!(operation = %commit.operation, collection = %commit.collection, "Update operation not implemented for this collection");
+            }
         }
         "delete" => {
             if commit.collection == SCROBBLE_NSID {
@@ -260,6 +414,83 @@ pub async fn save_scrobble(
                         tracing::error!(error = %e, operation = %commit.operation, collection = %commit.collection, "Failed to delete scrobble");
                     }
                 }
+            } else if commit.collection == PLAYLIST_NSID {
+                let uri = format!("at://{}/app.rocksky.playlist/{}", did, commit.rkey);
+                let mut tx = pool.begin().await?;
+                delete_playlist(&mut tx, &uri).await?;
+                tx.commit().await?;
+                tracing::info!(operation = %commit.operation, collection = %commit.collection, "Playlist deleted");
+            } else if commit.collection == PLAYLIST_ITEM_NSID {
+                let uri = format!("at://{}/app.rocksky.playlistItem/{}", did, commit.rkey);
+                sqlx::query("DELETE FROM playlist_tracks WHERE uri = $1")
+                    .bind(&uri)
+                    .execute(&*pool)
+                    .await?;
+                tracing::info!(operation = %commit.operation, collection = %commit.collection, "Playlist item deleted");
+            } else if commit.collection == LIKE_NSID {
+                let uri = format!("at://{}/app.rocksky.like/{}", did, commit.rkey);
+                let mut tx = pool.begin().await?;
+                let unloved_track = delete_like(&mut tx, &uri).await?;
+                tx.commit().await?;
+                if let Some(unloved_track) = unloved_track {
+                    publish_loved_track(&nc, "rocksky.unlike", &unloved_track, &uri).await?;
+                }
+                tracing::info!(operation = %commit.operation, collection = %commit.collection, "Like deleted");
+            } else if commit.collection == SHOUT_NSID {
+                let uri = format!("at://{}/app.rocksky.shout/{}", did, commit.rkey);
+                let mut tx = pool.begin().await?;
+                delete_shout(&mut tx, &uri).await?;
+                tx.commit().await?;
+                tracing::info!(operation = %commit.operation, collection = %commit.collection, "Shout deleted");
+            } else if commit.collection == SONG_NSID {
+                let uri = format!("at://{}/app.rocksky.song/{}", did, commit.rkey);
+                let mut tx = pool.begin().await?;
+                let removed = delete_user_track(&mut tx, &uri).await?;
+                tx.commit().await?;
+                if removed.is_some() {
+                    let user_id = save_user(&pool, did).await?;
+                    publish_user(&nc, &pool, &user_id).await?;
+                }
+                tracing::info!(operation = %commit.operation, collection = %commit.collection, "Song deleted");
+            } else if commit.collection == ALBUM_NSID {
+                let uri = format!("at://{}/app.rocksky.album/{}", did, commit.rkey);
+                let mut tx = pool.begin().await?;
+                let removed = delete_user_album(&mut tx, &uri).await?;
+                tx.commit().await?;
+                if removed.is_some() {
+                    let user_id = save_user(&pool, did).await?;
+                    publish_user(&nc, &pool, &user_id).await?;
+                }
+                tracing::info!(operation = %commit.operation, collection = %commit.collection, "Album deleted");
+            } else if commit.collection == ARTIST_NSID {
+                let uri = format!("at://{}/app.rocksky.artist/{}", did, commit.rkey);
+                let mut tx = pool.begin().await?;
+                let removed = delete_user_artist(&mut tx, &uri).await?;
+                tx.commit().await?;
+                if removed.is_some() {
+                    let user_id = save_user(&pool, did).await?;
+                    publish_user(&nc, &pool, &user_id).await?;
+                }
+                tracing::info!(operation = %commit.operation, collection = %commit.collection, "Artist deleted");
+            } else if commit.collection == FEED_GENERATOR_NSID {
+                let uri = format!("at://{}/app.rocksky.feed.generator/{}", did, commit.rkey);
+                sqlx::query("DELETE FROM feeds WHERE uri = $1")
+                    .bind(&uri)
+                    .execute(&*pool)
+                    .await?;
+                tracing::info!(operation = %commit.operation, collection = %commit.collection, "Feed generator deleted");
+            } else if commit.collection == FOLLOW_NSID {
+                let uri = format!("at://{}/app.rocksky.graph.follow/{}", did, commit.rkey);
+                let mut tx = pool.begin().await?;
+                let subject = delete_follow(&mut tx, &uri).await?;
+                tx.commit().await?;
+                if let Some(subject) = subject {
+                    let user_id = save_user(&pool, did).await?;
+                    let subject_user_id = save_user(&pool, &subject).await?;
+                    publish_user(&nc, &pool, &user_id).await?;
+                    publish_user(&nc, &pool, &subject_user_id).await?;
+                }
+                tracing::info!(operation = %commit.operation, collection = %commit.collection, "Follow deleted");
             } else {
                 tracing::warn!(operation = %commit.operation, collection = %commit.collection, "Delete operation not implemented for this collection");
             }
@@ -1235,6 +1466,12 @@ pub async fn save_feed_generator(
     ) VALUES (
         $1, $2, $3, $4, $5, $6
     )
+    ON CONFLICT (uri) DO UPDATE SET
+        display_name = EXCLUDED.display_name,
+        description = EXCLUDED.description,
+        did = EXCLUDED.did,
+        avatar = EXCLUDED.avatar,
+        xata_updatedat = now()
   "#,
     )
     .bind(user_id)
@@ -1276,10 +1513,1258 @@ pub async fn save_follow(
     Ok(())
 }
 
+/// user_id, track_id, album_id and artist_id of a scrobble.
+type ScrobbleRefs = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
 pub async fn delete_scrobble(pool: &Pool<Postgres>, uri: &str) -> Result<(), Error> {
-    sqlx::query("DELETE FROM scrobbles WHERE uri = $1")
-        .bind(uri)
-        .execute(pool)
+    let mut tx = pool.begin().await?;
+
+    let deleted: Option<ScrobbleRefs> = sqlx::query_as(
+        "DELETE FROM scrobbles WHERE uri = $1 RETURNING user_id, track_id, album_id, artist_id",
+    )
+    .bind(uri)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if let Some((Some(user_id), track_id, album_id, artist_id)) = deleted {
+        for (table, id) in [
+            (&USER_TRACKS, track_id),
+            (&USER_ALBUMS, album_id),
+            (&USER_ARTISTS, artist_id),
+        ] {
+            if let Some(id) = id {
+                recompute_scrobbles(&mut tx, table, &user_id, &id).await?;
+            }
+        }
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Collection of the record an at-uri points to.
+fn uri_collection(uri: &str) -> Option<&str> {
+    uri.strip_prefix("at://")?.split('/').nth(1)
+}
+
+/// Repo (DID) of the record an at-uri points to.
+fn uri_did(uri: &str) -> Option<&str> {
+    uri.strip_prefix("at://")?.split('/').next()
+}
+
+/// Cover of a playlist: its `pictureUrl`, else the uploaded blob served
+/// through the Bluesky CDN.
+fn playlist_picture(did: &str, record: &PlaylistRecord) -> Option<String> {
+    record.picture_url.clone().or_else(|| {
+        record.picture.as_ref().map(|blob| {
+            format!(
+                "https://cdn.bsky.app/img/feed_thumbnail/plain/{}/{}@{}",
+                did,
+                blob.r#ref.link,
+                blob.mime_type.split('/').next_back().unwrap_or("jpeg")
+            )
+        })
+    })
+}
+
+pub async fn save_playlist(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    user_id: &str,
+    record: PlaylistRecord,
+    uri: &str,
+) -> Result<String, Error> {
+    let did = uri_did(uri).ok_or_else(|| anyhow::anyhow!("Invalid URI: {}", uri))?;
+    let picture = playlist_picture(did, &record);
+
+    // Playlists imported from Spotify get their row before the record is
+    // written, the uri is only set once the PDS answers: claim that row, by
+    // its Spotify link, instead of inserting a duplicate.
+    let existing: Option<String> = sqlx::query_scalar(
+        "SELECT xata_id FROM playlists WHERE uri = $1 \
+         OR ($2::text IS NOT NULL AND spotify_link = $2 AND created_by = $3) \
+         ORDER BY CASE WHEN uri = $1 THEN 0 ELSE 1 END \
+         LIMIT 1",
+    )
+    .bind(uri)
+    .bind(&record.spotify_link)
+    .bind(user_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    let playlist_id: String = match existing {
+        Some(playlist_id) => {
+            tracing::info!(name = %record.name, uri = %uri, "Updating playlist");
+            sqlx::query(
+                r#"
+        UPDATE playlists
+        SET name = $2,
+            description = $3,
+            picture = COALESCE($4, picture),
+            spotify_link = COALESCE($5, spotify_link),
+            tidal_link = COALESCE($6, tidal_link),
+            apple_music_link = COALESCE($7, apple_music_link),
+            uri = $8,
+            xata_updatedat = now()
+        WHERE xata_id = $1
+      "#,
+            )
+            .bind(&playlist_id)
+            .bind(record.name)
+            .bind(record.description)
+            .bind(picture)
+            .bind(record.spotify_link)
+            .bind(record.tidal_link)
+            .bind(record.apple_music_link)
+            .bind(uri)
+            .execute(&mut **tx)
+            .await?;
+            playlist_id
+        }
+        None => {
+            tracing::info!(name = %record.name, uri = %uri, "Saving playlist");
+            sqlx::query_scalar(
+                r#"
+        INSERT INTO playlists (
+          name,
+          description,
+          picture,
+          spotify_link,
+          tidal_link,
+          apple_music_link,
+          uri,
+          created_by
+        ) VALUES (
+          $1, $2, $3, $4, $5, $6, $7, $8
+        )
+        RETURNING xata_id
+      "#,
+            )
+            .bind(record.name)
+            .bind(record.description)
+            .bind(picture)
+            .bind(record.spotify_link)
+            .bind(record.tidal_link)
+            .bind(record.apple_music_link)
+            .bind(uri)
+            .bind(user_id)
+            .fetch_one(&mut **tx)
+            .await?
+        }
+    };
+
+    sqlx::query(
+        r#"
+    INSERT INTO user_playlists (
+      user_id,
+      playlist_id,
+      uri
+    ) VALUES (
+      $1, $2, $3
+    )
+    ON CONFLICT (user_id, playlist_id) DO UPDATE SET uri = EXCLUDED.uri
+  "#,
+    )
+    .bind(user_id)
+    .bind(&playlist_id)
+    .bind(uri)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(playlist_id)
+}
+
+pub async fn delete_playlist(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    uri: &str,
+) -> Result<(), Error> {
+    let Some(playlist_id) =
+        sqlx::query_scalar::<_, String>("SELECT xata_id FROM playlists WHERE uri = $1")
+            .bind(uri)
+            .fetch_optional(&mut **tx)
+            .await?
+    else {
+        tracing::warn!(uri = %uri, "Playlist not found in database");
+        return Ok(());
+    };
+
+    sqlx::query("DELETE FROM playlist_tracks WHERE playlist_id = $1")
+        .bind(&playlist_id)
+        .execute(&mut **tx)
+        .await?;
+    sqlx::query("DELETE FROM user_playlists WHERE playlist_id = $1")
+        .bind(&playlist_id)
+        .execute(&mut **tx)
+        .await?;
+    sqlx::query("DELETE FROM playlists WHERE xata_id = $1")
+        .bind(&playlist_id)
+        .execute(&mut **tx)
         .await?;
     Ok(())
 }
+
+/// Saves a playlist item into `playlist_tracks`. Rows are keyed by the item
+/// uri, so an update (a reorder, say) moves the row instead of adding one.
+pub async fn save_playlist_item(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    record: PlaylistItemRecord,
+    uri: &str,
+) -> Result<(), Error> {
+    let playlist_uri = record.subject.uri;
+    let Some(playlist_id) =
+        sqlx::query_scalar::<_, String>("SELECT xata_id FROM playlists WHERE uri = $1")
+            .bind(&playlist_uri)
+            .fetch_optional(&mut **tx)
+            .await?
+    else {
+        tracing::warn!(playlist = %playlist_uri, uri = %uri, "Playlist of item not found in database");
+        return Ok(());
+    };
+
+    // Same lookup as a liked song, falling back to the song hash when the
+    // song record isn't indexed yet.
+    let Some(track_id) = sqlx::query_scalar::<_, String>(
+        "SELECT xata_id FROM tracks WHERE $1::text IS NOT NULL AND uri = $1 \
+         UNION ALL \
+         SELECT track_id FROM user_tracks WHERE $1::text IS NOT NULL AND uri = $1 \
+         UNION ALL \
+         SELECT xata_id FROM tracks WHERE $2::text IS NOT NULL AND sha256 = $2 \
+         LIMIT 1",
+    )
+    .bind(&record.track.uri)
+    .bind(playlist_item_hash(&record.track))
+    .fetch_optional(&mut **tx)
+    .await?
+    else {
+        tracing::warn!(track = ?record.track.uri, uri = %uri, "Playlist item song not found in database");
+        return Ok(());
+    };
+
+    tracing::info!(playlist_id = %playlist_id, track_id = %track_id, uri = %uri, "Saving playlist item");
+    sqlx::query(
+        r#"
+    INSERT INTO playlist_tracks (
+      playlist_id,
+      track_id,
+      uri,
+      position
+    ) VALUES (
+      $1, $2, $3, $4
+    )
+    ON CONFLICT (uri) DO UPDATE SET
+      playlist_id = EXCLUDED.playlist_id,
+      track_id = EXCLUDED.track_id,
+      position = EXCLUDED.position
+  "#,
+    )
+    .bind(&playlist_id)
+    .bind(&track_id)
+    .bind(uri)
+    .bind(i32::try_from(record.order).unwrap_or(i32::MAX))
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// The `tracks.sha256` of a playlist item's song: computed like
+/// [`save_track`] does when its title, artist and album are known, else the
+/// one the record carries.
+fn playlist_item_hash(track: &PlaylistItemTrack) -> Option<String> {
+    match (&track.title, &track.artist, &track.album) {
+        (Some(title), Some(artist), Some(album)) => Some(sha256::digest(
+            format!("{} - {} - {}", title, artist, album).to_lowercase(),
+        )),
+        _ => track.sha256.clone(),
+    }
+}
+
+/// Saves a like of a song into `loved_tracks`, or of a shout into
+/// `shout_likes`. Returns the loved track when a new one was inserted, so
+/// the caller can publish it once the transaction is committed.
+pub async fn save_like(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    user_id: &str,
+    record: LikeRecord,
+    uri: &str,
+) -> Result<Option<LovedTrack>, Error> {
+    let subject = record.subject.uri;
+
+    match uri_collection(&subject) {
+        Some(SONG_NSID) => {
+            // tracks.uri is only set once the song record has been indexed,
+            // the owner's user_tracks row may be the only one knowing it.
+            let Some(track_id) = sqlx::query_scalar::<_, String>(
+                "SELECT xata_id FROM tracks WHERE uri = $1 \
+                 UNION ALL \
+                 SELECT track_id FROM user_tracks WHERE uri = $1 \
+                 LIMIT 1",
+            )
+            .bind(&subject)
+            .fetch_optional(&mut **tx)
+            .await?
+            else {
+                tracing::warn!(subject = %subject, "Liked song not found in database");
+                return Ok(None);
+            };
+
+            // The API saves the loved track before writing the record and
+            // sets the uri afterwards.
+            let existing: Option<(String, Option<String>)> = sqlx::query_as(
+                "SELECT xata_id, uri FROM loved_tracks WHERE user_id = $1 AND track_id = $2 LIMIT 1",
+            )
+            .bind(user_id)
+            .bind(&track_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+            match existing {
+                Some((id, None)) => {
+                    tracing::info!(user_id = %user_id, track_id = %track_id, "Updating loved track");
+                    sqlx::query("UPDATE loved_tracks SET uri = $2 WHERE xata_id = $1")
+                        .bind(id)
+                        .bind(uri)
+                        .execute(&mut **tx)
+                        .await?;
+                    Ok(None)
+                }
+                Some(_) => {
+                    tracing::info!(user_id = %user_id, track_id = %track_id, "Track already loved");
+                    Ok(None)
+                }
+                None => {
+                    tracing::info!(user_id = %user_id, track_id = %track_id, "Saving loved track");
+                    let loved_track: Option<LovedTrack> = sqlx::query_as(
+                        r#"
+            INSERT INTO loved_tracks (
+              user_id,
+              track_id,
+              uri
+            ) VALUES (
+              $1, $2, $3
+            )
+            ON CONFLICT (uri) DO NOTHING
+            RETURNING *
+          "#,
+                    )
+                    .bind(user_id)
+                    .bind(&track_id)
+                    .bind(uri)
+                    .fetch_optional(&mut **tx)
+                    .await?;
+                    Ok(loved_track)
+                }
+            }
+        }
+        Some(SHOUT_NSID) => {
+            let Some(shout_id) =
+                sqlx::query_scalar::<_, String>("SELECT xata_id FROM shouts WHERE uri = $1")
+                    .bind(&subject)
+                    .fetch_optional(&mut **tx)
+                    .await?
+            else {
+                tracing::warn!(subject = %subject, "Liked shout not found in database");
+                return Ok(None);
+            };
+
+            tracing::info!(user_id = %user_id, shout_id = %shout_id, "Saving shout like");
+            sqlx::query(
+                r#"
+        INSERT INTO shout_likes (
+          user_id,
+          shout_id,
+          uri
+        )
+        SELECT $1, $2, $3
+        WHERE NOT EXISTS (
+          SELECT 1 FROM shout_likes WHERE user_id = $1 AND shout_id = $2
+        )
+        ON CONFLICT (uri) DO NOTHING
+      "#,
+            )
+            .bind(user_id)
+            .bind(&shout_id)
+            .bind(uri)
+            .execute(&mut **tx)
+            .await?;
+            Ok(None)
+        }
+        _ => {
+            tracing::warn!(subject = %subject, "Unsupported like subject");
+            Ok(None)
+        }
+    }
+}
+
+/// Deletes the like at `uri`, returning the loved track it removed.
+pub async fn delete_like(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    uri: &str,
+) -> Result<Option<LovedTrack>, Error> {
+    let loved_track: Option<LovedTrack> =
+        sqlx::query_as("DELETE FROM loved_tracks WHERE uri = $1 RETURNING *")
+            .bind(uri)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+    sqlx::query("DELETE FROM shout_likes WHERE uri = $1")
+        .bind(uri)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(loved_track)
+}
+
+/// Publishes a loved track the way the API does when a song is (un)liked.
+pub async fn publish_loved_track(
+    nc: &async_nats::Client,
+    subject: &str,
+    loved_track: &LovedTrack,
+    uri: &str,
+) -> Result<(), Error> {
+    let payload = json!({
+        "uri": uri,
+        "user_id": { "xata_id": loved_track.user_id },
+        "track_id": { "xata_id": loved_track.track_id },
+        "xata_createdat": loved_track.xata_createdat.to_rfc3339(),
+        "xata_id": loved_track.xata_id,
+        "xata_updatedat": loved_track.xata_createdat.to_rfc3339(),
+        "xata_version": 0,
+    });
+    let payload = serde_json::to_string(&payload)?;
+
+    nc.publish(subject.to_string(), payload.into()).await?;
+    nc.flush().await?;
+
+    Ok(())
+}
+
+/// Catalog table of the record a shout is about, `None` when it is a shout
+/// on a profile.
+fn shout_subject_table(subject: &str) -> Option<&'static str> {
+    match uri_collection(subject) {
+        Some(SONG_NSID) => Some("tracks"),
+        Some(ALBUM_NSID) => Some("albums"),
+        Some(ARTIST_NSID) => Some("artists"),
+        Some(SCROBBLE_NSID) => Some("scrobbles"),
+        _ => None,
+    }
+}
+
+/// The `shouts` column (or `profile_shouts` row) a shout subject lands in.
+#[derive(Debug, Default, PartialEq)]
+struct ShoutSubject {
+    track_id: Option<String>,
+    album_id: Option<String>,
+    artist_id: Option<String>,
+    scrobble_id: Option<String>,
+    profile_id: Option<String>,
+}
+
+impl ShoutSubject {
+    fn new(table: Option<&str>, id: Option<String>) -> Self {
+        let mut columns = Self::default();
+        let column = match table {
+            Some("tracks") => &mut columns.track_id,
+            Some("albums") => &mut columns.album_id,
+            Some("artists") => &mut columns.artist_id,
+            Some("scrobbles") => &mut columns.scrobble_id,
+            _ => &mut columns.profile_id,
+        };
+        *column = id;
+        columns
+    }
+}
+
+/// Facets as stored in `shouts.facets`, `None` when there are none.
+fn shout_facets(facets: Option<Vec<Value>>) -> Result<Option<String>, serde_json::Error> {
+    facets
+        .filter(|facets| !facets.is_empty())
+        .map(|facets| serde_json::to_string(&facets))
+        .transpose()
+}
+
+pub async fn save_shout(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    user_id: &str,
+    record: ShoutRecord,
+    uri: &str,
+) -> Result<(), Error> {
+    let subject = record.subject.uri;
+    let subject_table = shout_subject_table(&subject);
+    let subject_id = match subject_table {
+        Some(table) => {
+            sqlx::query_scalar::<_, String>(&format!(
+                "SELECT xata_id FROM {} WHERE uri = $1",
+                table
+            ))
+            .bind(&subject)
+            .fetch_optional(&mut **tx)
+            .await?
+        }
+        // Anything else is a shout on a profile.
+        None => {
+            sqlx::query_scalar::<_, String>("SELECT xata_id FROM users WHERE did = $1")
+                .bind(uri_did(&subject))
+                .fetch_optional(&mut **tx)
+                .await?
+        }
+    };
+    let columns = ShoutSubject::new(subject_table, subject_id);
+
+    let parent_id = match &record.parent {
+        Some(parent) => {
+            sqlx::query_scalar::<_, String>("SELECT xata_id FROM shouts WHERE uri = $1")
+                .bind(&parent.uri)
+                .fetch_optional(&mut **tx)
+                .await?
+        }
+        None => None,
+    };
+
+    let gif = record.gif;
+    let facets = shout_facets(record.facets)?;
+
+    tracing::info!(user_id = %user_id, subject = %subject, uri = %uri, "Saving shout");
+
+    // Shouts written through the API are inserted by the API too, the
+    // conflict then only refreshes what an edit can change.
+    let shout_id: String = sqlx::query_scalar(
+        r#"
+    INSERT INTO shouts (
+      content,
+      uri,
+      author_id,
+      track_id,
+      album_id,
+      artist_id,
+      scrobble_id,
+      parent_id,
+      gif_url,
+      gif_preview_url,
+      gif_alt,
+      gif_width,
+      gif_height,
+      facets
+    ) VALUES (
+      $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14::jsonb
+    )
+    ON CONFLICT (uri) DO UPDATE SET
+      content = EXCLUDED.content,
+      gif_url = EXCLUDED.gif_url,
+      gif_preview_url = EXCLUDED.gif_preview_url,
+      gif_alt = EXCLUDED.gif_alt,
+      gif_width = EXCLUDED.gif_width,
+      gif_height = EXCLUDED.gif_height,
+      facets = EXCLUDED.facets,
+      xata_updatedat = now()
+    RETURNING xata_id
+  "#,
+    )
+    .bind(record.message.unwrap_or_default())
+    .bind(uri)
+    .bind(user_id)
+    .bind(columns.track_id)
+    .bind(columns.album_id)
+    .bind(columns.artist_id)
+    .bind(columns.scrobble_id)
+    .bind(parent_id)
+    .bind(gif.as_ref().map(|gif| gif.url.clone()))
+    .bind(gif.as_ref().and_then(|gif| gif.preview_url.clone()))
+    .bind(gif.as_ref().and_then(|gif| gif.alt.clone()))
+    .bind(gif.as_ref().and_then(|gif| gif.width))
+    .bind(gif.as_ref().and_then(|gif| gif.height))
+    .bind(facets)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    if let Some(profile_id) = columns.profile_id {
+        sqlx::query(
+            r#"
+      INSERT INTO profile_shouts (
+        user_id,
+        shout_id
+      )
+      SELECT $1, $2
+      WHERE NOT EXISTS (
+        SELECT 1 FROM profile_shouts WHERE user_id = $1 AND shout_id = $2
+      )
+    "#,
+        )
+        .bind(profile_id)
+        .bind(&shout_id)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Deletes the shout at `uri` along with its replies, as removeShout does.
+pub async fn delete_shout(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    uri: &str,
+) -> Result<(), Error> {
+    let shout_ids: Vec<String> = sqlx::query_scalar(
+        r#"
+    SELECT xata_id FROM shouts WHERE uri = $1
+    UNION
+    SELECT replies.xata_id FROM shouts replies
+    JOIN shouts parent ON replies.parent_id = parent.xata_id
+    WHERE parent.uri = $1
+  "#,
+    )
+    .bind(uri)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    if shout_ids.is_empty() {
+        tracing::warn!(uri = %uri, "Shout not found in database");
+        return Ok(());
+    }
+
+    for table in [
+        "shout_likes",
+        "shout_reports",
+        "profile_shouts",
+        "notifications",
+    ] {
+        sqlx::query(&format!("DELETE FROM {} WHERE shout_id = ANY($1)", table))
+            .bind(&shout_ids)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    // Replies first, they reference the shout.
+    sqlx::query("DELETE FROM shouts WHERE xata_id = ANY($1) AND uri <> $2")
+        .bind(&shout_ids)
+        .bind(uri)
+        .execute(&mut **tx)
+        .await?;
+    sqlx::query("DELETE FROM shouts WHERE uri = $1")
+        .bind(uri)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+/// A `user_*` library table, with the catalog table its rows point to.
+pub struct LibraryTable {
+    table: &'static str,
+    column: &'static str,
+    catalog: &'static str,
+}
+
+impl LibraryTable {
+    fn recompute_scrobbles_sql(&self) -> String {
+        format!(
+            r#"
+    UPDATE {table}
+    SET scrobbles = GREATEST(1, (
+      SELECT count(*) FROM scrobbles WHERE user_id = $1 AND {column} = $2
+    ))
+    WHERE user_id = $1 AND {column} = $2
+  "#,
+            table = self.table,
+            column = self.column,
+        )
+    }
+
+    /// Points the catalog entry at the oldest remaining record of it, once
+    /// the record at `$2` is gone.
+    fn fallback_uri_sql(&self) -> String {
+        format!(
+            r#"
+    UPDATE {catalog}
+    SET uri = (
+      SELECT uri FROM {table}
+      WHERE {column} = $1 AND uri IS NOT NULL
+      ORDER BY xata_createdat
+      LIMIT 1
+    )
+    WHERE xata_id = $1 AND uri = $2
+  "#,
+            catalog = self.catalog,
+            table = self.table,
+            column = self.column,
+        )
+    }
+}
+
+/// What deleting a library record does to its row.
+#[derive(Debug, PartialEq)]
+enum LibraryRemoval {
+    /// The user scrobbled it: keep the row, without its record.
+    Detach {
+        scrobbles: i32,
+    },
+    Remove,
+}
+
+impl LibraryRemoval {
+    fn for_scrobbles(scrobbles: i64) -> Self {
+        match scrobbles {
+            n if n > 0 => LibraryRemoval::Detach {
+                scrobbles: i32::try_from(n).unwrap_or(i32::MAX),
+            },
+            _ => LibraryRemoval::Remove,
+        }
+    }
+}
+
+/// The catalog id an edited record keeps when the edit doesn't change what
+/// it describes, i.e. it still hashes to the entry it pointed at.
+fn edited_in_place(previous: Option<String>, current: Option<&String>) -> Option<String> {
+    previous.filter(|previous| Some(previous) == current)
+}
+
+pub const USER_TRACKS: LibraryTable = LibraryTable {
+    table: "user_tracks",
+    column: "track_id",
+    catalog: "tracks",
+};
+
+pub const USER_ALBUMS: LibraryTable = LibraryTable {
+    table: "user_albums",
+    column: "album_id",
+    catalog: "albums",
+};
+
+pub const USER_ARTISTS: LibraryTable = LibraryTable {
+    table: "user_artists",
+    column: "artist_id",
+    catalog: "artists",
+};
+
+/// Resets the `scrobbles` counter of a library row to the user's scrobbles
+/// of it. A row never goes below 1, it only exists because of a record.
+pub async fn recompute_scrobbles(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    library: &LibraryTable,
+    user_id: &str,
+    id: &str,
+) -> Result<(), Error> {
+    sqlx::query(&library.recompute_scrobbles_sql())
+        .bind(user_id)
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+/// Detaches the record at `uri` from the library. The row is dropped unless
+/// the user scrobbled it, and the catalog entry it was canonical for falls
+/// back to another user's record. Returns the catalog id it pointed to.
+async fn delete_library_record(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    library: &LibraryTable,
+    uri: &str,
+) -> Result<Option<String>, Error> {
+    let row: Option<(String, String)> = sqlx::query_as(&format!(
+        "SELECT user_id, {} FROM {} WHERE uri = $1",
+        library.column, library.table
+    ))
+    .bind(uri)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    let Some((user_id, id)) = row else {
+        tracing::warn!(table = %library.table, uri = %uri, "Record not found in library");
+        return Ok(None);
+    };
+
+    let scrobbles: i64 = sqlx::query_scalar(&format!(
+        "SELECT count(*) FROM scrobbles WHERE user_id = $1 AND {} = $2",
+        library.column
+    ))
+    .bind(&user_id)
+    .bind(&id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    match LibraryRemoval::for_scrobbles(scrobbles) {
+        LibraryRemoval::Detach { scrobbles } => {
+            tracing::info!(table = %library.table, user_id = %user_id, id = %id, "Detaching record from library");
+            sqlx::query(&format!(
+                "UPDATE {} SET uri = NULL, scrobbles = $2 WHERE uri = $1",
+                library.table
+            ))
+            .bind(uri)
+            .bind(scrobbles)
+            .execute(&mut **tx)
+            .await?;
+        }
+        LibraryRemoval::Remove => {
+            tracing::info!(table = %library.table, user_id = %user_id, id = %id, "Removing record from library");
+            sqlx::query(&format!("DELETE FROM {} WHERE uri = $1", library.table))
+                .bind(uri)
+                .execute(&mut **tx)
+                .await?;
+        }
+    }
+
+    sqlx::query(&library.fallback_uri_sql())
+        .bind(&id)
+        .bind(uri)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(Some(id))
+}
+
+pub async fn delete_user_track(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    uri: &str,
+) -> Result<Option<String>, Error> {
+    delete_library_record(tx, &USER_TRACKS, uri).await
+}
+
+pub async fn delete_user_album(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    uri: &str,
+) -> Result<Option<String>, Error> {
+    let album_id = delete_library_record(tx, &USER_ALBUMS, uri).await?;
+
+    // Tracks linked to the album through this record follow its new uri.
+    if let Some(album_id) = &album_id {
+        sqlx::query(
+            r#"
+      UPDATE tracks
+      SET album_uri = (SELECT uri FROM albums WHERE xata_id = $1)
+      WHERE album_uri = $2
+    "#,
+        )
+        .bind(album_id)
+        .bind(uri)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(album_id)
+}
+
+pub async fn delete_user_artist(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    uri: &str,
+) -> Result<Option<String>, Error> {
+    let artist_id = delete_library_record(tx, &USER_ARTISTS, uri).await?;
+
+    // Tracks and albums linked to the artist through this record follow its
+    // new uri.
+    if let Some(artist_id) = &artist_id {
+        for table in ["tracks", "albums"] {
+            sqlx::query(&format!(
+                r#"
+        UPDATE {}
+        SET artist_uri = (SELECT uri FROM artists WHERE xata_id = $1)
+        WHERE artist_uri = $2
+      "#,
+                table
+            ))
+            .bind(artist_id)
+            .bind(uri)
+            .execute(&mut **tx)
+            .await?;
+        }
+    }
+
+    Ok(artist_id)
+}
+
+/// Applies an edited song record. Metadata edits refresh the track when the
+/// record is its canonical one, an edited title, artist or album moves the
+/// record to the track it now describes.
+pub async fn update_user_track(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    user_id: &str,
+    record: SongRecord,
+    uri: &str,
+) -> Result<(), Error> {
+    let hash = sha256::digest(
+        format!("{} - {} - {}", record.title, record.artist, record.album).to_lowercase(),
+    );
+    let previous: Option<String> =
+        sqlx::query_scalar("SELECT track_id FROM user_tracks WHERE uri = $1")
+            .bind(uri)
+            .fetch_optional(&mut **tx)
+            .await?;
+    let current: Option<String> =
+        sqlx::query_scalar("SELECT xata_id FROM tracks WHERE sha256 = $1")
+            .bind(&hash)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+    if let Some(track_id) = edited_in_place(previous, current.as_ref()) {
+        tracing::info!(title = %record.title, uri = %uri, "Updating track");
+        sqlx::query(
+            r#"
+      UPDATE tracks
+      SET album_artist = $3,
+          duration = $4,
+          track_number = COALESCE($5, track_number),
+          disc_number = COALESCE($6, disc_number),
+          genre = COALESCE($7, genre),
+          composer = COALESCE($8, composer),
+          lyrics = COALESCE($9, lyrics),
+          copyright_message = COALESCE($10, copyright_message),
+          label = COALESCE($11, label),
+          mb_id = COALESCE($12, mb_id),
+          isrc = COALESCE($13, isrc),
+          album_art = COALESCE($14, album_art),
+          spotify_link = COALESCE($15, spotify_link),
+          tidal_link = COALESCE($16, tidal_link),
+          apple_music_link = COALESCE($17, apple_music_link),
+          youtube_link = COALESCE($18, youtube_link),
+          xata_updatedat = now()
+      WHERE xata_id = $1 AND uri = $2
+    "#,
+        )
+        .bind(track_id)
+        .bind(uri)
+        .bind(record.album_artist)
+        .bind(record.duration)
+        .bind(record.track_number)
+        .bind(record.disc_number)
+        .bind(record.genre)
+        .bind(record.composer)
+        .bind(record.lyrics)
+        .bind(record.copyright_message)
+        .bind(record.label)
+        .bind(record.mbid)
+        .bind(record.isrc)
+        .bind(record.album_art_url)
+        .bind(record.spotify_link)
+        .bind(record.tidal_link)
+        .bind(record.apple_music_link)
+        .bind(record.youtube_link)
+        .execute(&mut **tx)
+        .await?;
+        return Ok(());
+    }
+
+    delete_user_track(tx, uri).await?;
+    save_user_track(tx, user_id, record.clone(), uri).await?;
+    update_track_uri(tx, user_id, record, uri).await?;
+
+    let track_id: String = sqlx::query_scalar("SELECT track_id FROM user_tracks WHERE uri = $1")
+        .bind(uri)
+        .fetch_one(&mut **tx)
+        .await?;
+    recompute_scrobbles(tx, &USER_TRACKS, user_id, &track_id).await
+}
+
+/// Applies an edited album record, see [`update_user_track`].
+pub async fn update_user_album(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    user_id: &str,
+    record: AlbumRecord,
+    uri: &str,
+) -> Result<(), Error> {
+    let hash = sha256::digest(format!("{} - {}", record.title, record.artist).to_lowercase());
+    let previous: Option<String> =
+        sqlx::query_scalar("SELECT album_id FROM user_albums WHERE uri = $1")
+            .bind(uri)
+            .fetch_optional(&mut **tx)
+            .await?;
+    let current: Option<String> =
+        sqlx::query_scalar("SELECT xata_id FROM albums WHERE sha256 = $1")
+            .bind(&hash)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+    if let Some(album_id) = edited_in_place(previous, current.as_ref()) {
+        tracing::info!(title = %record.title, uri = %uri, "Updating album");
+        sqlx::query(
+            r#"
+      UPDATE albums
+      SET album_art = COALESCE($3, album_art),
+          year = COALESCE($4, year),
+          release_date = COALESCE($5, release_date),
+          spotify_link = COALESCE($6, spotify_link),
+          tidal_link = COALESCE($7, tidal_link),
+          apple_music_link = COALESCE($8, apple_music_link),
+          youtube_link = COALESCE($9, youtube_link),
+          xata_updatedat = now()
+      WHERE xata_id = $1 AND uri = $2
+    "#,
+        )
+        .bind(album_id)
+        .bind(uri)
+        .bind(record.album_art_url)
+        .bind(record.year)
+        .bind(record.release_date)
+        .bind(record.spotify_link)
+        .bind(record.tidal_link)
+        .bind(record.apple_music_link)
+        .bind(record.youtube_link)
+        .execute(&mut **tx)
+        .await?;
+        return Ok(());
+    }
+
+    delete_user_album(tx, uri).await?;
+    save_user_album(tx, user_id, record.clone(), uri).await?;
+    update_album_uri(tx, user_id, record, uri).await?;
+
+    let album_id: String = sqlx::query_scalar("SELECT album_id FROM user_albums WHERE uri = $1")
+        .bind(uri)
+        .fetch_one(&mut **tx)
+        .await?;
+    recompute_scrobbles(tx, &USER_ALBUMS, user_id, &album_id).await
+}
+
+/// Applies an edited artist record, see [`update_user_track`].
+pub async fn update_user_artist(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    user_id: &str,
+    record: ArtistRecord,
+    uri: &str,
+) -> Result<(), Error> {
+    let hash = sha256::digest(record.name.to_lowercase());
+    let previous: Option<String> =
+        sqlx::query_scalar("SELECT artist_id FROM user_artists WHERE uri = $1")
+            .bind(uri)
+            .fetch_optional(&mut **tx)
+            .await?;
+    let current: Option<String> =
+        sqlx::query_scalar("SELECT xata_id FROM artists WHERE sha256 = $1")
+            .bind(&hash)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+    if let Some(artist_id) = edited_in_place(previous, current.as_ref()) {
+        tracing::info!(name = %record.name, uri = %uri, "Updating artist");
+        sqlx::query(
+            r#"
+      UPDATE artists
+      SET picture = COALESCE($3, picture),
+          biography = COALESCE($4, biography),
+          born_in = COALESCE($5, born_in),
+          genres = COALESCE($6, genres),
+          xata_updatedat = now()
+      WHERE xata_id = $1 AND uri = $2
+    "#,
+        )
+        .bind(artist_id)
+        .bind(uri)
+        .bind(record.picture_url)
+        .bind(record.bio)
+        .bind(record.born_in)
+        .bind(record.tags)
+        .execute(&mut **tx)
+        .await?;
+        return Ok(());
+    }
+
+    delete_user_artist(tx, uri).await?;
+    save_user_artist(tx, user_id, record.clone(), uri).await?;
+    update_artist_uri(tx, user_id, record, uri).await?;
+
+    let artist_id: String = sqlx::query_scalar("SELECT artist_id FROM user_artists WHERE uri = $1")
+        .bind(uri)
+        .fetch_one(&mut **tx)
+        .await?;
+    recompute_scrobbles(tx, &USER_ARTISTS, user_id, &artist_id).await
+}
+
+/// Deletes the follow at `uri`, returning the DID it followed.
+pub async fn delete_follow(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    uri: &str,
+) -> Result<Option<String>, Error> {
+    let subject: Option<String> =
+        sqlx::query_scalar("DELETE FROM follows WHERE uri = $1 RETURNING subject_did")
+            .bind(uri)
+            .fetch_optional(&mut **tx)
+            .await?;
+    Ok(subject)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_at_uris() {
+        let uri = "at://did:plc:alice/app.rocksky.song/3kabc";
+        assert_eq!(uri_did(uri), Some("did:plc:alice"));
+        assert_eq!(uri_collection(uri), Some(SONG_NSID));
+        assert_eq!(uri_did("did:plc:alice"), None);
+        assert_eq!(uri_collection("at://did:plc:alice"), None);
+    }
+
+    #[test]
+    fn playlist_item_hash_matches_saved_tracks() {
+        let record: PlaylistItemRecord = serde_json::from_value(json!({
+            "subject": {
+                "uri": "at://did:plc:alice/app.rocksky.playlist/3kpl",
+                "cid": "bafyreiplaylist",
+            },
+            "track": {
+                "uri": "at://did:plc:alice/app.rocksky.song/3ksong",
+                "title": "Chaser",
+                "artist": "Calibro 35",
+                "album": "Jazzploitation",
+                "sha256": "ignored",
+            },
+            "order": 2,
+            "createdAt": "2025-01-01T00:00:00.000Z",
+        }))
+        .unwrap();
+        assert_eq!(record.order, 2);
+        assert_eq!(
+            playlist_item_hash(&record.track),
+            Some(sha256::digest("chaser - calibro 35 - jazzploitation"))
+        );
+
+        let track = PlaylistItemTrack {
+            sha256: Some("abc".into()),
+            ..Default::default()
+        };
+        assert_eq!(playlist_item_hash(&track).as_deref(), Some("abc"));
+        assert_eq!(playlist_item_hash(&PlaylistItemTrack::default()), None);
+    }
+
+    #[test]
+    fn playlist_picture_prefers_the_url() {
+        let record: PlaylistRecord = serde_json::from_value(json!({
+            "name": "Road trip",
+            "pictureUrl": "https://i.scdn.co/image/cover",
+            "picture": {
+                "$type": "blob",
+                "ref": { "$link": "bafkreicover" },
+                "mimeType": "image/png",
+                "size": 1024,
+            },
+            "createdAt": "2025-01-01T00:00:00.000Z",
+        }))
+        .unwrap();
+        assert_eq!(
+            playlist_picture("did:plc:alice", &record).as_deref(),
+            Some("https://i.scdn.co/image/cover")
+        );
+
+        let record = PlaylistRecord {
+            picture_url: None,
+            ..record
+        };
+        assert_eq!(
+            playlist_picture("did:plc:alice", &record).as_deref(),
+            Some("https://cdn.bsky.app/img/feed_thumbnail/plain/did:plc:alice/bafkreicover@png")
+        );
+
+        let record = PlaylistRecord {
+            picture: None,
+            ..record
+        };
+        assert_eq!(playlist_picture("did:plc:alice", &record), None);
+    }
+
+    #[test]
+    fn shout_subjects_map_to_their_table() {
+        for (subject, table) in [
+            ("at://did:plc:alice/app.rocksky.song/1", Some("tracks")),
+            ("at://did:plc:alice/app.rocksky.album/1", Some("albums")),
+            ("at://did:plc:alice/app.rocksky.artist/1", Some("artists")),
+            (
+                "at://did:plc:alice/app.rocksky.scrobble/1",
+                Some("scrobbles"),
+            ),
+            ("at://did:plc:alice/app.bsky.actor.profile/self", None),
+            ("at://did:plc:alice", None),
+        ] {
+            assert_eq!(shout_subject_table(subject), table, "{}", subject);
+        }
+    }
+
+    #[test]
+    fn shout_subject_fills_one_column() {
+        let id = || Some("rec_1".to_string());
+        assert_eq!(
+            ShoutSubject::new(Some("albums"), id()),
+            ShoutSubject {
+                album_id: id(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            ShoutSubject::new(Some("scrobbles"), id()),
+            ShoutSubject {
+                scrobble_id: id(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            ShoutSubject::new(None, id()),
+            ShoutSubject {
+                profile_id: id(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            ShoutSubject::new(Some("tracks"), None),
+            ShoutSubject::default()
+        );
+    }
+
+    #[test]
+    fn library_tables_pair_their_columns() {
+        for (library, table, column, catalog) in [
+            (&USER_TRACKS, "user_tracks", "track_id", "tracks"),
+            (&USER_ALBUMS, "user_albums", "album_id", "albums"),
+            (&USER_ARTISTS, "user_artists", "artist_id", "artists"),
+        ] {
+            let sql = library.recompute_scrobbles_sql();
+            assert!(sql.contains(&format!("UPDATE {}\n", table)), "{}", sql);
+            assert!(sql.contains(&format!("AND {} = $2", column)), "{}", sql);
+
+            let sql = library.fallback_uri_sql();
+            assert!(sql.contains(&format!("UPDATE {}\n", catalog)), "{}", sql);
+            assert!(
+                sql.contains(&format!("SELECT uri FROM {}\n", table)),
+                "{}",
+                sql
+            );
+            assert!(sql.contains(&format!("WHERE {} = $1", column)), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn scrobbled_records_are_detached() {
+        assert_eq!(LibraryRemoval::for_scrobbles(0), LibraryRemoval::Remove);
+        assert_eq!(
+            LibraryRemoval::for_scrobbles(3),
+            LibraryRemoval::Detach { scrobbles: 3 }
+        );
+        assert_eq!(
+            LibraryRemoval::for_scrobbles(i64::MAX),
+            LibraryRemoval::Detach {
+                scrobbles: i32::MAX
+            }
+        );
+    }
+
+    #[test]
+    fn edits_stay_in_place_only_on_the_same_entry() {
+        let id = |id: &str| Some(id.to_string());
+        assert_eq!(edited_in_place(id("a"), id("a").as_ref()), id("a"));
+        assert_eq!(edited_in_place(id("a"), id("b").as_ref()), None);
+        assert_eq!(edited_in_place(id("a"), None), None);
+        assert_eq!(edited_in_place(None, None), None);
+    }
+
+    #[test]
+    fn empty_facets_are_not_stored() {
+        assert_eq!(shout_facets(None).unwrap(), None);
+        assert_eq!(shout_facets(Some(vec![])).unwrap(), None);
+        assert_eq!(
+            shout_facets(Some(vec![json!({ "index": { "byteStart": 0 } })]))
+                .unwrap()
+                .as_deref(),
+            Some(r#"[{"index":{"byteStart":0}}]"#)
+        );
+    }
+}
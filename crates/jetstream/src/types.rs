@@ -255,3 +255,94 @@ pub struct FollowRecord {
     pub subject: String,
     pub created_at: String,
 }
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct StrongRef {
+    pub uri: String,
+    pub cid: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistRecord {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub picture: Option<ImageBlob>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub picture_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spotify_link: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tidal_link: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub youtube_link: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apple_music_link: Option<String>,
+    pub created_at: String,
+}
+
+/// An `app.rocksky.playlistItem`: `subject` is the playlist it belongs to.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistItemRecord {
+    pub subject: StrongRef,
+    pub track: PlaylistItemTrack,
+    pub order: i64,
+    pub created_at: String,
+}
+
+/// The `app.rocksky.song.defs#songViewBasic` of a playlist item, only the
+/// fields identifying the song.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistItemTrack {
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub album: Option<String>,
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LikeRecord {
+    pub subject: StrongRef,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShoutGif {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShoutRecord {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub subject: StrongRef,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<StrongRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gif: Option<ShoutGif>,
+    /// Mentions, stored as-is in `shouts.facets`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facets: Option<Vec<Value>>,
+    pub created_at: String,
+}
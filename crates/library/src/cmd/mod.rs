@@ -0,0 +1,3 @@
+pub mod scan;
+pub mod serve;
+pub mod watch;
@@ -0,0 +1,48 @@
+use std::{env, fs};
+
+use anyhow::{anyhow, Error};
+use sqlx::postgres::PgPoolOptions;
+
+use crate::{repo::user::find_user_id, scan::scan_library, source::Source};
+
+/// Scans the local directory `path`, or the bucket of the storage provider
+/// `storage_provider` under `prefix`, into the library of `did`.
+pub async fn scan(
+    did: &str,
+    path: Option<&str>,
+    storage_provider: Option<&str>,
+    prefix: &str,
+) -> Result<(), Error> {
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&env::var("XATA_POSTGRES_URL")?)
+        .await?;
+
+    let user_id = find_user_id(&pool, did)
+        .await?
+        .ok_or_else(|| anyhow!("User {} not found", did))?;
+
+    let source = match (path, storage_provider) {
+        (_, Some(provider_id)) => Source::s3(&pool, provider_id, &user_id, prefix).await?,
+        (Some(path), None) => {
+            // Paths are stored absolute, scans and watches of the directory
+            // then agree wherever they're started from.
+            let path = fs::canonicalize(path)?;
+            if !path.is_dir() {
+                return Err(anyhow!("{} is not a directory", path.display()));
+            }
+            Source::Local(path)
+        }
+        (None, None) => return Err(anyhow!("A directory or a storage provider is required")),
+    };
+
+    let summary = scan_library(&pool, did, &user_id, &source).await?;
+    tracing::info!(
+        indexed = summary.indexed,
+        skipped = summary.skipped,
+        failed = summary.failed,
+        "Library scanned"
+    );
+
+    Ok(())
+}
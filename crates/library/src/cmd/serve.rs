@@ -0,0 +1,81 @@
+use std::{
+    collections::HashSet,
+    env,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Error;
+use futures_util::StreamExt;
+use owo_colors::OwoColorize;
+use sqlx::postgres::PgPoolOptions;
+
+use crate::job::{run_scan_job, SCAN_NATS_TOPIC};
+
+/// Runs the scans requested on [`SCAN_NATS_TOPIC`], one at a time per user.
+/// Requests are acknowledged as soon as they're received.
+pub async fn serve() -> Result<(), Error> {
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&env::var("XATA_POSTGRES_URL")?)
+        .await?;
+    let pool = Arc::new(pool);
+
+    let addr = env::var("NATS_URL").unwrap_or_else(|_| "nats://localhost:4222".to_string());
+    let nc = async_nats::connect(&addr).await?;
+    tracing::info!(addr = %addr.bright_green(), "Connected to NATS server");
+
+    let mut sub = nc.subscribe(SCAN_NATS_TOPIC.to_string()).await?;
+    tracing::info!(subject = %SCAN_NATS_TOPIC.bright_green(), "Subscribed");
+
+    let running: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
+
+    while let Some(msg) = sub.next().await {
+        let Ok(user_id) = String::from_utf8(msg.payload.to_vec()) else {
+            tracing::warn!("Ignoring scan request with a non UTF-8 payload");
+            continue;
+        };
+        // A request for a user already being scanned is still acknowledged:
+        // the running scan reports itself finished when it's done.
+        let queued = running.lock().unwrap().insert(user_id.clone());
+        // Lets `startScan` know a scanner took the request.
+        if let Some(reply) = msg.reply {
+            if let Err(e) = nc.publish(reply, "".into()).await {
+                tracing::warn!(user_id = %user_id, error = %e, "Failed to acknowledge scan request");
+            }
+        }
+        if !queued {
+            tracing::info!(user_id = %user_id, "Scan already running");
+            continue;
+        }
+
+        let pool = pool.clone();
+        let guard = Running {
+            running: running.clone(),
+            user_id,
+        };
+        tokio::spawn(async move {
+            let user_id = &guard.user_id;
+            tracing::info!(user_id = %user_id.bright_green(), "Scanning library");
+            if let Err(e) = run_scan_job(pool, user_id).await {
+                tracing::error!(user_id = %user_id.bright_red(), error = %e, "Scan failed");
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Removes the user from the running scans when their scan task ends, even
+/// when it panics.
+struct Running {
+    running: Arc<Mutex<HashSet<String>>>,
+    user_id: String,
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        if let Ok(mut running) = self.running.lock() {
+            running.remove(&self.user_id);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use std::{env, fs};
+
+use anyhow::{anyhow, Error};
+use sqlx::postgres::PgPoolOptions;
+
+use crate::{repo::user::find_user_id, watch::watch_library};
+
+/// Keeps the library of `did` in sync with the local directory `path`.
+pub async fn watch(did: &str, path: &str) -> Result<(), Error> {
+    // Paths are stored absolute, so the library doesn't depend on where the
+    // watcher is started from.
+    let path = fs::canonicalize(path)?;
+    if !path.is_dir() {
+        return Err(anyhow!("{} is not a directory", path.display()));
+    }
+
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&env::var("XATA_POSTGRES_URL")?)
+        .await?;
+
+    let user_id = find_user_id(&pool, did)
+        .await?
+        .ok_or_else(|| anyhow!("User {} not found", did))?;
+
+    watch_library(&pool, did, &user_id, &path).await
+}
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Error};
+use owo_colors::OwoColorize;
+use rocksky_dropbox::scan::scan_user_dropbox;
+use rocksky_googledrive::scan::scan_user_googledrive;
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    repo::{
+        library_scan::{finish_library_scan, start_library_scan},
+        storage_provider::list_storage_provider_ids,
+        user::find_user_did,
+    },
+    scan::index_source,
+    source::Source,
+};
+
+pub use rocksky_navidrome::repo::scan::SCAN_NATS_TOPIC;
+
+/// Rescans every storage the user linked: their Dropbox, their Google Drive
+/// and the buckets of their storage providers. A storage failing doesn't stop
+/// the others from being scanned.
+pub async fn run_scan_job(pool: Arc<Pool<Postgres>>, user_id: &str) -> Result<(), Error> {
+    let did = find_user_did(&pool, user_id)
+        .await?
+        .ok_or_else(|| anyhow!("User {} not found", user_id))?;
+
+    start_library_scan(&pool, user_id).await?;
+    // Scanned in a task of its own so the scan is reported finished even when
+    // it fails or panics.
+    let result = tokio::spawn(scan_storages(
+        pool.clone(),
+        did.clone(),
+        user_id.to_string(),
+    ))
+    .await
+    .map_err(|e| anyhow!("Library scan panicked: {}", e))
+    .and_then(|result| result);
+
+    let count = finish_library_scan(&pool, user_id).await?;
+    result?;
+    tracing::info!(did = %did.bright_green(), files = count, "Library scanned");
+    Ok(())
+}
+
+/// Scans the storages of the user.
+async fn scan_storages(
+    pool: Arc<Pool<Postgres>>,
+    did: String,
+    user_id: String,
+) -> Result<(), Error> {
+    if let Err(e) = scan_user_dropbox(&pool, &did).await {
+        tracing::error!(did = %did.bright_red(), error = %e, "Failed to scan Dropbox");
+    }
+
+    if let Err(e) = scan_user_googledrive(pool.clone(), &did).await {
+        tracing::error!(did = %did.bright_red(), error = %e, "Failed to scan Google Drive");
+    }
+
+    for provider_id in list_storage_provider_ids(&pool, &user_id).await? {
+        let result = async {
+            let source = Source::s3(&pool, &provider_id, &user_id, "").await?;
+            index_source(&pool, &did, &user_id, &source).await
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::error!(
+                provider = %provider_id.bright_red(),
+                error = %e,
+                "Failed to scan storage provider"
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,7 @@
+pub mod cmd;
+pub mod job;
+pub mod repo;
+pub mod scan;
+pub mod source;
+mod token;
+pub mod watch;
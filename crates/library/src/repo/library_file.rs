@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use rocksky_media::path::descendants_pattern;
+use sqlx::{Pool, Postgres};
+
+/// A file of a local directory linked to its track.
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct LibraryFile {
+    pub path: String,
+    pub track_id: String,
+    pub sha256: String,
+    pub file_size: i64,
+    pub modified_at: DateTime<Utc>,
+}
+
+pub async fn find_library_file(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    path: &str,
+) -> Result<Option<LibraryFile>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+    SELECT path, track_id, sha256, file_size, modified_at
+    FROM library_files
+    WHERE user_id = $1
+      AND path = $2
+    "#,
+    )
+    .bind(user_id)
+    .bind(path)
+    .fetch_optional(pool)
+    .await
+}
+
+/// The files of the user among `paths`.
+pub async fn find_library_files(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    paths: &[String],
+) -> Result<Vec<LibraryFile>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+    SELECT path, track_id, sha256, file_size, modified_at
+    FROM library_files
+    WHERE user_id = $1
+      AND path = ANY($2)
+    "#,
+    )
+    .bind(user_id)
+    .bind(paths)
+    .fetch_all(pool)
+    .await
+}
+
+/// Paths of the user's files below the directory `dir`.
+pub async fn list_library_paths(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    dir: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+    SELECT path FROM library_files
+    WHERE user_id = $1
+      AND path LIKE $2
+    "#,
+    )
+    .bind(user_id)
+    .bind(descendants_pattern(dir))
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn save_library_file(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    file: &LibraryFile,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+    INSERT INTO library_files (
+      user_id,
+      path,
+      track_id,
+      sha256,
+      file_size,
+      modified_at
+    )
+    VALUES ($1, $2, $3, $4, $5, $6)
+    ON CONFLICT (user_id, path) DO UPDATE SET
+      track_id = EXCLUDED.track_id,
+      sha256 = EXCLUDED.sha256,
+      file_size = EXCLUDED.file_size,
+      modified_at = EXCLUDED.modified_at,
+      xata_updatedat = NOW()
+    "#,
+    )
+    .bind(user_id)
+    .bind(&file.path)
+    .bind(&file.track_id)
+    .bind(&file.sha256)
+    .bind(file.file_size)
+    .bind(file.modified_at)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Moves the file at `from` to `to`, replacing the file previously there.
+pub async fn move_library_file(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    from: &str,
+    to: &str,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM library_files WHERE user_id = $1 AND path = $2")
+        .bind(user_id)
+        .bind(to)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+    UPDATE library_files
+    SET path = $3,
+        xata_updatedat = NOW()
+    WHERE user_id = $1
+      AND path = $2
+    "#,
+    )
+    .bind(user_id)
+    .bind(from)
+    .bind(to)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+pub async fn delete_library_files(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    paths: &[String],
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM library_files WHERE user_id = $1 AND path = ANY($2)")
+        .bind(user_id)
+        .bind(paths)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,100 @@
+use sqlx::{Pool, Postgres};
+
+/// Number of files in the library of the user, from every storage.
+const USER_FILE_COUNT: &str = r#"
+      (SELECT COUNT(*) FROM user_uploads WHERE user_id = $1)
+      + (SELECT COUNT(*) FROM dropbox_paths p
+           JOIN dropbox d ON d.xata_id = p.dropbox_id
+           WHERE d.user_id = $1)
+      + (SELECT COUNT(*) FROM google_drive_paths p
+           JOIN google_drive g ON g.xata_id = p.google_drive_id
+           WHERE g.user_id = $1)
+      + (SELECT COUNT(*) FROM library_files WHERE user_id = $1)
+"#;
+
+/// Number of folders holding the files of [`USER_FILE_COUNT`].
+const USER_FOLDER_COUNT: &str = r#"
+      (SELECT COUNT(*) FROM (
+        SELECT 'upload', regexp_replace(r2_key, '/?[^/]*$', '')
+          FROM user_uploads WHERE user_id = $1
+        UNION
+        SELECT 'dropbox', regexp_replace(p.path, '/?[^/]*$', '')
+          FROM dropbox_paths p
+          JOIN dropbox d ON d.xata_id = p.dropbox_id
+          WHERE d.user_id = $1
+        UNION
+        SELECT 'google_drive', COALESCE(p.directory_id, '')
+          FROM google_drive_paths p
+          JOIN google_drive g ON g.xata_id = p.google_drive_id
+          WHERE g.user_id = $1
+        UNION
+        SELECT 'local', regexp_replace(path, '/?[^/]*$', '')
+          FROM library_files WHERE user_id = $1
+      ) folders)
+"#;
+
+/// Marks a scan of the user as running, over the folders their library has
+/// so far.
+pub async fn start_library_scan(pool: &Pool<Postgres>, user_id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!(
+        r#"
+    INSERT INTO library_scans (user_id, scanning, count, folder_count, started_at)
+    VALUES ($1, TRUE, 0, ({})::int, NOW())
+    ON CONFLICT (user_id) DO UPDATE SET
+      scanning = TRUE,
+      count = 0,
+      folder_count = EXCLUDED.folder_count,
+      started_at = NOW(),
+      xata_updatedat = NOW()
+    "#,
+        USER_FOLDER_COUNT
+    ))
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Number of files the running scan went through so far.
+pub async fn update_library_scan(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    count: usize,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+    UPDATE library_scans
+    SET count = $2,
+        xata_updatedat = NOW()
+    WHERE user_id = $1
+    "#,
+    )
+    .bind(user_id)
+    .bind(i32::try_from(count).unwrap_or(i32::MAX))
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Marks the scan as done with the files and folders now in the library,
+/// returns the number of files.
+pub async fn finish_library_scan(pool: &Pool<Postgres>, user_id: &str) -> Result<i32, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        r#"
+    UPDATE library_scans
+    SET scanning = FALSE,
+        count = ({})::int,
+        folder_count = ({})::int,
+        finished_at = NOW(),
+        xata_updatedat = NOW()
+    WHERE user_id = $1
+    RETURNING count
+    "#,
+        USER_FILE_COUNT, USER_FOLDER_COUNT
+    ))
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+}
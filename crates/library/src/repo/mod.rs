@@ -0,0 +1,6 @@
+pub mod library_file;
+pub mod library_scan;
+pub mod storage_provider;
+pub mod track;
+pub mod user;
+pub mod user_upload;
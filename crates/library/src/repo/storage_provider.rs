@@ -0,0 +1,49 @@
+use sqlx::{Pool, Postgres};
+
+/// A bucket the user brought, credentials are encrypted with
+/// `STORAGE_ENCRYPTION_KEY`.
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct StorageProvider {
+    pub xata_id: String,
+    pub label: String,
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key: String,
+    pub secret_key: String,
+}
+
+pub async fn find_storage_provider(
+    pool: &Pool<Postgres>,
+    provider_id: &str,
+    user_id: &str,
+) -> Result<Option<StorageProvider>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+    SELECT xata_id, label, endpoint, region, bucket, access_key, secret_key
+    FROM user_storage_providers
+    WHERE xata_id = $1
+      AND user_id = $2
+    "#,
+    )
+    .bind(provider_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn list_storage_provider_ids(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+    SELECT xata_id FROM user_storage_providers
+    WHERE user_id = $1
+    ORDER BY xata_createdat
+    "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,41 @@
+use rocksky_media::lyrics::Lyrics;
+use sqlx::{Pool, Postgres};
+
+pub async fn find_track_id(
+    pool: &Pool<Postgres>,
+    sha256: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT xata_id FROM tracks WHERE sha256 = $1 LIMIT 1")
+        .bind(sha256)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Stores the lyrics found in a scanned file, without overwriting lyrics
+/// the track already has.
+pub async fn update_track_lyrics(
+    pool: &Pool<Postgres>,
+    track_id: &str,
+    lyrics: &Lyrics,
+) -> Result<(), sqlx::Error> {
+    if lyrics.plain.is_none() && lyrics.synced.is_none() {
+        return Ok(());
+    }
+
+    sqlx::query(
+        r#"
+    UPDATE tracks
+    SET lyrics = COALESCE(lyrics, $2),
+        synced_lyrics = COALESCE(synced_lyrics, $3),
+        xata_updatedat = NOW()
+    WHERE xata_id = $1
+    "#,
+    )
+    .bind(track_id)
+    .bind(&lyrics.plain)
+    .bind(&lyrics.synced)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
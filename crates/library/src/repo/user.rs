@@ -0,0 +1,18 @@
+use sqlx::{Pool, Postgres};
+
+pub async fn find_user_id(pool: &Pool<Postgres>, did: &str) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT xata_id FROM users WHERE did = $1")
+        .bind(did)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn find_user_did(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT did FROM users WHERE xata_id = $1")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+}
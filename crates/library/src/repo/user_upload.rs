@@ -0,0 +1,71 @@
+use sqlx::{Pool, Postgres};
+
+/// An object of a storage provider indexed as one of the user's uploads,
+/// which makes it streamable through the Subsonic API.
+#[derive(Debug, Clone)]
+pub struct NewUpload<'a> {
+    pub user_id: &'a str,
+    pub track_id: &'a str,
+    pub storage_provider_id: &'a str,
+    pub r2_key: &'a str,
+    pub mime_type: &'a str,
+    pub file_size: u64,
+    pub original_filename: &'a str,
+    pub sample_rate: Option<u32>,
+}
+
+pub async fn has_upload(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    storage_provider_id: &str,
+    r2_key: &str,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+    SELECT EXISTS (
+      SELECT 1 FROM user_uploads
+      WHERE user_id = $1
+        AND storage_provider_id = $2
+        AND r2_key = $3
+    )
+    "#,
+    )
+    .bind(user_id)
+    .bind(storage_provider_id)
+    .bind(r2_key)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn create_user_upload(
+    pool: &Pool<Postgres>,
+    upload: &NewUpload<'_>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+    INSERT INTO user_uploads (
+      user_id,
+      track_id,
+      r2_key,
+      mime_type,
+      file_size,
+      original_filename,
+      sample_rate,
+      storage_provider_id
+    )
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+    "#,
+    )
+    .bind(upload.user_id)
+    .bind(upload.track_id)
+    .bind(upload.r2_key)
+    .bind(upload.mime_type)
+    .bind(i32::try_from(upload.file_size).unwrap_or(i32::MAX))
+    .bind(upload.original_filename)
+    .bind(upload.sample_rate.map(|rate| rate as i32))
+    .bind(upload.storage_provider_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
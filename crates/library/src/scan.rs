@@ -0,0 +1,505 @@
+use std::{
+    collections::{HashMap, HashSet},
+    env, fs,
+    io::ErrorKind,
+    path::Path,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Error};
+use chrono::{DateTime, Utc};
+use lofty::{
+    file::{AudioFile as _, TaggedFileExt},
+    probe::Probe,
+    tag::{Accessor, ItemKey, Tag},
+};
+use owo_colors::OwoColorize;
+use reqwest::Client;
+use rocksky_media::{
+    cover::upload_album_cover,
+    duration::get_track_duration,
+    lyrics::{read_lyrics, Lyrics},
+    partial::download_for_tags,
+};
+use sqlx::{Pool, Postgres};
+use tempfile::TempDir;
+
+use crate::{
+    repo::{
+        library_file::{
+            delete_library_files, find_library_file, find_library_files, list_library_paths,
+            move_library_file, save_library_file, LibraryFile,
+        },
+        library_scan::{finish_library_scan, start_library_scan, update_library_scan},
+        track::{find_track_id, update_track_lyrics},
+        user_upload::{create_user_upload, has_upload, NewUpload},
+    },
+    source::{mime_type, AudioFile, Source},
+    token::generate_token,
+};
+
+/// How long presigned URLs to bucket objects stay valid, tags are read
+/// right away.
+const PRESIGN_EXPIRY_SECS: u32 = 600;
+
+/// Number of files between two updates of the scan progress.
+const PROGRESS_INTERVAL: usize = 20;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ScanSummary {
+    pub indexed: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// A file linked to its track.
+struct Indexed {
+    track_id: String,
+    hash: String,
+    sample_rate: Option<u32>,
+}
+
+/// Indexes the audio files of `source` into the library of the user `did`.
+/// Objects of a bucket are also recorded as uploads of the user, so they can
+/// be streamed, and are skipped by later scans. Files of a local directory
+/// are kept in sync: unchanged ones are skipped and the ones which are gone
+/// are purged.
+pub async fn scan_library(
+    pool: &Pool<Postgres>,
+    did: &str,
+    user_id: &str,
+    source: &Source,
+) -> Result<ScanSummary, Error> {
+    let files = source.list().await?;
+    tracing::info!(files = files.len(), "Scanning library");
+
+    start_library_scan(pool, user_id).await?;
+    let result = index_files(pool, did, user_id, source, files).await;
+    finish_library_scan(pool, user_id).await?;
+    result
+}
+
+/// Indexes the audio files of `source` like [`scan_library`], as part of a
+/// scan whose status is reported by the caller.
+pub async fn index_source(
+    pool: &Pool<Postgres>,
+    did: &str,
+    user_id: &str,
+    source: &Source,
+) -> Result<ScanSummary, Error> {
+    let files = source.list().await?;
+    tracing::info!(files = files.len(), "Scanning library");
+    index_files(pool, did, user_id, source, files).await
+}
+
+async fn index_files(
+    pool: &Pool<Postgres>,
+    did: &str,
+    user_id: &str,
+    source: &Source,
+    files: Vec<AudioFile>,
+) -> Result<ScanSummary, Error> {
+    match source {
+        Source::Local(root) => {
+            let written: Vec<String> = files.into_iter().map(|file| file.key).collect();
+            let listed: HashSet<&str> = written.iter().map(String::as_str).collect();
+            let unlisted: Vec<String> = list_library_paths(pool, user_id, &root.to_string_lossy())
+                .await?
+                .into_iter()
+                .filter(|path| !listed.contains(path.as_str()))
+                .collect();
+            let removed = gone_paths(unlisted);
+            sync_local_files(pool, did, user_id, &written, &removed).await
+        }
+        Source::S3 { .. } => scan_objects(pool, did, user_id, source, &files).await,
+    }
+}
+
+/// The paths of `paths` which no longer exist. A file can be missing from a
+/// listing only because the walk couldn't read its directory, so it's purged
+/// once the filesystem confirms it's gone.
+fn gone_paths(paths: Vec<String>) -> Vec<String> {
+    paths
+        .into_iter()
+        .filter(|path| matches!(fs::metadata(path), Err(e) if e.kind() == ErrorKind::NotFound))
+        .collect()
+}
+
+async fn scan_objects(
+    pool: &Pool<Postgres>,
+    did: &str,
+    user_id: &str,
+    source: &Source,
+    files: &[AudioFile],
+) -> Result<ScanSummary, Error> {
+    let mut summary = ScanSummary::default();
+    for (i, file) in files.iter().enumerate() {
+        match scan_object(pool, did, user_id, source, file).await {
+            Ok(true) => summary.indexed += 1,
+            Ok(false) => summary.skipped += 1,
+            Err(e) => {
+                tracing::error!(file = %file.key.bright_red(), error = %e, "Failed to index file");
+                summary.failed += 1;
+            }
+        }
+        if (i + 1) % PROGRESS_INTERVAL == 0 {
+            update_library_scan(pool, user_id, i + 1).await?;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Returns false when the object was skipped.
+async fn scan_object(
+    pool: &Pool<Postgres>,
+    did: &str,
+    user_id: &str,
+    source: &Source,
+    file: &AudioFile,
+) -> Result<bool, Error> {
+    let Source::S3 {
+        provider_id,
+        bucket,
+        ..
+    } = source
+    else {
+        return Err(anyhow!("{} is not an object of a bucket", file.key));
+    };
+
+    if has_upload(pool, user_id, provider_id, &file.key).await? {
+        return Ok(false);
+    }
+
+    tracing::info!(key = %file.key.bright_green(), "Downloading tags");
+
+    let url = bucket
+        .presign_get(format!("/{}", file.key), PRESIGN_EXPIRY_SECS, None)
+        .await?;
+    let name = file.key.rsplit('/').next().unwrap_or(&file.key);
+    let temp_dir = TempDir::new()?;
+    let tmppath = temp_dir.path().join(name);
+    let client = Client::new();
+    download_for_tags(|| client.get(&url), &tmppath).await?;
+
+    let Some(indexed) = index_file(pool, did, &tmppath).await? else {
+        return Ok(false);
+    };
+
+    create_user_upload(
+        pool,
+        &NewUpload {
+            user_id,
+            track_id: &indexed.track_id,
+            storage_provider_id: provider_id,
+            r2_key: &file.key,
+            mime_type: mime_type(&file.key).unwrap_or("application/octet-stream"),
+            file_size: file.size,
+            original_filename: name,
+            sample_rate: indexed.sample_rate,
+        },
+    )
+    .await?;
+
+    Ok(true)
+}
+
+/// Syncs the local files of the user with the files at `written`, created or
+/// changed, and `removed`, which are purged. A removed file whose track is
+/// the one of a written file is moved there, matched on the track's hash, so
+/// renaming or moving files doesn't duplicate them.
+pub async fn sync_local_files(
+    pool: &Pool<Postgres>,
+    did: &str,
+    user_id: &str,
+    written: &[String],
+    removed: &[String],
+) -> Result<ScanSummary, Error> {
+    let written_paths: HashSet<&str> = written.iter().map(String::as_str).collect();
+    let removed: Vec<String> = removed
+        .iter()
+        .filter(|path| !written_paths.contains(path.as_str()))
+        .cloned()
+        .collect();
+
+    let mut removed_by_hash: HashMap<String, Vec<String>> = HashMap::new();
+    for file in find_library_files(pool, user_id, &removed).await? {
+        removed_by_hash
+            .entry(file.sha256)
+            .or_default()
+            .push(file.path);
+    }
+
+    let mut summary = ScanSummary::default();
+    for (i, path) in written.iter().enumerate() {
+        match sync_local_file(pool, did, user_id, path, &mut removed_by_hash).await {
+            Ok(true) => summary.indexed += 1,
+            Ok(false) => summary.skipped += 1,
+            Err(e) => {
+                tracing::error!(file = %path.bright_red(), error = %e, "Failed to index file");
+                summary.failed += 1;
+            }
+        }
+        if (i + 1) % PROGRESS_INTERVAL == 0 {
+            update_library_scan(pool, user_id, i + 1).await?;
+        }
+    }
+
+    let purged: Vec<String> = removed_by_hash.into_values().flatten().collect();
+    if !purged.is_empty() {
+        let count = delete_library_files(pool, user_id, &purged).await?;
+        tracing::info!(files = count, "Purged deleted files");
+    }
+
+    Ok(summary)
+}
+
+/// Returns false when the file was skipped.
+async fn sync_local_file(
+    pool: &Pool<Postgres>,
+    did: &str,
+    user_id: &str,
+    path: &str,
+    removed_by_hash: &mut HashMap<String, Vec<String>>,
+) -> Result<bool, Error> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            delete_library_files(pool, user_id, &[path.to_string()]).await?;
+            return Ok(false);
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let modified_at: DateTime<Utc> = metadata.modified()?.into();
+
+    let existing = find_library_file(pool, user_id, path).await?;
+    if existing
+        .as_ref()
+        .is_some_and(|file| is_unchanged(file, metadata.len(), modified_at))
+    {
+        return Ok(false);
+    }
+
+    tracing::info!(path = %path.bright_green(), "Reading file");
+    let Some(indexed) = index_file(pool, did, Path::new(path)).await? else {
+        if existing.is_some() {
+            delete_library_files(pool, user_id, &[path.to_string()]).await?;
+        }
+        return Ok(false);
+    };
+
+    if existing.is_none() {
+        if let Some(from) = take_moved(removed_by_hash, &indexed.hash) {
+            tracing::info!(from = %from, to = %path.bright_green(), "File moved");
+            move_library_file(pool, user_id, &from, path).await?;
+        }
+    }
+
+    save_library_file(
+        pool,
+        user_id,
+        &LibraryFile {
+            path: path.to_string(),
+            track_id: indexed.track_id,
+            sha256: indexed.hash,
+            file_size: i64::try_from(metadata.len()).unwrap_or(i64::MAX),
+            modified_at,
+        },
+    )
+    .await?;
+
+    Ok(true)
+}
+
+/// Whether the file on disk is still the one indexed, timestamps are stored
+/// with microseconds.
+fn is_unchanged(file: &LibraryFile, size: u64, modified_at: DateTime<Utc>) -> bool {
+    u64::try_from(file.file_size).ok() == Some(size)
+        && file.modified_at.timestamp_micros() == modified_at.timestamp_micros()
+}
+
+/// Takes a removed file with the track `hash`, the file a written one was
+/// moved from.
+fn take_moved(removed_by_hash: &mut HashMap<String, Vec<String>>, hash: &str) -> Option<String> {
+    let paths = removed_by_hash.get_mut(hash)?;
+    let path = paths.pop();
+    if paths.is_empty() {
+        removed_by_hash.remove(hash);
+    }
+    path
+}
+
+/// Links the file at `path` to its track, created from the file's tags when
+/// there's none yet. Returns `None` for files without tags.
+async fn index_file(
+    pool: &Pool<Postgres>,
+    did: &str,
+    path: &Path,
+) -> Result<Option<Indexed>, Error> {
+    let tagged_file = match Probe::open(path)?.read() {
+        Ok(tagged_file) => tagged_file,
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "Failed to read file");
+            return Ok(None);
+        }
+    };
+    let Some(tag) = tagged_file.primary_tag() else {
+        tracing::warn!(path = %path.display(), "No tag found in file");
+        return Ok(None);
+    };
+
+    let title = tag.get_string(&ItemKey::TrackTitle).unwrap_or_default();
+    let artist = tag.get_string(&ItemKey::TrackArtist).unwrap_or_default();
+    let album = tag.get_string(&ItemKey::AlbumTitle).unwrap_or_default();
+    let lyrics = read_lyrics(path, tag);
+
+    let hash = sha256::digest(format!("{} - {} - {}", title, artist, album).to_lowercase());
+
+    let track_id = match find_track_id(pool, &hash).await? {
+        Some(track_id) => {
+            tracing::info!(title = %title.bright_green(), "Track exists");
+            track_id
+        }
+        None => {
+            tracing::info!(title = %title.bright_green(), "Creating track");
+            create_track(did, path, tag, &lyrics).await?;
+            // The API saves the track once its record is written to the PDS.
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            find_track_id(pool, &hash)
+                .await?
+                .ok_or_else(|| anyhow!("Failed to create track {}", title))?
+        }
+    };
+
+    update_track_lyrics(pool, &track_id, &lyrics).await?;
+
+    Ok(Some(Indexed {
+        track_id,
+        hash,
+        sample_rate: tagged_file.properties().sample_rate(),
+    }))
+}
+
+/// Creates the track of a file through the Rocksky API, which also writes
+/// the song record to the user's PDS.
+async fn create_track(did: &str, path: &Path, tag: &Tag, lyrics: &Lyrics) -> Result<(), Error> {
+    let token = generate_token(did)?;
+
+    let album_artist = tag.get_string(&ItemKey::AlbumArtist).unwrap_or_default();
+    let album = tag.get_string(&ItemKey::AlbumTitle).unwrap_or_default();
+    let albumart_id = format!(
+        "{:x}",
+        md5::compute(format!("{} - {}", album_artist, album).to_lowercase())
+    );
+    let album_art = upload_album_cover(albumart_id, tag.pictures(), &token).await?;
+    let duration = get_track_duration(path).await?;
+
+    let url = env::var("ROCKSKY_API_URL").unwrap_or_else(|_| "https://api.rocksky.app".to_string());
+    let response = Client::new()
+        .post(format!("{}/tracks", url))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({
+            "title": tag.get_string(&ItemKey::TrackTitle),
+            "album": tag.get_string(&ItemKey::AlbumTitle),
+            "artist": tag.get_string(&ItemKey::TrackArtist),
+            "albumArtist": tag
+                .get_string(&ItemKey::AlbumArtist)
+                .or(tag.get_string(&ItemKey::TrackArtist)),
+            "duration": duration,
+            "trackNumber": tag.track(),
+            "releaseDate": tag
+                .get_string(&ItemKey::OriginalReleaseDate)
+                .filter(|date| date.contains('-')),
+            "year": tag.year(),
+            "discNumber": tag.disk().filter(|disc| *disc != 0).unwrap_or(1),
+            "composer": tag.get_string(&ItemKey::Composer),
+            "albumArt": album_art.map(|name| format!("https://cdn.rocksky.app/covers/{}", name)),
+            "lyrics": lyrics.plain,
+            "copyrightMessage": tag.get_string(&ItemKey::CopyrightMessage),
+        }))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    tracing::info!(status = %response.status(), "Track saved");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_unchanged() {
+        let modified_at = DateTime::from_timestamp(1_700_000_000, 123_456_789).unwrap();
+        let file = LibraryFile {
+            path: "/music/song.flac".into(),
+            track_id: "track".into(),
+            sha256: "hash".into(),
+            file_size: 1024,
+            modified_at: DateTime::from_timestamp(1_700_000_000, 123_456_000).unwrap(),
+        };
+
+        assert!(is_unchanged(&file, 1024, modified_at));
+        assert!(!is_unchanged(&file, 2048, modified_at));
+        assert!(!is_unchanged(
+            &file,
+            1024,
+            DateTime::from_timestamp(1_700_000_001, 0).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_take_moved() {
+        let mut removed = HashMap::from([(
+            "hash".to_string(),
+            vec!["/music/a.flac".to_string(), "/music/b.flac".to_string()],
+        )]);
+
+        assert_eq!(take_moved(&mut removed, "other"), None);
+        assert_eq!(
+            take_moved(&mut removed, "hash"),
+            Some("/music/b.flac".into())
+        );
+        assert_eq!(
+            take_moved(&mut removed, "hash"),
+            Some("/music/a.flac".into())
+        );
+        assert!(removed.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unreadable_dir_is_not_purged() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join("album")).unwrap();
+        fs::write(root.join("album/01.flac"), b"fLaC").unwrap();
+        // A symlink to itself can't be read, whatever the permissions of the
+        // user running the tests.
+        std::os::unix::fs::symlink(root.join("locked"), root.join("locked")).unwrap();
+
+        let indexed = [
+            root.join("album/01.flac"),
+            root.join("locked/01.flac"),
+            root.join("album/02.flac"),
+        ]
+        .map(|path| path.to_string_lossy().to_string());
+        let listed: Vec<String> = Source::Local(root.to_path_buf())
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|file| file.key)
+            .collect();
+        let unlisted = indexed
+            .into_iter()
+            .filter(|path| !listed.contains(path))
+            .collect();
+
+        assert_eq!(
+            gone_paths(unlisted),
+            vec![root.join("album/02.flac").to_string_lossy().to_string()]
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::{env, path::PathBuf};
+
+use anyhow::{anyhow, Error};
+use rocksky_navidrome::s3::decrypt_credential;
+use s3::{creds::Credentials, region::Region, Bucket};
+use sqlx::{Pool, Postgres};
+use walkdir::WalkDir;
+
+use crate::repo::storage_provider::find_storage_provider;
+
+/// The formats uploads accept, with their MIME types.
+pub const AUDIO_TYPES: [(&str, &str); 9] = [
+    ("mp3", "audio/mpeg"),
+    ("flac", "audio/flac"),
+    ("m4a", "audio/mp4"),
+    ("mp4", "audio/mp4"),
+    ("ogg", "audio/ogg"),
+    ("opus", "audio/ogg"),
+    ("wav", "audio/wav"),
+    ("aiff", "audio/aiff"),
+    ("aif", "audio/aiff"),
+];
+
+/// Where the files of a library scan come from.
+pub enum Source {
+    /// A directory on the machine running the scan.
+    Local(PathBuf),
+    /// The bucket of one of the user's storage providers, objects under
+    /// `prefix` only.
+    S3 {
+        provider_id: String,
+        bucket: Box<Bucket>,
+        prefix: String,
+    },
+}
+
+/// An audio file found by a scan, `key` is its path for local directories
+/// and its object key for buckets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioFile {
+    pub key: String,
+    pub size: u64,
+}
+
+impl Source {
+    /// The bucket of the storage provider `provider_id` of the user.
+    pub async fn s3(
+        pool: &Pool<Postgres>,
+        provider_id: &str,
+        user_id: &str,
+        prefix: &str,
+    ) -> Result<Self, Error> {
+        let provider = find_storage_provider(pool, provider_id, user_id)
+            .await?
+            .ok_or_else(|| anyhow!("Storage provider {} not found", provider_id))?;
+
+        let key = env::var("STORAGE_ENCRYPTION_KEY")
+            .map_err(|_| anyhow!("STORAGE_ENCRYPTION_KEY is not set"))?;
+        let credentials = Credentials::new(
+            Some(&decrypt_credential(&provider.access_key, &key)?),
+            Some(&decrypt_credential(&provider.secret_key, &key)?),
+            None,
+            None,
+            None,
+        )?;
+        let region = Region::Custom {
+            region: provider.region,
+            endpoint: provider.endpoint,
+        };
+
+        Ok(Source::S3 {
+            provider_id: provider.xata_id,
+            bucket: Bucket::new(&provider.bucket, region, credentials)?.with_path_style(),
+            prefix: prefix.trim_start_matches('/').to_string(),
+        })
+    }
+
+    pub async fn list(&self) -> Result<Vec<AudioFile>, Error> {
+        let mut files = match self {
+            Source::Local(root) => {
+                let mut files = Vec::new();
+                // An unreadable file, a dangling link or a link loop only
+                // leaves that entry out of the scan.
+                for entry in WalkDir::new(root).follow_links(true) {
+                    let entry = match entry {
+                        Ok(entry) => entry,
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Failed to walk directory");
+                            continue;
+                        }
+                    };
+                    if !entry.file_type().is_file() {
+                        continue;
+                    }
+                    let metadata = match entry.metadata() {
+                        Ok(metadata) => metadata,
+                        Err(e) => {
+                            tracing::warn!(path = %entry.path().display(), error = %e, "Failed to read file metadata");
+                            continue;
+                        }
+                    };
+                    files.push(AudioFile {
+                        key: entry.path().to_string_lossy().to_string(),
+                        size: metadata.len(),
+                    });
+                }
+                files
+            }
+            Source::S3 { bucket, prefix, .. } => bucket
+                .list(prefix.clone(), None)
+                .await?
+                .into_iter()
+                .flat_map(|page| page.contents)
+                .map(|object| AudioFile {
+                    key: object.key,
+                    size: object.size,
+                })
+                .collect(),
+        };
+
+        files.retain(|file| mime_type(&file.key).is_some());
+        files.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(files)
+    }
+}
+
+/// MIME type of an audio file from its extension, `None` for files which
+/// aren't audio.
+pub fn mime_type(name: &str) -> Option<&'static str> {
+    let (_, ext) = name.rsplit_once('.')?;
+    AUDIO_TYPES
+        .iter()
+        .find(|(audio_ext, _)| ext.eq_ignore_ascii_case(audio_ext))
+        .map(|(_, mime)| *mime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mime_type() {
+        assert_eq!(mime_type("Artist/Album/01 - Song.FLAC"), Some("audio/flac"));
+        assert_eq!(mime_type("song.m4a"), Some("audio/mp4"));
+        assert_eq!(mime_type("cover.jpg"), None);
+        assert_eq!(mime_type("README"), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_list_skips_bad_entries() {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join("album")).unwrap();
+        std::fs::write(root.join("album/01.flac"), b"fLaC").unwrap();
+        std::fs::write(root.join("album/cover.jpg"), b"").unwrap();
+        symlink(root.join("gone.mp3"), root.join("album/dangling.mp3")).unwrap();
+        symlink(root, root.join("album/loop")).unwrap();
+
+        let files = Source::Local(root.to_path_buf()).list().await.unwrap();
+        assert_eq!(
+            files,
+            vec![AudioFile {
+                key: root.join("album/01.flac").to_string_lossy().to_string(),
+                size: 4,
+            }]
+        );
+    }
+}
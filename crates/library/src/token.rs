@@ -0,0 +1,30 @@
+use std::env;
+
+use anyhow::Error;
+use jsonwebtoken::{EncodingKey, Header};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+struct Claims {
+    exp: usize,
+    iat: usize,
+    did: String,
+}
+
+/// Token the Rocksky API accepts on behalf of the user `did`.
+pub fn generate_token(did: &str) -> Result<String, Error> {
+    let secret = env::var("JWT_SECRET").map_err(|_| Error::msg("JWT_SECRET is not set"))?;
+    let now = chrono::Utc::now().timestamp() as usize;
+    let claims = Claims {
+        exp: now + 3600,
+        iat: now,
+        did: did.to_string(),
+    };
+
+    jsonwebtoken::encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_ref()),
+    )
+    .map_err(Into::into)
+}
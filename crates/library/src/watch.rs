@@ -0,0 +1,352 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Error};
+use notify::{
+    event::{AccessKind, AccessMode, ModifyKind, RemoveKind, RenameMode},
+    Event, EventKind, RecursiveMode,
+};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult};
+use owo_colors::OwoColorize;
+use sqlx::{Pool, Postgres};
+use tokio::sync::mpsc::unbounded_channel;
+use walkdir::WalkDir;
+
+use crate::{
+    repo::{
+        library_file::list_library_paths,
+        library_scan::{finish_library_scan, start_library_scan},
+    },
+    scan::{scan_library, sync_local_files, ScanSummary},
+    source::{mime_type, Source},
+};
+
+/// How long the directory has to be quiet before its changes are synced, a
+/// ripper writes a whole album in a burst.
+const DEBOUNCE: Duration = Duration::from_secs(3);
+
+/// Longest changes wait while writes keep coming.
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// How long a file has to be quiet before its events are reported, so files
+/// still being written aren't read.
+const SETTLE: Duration = Duration::from_secs(1);
+
+/// A change of the watched directory.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// An audio file was written, or moved in.
+    Write(PathBuf),
+    /// An audio file was deleted, or moved out.
+    Remove(PathBuf),
+    /// A directory was deleted, or moved out, with the files below it.
+    RemoveDir(PathBuf),
+    /// Events were dropped, the whole directory has to be scanned again.
+    Rescan,
+}
+
+/// The changes of a burst, the last one of a file wins.
+#[derive(Debug, Default, PartialEq)]
+pub struct Batch {
+    files: BTreeMap<PathBuf, bool>,
+    removed_dirs: Vec<PathBuf>,
+    rescan: bool,
+}
+
+impl Batch {
+    pub fn record(&mut self, change: Change) {
+        match change {
+            Change::Write(path) => {
+                self.files.insert(path, true);
+            }
+            Change::Remove(path) => {
+                self.files.insert(path, false);
+            }
+            Change::RemoveDir(dir) => {
+                // Files written below it before are gone with it.
+                self.files.retain(|path, _| !path.starts_with(&dir));
+                if !self
+                    .removed_dirs
+                    .iter()
+                    .any(|removed| dir.starts_with(removed))
+                {
+                    self.removed_dirs
+                        .retain(|removed| !removed.starts_with(&dir));
+                    self.removed_dirs.push(dir);
+                }
+            }
+            Change::Rescan => self.rescan = true,
+        }
+    }
+
+    fn paths(&self, written: bool) -> Vec<String> {
+        self.files
+            .iter()
+            .filter(|(_, w)| **w == written)
+            .map(|(path, _)| path.to_string_lossy().to_string())
+            .collect()
+    }
+}
+
+/// Keeps the library of the user in sync with the local directory `root`:
+/// scans it once, then syncs the files written, moved or deleted as they
+/// change. Only returns on errors.
+pub async fn watch_library(
+    pool: &Pool<Postgres>,
+    did: &str,
+    user_id: &str,
+    root: &Path,
+) -> Result<(), Error> {
+    let (tx, mut rx) = unbounded_channel();
+    // The watch is set before the scan, so changes made meanwhile aren't
+    // missed.
+    let mut debouncer = new_debouncer(
+        SETTLE,
+        None,
+        move |result: DebounceEventResult| match result {
+            Ok(events) => {
+                for change in events.iter().flat_map(|event| changes(event)) {
+                    let _ = tx.send(change);
+                }
+            }
+            Err(errors) => {
+                for e in errors {
+                    tracing::warn!(error = %e, "Failed to watch library");
+                }
+                let _ = tx.send(Change::Rescan);
+            }
+        },
+    )?;
+    debouncer.watch(root, RecursiveMode::Recursive)?;
+    tracing::info!(path = %root.display().bright_green(), "Watching library");
+
+    let summary = scan_library(pool, did, user_id, &Source::Local(root.to_path_buf())).await?;
+    log_summary(&summary);
+
+    while let Some(change) = rx.recv().await {
+        let mut batch = Batch::default();
+        batch.record(change);
+
+        let started = Instant::now();
+        loop {
+            let wait = DEBOUNCE.min(MAX_DELAY.saturating_sub(started.elapsed()));
+            match tokio::time::timeout(wait, rx.recv()).await {
+                Ok(Some(change)) => batch.record(change),
+                Ok(None) | Err(_) => break,
+            }
+        }
+
+        if let Err(e) = sync_batch(pool, did, user_id, root, batch).await {
+            tracing::error!(error = %e, "Failed to sync library changes");
+        }
+    }
+
+    Err(anyhow!("Stopped watching {}", root.display()))
+}
+
+async fn sync_batch(
+    pool: &Pool<Postgres>,
+    did: &str,
+    user_id: &str,
+    root: &Path,
+    batch: Batch,
+) -> Result<(), Error> {
+    if batch.rescan {
+        tracing::warn!("Library events were dropped, rescanning library");
+        let summary = scan_library(pool, did, user_id, &Source::Local(root.to_path_buf())).await?;
+        log_summary(&summary);
+        return Ok(());
+    }
+
+    let written = batch.paths(true);
+    let mut removed = batch.paths(false);
+    for dir in &batch.removed_dirs {
+        removed.extend(list_library_paths(pool, user_id, &dir.to_string_lossy()).await?);
+    }
+
+    start_library_scan(pool, user_id).await?;
+    let result = sync_local_files(pool, did, user_id, &written, &removed).await;
+    finish_library_scan(pool, user_id).await?;
+
+    log_summary(&result?);
+    Ok(())
+}
+
+fn log_summary(summary: &ScanSummary) {
+    tracing::info!(
+        indexed = summary.indexed,
+        skipped = summary.skipped,
+        failed = summary.failed,
+        "Library synced"
+    );
+}
+
+/// The changes of the library `event` is about.
+fn changes(event: &Event) -> Vec<Change> {
+    if event.need_rescan() {
+        return vec![Change::Rescan];
+    }
+    match event.kind {
+        EventKind::Create(_)
+        | EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Any)
+        | EventKind::Access(AccessKind::Close(AccessMode::Write)) => {
+            event.paths.iter().flat_map(|path| written(path)).collect()
+        }
+        EventKind::Remove(kind) => {
+            let dir = match kind {
+                RemoveKind::File => Some(false),
+                RemoveKind::Folder => Some(true),
+                _ => None,
+            };
+            event
+                .paths
+                .iter()
+                .filter_map(|path| removed(path, dir))
+                .collect()
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => match event.paths.as_slice() {
+            [from, to] => removed(from, None).into_iter().chain(written(to)).collect(),
+            _ => Vec::new(),
+        },
+        // Moved in or out of the directory.
+        EventKind::Modify(ModifyKind::Name(_)) => event
+            .paths
+            .iter()
+            .flat_map(|path| match path.exists() {
+                true => written(path),
+                false => removed(path, None).into_iter().collect(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The audio files written at `path`, the ones below it for directories
+/// created or moved in.
+fn written(path: &Path) -> Vec<Change> {
+    if !path.is_dir() {
+        return match mime_type(&path.to_string_lossy()) {
+            Some(_) => vec![Change::Write(path.to_path_buf())],
+            None => Vec::new(),
+        };
+    }
+
+    WalkDir::new(path)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to walk directory");
+                None
+            }
+        })
+        .filter(|entry| {
+            entry.file_type().is_file() && mime_type(&entry.path().to_string_lossy()).is_some()
+        })
+        .map(|entry| Change::Write(entry.into_path()))
+        .collect()
+}
+
+/// The removal of `path`, which is gone so it's taken for a directory unless
+/// named like an audio file when `dir` doesn't tell.
+fn removed(path: &Path, dir: Option<bool>) -> Option<Change> {
+    let audio = mime_type(&path.to_string_lossy()).is_some();
+    if dir.unwrap_or(!audio) {
+        Some(Change::RemoveDir(path.to_path_buf()))
+    } else {
+        audio.then(|| Change::Remove(path.to_path_buf()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_record() {
+        let mut batch = Batch::default();
+        batch.record(Change::Write("/music/a/1.flac".into()));
+        batch.record(Change::Write("/music/b/1.flac".into()));
+        batch.record(Change::Remove("/music/b/1.flac".into()));
+        batch.record(Change::Write("/music/c/1.flac".into()));
+        batch.record(Change::RemoveDir("/music/c/cd1".into()));
+        batch.record(Change::RemoveDir("/music/c".into()));
+        batch.record(Change::RemoveDir("/music/c/cd2".into()));
+        batch.record(Change::Write("/music/c/2.flac".into()));
+
+        assert_eq!(
+            batch.paths(true),
+            vec!["/music/a/1.flac".to_string(), "/music/c/2.flac".to_string()]
+        );
+        assert_eq!(batch.paths(false), vec!["/music/b/1.flac".to_string()]);
+        assert_eq!(batch.removed_dirs, vec![PathBuf::from("/music/c")]);
+        assert!(!batch.rescan);
+
+        batch.record(Change::Rescan);
+        assert!(batch.rescan);
+    }
+
+    #[test]
+    fn test_changes() {
+        use notify::event::{CreateKind, Flag};
+
+        let event = |kind, paths: &[&str]| {
+            paths
+                .iter()
+                .fold(Event::new(kind), |event, path| event.add_path(path.into()))
+        };
+
+        assert_eq!(
+            changes(&event(
+                EventKind::Create(CreateKind::File),
+                &["/music/a/1.flac", "/music/a/cover.jpg"]
+            )),
+            vec![Change::Write("/music/a/1.flac".into())]
+        );
+        assert_eq!(
+            changes(&event(
+                EventKind::Remove(RemoveKind::File),
+                &["/music/a/1.flac", "/music/a/cover.jpg"]
+            )),
+            vec![Change::Remove("/music/a/1.flac".into())]
+        );
+        assert_eq!(
+            changes(&event(EventKind::Remove(RemoveKind::Folder), &["/music/a"])),
+            vec![Change::RemoveDir("/music/a".into())]
+        );
+        assert_eq!(
+            changes(&event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                &["/music/a/1.flac", "/music/b/1.flac"]
+            )),
+            vec![
+                Change::Remove("/music/a/1.flac".into()),
+                Change::Write("/music/b/1.flac".into())
+            ]
+        );
+        assert_eq!(
+            changes(&event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::From)),
+                &["/music/a"]
+            )),
+            vec![Change::RemoveDir("/music/a".into())]
+        );
+        assert_eq!(
+            changes(&event(EventKind::Any, &["/music/a"]).set_flag(Flag::Rescan)),
+            vec![Change::Rescan]
+        );
+    }
+
+    #[test]
+    fn test_batch_record_keeps_sibling_directories() {
+        let mut batch = Batch::default();
+        batch.record(Change::Write("/music/album 2/1.flac".into()));
+        batch.record(Change::RemoveDir("/music/album".into()));
+
+        assert_eq!(batch.paths(true), vec!["/music/album 2/1.flac".to_string()]);
+    }
+}
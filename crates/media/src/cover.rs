@@ -0,0 +1,62 @@
+use anyhow::Error;
+use lofty::picture::{MimeType, Picture};
+use reqwest::{multipart, Client};
+
+/// Uploads the first picture of a file as the cover `name`, returns the file
+/// name it's served under or `None` when the file has no usable picture.
+pub async fn upload_album_cover(
+    name: String,
+    pictures: &[Picture],
+    token: &str,
+) -> Result<Option<String>, Error> {
+    if pictures.is_empty() {
+        return Ok(None);
+    }
+
+    let picture = &pictures[0];
+
+    let buffer = match picture.mime_type() {
+        Some(MimeType::Jpeg) => Some(picture.data().to_vec()),
+        Some(MimeType::Png) => Some(picture.data().to_vec()),
+        Some(MimeType::Gif) => Some(picture.data().to_vec()),
+        Some(MimeType::Bmp) => Some(picture.data().to_vec()),
+        Some(MimeType::Tiff) => Some(picture.data().to_vec()),
+        _ => None,
+    };
+
+    if buffer.is_none() {
+        return Ok(None);
+    }
+
+    let buffer = buffer.unwrap();
+
+    let ext = match picture.mime_type() {
+        Some(MimeType::Jpeg) => "jpg",
+        Some(MimeType::Png) => "png",
+        Some(MimeType::Gif) => "gif",
+        Some(MimeType::Bmp) => "bmp",
+        Some(MimeType::Tiff) => "tiff",
+        _ => {
+            return Ok(None);
+        }
+    };
+
+    let name = format!("{}.{}", name, ext);
+
+    let part = multipart::Part::bytes(buffer).file_name(name.clone());
+    let form = multipart::Form::new().part("file", part);
+    let client = Client::new();
+
+    const URL: &str = "https://uploads.rocksky.app";
+
+    let response = client
+        .post(URL)
+        .header("Authorization", format!("Bearer {}", token))
+        .multipart(form)
+        .send()
+        .await?;
+
+    tracing::info!(status = %response.status(), "Cover uploaded");
+
+    Ok(Some(name))
+}
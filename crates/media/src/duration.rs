@@ -0,0 +1,43 @@
+use std::path::Path;
+
+use anyhow::Error;
+use symphonia::core::{
+    formats::FormatOptions, io::MediaSourceStream, meta::MetadataOptions, probe::Hint,
+};
+
+/// Duration of the audio file at `path` in milliseconds, 0 when it can't be
+/// probed.
+pub async fn get_track_duration(path: &Path) -> Result<u64, Error> {
+    let duration = 0;
+    let media_source =
+        MediaSourceStream::new(Box::new(std::fs::File::open(path)?), Default::default());
+    let mut hint = Hint::new();
+
+    if let Some(extension) = path.extension() {
+        if let Some(extension) = extension.to_str() {
+            hint.with_extension(extension);
+        }
+    }
+
+    let meta_opts = MetadataOptions::default();
+    let format_opts = FormatOptions::default();
+
+    let probed =
+        match symphonia::default::get_probe().format(&hint, media_source, &format_opts, &meta_opts)
+        {
+            Ok(probed) => probed,
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "Failed to probe media");
+                return Ok(duration);
+            }
+        };
+
+    if let Some(track) = probed.format.tracks().first() {
+        if let Some(duration) = track.codec_params.n_frames {
+            if let Some(sample_rate) = track.codec_params.sample_rate {
+                return Ok((duration as f64 / sample_rate as f64) as u64 * 1000);
+            }
+        }
+    }
+    Ok(duration)
+}
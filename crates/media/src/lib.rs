@@ -0,0 +1,8 @@
+//! Reading audio files into tracks and indexing their paths, shared by the
+//! Dropbox, Google Drive and local library scanners.
+
+pub mod cover;
+pub mod duration;
+pub mod lyrics;
+pub mod partial;
+pub mod path;
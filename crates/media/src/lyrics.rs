@@ -0,0 +1,155 @@
+use std::{fs::File, path::Path};
+
+use lofty::{
+    config::ParseOptions,
+    file::AudioFile,
+    id3::v2::{Frame, SynchronizedTextFrame, TimestampFormat},
+    mpeg::MpegFile,
+    tag::{ItemKey, Tag},
+};
+
+/// Lyrics embedded in an audio file. `synced` is stored in LRC format.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Lyrics {
+    pub plain: Option<String>,
+    pub synced: Option<String>,
+}
+
+/// Reads the embedded lyrics of a file: USLT (ID3v2), LYRICS (Vorbis
+/// comments) or ©lyr (MP4) through the generic tag, and SYLT frames which
+/// lofty only exposes through the MPEG specific API.
+///
+/// Many taggers write LRC into the unsynchronized lyrics field, so when the
+/// plain lyrics carry timestamps they are used as the synced lyrics as well.
+pub fn read_lyrics(path: &Path, tag: &Tag) -> Lyrics {
+    let text = tag
+        .get_string(&ItemKey::Lyrics)
+        .map(|lyrics| lyrics.trim().to_string())
+        .filter(|lyrics| !lyrics.is_empty());
+
+    let synced = read_sylt(path).or_else(|| text.clone().filter(|text| is_lrc(text)));
+    let plain = match (&text, &synced) {
+        (Some(text), _) if is_lrc(text) => Some(strip_lrc(text)),
+        (Some(text), _) => Some(text.clone()),
+        (None, Some(synced)) => Some(strip_lrc(synced)),
+        (None, None) => None,
+    };
+
+    Lyrics { plain, synced }
+}
+
+fn read_sylt(path: &Path) -> Option<String> {
+    let is_mp3 = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mp3"));
+    if !is_mp3 {
+        return None;
+    }
+
+    let mut file = File::open(path).ok()?;
+    let mpeg = MpegFile::read_from(&mut file, ParseOptions::new()).ok()?;
+    let id3v2 = mpeg.id3v2()?;
+
+    id3v2.into_iter().find_map(|frame| match frame {
+        Frame::Binary(binary) if binary.id().as_str() == "SYLT" => {
+            let sylt = SynchronizedTextFrame::parse(&binary.data, binary.flags()).ok()?;
+            // Timestamps in MPEG frames can't be converted without decoding
+            // the stream, only absolute times are supported.
+            if sylt.timestamp_format != TimestampFormat::MS || sylt.content.is_empty() {
+                return None;
+            }
+            Some(format_lrc(&sylt.content))
+        }
+        _ => None,
+    })
+}
+
+/// Whether `text` contains at least one `[mm:ss.xx]` timestamped line.
+pub fn is_lrc(text: &str) -> bool {
+    text.lines()
+        .any(|line| split_timestamps(line.trim()).0.is_some())
+}
+
+/// Removes the LRC timestamps and metadata tags (`[ar:...]`, `[offset:...]`).
+pub fn strip_lrc(text: &str) -> String {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            match split_timestamps(line) {
+                (Some(_), text) => Some(text.trim().to_string()),
+                (None, _) if line.starts_with('[') && line.ends_with(']') => None,
+                (None, _) => Some(line.to_string()),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Formats `(milliseconds, text)` pairs as LRC lines.
+pub fn format_lrc(lines: &[(u32, String)]) -> String {
+    lines
+        .iter()
+        .map(|(ms, text)| {
+            format!(
+                "[{:02}:{:02}.{:02}]{}",
+                ms / 60_000,
+                (ms / 1000) % 60,
+                (ms % 1000) / 10,
+                text.trim()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Splits the leading `[mm:ss.xx]` timestamp of a line from its text, if any.
+fn split_timestamps(line: &str) -> (Option<&str>, &str) {
+    let Some(rest) = line.strip_prefix('[') else {
+        return (None, line);
+    };
+    let Some(end) = rest.find(']') else {
+        return (None, line);
+    };
+    let timestamp = &rest[..end];
+    let is_timestamp = timestamp.split_once(':').is_some_and(|(minutes, seconds)| {
+        !minutes.is_empty()
+            && minutes.chars().all(|c| c.is_ascii_digit())
+            && seconds.parse::<f64>().is_ok()
+    });
+    if !is_timestamp {
+        return (None, line);
+    }
+
+    // Lines can repeat timestamps: `[00:12.00][00:45.00]chorus`.
+    (Some(timestamp), split_timestamps(&rest[end + 1..]).1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_lrc() {
+        assert!(is_lrc("[ar:Artist]\n[00:12.34]Hello\n[00:15.00]World"));
+        assert!(!is_lrc("Hello\nWorld"));
+        assert!(!is_lrc("[Chorus]\nHello"));
+    }
+
+    #[test]
+    fn test_strip_lrc() {
+        assert_eq!(
+            strip_lrc("[ti:Song]\n[00:12.34]Hello\n[00:15.00][01:15.00]World"),
+            "Hello\nWorld"
+        );
+    }
+
+    #[test]
+    fn test_format_lrc() {
+        assert_eq!(
+            format_lrc(&[(12_345, "Hello".into()), (75_000, " World ".into())]),
+            "[00:12.34]Hello\n[01:15.00]World"
+        );
+    }
+}
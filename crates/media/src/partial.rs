@@ -0,0 +1,608 @@
+use std::{
+    fs::File,
+    io::{Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use anyhow::{anyhow, Error};
+use lofty::{file::TaggedFileExt, probe::Probe};
+use reqwest::{header::RANGE, RequestBuilder, StatusCode};
+
+/// Bytes fetched from the start of a file, enough for the tags of most files
+/// and the first audio frames.
+const HEAD_SIZE: u64 = 256 * 1024;
+/// Bytes fetched from the end of a file, for ID3v1/APE tags and the last
+/// frames stream properties are computed from.
+const TAIL_SIZE: u64 = 64 * 1024;
+/// Smallest range requested, walking headers one by one would otherwise
+/// take a request each.
+const MIN_RANGE: u64 = 64 * 1024;
+/// Past this much metadata a full download is as good.
+const MAX_METADATA: u64 = 32 * 1024 * 1024;
+
+/// Writes to `path` what is needed to read the tags and the duration of the
+/// file `request` downloads. For ID3, FLAC, MP4 and Ogg files that's only the
+/// byte ranges holding them, the rest of the file is left sparse. The whole
+/// file is downloaded when the format is another one or when the tags can't
+/// be read from the ranges.
+pub async fn download_for_tags<F>(request: F, path: &Path) -> Result<(), Error>
+where
+    F: Fn() -> RequestBuilder,
+{
+    match download_tag_ranges(&request, path).await {
+        Ok(true) => match Probe::open(path)?.read() {
+            Ok(tagged_file) if tagged_file.primary_tag().is_some() => return Ok(()),
+            _ => {
+                tracing::info!(path = %path.display(), "Tags not found in ranges, downloading file")
+            }
+        },
+        Ok(false) => {}
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "Failed to fetch tag ranges, downloading file")
+        }
+    }
+
+    let bytes = request().send().await?.error_for_status()?.bytes().await?;
+    File::create(path)?.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Returns false when the layout of the file isn't known.
+async fn download_tag_ranges<F>(request: &F, path: &Path) -> Result<bool, Error>
+where
+    F: Fn() -> RequestBuilder,
+{
+    let mut file = RemoteFile::open(request).await?;
+    if file.complete {
+        file.write_to(path)?;
+        return Ok(true);
+    }
+
+    let size = file.size;
+    let mut offset = 0;
+    if let Some(tag_size) = id3v2_size(&file.read(0, 10)) {
+        offset = tag_size;
+        file.ensure(0, offset + HEAD_SIZE).await?;
+    }
+
+    let magic = file.read(offset, 12);
+    let known = if magic.starts_with(b"fLaC") {
+        let end = flac_metadata_end(&mut file, offset + 4).await?;
+        file.ensure(offset, end + MIN_RANGE).await?;
+        true
+    } else if magic.get(4..8) == Some(b"ftyp") {
+        mp4_moov(&mut file, offset).await?
+    } else if magic.starts_with(b"OggS") {
+        let end = ogg_headers_end(&mut file, offset).await?;
+        file.ensure(offset, end + MIN_RANGE).await?;
+        true
+    } else {
+        is_mpeg_frame(&magic)
+    };
+
+    if !known {
+        return Ok(false);
+    }
+
+    file.ensure(size.saturating_sub(TAIL_SIZE), size).await?;
+    let tail = file.read(size.saturating_sub(TAIL_SIZE), TAIL_SIZE);
+    if let Some(ape_size) = ape_tag_size(&tail) {
+        file.ensure(size.saturating_sub(ape_size + 128), size)
+            .await?;
+    }
+
+    file.write_to(path)?;
+    Ok(true)
+}
+
+/// The byte ranges of a remote file fetched so far.
+struct RemoteFile<'a, F> {
+    request: &'a F,
+    size: u64,
+    complete: bool,
+    chunks: Vec<(u64, Vec<u8>)>,
+}
+
+impl<'a, F> RemoteFile<'a, F>
+where
+    F: Fn() -> RequestBuilder,
+{
+    async fn open(request: &'a F) -> Result<Self, Error> {
+        let mut file = RemoteFile {
+            request,
+            size: u64::MAX,
+            complete: false,
+            chunks: Vec::new(),
+        };
+        file.fetch(0, HEAD_SIZE).await?;
+        Ok(file)
+    }
+
+    async fn fetch(&mut self, start: u64, end: u64) -> Result<(), Error> {
+        let res = (self.request)()
+            .header(RANGE, format!("bytes={}-{}", start, end - 1))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        match res.status() {
+            StatusCode::PARTIAL_CONTENT => {
+                let total = res
+                    .headers()
+                    .get("content-range")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(content_range_size)
+                    .ok_or_else(|| anyhow!("Missing Content-Range"))?;
+                self.size = total;
+                self.chunks.push((start, res.bytes().await?.to_vec()));
+            }
+            // The range was ignored, that's the whole file.
+            _ => {
+                let bytes = res.bytes().await?.to_vec();
+                self.size = bytes.len() as u64;
+                self.complete = true;
+                self.chunks = vec![(0, bytes)];
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetches what's missing of `start..end`.
+    async fn ensure(&mut self, start: u64, end: u64) -> Result<(), Error> {
+        let end = end.min(self.size);
+        if end.saturating_sub(start) > MAX_METADATA {
+            return Err(anyhow!("Range too large: {}-{}", start, end));
+        }
+        if let Some(missing) = self.missing(start, end) {
+            let fetch_end = end.max(missing + MIN_RANGE).min(self.size);
+            self.fetch(missing, fetch_end).await?;
+        }
+        Ok(())
+    }
+
+    /// First offset of `start..end` not fetched yet.
+    fn missing(&self, start: u64, end: u64) -> Option<u64> {
+        if self.complete {
+            return None;
+        }
+        let mut pos = start;
+        while pos < end {
+            let Some(next) = self
+                .chunks
+                .iter()
+                .find(|(offset, data)| *offset <= pos && pos < offset + data.len() as u64)
+                .map(|(offset, data)| offset + data.len() as u64)
+            else {
+                return Some(pos);
+            };
+            pos = next;
+        }
+        None
+    }
+
+    /// The fetched bytes of `start..start + len`, zeros where nothing was
+    /// fetched, truncated at the end of the file.
+    fn read(&self, start: u64, len: u64) -> Vec<u8> {
+        let end = (start + len).min(self.size);
+        let mut buf = vec![0; end.saturating_sub(start) as usize];
+        for (offset, data) in &self.chunks {
+            let from = start.max(*offset);
+            let to = end.min(offset + data.len() as u64);
+            if from < to {
+                buf[(from - start) as usize..(to - start) as usize]
+                    .copy_from_slice(&data[(from - offset) as usize..(to - offset) as usize]);
+            }
+        }
+        buf
+    }
+
+    fn write_to(&self, path: &Path) -> Result<(), Error> {
+        let mut file = File::create(path)?;
+        file.set_len(self.size)?;
+        for (offset, data) in &self.chunks {
+            file.seek(SeekFrom::Start(*offset))?;
+            file.write_all(data)?;
+        }
+        Ok(())
+    }
+}
+
+/// Offset right after the last FLAC metadata block, the first block header
+/// being at `offset`.
+async fn flac_metadata_end<F>(file: &mut RemoteFile<'_, F>, mut offset: u64) -> Result<u64, Error>
+where
+    F: Fn() -> RequestBuilder,
+{
+    loop {
+        file.ensure(offset, offset + 4).await?;
+        let (last, len) =
+            flac_block_header(&file.read(offset, 4)).ok_or_else(|| anyhow!("Truncated FLAC"))?;
+        offset += 4 + len;
+        file.ensure(offset - len, offset).await?;
+        if last {
+            return Ok(offset);
+        }
+    }
+}
+
+/// Fetches the `moov` atom, returns false when there's none.
+async fn mp4_moov<F>(file: &mut RemoteFile<'_, F>, mut offset: u64) -> Result<bool, Error>
+where
+    F: Fn() -> RequestBuilder,
+{
+    while offset < file.size {
+        file.ensure(offset, offset.saturating_add(16)).await?;
+        let Some((name, len)) = mp4_atom_header(&file.read(offset, 16), file.size - offset) else {
+            return Ok(false);
+        };
+        let Some(end) = offset.checked_add(len) else {
+            return Ok(false);
+        };
+        if &name == b"moov" {
+            file.ensure(offset, end).await?;
+            return Ok(true);
+        }
+        offset = end;
+    }
+    Ok(false)
+}
+
+/// Offset right after the Ogg pages of the header packets, the first page
+/// being at `offset`. Header pages have a granule position of 0, audio pages
+/// don't.
+async fn ogg_headers_end<F>(file: &mut RemoteFile<'_, F>, mut offset: u64) -> Result<u64, Error>
+where
+    F: Fn() -> RequestBuilder,
+{
+    while offset < file.size {
+        file.ensure(offset, offset.saturating_add(27 + 255)).await?;
+        let header = file.read(offset, 27 + 255);
+        let Some((granule, len)) = ogg_page_header(&header, file.size - offset) else {
+            return Ok(offset);
+        };
+        if granule != 0 {
+            return Ok(offset);
+        }
+        let end = offset
+            .checked_add(len)
+            .ok_or_else(|| anyhow!("Truncated Ogg"))?;
+        file.ensure(offset, end).await?;
+        offset = end;
+    }
+    Ok(offset)
+}
+
+/// Size of the ID3v2 tag at the start of a file, header and footer included.
+fn id3v2_size(header: &[u8]) -> Option<u64> {
+    if header.len() < 10 || !header.starts_with(b"ID3") {
+        return None;
+    }
+    let size = header[6..10]
+        .iter()
+        .fold(0u64, |size, byte| (size << 7) | (*byte & 0x7f) as u64);
+    let footer = if header[5] & 0x10 != 0 { 10 } else { 0 };
+    Some(10 + size + footer)
+}
+
+/// Whether the last flag is set and the length of the FLAC metadata block.
+fn flac_block_header(header: &[u8]) -> Option<(bool, u64)> {
+    let header = header.get(..4)?;
+    let len = u32::from_be_bytes([0, header[1], header[2], header[3]]) as u64;
+    Some((header[0] & 0x80 != 0, len))
+}
+
+/// Name and size of the MP4 atom starting with `header`, `remaining` being
+/// the bytes left in the file. `None` when the atom doesn't fit in them.
+fn mp4_atom_header(header: &[u8], remaining: u64) -> Option<([u8; 4], u64)> {
+    let name: [u8; 4] = header.get(4..8)?.try_into().ok()?;
+    let len = match u32::from_be_bytes(header[..4].try_into().ok()?) as u64 {
+        0 => remaining,
+        1 => u64::from_be_bytes(header.get(8..16)?.try_into().ok()?),
+        len => len,
+    };
+    (8..=remaining).contains(&len).then_some((name, len))
+}
+
+/// Granule position and size of the Ogg page starting with `header`,
+/// `remaining` being the bytes left in the file. `None` when the page
+/// doesn't fit in them.
+fn ogg_page_header(header: &[u8], remaining: u64) -> Option<(u64, u64)> {
+    if !header.starts_with(b"OggS") || header.len() < 27 {
+        return None;
+    }
+    let granule = u64::from_le_bytes(header[6..14].try_into().ok()?);
+    let segments = header[26] as usize;
+    let body: u64 = header
+        .get(27..27 + segments)?
+        .iter()
+        .map(|lacing| *lacing as u64)
+        .sum();
+    let len = 27 + segments as u64 + body;
+    (len <= remaining).then_some((granule, len))
+}
+
+/// Size of the APE tag ending the file or right before its ID3v1 tag,
+/// header included.
+fn ape_tag_size(tail: &[u8]) -> Option<u64> {
+    [32, 128 + 32].into_iter().find_map(|from_end| {
+        let footer = tail.get(tail.len().checked_sub(from_end)?..)?.get(..32)?;
+        if !footer.starts_with(b"APETAGEX") {
+            return None;
+        }
+        let size = u32::from_le_bytes(footer[12..16].try_into().ok()?) as u64;
+        Some(size + 32)
+    })
+}
+
+fn is_mpeg_frame(header: &[u8]) -> bool {
+    header.len() >= 2 && header[0] == 0xff && header[1] & 0xe0 == 0xe0
+}
+
+/// Total size from a `bytes 0-1023/4096` Content-Range.
+fn content_range_size(value: &str) -> Option<u64> {
+    value.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lofty::tag::{Accessor, TagType};
+    use std::sync::{Arc, Mutex};
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+    };
+
+    /// A fake file server honouring `Range`, which records the ranges it is
+    /// asked for, `None` for whole-file requests.
+    async fn serve(body: Vec<u8>) -> (String, Arc<Mutex<Vec<Option<(u64, u64)>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/track", listener.local_addr().unwrap());
+        let ranges = Arc::new(Mutex::new(vec![]));
+
+        let received = ranges.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let (reader, mut writer) = stream.into_split();
+                let mut lines = BufReader::new(reader).lines();
+                let mut range = None;
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some(value) = line.to_lowercase().strip_prefix("range: bytes=") {
+                        let (start, end) = value.split_once('-').unwrap();
+                        range = Some((start.parse().unwrap(), end.parse::<u64>().unwrap()));
+                    }
+                }
+                received.lock().unwrap().push(range);
+
+                let size = body.len() as u64;
+                let head = match range {
+                    Some((start, end)) => {
+                        let end = end.min(size - 1);
+                        writer
+                            .write_all(
+                                format!(
+                                    "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {}-{}/{}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                                    start, end, size, end + 1 - start
+                                )
+                                .as_bytes(),
+                            )
+                            .await
+                            .unwrap();
+                        start as usize..end as usize + 1
+                    }
+                    None => {
+                        writer
+                            .write_all(
+                                format!(
+                                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                                    size
+                                )
+                                .as_bytes(),
+                            )
+                            .await
+                            .unwrap();
+                        0..body.len()
+                    }
+                };
+                writer.write_all(&body[head]).await.unwrap();
+            }
+        });
+        (url, ranges)
+    }
+
+    /// Bytes served for `ranges`, asserting the file was never downloaded
+    /// whole.
+    fn served(ranges: &Mutex<Vec<Option<(u64, u64)>>>) -> u64 {
+        ranges
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|range| {
+                let (start, end) = range.expect("whole file downloaded");
+                end + 1 - start
+            })
+            .sum()
+    }
+
+    fn flac_block(kind: u8, last: bool, data: &[u8]) -> Vec<u8> {
+        let len = (data.len() as u32).to_be_bytes();
+        let mut block = vec![kind | if last { 0x80 } else { 0 }, len[1], len[2], len[3]];
+        block.extend(data);
+        block
+    }
+
+    #[tokio::test]
+    async fn test_flac_with_large_picture() {
+        let mut streaminfo = vec![0x10, 0x00, 0x10, 0x00, 0, 0, 0, 0, 0, 0];
+        streaminfo.extend(((44100u64 << 44) | (1 << 41) | (15 << 36) | 441_000).to_be_bytes());
+        streaminfo.extend([0; 16]);
+
+        let mut picture = 3u32.to_be_bytes().to_vec();
+        picture.extend(9u32.to_be_bytes());
+        picture.extend(b"image/png");
+        picture.extend([0; 20]);
+        picture.extend((400 * 1024u32).to_be_bytes());
+        picture.extend(vec![0x42; 400 * 1024]);
+
+        let mut comment = 6u32.to_le_bytes().to_vec();
+        comment.extend(b"vendor");
+        comment.extend(1u32.to_le_bytes());
+        comment.extend(10u32.to_le_bytes());
+        comment.extend(b"TITLE=Song");
+
+        let mut body = b"fLaC".to_vec();
+        body.extend(flac_block(0, false, &streaminfo));
+        body.extend(flac_block(6, false, &picture));
+        body.extend(flac_block(4, true, &comment));
+        body.extend(vec![0; 4 * 1024 * 1024]);
+
+        let (url, ranges) = serve(body.clone()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("track.flac");
+        let client = reqwest::Client::new();
+        download_for_tags(|| client.get(&url), &path).await.unwrap();
+
+        let tagged_file = Probe::open(&path).unwrap().read().unwrap();
+        assert_eq!(
+            tagged_file.primary_tag().unwrap().title().as_deref(),
+            Some("Song")
+        );
+        assert!(served(&ranges) < body.len() as u64 / 4);
+    }
+
+    #[tokio::test]
+    async fn test_mp4_with_moov_at_end() {
+        let mut body = b"\x00\x00\x00\x18ftypM4A \x00\x00\x00\x00M4A mp42".to_vec();
+        body.extend((8 + 4 * 1024 * 1024u32).to_be_bytes());
+        body.extend(b"mdat");
+        body.extend(vec![0; 4 * 1024 * 1024]);
+        let moov_offset = body.len();
+        body.extend((8 + 1024u32).to_be_bytes());
+        body.extend(b"moov");
+        body.extend(vec![0x42; 1024]);
+
+        let (url, ranges) = serve(body.clone()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("track.m4a");
+        let client = reqwest::Client::new();
+        assert!(download_tag_ranges(&|| client.get(&url), &path)
+            .await
+            .unwrap());
+
+        let written = std::fs::read(&path).unwrap();
+        assert_eq!(written.len(), body.len());
+        assert_eq!(&written[moov_offset..], &body[moov_offset..]);
+        assert!(served(&ranges) < body.len() as u64 / 4);
+    }
+
+    #[tokio::test]
+    async fn test_mpeg_with_id3v1_tail() {
+        let mut frame = vec![0xff, 0xfb, 0x90, 0x64];
+        frame.extend([0; 413]);
+        let mut body = frame.repeat(10_000);
+        let mut id3v1 = b"TAGSong".to_vec();
+        id3v1.resize(128, 0);
+        body.extend(&id3v1);
+
+        let (url, ranges) = serve(body.clone()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("track.mp3");
+        let client = reqwest::Client::new();
+        assert!(download_tag_ranges(&|| client.get(&url), &path)
+            .await
+            .unwrap());
+
+        let tagged_file = Probe::open(&path).unwrap().read().unwrap();
+        assert_eq!(
+            tagged_file.tag(TagType::Id3v1).unwrap().title().as_deref(),
+            Some("Song")
+        );
+        assert!(served(&ranges) < body.len() as u64 / 4);
+    }
+
+    #[test]
+    fn test_id3v2_size() {
+        assert_eq!(
+            id3v2_size(b"ID3\x04\x00\x00\x00\x00\x02\x01"),
+            Some(10 + 257)
+        );
+        assert_eq!(
+            id3v2_size(b"ID3\x04\x00\x10\x00\x00\x00\x0a"),
+            Some(10 + 10 + 10)
+        );
+        assert_eq!(id3v2_size(b"fLaC\x00\x00\x00\x22\x00\x00"), None);
+    }
+
+    #[test]
+    fn test_flac_block_header() {
+        assert_eq!(flac_block_header(b"\x00\x00\x00\x22"), Some((false, 34)));
+        assert_eq!(flac_block_header(b"\x86\x01\x00\x00"), Some((true, 65536)));
+        assert_eq!(flac_block_header(b"\x86"), None);
+    }
+
+    #[test]
+    fn test_mp4_atom_header() {
+        assert_eq!(
+            mp4_atom_header(b"\x00\x00\x00\x20ftypM4A ", 1000),
+            Some((*b"ftyp", 32))
+        );
+        assert_eq!(
+            mp4_atom_header(
+                b"\x00\x00\x00\x01mdat\x00\x00\x00\x01\x00\x00\x00\x00",
+                1 << 40
+            ),
+            Some((*b"mdat", 1 << 32))
+        );
+        assert_eq!(
+            mp4_atom_header(b"\x00\x00\x00\x00moov", 1000),
+            Some((*b"moov", 1000))
+        );
+        assert_eq!(mp4_atom_header(b"\x00\x00\x00\x04free", 1000), None);
+        assert_eq!(mp4_atom_header(b"\x00\x00\x04\x00free", 1000), None);
+        assert_eq!(
+            mp4_atom_header(
+                b"\x00\x00\x00\x01mdat\xff\xff\xff\xff\xff\xff\xff\xff",
+                1 << 40
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ogg_page_header() {
+        let mut page = b"OggS\x00\x02".to_vec();
+        page.extend(0u64.to_le_bytes());
+        page.extend([0; 12]);
+        page.extend([2, 255, 30]);
+        assert_eq!(ogg_page_header(&page, 1000), Some((0, 27 + 2 + 285)));
+        assert_eq!(ogg_page_header(&page, 27 + 2 + 284), None);
+        assert_eq!(ogg_page_header(&page[..27], 1000), None);
+        assert_eq!(ogg_page_header(b"RIFF", 1000), None);
+    }
+
+    #[test]
+    fn test_ape_tag_size() {
+        let mut footer = b"APETAGEX".to_vec();
+        footer.extend(2000u32.to_le_bytes());
+        footer.extend(100u32.to_le_bytes());
+        footer.extend([0; 16]);
+
+        let mut tail = vec![0; 64];
+        tail.extend(&footer);
+        assert_eq!(ape_tag_size(&tail), Some(132));
+
+        tail.extend([0; 128]);
+        assert_eq!(ape_tag_size(&tail), Some(132));
+        assert_eq!(ape_tag_size(&[0; 256]), None);
+    }
+
+    #[test]
+    fn test_content_range_size() {
+        assert_eq!(content_range_size("bytes 0-1023/4096"), Some(4096));
+        assert_eq!(content_range_size("bytes 0-1023/*"), None);
+    }
+}
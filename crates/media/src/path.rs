@@ -0,0 +1,23 @@
+/// LIKE pattern matching every path below `path`.
+pub fn descendants_pattern(path: &str) -> String {
+    let escaped = path
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("{}/%", escaped.trim_end_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_descendants_pattern() {
+        assert_eq!(descendants_pattern("/music/album"), "/music/album/%");
+        assert_eq!(descendants_pattern("/music/"), "/music/%");
+        assert_eq!(
+            descendants_pattern("/music/100%_hits"),
+            "/music/100\\%\\_hits/%"
+        );
+    }
+}
@@ -33,7 +33,7 @@ fn first_letter(name: &str) -> String {
         .unwrap_or_else(|| "#".to_string())
 }
 
-fn artist_to_json(a: &ArtistWithStats) -> Value {
+pub fn artist_to_json(a: &ArtistWithStats) -> Value {
     let mut obj = json!({
         "id": a.xata_id,
         "name": a.name,
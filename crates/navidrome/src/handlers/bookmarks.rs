@@ -0,0 +1,142 @@
+use actix_web::HttpResponse;
+use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{handlers::songs::track_to_json, repo, response};
+
+/// The `position` parameter, in milliseconds.
+fn position_param(params: &HashMap<String, String>) -> Result<i64, &'static str> {
+    match params.get("position") {
+        Some(position) => position
+            .parse::<i64>()
+            .ok()
+            .filter(|position| *position >= 0)
+            .ok_or("Invalid position parameter"),
+        None => Err("Missing position parameter"),
+    }
+}
+
+pub async fn handle_get_bookmarks(
+    format: &str,
+    user_id: &str,
+    username: &str,
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    let bookmarks = match repo::bookmark::get_bookmarks(pool, user_id).await {
+        Ok(bookmarks) => bookmarks,
+        Err(e) => {
+            tracing::error!("getBookmarks error: {}", e);
+            return response::err(format, 0, "Internal server error");
+        }
+    };
+
+    let track_ids: Vec<String> = bookmarks.iter().map(|b| b.track_id.clone()).collect();
+    let tracks = match repo::track::get_tracks_by_ids(pool, &track_ids, user_id).await {
+        Ok(tracks) => tracks,
+        Err(e) => {
+            tracing::error!("getBookmarks tracks error: {}", e);
+            return response::err(format, 0, "Internal server error");
+        }
+    };
+
+    // Bookmarks of tracks no longer in the library are left out.
+    let list: Vec<Value> = bookmarks
+        .iter()
+        .filter_map(|b| {
+            let track = tracks.iter().find(|t| t.xata_id == b.track_id)?;
+            let mut obj = json!({
+                "entry": track_to_json(track, user_id),
+                "position": b.position_ms,
+                "username": username,
+                "created": b.xata_createdat.to_rfc3339(),
+                "changed": b.xata_updatedat.to_rfc3339(),
+            });
+            if let Some(comment) = &b.comment {
+                obj["comment"] = json!(comment);
+            }
+            Some(obj)
+        })
+        .collect();
+
+    response::ok(format, json!({ "bookmarks": { "bookmark": list } }))
+}
+
+pub async fn handle_create_bookmark(
+    format: &str,
+    user_id: &str,
+    params: &HashMap<String, String>,
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    let track_id = match params.get("id") {
+        Some(id) => id.as_str(),
+        None => return response::err(format, 10, "Missing id parameter"),
+    };
+    let position_ms = match position_param(params) {
+        Ok(position) => position,
+        Err(message) => return response::err(format, 10, message),
+    };
+    let comment = params
+        .get("comment")
+        .map(|s| s.as_str())
+        .filter(|s| !s.is_empty());
+
+    match repo::track::get_track_by_id(pool, track_id, user_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return response::err(format, 70, "Song not found"),
+        Err(e) => {
+            tracing::error!("createBookmark track lookup error: {}", e);
+            return response::err(format, 0, "Internal server error");
+        }
+    }
+
+    match repo::bookmark::save_bookmark(pool, user_id, track_id, position_ms, comment).await {
+        Ok(_) => response::ok(format, json!({})),
+        Err(e) => {
+            tracing::error!("createBookmark error: {}", e);
+            response::err(format, 0, "Failed to save bookmark")
+        }
+    }
+}
+
+pub async fn handle_delete_bookmark(
+    format: &str,
+    user_id: &str,
+    track_id: &str,
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    match repo::bookmark::delete_bookmark(pool, user_id, track_id).await {
+        Ok(true) => response::ok(format, json!({})),
+        Ok(false) => response::err(format, 70, "Bookmark not found"),
+        Err(e) => {
+            tracing::error!("deleteBookmark error: {}", e);
+            response::err(format, 0, "Failed to delete bookmark")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_param() {
+        let params =
+            |position: &str| HashMap::from([("position".to_string(), position.to_string())]);
+
+        assert_eq!(position_param(&params("5400000")), Ok(5_400_000));
+        assert_eq!(position_param(&params("0")), Ok(0));
+        assert_eq!(
+            position_param(&params("-1")),
+            Err("Invalid position parameter")
+        );
+        assert_eq!(
+            position_param(&params("1.5")),
+            Err("Invalid position parameter")
+        );
+        assert_eq!(
+            position_param(&HashMap::new()),
+            Err("Missing position parameter")
+        );
+    }
+}
@@ -1,9 +1,76 @@
 use actix_web::HttpResponse;
+use lru::LruCache;
 use serde_json::{json, Value};
 use sqlx::{Pool, Postgres};
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
 
-use crate::{handlers::albums::mime_to_suffix, repo, response};
+use crate::{
+    handlers::{albums::mime_to_suffix, artists::artist_to_json},
+    musicbrainz, repo, response,
+};
+
+const DEFAULT_SIMILAR_ARTISTS: i64 = 20;
+const MAX_SIMILAR_ARTISTS: i64 = 100;
+
+// Info is cached per user (similar artists are limited to their library),
+// the least recently used entries are dropped once the cache is full.
+// MusicBrainz data comes from the scrobbler's cache, entries missing it
+// expire sooner so they pick it up once the scrobbler fetched it.
+const INFO_TTL: Duration = Duration::from_secs(60 * 60);
+const PARTIAL_INFO_TTL: Duration = Duration::from_secs(60 * 10);
+const INFO_CACHE_CAPACITY: usize = 1024;
+
+type InfoCache = LruCache<String, (Value, Instant, Duration)>;
+
+static INFO_CACHE: OnceLock<Mutex<InfoCache>> = OnceLock::new();
+
+fn info_cache() -> &'static Mutex<InfoCache> {
+    INFO_CACHE.get_or_init(|| {
+        Mutex::new(LruCache::new(
+            NonZeroUsize::new(INFO_CACHE_CAPACITY).unwrap(),
+        ))
+    })
+}
+
+fn get_cached_info(key: &str) -> Option<Value> {
+    let mut cache = info_cache().lock().unwrap();
+    match cache.get(key) {
+        Some((info, inserted, ttl)) if inserted.elapsed() < *ttl => Some(info.clone()),
+        Some(_) => {
+            cache.pop(key);
+            None
+        }
+        None => None,
+    }
+}
+
+fn cache_info(key: String, info: &Value) {
+    let ttl = if info.get("musicBrainzId").is_some() {
+        INFO_TTL
+    } else {
+        PARTIAL_INFO_TTL
+    };
+    let mut cache = info_cache().lock().unwrap();
+    cache.put(key, (info.clone(), Instant::now(), ttl));
+}
+
+/// The MusicBrainz recordings of the stored recording MBIDs, missing data
+/// only costs the MusicBrainz fields.
+async fn recordings(mbids: Result<Vec<String>, anyhow::Error>) -> Vec<musicbrainz::Recording> {
+    let result = match mbids {
+        Ok(mbids) => musicbrainz::get_recordings(&mbids).await,
+        Err(e) => Err(e),
+    };
+    result.unwrap_or_else(|e| {
+        tracing::warn!("Failed to read MusicBrainz recordings: {}", e);
+        vec![]
+    })
+}
 
 pub async fn handle_get_artist_info(
     format: &str,
@@ -11,7 +78,20 @@ pub async fn handle_get_artist_info(
     artist_id: &str,
     pool: &Arc<Pool<Postgres>>,
     is_v2: bool,
+    params: &HashMap<String, String>,
 ) -> HttpResponse {
+    let count = params
+        .get("count")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_SIMILAR_ARTISTS)
+        .clamp(0, MAX_SIMILAR_ARTISTS);
+    let key = if is_v2 { "artistInfo2" } else { "artistInfo" };
+
+    let cache_key = format!("artist:{}:{}:{}", user_id, artist_id, count);
+    if let Some(info) = get_cached_info(&cache_key) {
+        return response::ok(format, json!({ key: info }));
+    }
+
     let artist = match repo::artist::get_artist_by_id(pool, artist_id, user_id).await {
         Ok(Some(a)) => a,
         Ok(None) => return response::err(format, 70, "Artist not found"),
@@ -21,31 +101,110 @@ pub async fn handle_get_artist_info(
         }
     };
 
+    let details = tokio::try_join!(
+        repo::artist::get_biography_by_artist_id(pool, artist_id),
+        repo::artist::get_similar_artists(pool, user_id, artist_id, count),
+    );
+    let (biography, similar) = match details {
+        Ok(details) => details,
+        Err(e) => {
+            tracing::error!("getArtistInfo error: {}", e);
+            return response::err(format, 0, "Internal server error");
+        }
+    };
+
     let mut info = json!({
-        "similarArtist": [],
+        "lastFmUrl": lastfm_url(&[&artist.name]),
+        "similarArtist": similar.iter().map(artist_to_json).collect::<Vec<_>>(),
     });
 
+    if let Some(biography) = biography {
+        info["biography"] = json!(biography);
+    }
+    let recordings =
+        recordings(repo::track::get_recording_mbids_for_artist(pool, artist_id).await).await;
+    if let Some(mb_artist) = musicbrainz::credited_artist(&recordings, &artist.name) {
+        info["musicBrainzId"] = json!(mb_artist.id);
+    }
     if let Some(pic) = &artist.picture {
         info["smallImageUrl"] = json!(pic);
         info["mediumImageUrl"] = json!(pic);
         info["largeImageUrl"] = json!(pic);
     }
 
-    let key = if is_v2 { "artistInfo2" } else { "artistInfo" };
+    cache_info(cache_key, &info);
+
     response::ok(format, json!({ key: info }))
 }
 
 pub async fn handle_get_album_info(
     format: &str,
+    user_id: &str,
     album_id: &str,
     pool: &Arc<Pool<Postgres>>,
     is_v2: bool,
-    _params: &HashMap<String, String>,
 ) -> HttpResponse {
-    let _ = pool;
-    let _ = album_id;
     let key = if is_v2 { "albumInfo2" } else { "albumInfo" };
-    response::ok(format, json!({ key: {} }))
+
+    let cache_key = format!("album:{}:{}", user_id, album_id);
+    if let Some(info) = get_cached_info(&cache_key) {
+        return response::ok(format, json!({ key: info }));
+    }
+
+    let album = match repo::album::get_album_by_id(pool, album_id, user_id).await {
+        Ok(Some(a)) => a,
+        Ok(None) => return response::err(format, 70, "Album not found"),
+        Err(e) => {
+            tracing::error!("getAlbumInfo error: {}", e);
+            return response::err(format, 0, "Internal server error");
+        }
+    };
+
+    let mut info = json!({
+        "lastFmUrl": lastfm_url(&[&album.artist, &album.title]),
+    });
+
+    let recordings =
+        recordings(repo::track::get_recording_mbids_for_album(pool, album_id).await).await;
+    if let Some(release) = musicbrainz::matching_release(&recordings, &album.title) {
+        info["musicBrainzId"] = json!(release.id);
+        if let Some(notes) = musicbrainz::release_notes(release) {
+            info["notes"] = json!(notes);
+        }
+    }
+    if let Some(art) = &album.album_art {
+        info["smallImageUrl"] = json!(art);
+        info["mediumImageUrl"] = json!(art);
+        info["largeImageUrl"] = json!(art);
+    }
+
+    cache_info(cache_key, &info);
+
+    response::ok(format, json!({ key: info }))
+}
+
+/// Last.fm page of an artist (`[artist]`) or an album (`[artist, album]`).
+fn lastfm_url(segments: &[&str]) -> String {
+    let path = segments
+        .iter()
+        .map(|segment| lastfm_encode(segment))
+        .collect::<Vec<_>>()
+        .join("/");
+    format!("https://www.last.fm/music/{}", path)
+}
+
+// Last.fm paths encode spaces as `+` and everything else percent-encoded.
+fn lastfm_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b' ' => "+".to_string(),
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
 }
 
 pub async fn handle_get_now_playing(
@@ -92,3 +251,20 @@ pub async fn handle_get_now_playing(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lastfm_url() {
+        assert_eq!(
+            lastfm_url(&["Sigur Rós"]),
+            "https://www.last.fm/music/Sigur+R%C3%B3s"
+        );
+        assert_eq!(
+            lastfm_url(&["AC/DC", "Back in Black"]),
+            "https://www.last.fm/music/AC%2FDC/Back+in+Black"
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use actix_web::HttpResponse;
+use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{repo, response, xata::track::TrackLyrics};
+
+// Language reported for structured lyrics; embedded lyrics don't say which
+// language they are in.
+const UNKNOWN_LANG: &str = "xxx";
+
+pub async fn handle_get_lyrics(
+    format: &str,
+    user_id: &str,
+    pool: &Arc<Pool<Postgres>>,
+    params: &HashMap<String, String>,
+) -> HttpResponse {
+    let artist = params
+        .get("artist")
+        .map(|s| s.as_str())
+        .filter(|s| !s.is_empty());
+    let title = params
+        .get("title")
+        .map(|s| s.as_str())
+        .filter(|s| !s.is_empty());
+    if artist.is_none() && title.is_none() {
+        return response::ok(format, json!({ "lyrics": {} }));
+    }
+
+    match repo::track::find_track_lyrics(pool, user_id, artist, title).await {
+        Ok(Some(t)) => {
+            let value = match (&t.lyrics, &t.synced_lyrics) {
+                (Some(lyrics), _) => lyrics.clone(),
+                (None, Some(synced)) => parse_lrc(synced)
+                    .1
+                    .into_iter()
+                    .map(|(_, line)| line)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                (None, None) => String::new(),
+            };
+            response::ok(
+                format,
+                json!({
+                    "lyrics": {
+                        "artist": t.artist,
+                        "title": t.title,
+                        "value": value,
+                    }
+                }),
+            )
+        }
+        Ok(None) => response::ok(format, json!({ "lyrics": {} })),
+        Err(e) => {
+            tracing::error!("getLyrics error: {}", e);
+            response::err(format, 0, "Internal server error")
+        }
+    }
+}
+
+pub async fn handle_get_lyrics_by_song_id(
+    format: &str,
+    user_id: &str,
+    song_id: &str,
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    match repo::track::get_track_lyrics(pool, song_id, user_id).await {
+        Ok(Some(t)) => response::ok(
+            format,
+            json!({ "lyricsList": { "structuredLyrics": structured_lyrics(&t) } }),
+        ),
+        Ok(None) => response::err(format, 70, "Song not found"),
+        Err(e) => {
+            tracing::error!("getLyricsBySongId error: {}", e);
+            response::err(format, 0, "Internal server error")
+        }
+    }
+}
+
+/// OpenSubsonic structured lyrics: the synced version first when there is
+/// one, then the plain version.
+fn structured_lyrics(t: &TrackLyrics) -> Vec<Value> {
+    let mut structured = vec![];
+
+    if let Some(synced) = &t.synced_lyrics {
+        let (offset, lines) = parse_lrc(synced);
+        if !lines.is_empty() {
+            structured.push(json!({
+                "displayArtist": t.artist,
+                "displayTitle": t.title,
+                "lang": UNKNOWN_LANG,
+                "offset": offset,
+                "synced": true,
+                "line": lines
+                    .into_iter()
+                    .map(|(start, value)| json!({ "start": start, "value": value }))
+                    .collect::<Vec<_>>(),
+            }));
+        }
+    }
+
+    if let Some(lyrics) = &t.lyrics {
+        structured.push(json!({
+            "displayArtist": t.artist,
+            "displayTitle": t.title,
+            "lang": UNKNOWN_LANG,
+            "synced": false,
+            "line": lyrics
+                .lines()
+                .map(|value| json!({ "value": value }))
+                .collect::<Vec<_>>(),
+        }));
+    }
+
+    structured
+}
+
+/// Parses LRC lyrics into their `[offset:...]` (in milliseconds) and the
+/// `(start in milliseconds, text)` lines, sorted by start time. Lines with
+/// several timestamps are repeated for each of them.
+pub fn parse_lrc(lrc: &str) -> (i64, Vec<(u64, String)>) {
+    let mut offset = 0;
+    let mut lines = vec![];
+
+    for line in lrc.lines() {
+        let mut rest = line.trim();
+        let mut starts = vec![];
+
+        while let Some(tag) = rest.strip_prefix('[') {
+            let Some(end) = tag.find(']') else {
+                break;
+            };
+            let (content, remaining) = (&tag[..end], &tag[end + 1..]);
+            if let Some(value) = content.strip_prefix("offset:") {
+                offset = value.trim().parse().unwrap_or(0);
+            } else if let Some(start) = parse_timestamp(content) {
+                starts.push(start);
+            }
+            rest = remaining;
+        }
+
+        for start in starts {
+            lines.push((start, rest.trim().to_string()));
+        }
+    }
+
+    lines.sort_by_key(|(start, _)| *start);
+    (offset, lines)
+}
+
+// `mm:ss`, `mm:ss.xx` or `mm:ss.xxx` to milliseconds.
+fn parse_timestamp(timestamp: &str) -> Option<u64> {
+    let (minutes, seconds) = timestamp.split_once(':')?;
+    let minutes: u64 = minutes.parse().ok()?;
+    let seconds: f64 = seconds.parse().ok()?;
+    if seconds < 0.0 {
+        return None;
+    }
+    Some(minutes * 60_000 + (seconds * 1000.0).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lrc() {
+        let (offset, lines) =
+            parse_lrc("[ar:Artist]\n[offset:-250]\n[00:12.34]Hello\n[00:05.00][01:00.5]World\n");
+        assert_eq!(offset, -250);
+        assert_eq!(
+            lines,
+            vec![
+                (5_000, "World".to_string()),
+                (12_340, "Hello".to_string()),
+                (60_500, "World".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_lrc_without_timestamps() {
+        let (offset, lines) = parse_lrc("Hello\nWorld");
+        assert_eq!(offset, 0);
+        assert!(lines.is_empty());
+    }
+}
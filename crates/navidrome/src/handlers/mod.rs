@@ -1,15 +1,20 @@
 pub mod albums;
 pub mod artists;
+pub mod bookmarks;
 pub mod cover_art;
 pub mod directory;
 pub mod genres;
 pub mod info;
+pub mod lyrics;
 pub mod music_folders;
 pub mod ping;
 pub mod playlists;
 pub mod playqueue;
+pub mod radio;
 pub mod scrobble;
 pub mod search;
+pub mod shares;
+pub mod similar;
 pub mod songs;
 pub mod star;
 pub mod starred;
@@ -20,7 +25,7 @@ use actix_web::{get, post, route, web, HttpRequest, HttpResponse};
 use sqlx::{Pool, Postgres};
 use std::{collections::HashMap, sync::Arc};
 
-use crate::{auth, repo, response, typesense::TypesenseClient};
+use crate::{auth, repo, response, transcode::StreamOptions, typesense::TypesenseClient};
 
 fn get_format(params: &HashMap<String, String>) -> String {
     params
@@ -61,8 +66,9 @@ async fn dispatch(
 ) -> HttpResponse {
     let format = get_format(&params);
 
-    // Auth is required for all endpoints except ping
-    let user = if method != "ping" {
+    // Auth is required for all endpoints except ping and the OpenSubsonic
+    // extensions discovery
+    let user = if method != "ping" && method != "getOpenSubsonicExtensions" {
         let username = match params.get("u") {
             Some(u) => u.as_str(),
             None => return response::err(&format, 10, "Missing u parameter"),
@@ -110,6 +116,14 @@ async fn dispatch(
 
     match method {
         "ping" => ping::handle(&format),
+        "getOpenSubsonicExtensions" => response::ok(
+            &format,
+            serde_json::json!({
+                "openSubsonicExtensions": [
+                    { "name": "songLyrics", "versions": [1] }
+                ]
+            }),
+        ),
         "getMusicFolders" => music_folders::handle(&format),
         "getArtists" => artists::handle_get_artists(&format, user_id, pool, false).await,
         "getIndexes" => artists::handle_get_artists(&format, user_id, pool, true).await,
@@ -139,14 +153,20 @@ async fn dispatch(
                 Some(id) => id.as_str(),
                 None => return response::err(&format, 10, "Missing id parameter"),
             };
-            stream::handle(&format, user_id, id, pool, range.as_deref()).await
+            if repo::radio::is_station_id(id) {
+                return radio::handle_stream(&format, user_id, id, pool, nc).await;
+            }
+            let options = StreamOptions::from_params(&params);
+            stream::handle(&format, user_id, id, pool, range.as_deref(), &options).await
         }
         "download" => {
             let id = match params.get("id") {
                 Some(id) => id.as_str(),
                 None => return response::err(&format, 10, "Missing id parameter"),
             };
-            stream::handle(&format, user_id, id, pool, range.as_deref()).await
+            // Downloads are always the original file.
+            let options = StreamOptions::default();
+            stream::handle(&format, user_id, id, pool, range.as_deref(), &options).await
         }
         "getCoverArt" => {
             let id = match params.get("id") {
@@ -171,7 +191,8 @@ async fn dispatch(
             user::handle_get_user(&format, u)
         }
         "getLicense" => user::handle_get_license(&format),
-        "getScanStatus" | "startScan" => user::handle_get_scan_status(&format, pool),
+        "getScanStatus" => user::handle_get_scan_status(&format, user_id, pool).await,
+        "startScan" => user::handle_start_scan(&format, user_id, pool, nc).await,
         "getGenres" => genres::handle_get_genres(&format, user_id, pool).await,
         "getSongsByGenre" => {
             genres::handle_get_songs_by_genre(&format, user_id, pool, &params).await
@@ -184,28 +205,28 @@ async fn dispatch(
                 Some(id) => id.as_str(),
                 None => return response::err(&format, 10, "Missing id parameter"),
             };
-            info::handle_get_artist_info(&format, user_id, id, pool, false).await
+            info::handle_get_artist_info(&format, user_id, id, pool, false, &params).await
         }
         "getArtistInfo2" => {
             let id = match params.get("id") {
                 Some(id) => id.as_str(),
                 None => return response::err(&format, 10, "Missing id parameter"),
             };
-            info::handle_get_artist_info(&format, user_id, id, pool, true).await
+            info::handle_get_artist_info(&format, user_id, id, pool, true, &params).await
         }
         "getAlbumInfo" => {
             let id = match params.get("id") {
                 Some(id) => id.as_str(),
                 None => return response::err(&format, 10, "Missing id parameter"),
             };
-            info::handle_get_album_info(&format, id, pool, false, &params).await
+            info::handle_get_album_info(&format, user_id, id, pool, false).await
         }
         "getAlbumInfo2" => {
             let id = match params.get("id") {
                 Some(id) => id.as_str(),
                 None => return response::err(&format, 10, "Missing id parameter"),
             };
-            info::handle_get_album_info(&format, id, pool, true, &params).await
+            info::handle_get_album_info(&format, user_id, id, pool, true).await
         }
         "getNowPlaying" => info::handle_get_now_playing(&format, user_id, pool).await,
         "getMusicDirectory" => {
@@ -242,16 +263,74 @@ async fn dispatch(
             };
             playlists::handle_delete_playlist(&format, user_id, id, pool).await
         }
-        "getSimilarSongs" | "getSimilarSongs2" => response::ok(
-            &format,
-            serde_json::json!({ "similarSongs": { "song": [] } }),
-        ),
-        "getTopSongs" => response::ok(&format, serde_json::json!({ "topSongs": { "song": [] } })),
-        "getLyrics" => response::ok(&format, serde_json::json!({ "lyrics": {} })),
-        "getInternetRadioStations" => response::ok(
-            &format,
-            serde_json::json!({ "internetRadioStations": { "internetRadioStation": [] } }),
-        ),
+        "getSimilarSongs" | "getSimilarSongs2" => {
+            let id = match params.get("id") {
+                Some(id) => id.as_str(),
+                None => return response::err(&format, 10, "Missing id parameter"),
+            };
+            similar::handle_get_similar_songs(
+                &format,
+                user_id,
+                id,
+                pool,
+                &params,
+                method == "getSimilarSongs2",
+            )
+            .await
+        }
+        "getTopSongs" => similar::handle_get_top_songs(&format, user_id, pool, &params).await,
+        "getLyrics" => lyrics::handle_get_lyrics(&format, user_id, pool, &params).await,
+        "getLyricsBySongId" => {
+            let id = match params.get("id") {
+                Some(id) => id.as_str(),
+                None => return response::err(&format, 10, "Missing id parameter"),
+            };
+            lyrics::handle_get_lyrics_by_song_id(&format, user_id, id, pool).await
+        }
+        "getBookmarks" => {
+            let u = user.as_ref().unwrap();
+            bookmarks::handle_get_bookmarks(&format, user_id, &u.handle, pool).await
+        }
+        "createBookmark" => {
+            bookmarks::handle_create_bookmark(&format, user_id, &params, pool).await
+        }
+        "deleteBookmark" => {
+            let id = match params.get("id") {
+                Some(id) => id.as_str(),
+                None => return response::err(&format, 10, "Missing id parameter"),
+            };
+            bookmarks::handle_delete_bookmark(&format, user_id, id, pool).await
+        }
+        "getShares" => {
+            let u = user.as_ref().unwrap();
+            shares::handle_get_shares(&format, user_id, &u.handle, pool).await
+        }
+        "createShare" => {
+            let u = user.as_ref().unwrap();
+            shares::handle_create_share(&format, user_id, &u.handle, &params, pool).await
+        }
+        "updateShare" => shares::handle_update_share(&format, user_id, &params, pool).await,
+        "deleteShare" => {
+            let id = match params.get("id") {
+                Some(id) => id.as_str(),
+                None => return response::err(&format, 10, "Missing id parameter"),
+            };
+            shares::handle_delete_share(&format, user_id, id, pool).await
+        }
+        "getInternetRadioStations" => radio::handle_get_stations(&format, user_id, pool).await,
+        "createInternetRadioStation" => {
+            radio::handle_create_station(&format, user_id, &params, pool).await
+        }
+        "updateInternetRadioStation" => {
+            radio::handle_update_station(&format, user_id, &params, pool).await
+        }
+        "deleteInternetRadioStation" => {
+            let id = match params.get("id") {
+                Some(id) => id.as_str(),
+                None => return response::err(&format, 10, "Missing id parameter"),
+            };
+            radio::handle_delete_station(&format, user_id, id, pool).await
+        }
         _ => response::err(
             &format,
             70,
@@ -349,7 +428,14 @@ pub async fn handle_head(
 
     let _ = req;
     let _ = nc_data;
-    stream::handle_head(&format, &user.xata_id, id, pool.get_ref()).await
+    if method == "stream" && repo::radio::is_station_id(id) {
+        return radio::handle_head(&format, &user.xata_id, id, pool.get_ref()).await;
+    }
+    let options = match method.as_str() {
+        "stream" => StreamOptions::from_params(&params),
+        _ => StreamOptions::default(),
+    };
+    stream::handle_head(&format, &user.xata_id, id, pool.get_ref(), &options).await
 }
 
 #[post("/rest/{method}")]
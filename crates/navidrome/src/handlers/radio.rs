@@ -0,0 +1,493 @@
+use actix_web::HttpResponse;
+use bytes::Bytes;
+use futures::StreamExt;
+use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+
+use crate::{icy, repo, response};
+
+/// Redirects followed to reach the stream of a station.
+const MAX_REDIRECTS: usize = 5;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+// Between two reads of the stream, stations send audio continuously.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+static HTTP: OnceLock<reqwest::Client> = OnceLock::new();
+
+// Redirects are followed by `connect`, which checks every hop.
+fn http() -> &'static reqwest::Client {
+    HTTP.get_or_init(|| {
+        reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .dns_resolver(Arc::new(PublicResolver))
+            .connect_timeout(CONNECT_TIMEOUT)
+            .read_timeout(READ_TIMEOUT)
+            .build()
+            .expect("Failed to build radio HTTP client")
+    })
+}
+
+/// Resolves station hosts to public addresses only. The client connects to
+/// the addresses checked here, so a host answering with a private address
+/// on a second lookup can't point the server at its own network.
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            if addrs.is_empty() || !addrs.iter().all(|addr| is_public_ip(&addr.ip())) {
+                return Err(format!("{} is not a public address", name.as_str()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+fn station_to_json(s: &repo::radio::RadioStation) -> Value {
+    let mut obj = json!({
+        "id": s.xata_id,
+        "name": s.name,
+        "streamUrl": s.stream_url,
+    });
+    if let Some(homepage) = &s.homepage_url {
+        obj["homePageUrl"] = json!(homepage);
+    }
+    obj
+}
+
+/// The `streamUrl`, `name` and `homepageUrl` parameters of the create and
+/// update methods.
+fn station_params(
+    params: &HashMap<String, String>,
+) -> Result<(&str, &str, Option<&str>), &'static str> {
+    let stream_url = match params.get("streamUrl").filter(|s| !s.is_empty()) {
+        Some(url) => url.as_str(),
+        None => return Err("Missing streamUrl parameter"),
+    };
+    let name = match params.get("name").filter(|s| !s.is_empty()) {
+        Some(name) => name.as_str(),
+        None => return Err("Missing name parameter"),
+    };
+    if !is_http_url(stream_url) {
+        return Err("streamUrl must be an http or https URL");
+    }
+    let homepage_url = params
+        .get("homepageUrl")
+        .map(|s| s.as_str())
+        .filter(|s| !s.is_empty());
+    Ok((stream_url, name, homepage_url))
+}
+
+fn is_http_url(url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some())
+}
+
+pub async fn handle_get_stations(
+    format: &str,
+    user_id: &str,
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    match repo::radio::get_stations(pool, user_id).await {
+        Ok(stations) => {
+            let list: Vec<Value> = stations.iter().map(station_to_json).collect();
+            response::ok(
+                format,
+                json!({ "internetRadioStations": { "internetRadioStation": list } }),
+            )
+        }
+        Err(e) => {
+            tracing::error!("getInternetRadioStations error: {}", e);
+            response::err(format, 0, "Internal server error")
+        }
+    }
+}
+
+pub async fn handle_create_station(
+    format: &str,
+    user_id: &str,
+    params: &HashMap<String, String>,
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    let (stream_url, name, homepage_url) = match station_params(params) {
+        Ok(station) => station,
+        Err(message) => return response::err(format, 10, message),
+    };
+
+    match repo::radio::create_station(pool, user_id, name, stream_url, homepage_url).await {
+        Ok(_) => response::ok(format, json!({})),
+        Err(e) => {
+            tracing::error!("createInternetRadioStation error: {}", e);
+            response::err(format, 0, "Failed to create radio station")
+        }
+    }
+}
+
+pub async fn handle_update_station(
+    format: &str,
+    user_id: &str,
+    params: &HashMap<String, String>,
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    let station_id = match params.get("id") {
+        Some(id) => id.as_str(),
+        None => return response::err(format, 10, "Missing id parameter"),
+    };
+    let (stream_url, name, homepage_url) = match station_params(params) {
+        Ok(station) => station,
+        Err(message) => return response::err(format, 10, message),
+    };
+
+    match repo::radio::update_station(pool, user_id, station_id, name, stream_url, homepage_url)
+        .await
+    {
+        Ok(true) => response::ok(format, json!({})),
+        Ok(false) => response::err(format, 70, "Radio station not found"),
+        Err(e) => {
+            tracing::error!("updateInternetRadioStation error: {}", e);
+            response::err(format, 0, "Failed to update radio station")
+        }
+    }
+}
+
+pub async fn handle_delete_station(
+    format: &str,
+    user_id: &str,
+    station_id: &str,
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    match repo::radio::delete_station(pool, user_id, station_id).await {
+        Ok(true) => response::ok(format, json!({})),
+        Ok(false) => response::err(format, 70, "Radio station not found"),
+        Err(e) => {
+            tracing::error!("deleteInternetRadioStation error: {}", e);
+            response::err(format, 0, "Failed to delete radio station")
+        }
+    }
+}
+
+/// Relays the station when a client streams it through Rocksky
+/// (`stream?id=<station id>`), publishing the `StreamTitle`s the station
+/// announces as the user's now playing.
+pub async fn handle_stream(
+    format: &str,
+    user_id: &str,
+    station_id: &str,
+    pool: &Arc<Pool<Postgres>>,
+    nc: &Arc<async_nats::Client>,
+) -> HttpResponse {
+    let station = match repo::radio::get_station(pool, user_id, station_id).await {
+        Ok(Some(station)) => station,
+        Ok(None) => return response::err(format, 70, "Radio station not found"),
+        Err(e) => {
+            tracing::error!("radio station lookup error: {}", e);
+            return response::err(format, 0, "Internal server error");
+        }
+    };
+
+    let upstream = match connect(&station.stream_url).await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            tracing::warn!(station = %station.xata_id, "radio stream error: {}", e);
+            return response::err(format, 0, "Failed to reach radio station");
+        }
+    };
+
+    let content_type = upstream
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("audio/mpeg")
+        .to_string();
+    let metaint = upstream
+        .headers()
+        .get("icy-metaint")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|metaint| *metaint > 0);
+
+    let mut demuxer = metaint.map(icy::Demuxer::new);
+    let did = match repo::user::get_user_did_by_id(pool, user_id).await {
+        Ok(did) => did,
+        Err(e) => {
+            tracing::warn!(user_id, "DID lookup error: {}", e);
+            None
+        }
+    };
+    let nc = Arc::clone(nc);
+    let mut last_title: Option<String> = None;
+
+    let body = upstream.bytes_stream().map(move |chunk| {
+        let chunk = chunk.map_err(actix_web::error::ErrorBadGateway)?;
+        let Some(demuxer) = demuxer.as_mut() else {
+            return Ok::<_, actix_web::Error>(chunk);
+        };
+
+        let mut audio = Vec::with_capacity(chunk.len());
+        for block in demuxer.push(&chunk, &mut audio) {
+            let Some(title) = icy::stream_title(&block) else {
+                continue;
+            };
+            if last_title.as_deref() == Some(title.as_str()) {
+                continue;
+            }
+            if let (Some(did), Some((artist, name))) = (&did, icy::split_title(&title)) {
+                publish_now_playing(&nc, did, &artist, &name);
+            }
+            last_title = Some(title);
+        }
+        Ok(Bytes::from(audio))
+    });
+
+    HttpResponse::Ok()
+        .content_type(content_type)
+        .append_header(("Access-Control-Allow-Origin", "*"))
+        .append_header(("Cache-Control", "no-cache"))
+        .streaming(body)
+}
+
+/// Stations are live, there's nothing to tell about their stream before
+/// connecting to it.
+pub async fn handle_head(
+    format: &str,
+    user_id: &str,
+    station_id: &str,
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    match repo::radio::get_station(pool, user_id, station_id).await {
+        Ok(Some(_)) => HttpResponse::Ok()
+            .append_header(("Access-Control-Allow-Origin", "*"))
+            .finish(),
+        Ok(None) => response::err(format, 70, "Radio station not found"),
+        Err(e) => {
+            tracing::error!("radio station lookup error: {}", e);
+            response::err(format, 0, "Internal server error")
+        }
+    }
+}
+
+/// Requests the stream with ICY metadata. Stations are fetched by the
+/// server, so neither they nor the redirects they answer with may point at
+/// it or at its private network.
+async fn connect(url: &str) -> Result<reqwest::Response, anyhow::Error> {
+    let mut url = reqwest::Url::parse(url)?;
+    for _ in 0..=MAX_REDIRECTS {
+        if !is_public_url(&url) {
+            return Err(anyhow::anyhow!("{} is not a public address", url));
+        }
+        let response = http()
+            .get(url.clone())
+            .header("Icy-MetaData", "1")
+            .send()
+            .await?;
+        if !response.status().is_redirection() {
+            return Ok(response.error_for_status()?);
+        }
+        let location = response
+            .headers()
+            .get("location")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| anyhow::anyhow!("redirect without a location"))?;
+        url = url.join(location)?;
+    }
+    Err(anyhow::anyhow!("too many redirects"))
+}
+
+fn publish_now_playing(nc: &Arc<async_nats::Client>, did: &str, artist: &str, name: &str) {
+    let nc = Arc::clone(nc);
+    let payload = json!({
+        "did": did,
+        "track": { "name": name, "artist": artist, "source": "navidrome" },
+    });
+    tokio::spawn(async move {
+        match nc
+            .publish(
+                "rocksky.song.changed",
+                bytes::Bytes::from(payload.to_string()),
+            )
+            .await
+        {
+            Ok(_) => tracing::info!(payload = %payload, "radio song.changed published"),
+            Err(e) => tracing::warn!("radio song.changed publish error: {}", e),
+        }
+    });
+}
+
+/// Hosts are checked by [`PublicResolver`] when connecting, IP addresses
+/// aren't resolved so they're checked here.
+fn is_public_url(url: &reqwest::Url) -> bool {
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    match host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+    {
+        Ok(ip) => is_public_ip(&ip),
+        Err(_) => true,
+    }
+}
+
+fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match embedded_ipv4(ip) {
+            Some(ip) => is_public_ipv4(&ip),
+            None => {
+                let unique_local = (ip.segments()[0] & 0xfe00) == 0xfc00;
+                let link_local = (ip.segments()[0] & 0xffc0) == 0xfe80;
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || unique_local
+                    || link_local)
+            }
+        },
+    }
+}
+
+/// The IPv4 address an IPv6 one reaches: IPv4-mapped, NAT64
+/// (64:ff9b::/96) and 6to4 (2002::/16) addresses are forwarded to it.
+fn embedded_ipv4(ip: &Ipv6Addr) -> Option<Ipv4Addr> {
+    let segments = ip.segments();
+    let octets = ip.octets();
+    if let Some(ip) = ip.to_ipv4_mapped() {
+        Some(ip)
+    } else if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        Some(Ipv4Addr::new(
+            octets[12], octets[13], octets[14], octets[15],
+        ))
+    } else if segments[0] == 0x2002 {
+        Some(Ipv4Addr::new(octets[2], octets[3], octets[4], octets[5]))
+    } else {
+        None
+    }
+}
+
+fn is_public_ipv4(ip: &Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    // 0.0.0.0/8, "this network".
+    let this_network = a == 0;
+    // 100.64.0.0/10, carrier-grade NAT.
+    let shared = a == 100 && (b & 0xc0) == 64;
+    // 198.18.0.0/15, benchmarking.
+    let benchmarking = a == 198 && (b & 0xfe) == 18;
+    // 240.0.0.0/4, reserved, broadcast included.
+    let reserved = a >= 240;
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_multicast()
+        || this_network
+        || shared
+        || benchmarking
+        || reserved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_station_params() {
+        assert_eq!(
+            station_params(&params(&[
+                ("streamUrl", "https://stream.radioparadise.com/mp3-192"),
+                ("name", "Radio Paradise"),
+                ("homepageUrl", ""),
+            ])),
+            Ok((
+                "https://stream.radioparadise.com/mp3-192",
+                "Radio Paradise",
+                None
+            ))
+        );
+        assert_eq!(
+            station_params(&params(&[("name", "Radio Paradise")])),
+            Err("Missing streamUrl parameter")
+        );
+        assert_eq!(
+            station_params(&params(&[
+                ("streamUrl", "file:///etc/passwd"),
+                ("name", "Radio Paradise"),
+            ])),
+            Err("streamUrl must be an http or https URL")
+        );
+    }
+
+    #[test]
+    fn test_is_public_url() {
+        let url = |url: &str| reqwest::Url::parse(url).unwrap();
+        assert!(is_public_url(&url(
+            "https://stream.radioparadise.com/mp3-192"
+        )));
+        assert!(is_public_url(&url("http://93.184.216.34:8000/live")));
+        assert!(!is_public_url(&url("http://127.0.0.1:4533/rest/ping")));
+        assert!(!is_public_url(&url("http://[::1]/")));
+        assert!(!is_public_url(&url("ftp://stream.radioparadise.com/")));
+    }
+
+    #[tokio::test]
+    async fn test_public_resolver_rejects_private_hosts() {
+        use reqwest::dns::Resolve;
+
+        let name = "localhost".parse().unwrap();
+        assert!(PublicResolver.resolve(name).await.is_err());
+    }
+
+    #[test]
+    fn test_is_public_ip() {
+        let cases = [
+            ("93.184.216.34", true),
+            ("2606:2800:220:1::1", true),
+            ("127.0.0.1", false),
+            ("10.0.0.12", false),
+            ("169.254.169.254", false),
+            ("0.0.0.0", false),
+            ("0.1.2.3", false),
+            ("100.100.1.1", false),
+            ("198.18.0.1", false),
+            ("198.19.255.254", false),
+            ("198.20.0.1", true),
+            ("224.0.0.1", false),
+            ("240.0.0.1", false),
+            ("255.255.255.255", false),
+            ("::", false),
+            ("::1", false),
+            ("fd00::1", false),
+            ("fe80::1", false),
+            ("ff02::1", false),
+            ("::ffff:192.168.1.1", false),
+            ("::ffff:93.184.216.34", true),
+            ("64:ff9b::7f00:1", false),
+            ("64:ff9b::a9fe:a9fe", false),
+            ("64:ff9b::5db8:d822", true),
+            ("2002:7f00:1::", false),
+            ("2002:c0a8:101::1", false),
+            ("2002:5db8:d822::1", true),
+        ];
+        for (ip, public) in cases {
+            assert_eq!(is_public_ip(&ip.parse().unwrap()), public, "{ip}");
+        }
+    }
+}
@@ -0,0 +1,422 @@
+use actix_web::{get, web, HttpResponse};
+use chrono::{DateTime, TimeZone, Utc};
+use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
+use std::{collections::HashMap, env, sync::Arc};
+
+use crate::{
+    handlers::{songs::track_to_json, stream},
+    repo,
+    repo::share::Share,
+    response,
+    xata::track::TrackWithUpload,
+};
+
+/// How long the URLs shared tracks redirect to stay valid, visitors get new
+/// ones from the share page.
+const SHARE_URL_TTL: u32 = 300;
+
+fn public_url() -> String {
+    env::var("NAVIDROME_PUBLIC_URL").unwrap_or_else(|_| "https://navidrome.rocksky.app".to_string())
+}
+
+fn share_url(base: &str, share_id: &str) -> String {
+    format!("{}/share/{}", base.trim_end_matches('/'), share_id)
+}
+
+/// The `expires` parameter, in milliseconds since the epoch. `Some(None)`
+/// when it's 0 or negative, which clears the expiry.
+fn expires_param(params: &HashMap<String, String>) -> Result<Option<Option<DateTime<Utc>>>, ()> {
+    let Some(expires) = params.get("expires").filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    let ms: i64 = expires.parse().map_err(|_| ())?;
+    if ms <= 0 {
+        return Ok(Some(None));
+    }
+    let expires_at = Utc.timestamp_millis_opt(ms).single().ok_or(())?;
+    Ok(Some(Some(expires_at)))
+}
+
+fn share_to_json(
+    share: &Share,
+    username: &str,
+    tracks: &[TrackWithUpload],
+    user_id: &str,
+) -> Value {
+    let entries: Vec<Value> = share
+        .track_ids
+        .iter()
+        .filter_map(|id| tracks.iter().find(|t| &t.xata_id == id))
+        .map(|t| track_to_json(t, user_id))
+        .collect();
+    let mut obj = json!({
+        "id": share.xata_id,
+        "url": share_url(&public_url(), &share.xata_id),
+        "username": username,
+        "created": share.xata_createdat.to_rfc3339(),
+        "visitCount": share.visit_count,
+        "entry": entries,
+    });
+    if let Some(description) = &share.description {
+        obj["description"] = json!(description);
+    }
+    if let Some(expires_at) = share.expires_at {
+        obj["expires"] = json!(expires_at.to_rfc3339());
+    }
+    if let Some(last_visited_at) = share.last_visited_at {
+        obj["lastVisited"] = json!(last_visited_at.to_rfc3339());
+    }
+    obj
+}
+
+async fn shares_response(
+    format: &str,
+    user_id: &str,
+    username: &str,
+    shares: &[Share],
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    let mut track_ids: Vec<String> = shares.iter().flat_map(|s| s.track_ids.clone()).collect();
+    track_ids.sort();
+    track_ids.dedup();
+    let tracks = match repo::track::get_tracks_by_ids(pool, &track_ids, user_id).await {
+        Ok(tracks) => tracks,
+        Err(e) => {
+            tracing::error!("share tracks error: {}", e);
+            return response::err(format, 0, "Internal server error");
+        }
+    };
+
+    let list: Vec<Value> = shares
+        .iter()
+        .map(|s| share_to_json(s, username, &tracks, user_id))
+        .collect();
+    response::ok(format, json!({ "shares": { "share": list } }))
+}
+
+/// The tracks of the songs, albums and playlists `ids` refer to, in order.
+async fn resolve_track_ids(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    ids: &[String],
+) -> Result<Vec<String>, anyhow::Error> {
+    let mut track_ids: Vec<String> = Vec::new();
+    for id in ids {
+        let resolved: Vec<String> = if repo::track::get_track_by_id(pool, id, user_id)
+            .await?
+            .is_some()
+        {
+            vec![id.clone()]
+        } else {
+            let album = repo::track::get_tracks_by_album(pool, id, user_id).await?;
+            if !album.is_empty() {
+                album.into_iter().map(|t| t.xata_id).collect()
+            } else {
+                match repo::playlist::get_playlist(pool, id, user_id).await? {
+                    Some((_, tracks)) => tracks.into_iter().map(|t| t.xata_id).collect(),
+                    None => vec![],
+                }
+            }
+        };
+        for track_id in resolved {
+            if !track_ids.contains(&track_id) {
+                track_ids.push(track_id);
+            }
+        }
+    }
+    Ok(track_ids)
+}
+
+pub async fn handle_get_shares(
+    format: &str,
+    user_id: &str,
+    username: &str,
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    match repo::share::get_shares(pool, user_id).await {
+        Ok(shares) => shares_response(format, user_id, username, &shares, pool).await,
+        Err(e) => {
+            tracing::error!("getShares error: {}", e);
+            response::err(format, 0, "Internal server error")
+        }
+    }
+}
+
+pub async fn handle_create_share(
+    format: &str,
+    user_id: &str,
+    username: &str,
+    params: &HashMap<String, String>,
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    // Like savePlayQueue, several ids arrive comma separated.
+    let ids: Vec<String> = params
+        .get("id")
+        .map(|v| {
+            v.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    if ids.is_empty() {
+        return response::err(format, 10, "Missing id parameter");
+    }
+    let expires_at = match expires_param(params) {
+        Ok(expires) => expires.flatten(),
+        Err(_) => return response::err(format, 10, "Invalid expires parameter"),
+    };
+    let description = params
+        .get("description")
+        .map(|s| s.as_str())
+        .filter(|s| !s.is_empty());
+
+    let track_ids = match resolve_track_ids(pool, user_id, &ids).await {
+        Ok(track_ids) => track_ids,
+        Err(e) => {
+            tracing::error!("createShare lookup error: {}", e);
+            return response::err(format, 0, "Internal server error");
+        }
+    };
+    if track_ids.is_empty() {
+        return response::err(format, 70, "Nothing to share");
+    }
+
+    let share = async {
+        let share_id =
+            repo::share::create_share(pool, user_id, &track_ids, description, expires_at).await?;
+        repo::share::get_share(pool, &share_id).await
+    }
+    .await;
+    match share {
+        Ok(Some(share)) => shares_response(format, user_id, username, &[share], pool).await,
+        Ok(None) => response::err(format, 0, "Failed to create share"),
+        Err(e) => {
+            tracing::error!("createShare error: {}", e);
+            response::err(format, 0, "Failed to create share")
+        }
+    }
+}
+
+pub async fn handle_update_share(
+    format: &str,
+    user_id: &str,
+    params: &HashMap<String, String>,
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    let share_id = match params.get("id") {
+        Some(id) => id.as_str(),
+        None => return response::err(format, 10, "Missing id parameter"),
+    };
+    let expires = match expires_param(params) {
+        Ok(expires) => expires,
+        Err(_) => return response::err(format, 10, "Invalid expires parameter"),
+    };
+
+    let share = match repo::share::get_share(pool, share_id).await {
+        Ok(Some(share)) if share.user_id == user_id => share,
+        Ok(_) => return response::err(format, 70, "Share not found"),
+        Err(e) => {
+            tracing::error!("updateShare lookup error: {}", e);
+            return response::err(format, 0, "Internal server error");
+        }
+    };
+
+    // Parameters left out keep their value.
+    let description = match params.get("description") {
+        Some(description) => Some(description.as_str()).filter(|s| !s.is_empty()),
+        None => share.description.as_deref(),
+    };
+    let expires_at = expires.unwrap_or(share.expires_at);
+
+    match repo::share::update_share(pool, user_id, share_id, description, expires_at).await {
+        Ok(true) => response::ok(format, json!({})),
+        Ok(false) => response::err(format, 70, "Share not found"),
+        Err(e) => {
+            tracing::error!("updateShare error: {}", e);
+            response::err(format, 0, "Failed to update share")
+        }
+    }
+}
+
+pub async fn handle_delete_share(
+    format: &str,
+    user_id: &str,
+    share_id: &str,
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    match repo::share::delete_share(pool, user_id, share_id).await {
+        Ok(true) => response::ok(format, json!({})),
+        Ok(false) => response::err(format, 70, "Share not found"),
+        Err(e) => {
+            tracing::error!("deleteShare error: {}", e);
+            response::err(format, 0, "Failed to delete share")
+        }
+    }
+}
+
+/// The share when it can be visited, otherwise the response to send.
+async fn visitable_share(pool: &Pool<Postgres>, share_id: &str) -> Result<Share, HttpResponse> {
+    match repo::share::get_share(pool, share_id).await {
+        Ok(Some(share)) if share.is_expired(Utc::now()) => {
+            Err(HttpResponse::Gone().body("This share has expired"))
+        }
+        Ok(Some(share)) => Ok(share),
+        Ok(None) => Err(HttpResponse::NotFound().body("Share not found")),
+        Err(e) => {
+            tracing::error!("share lookup error: {}", e);
+            Err(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+/// Public page of a share, playable without a Rocksky account.
+#[get("/share/{id}")]
+pub async fn share_page(
+    path: web::Path<String>,
+    pool: web::Data<Arc<Pool<Postgres>>>,
+) -> HttpResponse {
+    let share_id = path.into_inner();
+    let share = match visitable_share(pool.get_ref(), &share_id).await {
+        Ok(share) => share,
+        Err(response) => return response,
+    };
+
+    let page = async {
+        repo::share::record_visit(pool.get_ref(), &share.xata_id).await?;
+        let username = repo::user::get_user_handle_by_id(pool.get_ref(), &share.user_id).await?;
+        let tracks =
+            repo::track::get_tracks_by_ids(pool.get_ref(), &share.track_ids, &share.user_id)
+                .await?;
+        Ok::<_, anyhow::Error>(render_share_page(
+            &share,
+            username.as_deref().unwrap_or_default(),
+            &tracks,
+        ))
+    }
+    .await;
+
+    match page {
+        Ok(page) => HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body(page),
+        Err(e) => {
+            tracing::error!("share page error: {}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Streams a track of a share, it isn't counted as a visit since players
+/// request it again when seeking.
+#[get("/share/{id}/{track_id}")]
+pub async fn share_stream(
+    path: web::Path<(String, String)>,
+    pool: web::Data<Arc<Pool<Postgres>>>,
+) -> HttpResponse {
+    let (share_id, track_id) = path.into_inner();
+    let share = match visitable_share(pool.get_ref(), &share_id).await {
+        Ok(share) => share,
+        Err(response) => return response,
+    };
+    if !share.track_ids.contains(&track_id) {
+        return HttpResponse::NotFound().body("Song not found");
+    }
+
+    stream::redirect_shared(&share.user_id, &track_id, pool.get_ref(), SHARE_URL_TTL).await
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn render_share_page(share: &Share, username: &str, tracks: &[TrackWithUpload]) -> String {
+    let title = share
+        .description
+        .as_deref()
+        .filter(|d| !d.is_empty())
+        .unwrap_or("Shared on Rocksky");
+
+    let items: String = share
+        .track_ids
+        .iter()
+        .filter_map(|id| tracks.iter().find(|t| &t.xata_id == id))
+        .map(|t| {
+            format!(
+                "<li><p>{} &mdash; {}</p><audio controls preload=\"none\" src=\"/share/{}/{}\"></audio></li>\n",
+                escape_html(&t.title),
+                escape_html(&t.artist),
+                escape_html(&share.xata_id),
+                escape_html(&t.xata_id),
+            )
+        })
+        .collect();
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>\n\
+         <p>Shared by @{username}</p>\n<ol>\n{items}</ol>\n</body>\n</html>\n",
+        title = escape_html(title),
+        username = escape_html(username),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expires_param() {
+        let params = |expires: &str| HashMap::from([("expires".to_string(), expires.to_string())]);
+
+        assert_eq!(expires_param(&HashMap::new()), Ok(None));
+        assert_eq!(expires_param(&params("0")), Ok(Some(None)));
+        assert_eq!(
+            expires_param(&params("1767225600000")),
+            Ok(Some(Some(
+                Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap()
+            )))
+        );
+        assert_eq!(expires_param(&params("tomorrow")), Err(()));
+    }
+
+    #[test]
+    fn test_share_url() {
+        assert_eq!(
+            share_url("https://navidrome.rocksky.app/", "3f2a"),
+            "https://navidrome.rocksky.app/share/3f2a"
+        );
+    }
+
+    #[test]
+    fn test_render_share_page_escapes() {
+        let share = Share {
+            xata_id: "3f2a".to_string(),
+            user_id: "rec_1".to_string(),
+            description: Some("<script>alert(1)</script>".to_string()),
+            track_ids: vec![],
+            expires_at: None,
+            last_visited_at: None,
+            visit_count: 0,
+            xata_createdat: Utc::now(),
+        };
+        let page = render_share_page(&share, "alice.bsky.social", &[]);
+
+        assert!(page.contains("<title>&lt;script&gt;alert(1)&lt;/script&gt;</title>"));
+        assert!(!page.contains("<script>"));
+        assert!(page.contains("Shared by @alice.bsky.social"));
+    }
+}
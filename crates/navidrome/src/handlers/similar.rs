@@ -0,0 +1,181 @@
+use actix_web::HttpResponse;
+use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use crate::{handlers::songs::track_to_json, repo, response};
+
+const DEFAULT_COUNT: usize = 50;
+const MAX_COUNT: usize = 500;
+
+// How much each source weighs in the final ranking. Co-listening is the best
+// signal of "sounds like", genres widen the mix beyond the seed artist.
+const CO_LISTENED_WEIGHT: f64 = 1.0;
+const GENRE_WEIGHT: f64 = 0.6;
+const ARTIST_WEIGHT: f64 = 0.5;
+
+pub async fn handle_get_similar_songs(
+    format: &str,
+    user_id: &str,
+    id: &str,
+    pool: &Arc<Pool<Postgres>>,
+    params: &HashMap<String, String>,
+    v2: bool,
+) -> HttpResponse {
+    let key = if v2 { "similarSongs2" } else { "similarSongs" };
+    let count = parse_count(params);
+
+    let seed = match repo::similar::resolve_seed(pool, id).await {
+        Ok(Some(seed)) => seed,
+        Ok(None) => return response::err(format, 70, "Song not found"),
+        Err(e) => {
+            tracing::error!("getSimilarSongs error: {}", e);
+            return response::err(format, 0, "Internal server error");
+        }
+    };
+
+    let limit = (count * 2) as i64;
+    let candidates = tokio::try_join!(
+        repo::similar::get_co_listened_tracks(pool, user_id, &seed, limit),
+        repo::similar::get_genre_tracks(pool, user_id, &seed, limit),
+        repo::similar::get_artist_tracks(pool, user_id, &seed, limit),
+    );
+    let (co_listened, genre, artist) = match candidates {
+        Ok(candidates) => candidates,
+        Err(e) => {
+            tracing::error!("getSimilarSongs error: {}", e);
+            return response::err(format, 0, "Internal server error");
+        }
+    };
+
+    let ids = rank(
+        &[
+            (&co_listened, CO_LISTENED_WEIGHT),
+            (&genre, GENRE_WEIGHT),
+            (&artist, ARTIST_WEIGHT),
+        ],
+        &seed.track_ids,
+        count,
+    );
+
+    match repo::track::get_tracks_by_ids(pool, &ids, user_id).await {
+        Ok(tracks) => {
+            let songs: Vec<Value> = tracks.iter().map(|t| track_to_json(t, user_id)).collect();
+            response::ok(format, json!({ key: { "song": songs } }))
+        }
+        Err(e) => {
+            tracing::error!("getSimilarSongs error: {}", e);
+            response::err(format, 0, "Internal server error")
+        }
+    }
+}
+
+pub async fn handle_get_top_songs(
+    format: &str,
+    user_id: &str,
+    pool: &Arc<Pool<Postgres>>,
+    params: &HashMap<String, String>,
+) -> HttpResponse {
+    let artist = match params.get("artist").filter(|s| !s.is_empty()) {
+        Some(artist) => artist,
+        None => return response::err(format, 10, "Missing artist parameter"),
+    };
+    let count = parse_count(params);
+
+    let tracks = match repo::similar::get_top_track_ids(pool, user_id, artist, count as i64).await {
+        Ok(ids) => repo::track::get_tracks_by_ids(pool, &ids, user_id).await,
+        Err(e) => Err(e),
+    };
+
+    match tracks {
+        Ok(tracks) => {
+            let songs: Vec<Value> = tracks.iter().map(|t| track_to_json(t, user_id)).collect();
+            response::ok(format, json!({ "topSongs": { "song": songs } }))
+        }
+        Err(e) => {
+            tracing::error!("getTopSongs error: {}", e);
+            response::err(format, 0, "Internal server error")
+        }
+    }
+}
+
+fn parse_count(params: &HashMap<String, String>) -> usize {
+    params
+        .get("count")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_COUNT)
+        .min(MAX_COUNT)
+}
+
+/// Merges scored candidate lists into the `count` best track ids. Scores are
+/// normalized per list, so that play counts and shared genre counts can be
+/// weighed against each other, and summed for tracks found by several lists.
+pub fn rank(sources: &[(&[(String, i64)], f64)], exclude: &[String], count: usize) -> Vec<String> {
+    let exclude: HashSet<&str> = exclude.iter().map(|id| id.as_str()).collect();
+    let mut scores: HashMap<&str, f64> = HashMap::new();
+    // First-seen order breaks ties, keeping the result stable.
+    let mut order: Vec<&str> = vec![];
+
+    for (candidates, weight) in sources {
+        let max = candidates
+            .iter()
+            .map(|(_, score)| *score)
+            .max()
+            .unwrap_or(0)
+            .max(1) as f64;
+        for (id, score) in candidates.iter() {
+            if exclude.contains(id.as_str()) {
+                continue;
+            }
+            // Unplayed tracks still count for having been found.
+            let normalized = (*score).max(0) as f64 / max;
+            let entry = scores.entry(id.as_str()).or_insert_with(|| {
+                order.push(id.as_str());
+                0.0
+            });
+            *entry += weight * (0.5 + 0.5 * normalized);
+        }
+    }
+
+    let mut ranked: Vec<(usize, &str)> = order.into_iter().enumerate().collect();
+    ranked.sort_by(|(a_pos, a), (b_pos, b)| {
+        scores[b]
+            .partial_cmp(&scores[a])
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a_pos.cmp(b_pos))
+    });
+    ranked
+        .into_iter()
+        .take(count)
+        .map(|(_, id)| id.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scored(items: &[(&str, i64)]) -> Vec<(String, i64)> {
+        items.iter().map(|(id, s)| (id.to_string(), *s)).collect()
+    }
+
+    #[test]
+    fn test_rank() {
+        let co_listened = scored(&[("a", 10), ("b", 5), ("seed", 20)]);
+        let genre = scored(&[("c", 3), ("b", 3)]);
+        let artist = scored(&[("d", 0)]);
+
+        let ids = rank(
+            &[(&co_listened, 1.0), (&genre, 0.6), (&artist, 0.5)],
+            &["seed".to_string()],
+            10,
+        );
+        assert_eq!(ids, vec!["b", "a", "c", "d"]);
+
+        let ids = rank(&[(&co_listened, 1.0)], &["seed".to_string()], 1);
+        assert_eq!(ids, vec!["a"]);
+    }
+}
@@ -1,13 +1,28 @@
+use ::s3::Bucket;
 use actix_web::HttpResponse;
+use anyhow::anyhow;
+use bytes::Bytes;
+use lru::LruCache;
 use sqlx::{Pool, Postgres};
 use std::{
     collections::HashMap,
     env,
+    num::NonZeroUsize,
     sync::{Arc, Mutex, OnceLock},
     time::{Duration, Instant},
 };
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+    sync::{mpsc, Semaphore},
+};
 
-use crate::{repo, repo::track::StreamTrack, response, s3};
+use crate::{
+    repo,
+    repo::track::StreamTrack,
+    response, s3,
+    transcode::{self, select_rendition, Rendition, StreamOptions},
+};
 
 // Cache decrypted credentials keyed by the encrypted value — safe against
 // credential rotation since the key changes when the stored bytes change.
@@ -54,6 +69,34 @@ async fn get_stream_track_cached(
     Ok(track)
 }
 
+// Renditions known to be cached in object storage, keyed by
+// "storage_provider_id:key", so repeats skip the HEAD request. The least
+// recently streamed ones are deleted from storage once there are too many.
+const MAX_RENDITIONS: usize = 4096;
+
+static RENDITION_CACHE: OnceLock<Mutex<LruCache<String, StreamTrack>>> = OnceLock::new();
+
+fn rendition_cache() -> &'static Mutex<LruCache<String, StreamTrack>> {
+    RENDITION_CACHE
+        .get_or_init(|| Mutex::new(LruCache::new(NonZeroUsize::new(MAX_RENDITIONS).unwrap())))
+}
+
+// Renditions are spooled to a temporary file until they're uploaded, longer
+// ones (over an hour at 320kbps) are streamed without being cached.
+const MAX_CACHED_RENDITION: u64 = 160 * 1024 * 1024;
+
+// ffmpeg processes running at once, clients asking for more are told to
+// retry later.
+const MAX_TRANSCODES: usize = 8;
+
+static TRANSCODES: Semaphore = Semaphore::const_new(MAX_TRANSCODES);
+
+// Transcodes which go on after their client disconnected, to cache their
+// rendition. The others stop with their client.
+const MAX_DETACHED_TRANSCODES: usize = 4;
+
+static DETACHED_TRANSCODES: Semaphore = Semaphore::const_new(MAX_DETACHED_TRANSCODES);
+
 static ENC_KEY: OnceLock<String> = OnceLock::new();
 
 fn enc_key() -> &'static str {
@@ -75,7 +118,12 @@ fn decrypt_cached(encoded: &str) -> Result<String, anyhow::Error> {
     Ok(plaintext)
 }
 
-async fn resolve_url(track: &StreamTrack) -> Result<String, anyhow::Error> {
+/// How long URLs presigned for the owner of a track stay valid.
+const STREAM_URL_TTL: u32 = 3600;
+
+/// The URL of the object of `track`, presigned for `expires_secs` when it's
+/// in a private bucket the user brought.
+async fn resolve_url(track: &StreamTrack, expires_secs: u32) -> Result<String, anyhow::Error> {
     if track.storage_provider_id.is_none() {
         return Ok(s3::public_url(&track.r2_key));
     }
@@ -95,19 +143,295 @@ async fn resolve_url(track: &StreamTrack) -> Result<String, anyhow::Error> {
         track.storage_bucket.as_deref().unwrap_or_default(),
         &access_key,
         &secret_key,
-        3600,
+        expires_secs,
     )
     .await
 }
 
+fn track_bucket(track: &StreamTrack) -> Result<Box<Bucket>, anyhow::Error> {
+    if track.storage_provider_id.is_none() {
+        return s3::default_bucket();
+    }
+
+    let access_key = decrypt_cached(track.storage_access_key.as_deref().unwrap_or_default())?;
+    let secret_key = decrypt_cached(track.storage_secret_key.as_deref().unwrap_or_default())?;
+
+    s3::bucket_with_creds(
+        track.storage_endpoint.as_deref().unwrap_or_default(),
+        track.storage_region.as_deref().unwrap_or("auto"),
+        track.storage_bucket.as_deref().unwrap_or_default(),
+        &access_key,
+        &secret_key,
+    )
+}
+
+/// The rendition of a track, stored alongside the original so it resolves
+/// to a URL the same way.
+fn rendition_track(track: &StreamTrack, rendition: &Rendition) -> StreamTrack {
+    StreamTrack {
+        r2_key: rendition.key(&track.r2_key),
+        mime_type: rendition.codec.content_type().to_string(),
+        ..track.clone()
+    }
+}
+
+fn rendition_cache_key(rendition: &StreamTrack) -> String {
+    format!(
+        "{}:{}",
+        rendition.storage_provider_id.as_deref().unwrap_or_default(),
+        rendition.r2_key
+    )
+}
+
+fn remember_rendition(rendition: &StreamTrack) {
+    let cache_key = rendition_cache_key(rendition);
+    let evicted = rendition_cache()
+        .lock()
+        .unwrap()
+        .push(cache_key.clone(), rendition.clone());
+    if let Some((key, evicted)) = evicted {
+        if key != cache_key {
+            tokio::spawn(delete_rendition(evicted));
+        }
+    }
+}
+
+async fn delete_rendition(rendition: StreamTrack) {
+    let deleted = async {
+        let bucket = track_bucket(&rendition)?;
+        bucket
+            .delete_object(s3::object_path(&rendition.r2_key))
+            .await?;
+        Ok::<_, anyhow::Error>(())
+    }
+    .await;
+    match deleted {
+        Ok(()) => tracing::info!(key = %rendition.r2_key, "rendition evicted"),
+        Err(e) => tracing::warn!("rendition delete error: {}", e),
+    }
+}
+
+async fn is_cached(rendition: &StreamTrack) -> bool {
+    let cache_key = rendition_cache_key(rendition);
+    if rendition_cache().lock().unwrap().get(&cache_key).is_some() {
+        return true;
+    }
+
+    let head = async {
+        let bucket = track_bucket(rendition)?;
+        let head = bucket
+            .head_object(s3::object_path(&rendition.r2_key))
+            .await?;
+        Ok::<_, anyhow::Error>(head)
+    }
+    .await;
+    match head {
+        Ok((_, 200)) => {
+            remember_rendition(rendition);
+            true
+        }
+        Ok(_) => false,
+        Err(e) => {
+            tracing::warn!("rendition lookup error: {}", e);
+            false
+        }
+    }
+}
+
+async fn cache_rendition(bucket: &Bucket, rendition: &StreamTrack, file: &mut File) {
+    if let Err(e) = file.rewind().await {
+        tracing::warn!("rendition spool error: {}", e);
+        return;
+    }
+    match bucket
+        .put_object_stream_with_content_type(
+            file,
+            s3::object_path(&rendition.r2_key),
+            &rendition.mime_type,
+        )
+        .await
+    {
+        Ok(response) if response.status_code() == 200 => {
+            tracing::info!(key = %rendition.r2_key, "rendition cached");
+            remember_rendition(rendition);
+        }
+        Ok(response) => tracing::warn!(
+            "rendition upload failed with status {}",
+            response.status_code()
+        ),
+        Err(e) => tracing::warn!("rendition upload error: {}", e),
+    }
+}
+
+/// Streams the output of ffmpeg transcoding the track, 503 when
+/// [`MAX_TRANSCODES`] already run. Renditions from the start of the track are
+/// uploaded once complete, which goes on when the client stops listening
+/// halfway unless [`MAX_DETACHED_TRANSCODES`] already do.
+async fn transcode(
+    track: &StreamTrack,
+    rendition: Rendition,
+    cached: StreamTrack,
+    options: &StreamOptions,
+) -> Result<HttpResponse, anyhow::Error> {
+    let Ok(permit) = TRANSCODES.try_acquire() else {
+        tracing::warn!("too many transcodes, rejecting stream");
+        return Ok(HttpResponse::ServiceUnavailable()
+            .append_header(("Access-Control-Allow-Origin", "*"))
+            .append_header(("Retry-After", "10"))
+            .finish());
+    };
+
+    let url = resolve_url(track, STREAM_URL_TTL).await?;
+    let mut child = transcode::spawn(&url, &rendition, options)?;
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow!("ffmpeg stdout is not piped"))?;
+    let bucket = match options.time_offset {
+        Some(_) => None,
+        None => Some(track_bucket(&cached)?),
+    };
+
+    let (tx, rx) = mpsc::channel::<Bytes>(16);
+    tokio::spawn(async move {
+        let _permit = permit;
+        let mut spool = match bucket {
+            Some(_) => match tempfile::tempfile() {
+                Ok(file) => Some((File::from_std(file), 0)),
+                Err(e) => {
+                    tracing::warn!("rendition spool error: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let mut detached = None;
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            let read = match stdout.read(&mut buffer).await {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) => {
+                    tracing::warn!("transcode read error: {}", e);
+                    return;
+                }
+            };
+            let chunk = Bytes::copy_from_slice(&buffer[..read]);
+
+            if let Some((file, written)) = spool.as_mut() {
+                *written += read as u64;
+                if *written > MAX_CACHED_RENDITION {
+                    spool = None;
+                } else if let Err(e) = file.write_all(&chunk).await {
+                    tracing::warn!("rendition spool error: {}", e);
+                    spool = None;
+                }
+            }
+
+            // ffmpeg is killed when `child` is dropped.
+            if detached.is_none() && tx.send(chunk).await.is_err() {
+                match DETACHED_TRANSCODES.try_acquire() {
+                    Ok(permit) if spool.is_some() => detached = Some(permit),
+                    _ => return,
+                }
+            }
+            if detached.is_some() && spool.is_none() {
+                return;
+            }
+        }
+
+        match child.wait().await {
+            Ok(status) if status.success() => {}
+            Ok(status) => {
+                tracing::warn!("ffmpeg exited with {}", status);
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("ffmpeg wait error: {}", e);
+                return;
+            }
+        }
+        if let (Some(bucket), Some((mut file, _))) = (bucket, spool) {
+            if let Err(e) = file.flush().await {
+                tracing::warn!("rendition spool error: {}", e);
+                return;
+            }
+            cache_rendition(&bucket, &cached, &mut file).await;
+        }
+    });
+
+    let body = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv()
+            .await
+            .map(|chunk| (Ok::<_, actix_web::Error>(chunk), rx))
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type(rendition.codec.content_type())
+        .append_header(("Access-Control-Allow-Origin", "*"))
+        .append_header(("Cache-Control", "no-cache"))
+        .streaming(body))
+}
+
 /// Resolve the object URL and hand the client a 302 straight to the CDN /
 /// object store. The bytes never pass through this server, so seeking, range
 /// requests and edge caching are all served by the origin.
-async fn redirect(
+async fn redirect(format: &str, track: &StreamTrack) -> HttpResponse {
+    let url = match resolve_url(track, STREAM_URL_TTL).await {
+        Ok(u) => u,
+        Err(e) => {
+            tracing::error!("stream url resolve error: {}", e);
+            return response::err(format, 0, "Failed to resolve audio URL");
+        }
+    };
+
+    found(url)
+}
+
+fn found(url: String) -> HttpResponse {
+    HttpResponse::Found()
+        .append_header(("Location", url))
+        .append_header(("Access-Control-Allow-Origin", "*"))
+        .append_header(("Cache-Control", "no-cache"))
+        .finish()
+}
+
+/// Redirects a visitor of a share to the original of one of its tracks. The
+/// link is public, so the URL is presigned for `expires_secs` only.
+pub async fn redirect_shared(
+    owner_id: &str,
+    track_id: &str,
+    pool: &Arc<Pool<Postgres>>,
+    expires_secs: u32,
+) -> HttpResponse {
+    let track = match get_stream_track_cached(pool, track_id, owner_id).await {
+        Ok(Some(t)) => t,
+        Ok(None) => return HttpResponse::NotFound().body("Song not found"),
+        Err(e) => {
+            tracing::error!("shared stream lookup error: {}", e);
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    match resolve_url(&track, expires_secs).await {
+        Ok(url) => found(url),
+        Err(e) => {
+            tracing::error!("shared stream url resolve error: {}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Redirects to the original, or to the rendition `options` ask for once it
+/// is cached. Renditions not cached yet, or from an offset, are transcoded
+/// on the fly.
+async fn stream(
     format: &str,
     user_id: &str,
     song_id: &str,
     pool: &Arc<Pool<Postgres>>,
+    options: &StreamOptions,
+    head: bool,
 ) -> HttpResponse {
     let track = match get_stream_track_cached(pool, song_id, user_id).await {
         Ok(Some(t)) => t,
@@ -118,19 +442,30 @@ async fn redirect(
         }
     };
 
-    let url = match resolve_url(&track).await {
-        Ok(u) => u,
-        Err(e) => {
-            tracing::error!("stream url resolve error: {}", e);
-            return response::err(format, 0, "Failed to resolve audio URL");
-        }
+    let source_bit_rate = track.bit_rate.and_then(|rate| u32::try_from(rate).ok());
+    let Some(rendition) = select_rendition(options, &track.mime_type, source_bit_rate) else {
+        return redirect(format, &track).await;
     };
 
-    HttpResponse::Found()
-        .append_header(("Location", url))
-        .append_header(("Access-Control-Allow-Origin", "*"))
-        .append_header(("Cache-Control", "no-cache"))
-        .finish()
+    let cached = rendition_track(&track, &rendition);
+    if options.time_offset.is_none() && is_cached(&cached).await {
+        return redirect(format, &cached).await;
+    }
+
+    if head {
+        return HttpResponse::Ok()
+            .content_type(rendition.codec.content_type())
+            .append_header(("Access-Control-Allow-Origin", "*"))
+            .finish();
+    }
+
+    match transcode(&track, rendition, cached, options).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!("transcode error: {}", e);
+            response::err(format, 0, "Failed to transcode audio")
+        }
+    }
 }
 
 pub async fn handle_head(
@@ -138,8 +473,9 @@ pub async fn handle_head(
     user_id: &str,
     song_id: &str,
     pool: &Arc<Pool<Postgres>>,
+    options: &StreamOptions,
 ) -> HttpResponse {
-    redirect(format, user_id, song_id, pool).await
+    stream(format, user_id, song_id, pool, options, true).await
 }
 
 pub async fn handle(
@@ -148,6 +484,7 @@ pub async fn handle(
     song_id: &str,
     pool: &Arc<Pool<Postgres>>,
     _range: Option<&str>,
+    options: &StreamOptions,
 ) -> HttpResponse {
-    redirect(format, user_id, song_id, pool).await
+    stream(format, user_id, song_id, pool, options, false).await
 }
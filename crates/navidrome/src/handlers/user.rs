@@ -1,9 +1,11 @@
 use actix_web::HttpResponse;
+use anyhow::Error;
+use chrono::{DateTime, SecondsFormat, Utc};
 use serde_json::json;
 use sqlx::{Pool, Postgres};
 use std::sync::Arc;
 
-use crate::{response, xata::user::UserWithApiKey};
+use crate::{repo, repo::scan::SCAN_NATS_TOPIC, response, xata::user::UserWithApiKey};
 
 pub fn handle_get_user(format: &str, user: &UserWithApiKey) -> HttpResponse {
     response::ok(
@@ -44,17 +46,141 @@ pub fn handle_get_license(format: &str) -> HttpResponse {
     )
 }
 
-pub fn handle_get_scan_status(format: &str, pool: &Arc<Pool<Postgres>>) -> HttpResponse {
-    let _ = pool;
-    response::ok(
-        format,
-        json!({
-            "scanStatus": {
-                "scanning": false,
-                "count": 0,
-                "folderCount": 0,
-                "lastScan": "1970-01-01T00:00:00.000Z"
-            }
-        }),
-    )
+/// Reports the progress of the library scans of the user, the last one when
+/// no scan is running.
+pub async fn handle_get_scan_status(
+    format: &str,
+    user_id: &str,
+    pool: &Arc<Pool<Postgres>>,
+) -> HttpResponse {
+    let status = match repo::scan::get_scan_status(pool, user_id).await {
+        Ok(status) => status,
+        Err(e) => {
+            tracing::error!("getScanStatus error: {}", e);
+            return response::err(format, 0, "Internal server error");
+        }
+    };
+
+    let Some(status) = status else {
+        return response::ok(
+            format,
+            json!({
+                "scanStatus": {
+                    "scanning": false,
+                    "count": 0,
+                    "folderCount": 0,
+                    "lastScan": format_last_scan(DateTime::UNIX_EPOCH)
+                }
+            }),
+        );
+    };
+
+    let mut scan_status = json!({
+        "scanning": status.scanning,
+        "count": status.count,
+        "folderCount": status.folder_count,
+    });
+    if let Some(finished_at) = status.finished_at {
+        scan_status["lastScan"] = json!(format_last_scan(finished_at));
+    }
+
+    response::ok(format, json!({ "scanStatus": scan_status }))
+}
+
+/// `lastScan` as Subsonic clients parse it, e.g. `2024-05-01T12:00:00.000Z`.
+fn format_last_scan(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Hands the scan to `rockskyd library serve`, failing when no scanner
+/// takes it.
+async fn request_scanner(nc: &async_nats::Client, user_id: &str) -> Result<(), Error> {
+    nc.request(SCAN_NATS_TOPIC, bytes::Bytes::from(user_id.to_string()))
+        .await?;
+    Ok(())
+}
+
+/// Marks the scan as running before handing it to the scanner, so a scanner
+/// finishing right away isn't overwritten. The scan is marked as not running
+/// again when no scanner takes it.
+async fn request_scan(
+    pool: &Pool<Postgres>,
+    nc: &async_nats::Client,
+    user_id: &str,
+) -> Result<(), Error> {
+    repo::scan::queue_scan(pool, user_id).await?;
+    if let Err(e) = request_scanner(nc, user_id).await {
+        if let Err(e) = repo::scan::cancel_scan(pool, user_id).await {
+            tracing::error!("startScan cancel error: {}", e);
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Requests a scan of the storages of the user from `rockskyd library serve`,
+/// then reports it like `getScanStatus`.
+pub async fn handle_start_scan(
+    format: &str,
+    user_id: &str,
+    pool: &Arc<Pool<Postgres>>,
+    nc: &Arc<async_nats::Client>,
+) -> HttpResponse {
+    match repo::scan::has_storage(pool, user_id).await {
+        Ok(true) => {}
+        Ok(false) => return response::err(format, 70, "No storage to scan"),
+        Err(e) => {
+            tracing::error!("startScan error: {}", e);
+            return response::err(format, 0, "Internal server error");
+        }
+    }
+
+    if let Err(e) = request_scan(pool, nc, user_id).await {
+        tracing::error!("startScan error: {}", e);
+        return response::err(format, 0, "Internal server error");
+    }
+
+    handle_get_scan_status(format, user_id, pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+
+    #[test]
+    fn test_format_last_scan() {
+        assert_eq!(
+            format_last_scan(DateTime::UNIX_EPOCH),
+            "1970-01-01T00:00:00.000Z"
+        );
+        let at = DateTime::parse_from_rfc3339("2024-05-01T12:30:45.123456+02:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(format_last_scan(at), "2024-05-01T10:30:45.123Z");
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a NATS server at NATS_URL"]
+    async fn test_request_scanner() {
+        let addr = std::env::var("NATS_URL").unwrap_or_else(|_| "nats://localhost:4222".into());
+        let nc = async_nats::connect(&addr).await.unwrap();
+
+        assert!(request_scanner(&nc, "user").await.is_err());
+
+        let mut sub = nc.subscribe(SCAN_NATS_TOPIC).await.unwrap();
+        let scanner = nc.clone();
+        let scanner = tokio::spawn(async move {
+            let msg = sub.next().await.unwrap();
+            scanner
+                .publish(msg.reply.unwrap(), "".into())
+                .await
+                .unwrap();
+            msg.payload
+        });
+
+        request_scanner(&nc, "user").await.unwrap();
+        assert_eq!(scanner.await.unwrap(), "user");
+    }
 }
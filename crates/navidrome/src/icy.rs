@@ -0,0 +1,148 @@
+/// Splits a SHOUTcast/Icecast stream requested with `Icy-MetaData: 1` into
+/// its audio and metadata: every `metaint` audio bytes come with a length
+/// byte, then that many times 16 bytes of metadata.
+#[derive(Debug)]
+pub struct Demuxer {
+    metaint: usize,
+    state: State,
+}
+
+#[derive(Debug, PartialEq)]
+enum State {
+    /// Audio bytes left before the next length byte.
+    Audio(usize),
+    /// Metadata bytes left, and the ones read so far.
+    Metadata(usize, Vec<u8>),
+}
+
+impl Demuxer {
+    /// `metaint` is the `icy-metaint` response header.
+    pub fn new(metaint: usize) -> Self {
+        Demuxer {
+            metaint,
+            state: State::Audio(metaint),
+        }
+    }
+
+    /// Appends the audio bytes of `chunk` to `audio` and returns the
+    /// non-empty metadata blocks completed by it.
+    pub fn push(&mut self, mut chunk: &[u8], audio: &mut Vec<u8>) -> Vec<Vec<u8>> {
+        let mut blocks = Vec::new();
+        while !chunk.is_empty() {
+            match &mut self.state {
+                State::Audio(0) => {
+                    let len = chunk[0] as usize * 16;
+                    chunk = &chunk[1..];
+                    self.state = if len == 0 {
+                        State::Audio(self.metaint)
+                    } else {
+                        State::Metadata(len, Vec::with_capacity(len))
+                    };
+                }
+                State::Audio(left) => {
+                    let n = (*left).min(chunk.len());
+                    audio.extend_from_slice(&chunk[..n]);
+                    *left -= n;
+                    chunk = &chunk[n..];
+                }
+                State::Metadata(left, block) => {
+                    let n = (*left).min(chunk.len());
+                    block.extend_from_slice(&chunk[..n]);
+                    *left -= n;
+                    chunk = &chunk[n..];
+                    if *left == 0 {
+                        blocks.push(std::mem::take(block));
+                        self.state = State::Audio(self.metaint);
+                    }
+                }
+            }
+        }
+        blocks
+    }
+}
+
+/// The `StreamTitle` of a metadata block, e.g.
+/// `StreamTitle='Artist - Title';StreamUrl='';` padded with NULs.
+pub fn stream_title(block: &[u8]) -> Option<String> {
+    let block = String::from_utf8_lossy(block);
+    let block = block.trim_end_matches('\0');
+    let start = block.find("StreamTitle='")? + "StreamTitle='".len();
+    let rest = &block[start..];
+    // Titles may contain quotes, the value ends at the next field.
+    let end = rest.find("';").or_else(|| rest.rfind('\''))?;
+    Some(rest[..end].trim().to_string()).filter(|title| !title.is_empty())
+}
+
+/// Splits a `StreamTitle` into its artist and title, stations announcing
+/// themselves or ads don't follow the `Artist - Title` convention.
+pub fn split_title(stream_title: &str) -> Option<(String, String)> {
+    let (artist, title) = stream_title.split_once(" - ")?;
+    let (artist, title) = (artist.trim(), title.trim());
+    if artist.is_empty() || title.is_empty() {
+        return None;
+    }
+    Some((artist.to_string(), title.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(text: &str) -> Vec<u8> {
+        let mut block = text.as_bytes().to_vec();
+        block.resize(text.len().div_ceil(16) * 16, 0);
+        let mut bytes = vec![(block.len() / 16) as u8];
+        bytes.extend(block);
+        bytes
+    }
+
+    #[test]
+    fn test_demuxer_push() {
+        let mut stream = b"abcd".to_vec();
+        stream.extend(metadata("StreamTitle='A - B';"));
+        stream.extend(b"efgh");
+        stream.push(0);
+        stream.extend(b"ij");
+
+        // Chunk boundaries anywhere within the blocks.
+        for size in 1..stream.len() {
+            let mut demuxer = Demuxer::new(4);
+            let mut audio = Vec::new();
+            let mut blocks = Vec::new();
+            for chunk in stream.chunks(size) {
+                blocks.extend(demuxer.push(chunk, &mut audio));
+            }
+            assert_eq!(audio, b"abcdefghij");
+            assert_eq!(blocks.len(), 1);
+            assert_eq!(stream_title(&blocks[0]), Some("A - B".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_stream_title() {
+        assert_eq!(
+            stream_title(b"StreamTitle='Daft Punk - One More Time';StreamUrl='';\0\0"),
+            Some("Daft Punk - One More Time".to_string())
+        );
+        assert_eq!(
+            stream_title(b"StreamTitle='Guns N' Roses - Don't Cry';\0"),
+            Some("Guns N' Roses - Don't Cry".to_string())
+        );
+        assert_eq!(stream_title(b"StreamTitle='';\0\0"), None);
+        assert_eq!(stream_title(b"StreamUrl='https://example.com';"), None);
+    }
+
+    #[test]
+    fn test_split_title() {
+        assert_eq!(
+            split_title("Daft Punk - One More Time"),
+            Some(("Daft Punk".to_string(), "One More Time".to_string()))
+        );
+        assert_eq!(
+            split_title("Jay-Z - 99 Problems - Live"),
+            Some(("Jay-Z".to_string(), "99 Problems - Live".to_string()))
+        );
+        assert_eq!(split_title("Radio Paradise"), None);
+        assert_eq!(split_title(" - Untitled"), None);
+    }
+}
@@ -1,9 +1,12 @@
 pub mod api;
 pub mod auth;
 pub mod handlers;
+pub mod icy;
+pub mod musicbrainz;
 pub mod repo;
 pub mod response;
 pub mod s3;
+pub mod transcode;
 pub mod typesense;
 pub mod xata;
 
@@ -48,9 +51,18 @@ pub const INFO: &str = r#"
   getNowPlaying     getMusicDirectory
   getPlaylists      getPlaylist
   getSimilarSongs2  getTopSongs
-  getLyrics         getInternetRadioStations
+  getLyrics         getLyricsBySongId
   getPlayQueue      savePlayQueue
   star              unstar
+  getBookmarks      createBookmark
+  deleteBookmark    getShares
+  createShare       updateShare
+  deleteShare
+  getInternetRadioStations
+  createInternetRadioStation
+  updateInternetRadioStation
+  deleteInternetRadioStation
+  getOpenSubsonicExtensions
 "#;
 
 #[get("/")]
@@ -110,6 +122,8 @@ pub async fn run() -> Result<(), Error> {
             .service(handlers::handle_get)
             .service(handlers::handle_post)
             .service(handlers::handle_head)
+            .service(handlers::shares::share_page)
+            .service(handlers::shares::share_stream)
     })
     .bind((host, port))?
     .run()
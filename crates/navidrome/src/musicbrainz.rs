@@ -0,0 +1,173 @@
+//! MusicBrainz recordings the scrobbler already fetched, read from its Redis
+//! cache so info requests never wait on MusicBrainz.
+
+use anyhow::Error;
+use redis::{aio::MultiplexedConnection, AsyncCommands};
+use serde::Deserialize;
+use std::env;
+use tokio::sync::OnceCell;
+
+// Same key as the scrobbler's MusicBrainz client.
+const CACHE_REC_PREFIX: &str = "mb:cache:rec:";
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Recording {
+    #[serde(rename = "artist-credit")]
+    pub artist_credit: Option<Vec<ArtistCredit>>,
+    pub releases: Option<Vec<Release>>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ArtistCredit {
+    pub name: String,
+    pub artist: Artist,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Artist {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Release {
+    pub id: String,
+    pub title: String,
+    pub disambiguation: Option<String>,
+    #[serde(rename = "release-group")]
+    pub release_group: Option<ReleaseGroup>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ReleaseGroup {
+    pub disambiguation: Option<String>,
+}
+
+static REDIS: OnceCell<Option<MultiplexedConnection>> = OnceCell::const_new();
+
+async fn redis() -> Option<MultiplexedConnection> {
+    REDIS
+        .get_or_init(|| async {
+            let url = env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1".to_string());
+            let connect = async {
+                redis::Client::open(url)?
+                    .get_multiplexed_tokio_connection()
+                    .await
+            };
+            match connect.await {
+                Ok(connection) => Some(connection),
+                Err(e) => {
+                    tracing::warn!("MusicBrainz data disabled: {}", e);
+                    None
+                }
+            }
+        })
+        .await
+        .clone()
+}
+
+/// The stored recordings of `mbids`, skipping the ones the scrobbler hasn't
+/// fetched.
+pub async fn get_recordings(mbids: &[String]) -> Result<Vec<Recording>, Error> {
+    let Some(mut redis) = redis().await else {
+        return Ok(vec![]);
+    };
+    if mbids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let keys: Vec<String> = mbids
+        .iter()
+        .map(|mbid| format!("{}{}", CACHE_REC_PREFIX, mbid))
+        .collect();
+    let values: Vec<Option<String>> = redis.mget(&keys).await?;
+
+    Ok(values
+        .into_iter()
+        .flatten()
+        .filter_map(|json| serde_json::from_str(&json).ok())
+        .collect())
+}
+
+/// The MusicBrainz artist credited as `name` on any of the recordings.
+pub fn credited_artist<'a>(recordings: &'a [Recording], name: &str) -> Option<&'a Artist> {
+    recordings
+        .iter()
+        .filter_map(|recording| recording.artist_credit.as_ref())
+        .flatten()
+        .find(|credit| {
+            credit.name.eq_ignore_ascii_case(name) || credit.artist.name.eq_ignore_ascii_case(name)
+        })
+        .map(|credit| &credit.artist)
+}
+
+/// The release titled `album` of the recordings: a recording usually appears
+/// on several releases (compilations, singles), only the album's own one is
+/// relevant.
+pub fn matching_release<'a>(recordings: &'a [Recording], album: &str) -> Option<&'a Release> {
+    recordings
+        .iter()
+        .filter_map(|recording| recording.releases.as_ref())
+        .flatten()
+        .find(|release| release.title.eq_ignore_ascii_case(album))
+}
+
+/// The release's comment on MusicBrainz, falling back to its release group's.
+pub fn release_notes(release: &Release) -> Option<&str> {
+    let group = release
+        .release_group
+        .as_ref()
+        .and_then(|group| group.disambiguation.as_deref());
+    release
+        .disambiguation
+        .as_deref()
+        .filter(|notes| !notes.trim().is_empty())
+        .or(group.filter(|notes| !notes.trim().is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recordings() -> Vec<Recording> {
+        serde_json::from_value(serde_json::json!([
+            {
+                "artist-credit": [
+                    { "name": "Radiohead", "artist": { "id": "a74b1b7f", "name": "Radiohead" } }
+                ],
+                "releases": [
+                    { "id": "r-single", "title": "Paranoid Android" },
+                    {
+                        "id": "r-album",
+                        "title": "OK Computer",
+                        "disambiguation": "",
+                        "release-group": { "disambiguation": "1997 studio album" }
+                    }
+                ]
+            }
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_credited_artist() {
+        let recordings = recordings();
+        assert_eq!(
+            credited_artist(&recordings, "radiohead").map(|a| a.id.as_str()),
+            Some("a74b1b7f")
+        );
+        assert!(credited_artist(&recordings, "Portishead").is_none());
+    }
+
+    #[test]
+    fn test_matching_release() {
+        let recordings = recordings();
+        let release = matching_release(&recordings, "OK Computer").unwrap();
+        assert_eq!(release.id, "r-album");
+        assert_eq!(release_notes(release), Some("1997 studio album"));
+
+        let single = matching_release(&recordings, "Paranoid Android").unwrap();
+        assert_eq!(release_notes(single), None);
+        assert!(matching_release(&recordings, "Kid A").is_none());
+    }
+}
@@ -181,3 +181,78 @@ pub async fn get_picture_by_artist_id(
 
     Ok(row.and_then(|(p,)| p))
 }
+
+pub async fn get_biography_by_artist_id(
+    pool: &Pool<Postgres>,
+    artist_id: &str,
+) -> Result<Option<String>, Error> {
+    let row: Option<(Option<String>,)> =
+        sqlx::query_as(r#"SELECT biography FROM artists WHERE xata_id = $1"#)
+            .bind(artist_id)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(row.and_then(|(b,)| b).filter(|b| !b.trim().is_empty()))
+}
+
+/// Artists of the user's library most played by the listeners of `artist_id`,
+/// ranked by the number of those listeners.
+pub async fn get_similar_artists(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    artist_id: &str,
+    count: i64,
+) -> Result<Vec<ArtistWithStats>, Error> {
+    // Same junction-table consistency check as get_all_artists.
+    let rows: Vec<ArtistWithStats> = sqlx::query_as(
+        r#"
+        WITH listeners AS (
+            SELECT user_id
+            FROM scrobbles
+            WHERE artist_id = $2
+            GROUP BY user_id
+            ORDER BY COUNT(*) DESC
+            LIMIT 500
+        ),
+        scores AS (
+            SELECT s.artist_id, COUNT(DISTINCT s.user_id) AS score
+            FROM scrobbles s
+            JOIN listeners l ON s.user_id = l.user_id
+            WHERE s.artist_id IS NOT NULL AND s.artist_id <> $2
+            GROUP BY s.artist_id
+        )
+        SELECT
+            artists.xata_id,
+            artists.name,
+            artists.picture,
+            COALESCE((
+                SELECT COUNT(DISTINCT aa.album_id)
+                FROM artist_albums aa
+                JOIN album_tracks atr ON atr.album_id = aa.album_id
+                JOIN tracks tr ON tr.xata_id = atr.track_id
+                              AND tr.album_artist = artists.name
+                JOIN user_uploads uu  ON uu.track_id = atr.track_id
+                WHERE aa.artist_id = artists.xata_id
+                  AND uu.user_id = $1
+            ), 0) AS album_count
+        FROM scores
+        JOIN artists ON artists.xata_id = scores.artist_id
+        WHERE EXISTS (
+            SELECT 1 FROM artist_tracks atk
+            JOIN tracks tr ON tr.xata_id = atk.track_id
+                          AND tr.album_artist = artists.name
+            JOIN user_uploads uu ON uu.track_id = atk.track_id
+            WHERE atk.artist_id = artists.xata_id AND uu.user_id = $1
+        )
+        ORDER BY scores.score DESC, artists.name ASC
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(artist_id)
+    .bind(count)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
@@ -0,0 +1,71 @@
+use anyhow::Error;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+
+#[derive(sqlx::FromRow)]
+pub struct Bookmark {
+    pub track_id: String,
+    pub position_ms: i64,
+    pub comment: Option<String>,
+    pub xata_createdat: DateTime<Utc>,
+    pub xata_updatedat: DateTime<Utc>,
+}
+
+pub async fn get_bookmarks(pool: &Pool<Postgres>, user_id: &str) -> Result<Vec<Bookmark>, Error> {
+    let rows: Vec<Bookmark> = sqlx::query_as(
+        r#"
+        SELECT track_id, position_ms, comment, xata_createdat, xata_updatedat
+        FROM navidrome_bookmarks
+        WHERE user_id = $1
+        ORDER BY xata_updatedat DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Creates the bookmark of `user_id` in the track, or moves it.
+pub async fn save_bookmark(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    track_id: &str,
+    position_ms: i64,
+    comment: Option<&str>,
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO navidrome_bookmarks (xata_id, user_id, track_id, position_ms, comment)
+        VALUES (gen_random_uuid()::text, $1, $2, $3, $4)
+        ON CONFLICT (user_id, track_id) DO UPDATE SET
+            position_ms = EXCLUDED.position_ms,
+            comment = EXCLUDED.comment,
+            xata_updatedat = now()
+        "#,
+    )
+    .bind(user_id)
+    .bind(track_id)
+    .bind(position_ms)
+    .bind(comment)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// False when `user_id` has no bookmark in the track.
+pub async fn delete_bookmark(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    track_id: &str,
+) -> Result<bool, Error> {
+    let result =
+        sqlx::query(r#"DELETE FROM navidrome_bookmarks WHERE user_id = $1 AND track_id = $2"#)
+            .bind(user_id)
+            .bind(track_id)
+            .execute(pool)
+            .await?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -1,10 +1,15 @@
 pub mod album;
 pub mod artist;
+pub mod bookmark;
 pub mod genre;
 pub mod nowplaying;
 pub mod playlist;
 pub mod playqueue;
+pub mod radio;
+pub mod scan;
 pub mod scrobble;
+pub mod share;
+pub mod similar;
 pub mod starred;
 pub mod track;
 pub mod user;
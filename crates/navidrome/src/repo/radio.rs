@@ -0,0 +1,126 @@
+use anyhow::Error;
+use sqlx::{Pool, Postgres};
+
+/// Station ids carry this prefix so `stream` can tell them from song ids
+/// without a lookup.
+pub const STATION_ID_PREFIX: &str = "radio-";
+
+#[derive(sqlx::FromRow)]
+pub struct RadioStation {
+    pub xata_id: String,
+    pub name: String,
+    pub stream_url: String,
+    pub homepage_url: Option<String>,
+}
+
+pub fn is_station_id(id: &str) -> bool {
+    id.starts_with(STATION_ID_PREFIX)
+}
+
+pub async fn get_stations(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+) -> Result<Vec<RadioStation>, Error> {
+    let rows: Vec<RadioStation> = sqlx::query_as(
+        r#"
+        SELECT xata_id, name, stream_url, homepage_url
+        FROM radio_stations
+        WHERE user_id = $1
+        ORDER BY lower(name) ASC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn get_station(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    station_id: &str,
+) -> Result<Option<RadioStation>, Error> {
+    let row: Option<RadioStation> = sqlx::query_as(
+        r#"
+        SELECT xata_id, name, stream_url, homepage_url
+        FROM radio_stations
+        WHERE xata_id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(station_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// Adds a station for `user_id`; returns the new station id.
+pub async fn create_station(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    name: &str,
+    stream_url: &str,
+    homepage_url: Option<&str>,
+) -> Result<String, Error> {
+    let station_id: String = sqlx::query_scalar(
+        r#"
+        INSERT INTO radio_stations (xata_id, user_id, name, stream_url, homepage_url)
+        VALUES ($1 || gen_random_uuid()::text, $2, $3, $4, $5)
+        RETURNING xata_id
+        "#,
+    )
+    .bind(STATION_ID_PREFIX)
+    .bind(user_id)
+    .bind(name)
+    .bind(stream_url)
+    .bind(homepage_url)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(station_id)
+}
+
+/// Replaces the fields of a station of `user_id`; false when there's no such
+/// station.
+pub async fn update_station(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    station_id: &str,
+    name: &str,
+    stream_url: &str,
+    homepage_url: Option<&str>,
+) -> Result<bool, Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE radio_stations
+        SET name = $3, stream_url = $4, homepage_url = $5, xata_updatedat = now()
+        WHERE xata_id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(station_id)
+    .bind(user_id)
+    .bind(name)
+    .bind(stream_url)
+    .bind(homepage_url)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// False when `user_id` has no such station.
+pub async fn delete_station(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    station_id: &str,
+) -> Result<bool, Error> {
+    let result = sqlx::query(r#"DELETE FROM radio_stations WHERE xata_id = $1 AND user_id = $2"#)
+        .bind(station_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,84 @@
+use anyhow::Error;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+
+/// NATS subject scans are requested on, the payload is the id of the user.
+pub const SCAN_NATS_TOPIC: &str = "rocksky.library.scan";
+
+/// Progress of the library scans of a user, written by `rockskyd library`.
+#[derive(Debug, sqlx::FromRow)]
+pub struct ScanStatus {
+    pub scanning: bool,
+    pub count: i32,
+    pub folder_count: i32,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+pub async fn get_scan_status(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+) -> Result<Option<ScanStatus>, Error> {
+    let status = sqlx::query_as(
+        r#"
+        SELECT scanning, count, folder_count, finished_at
+        FROM library_scans
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(status)
+}
+
+/// Whether the user linked a storage a scan can go through: a Dropbox, a
+/// Google Drive or a bucket.
+pub async fn has_storage(pool: &Pool<Postgres>, user_id: &str) -> Result<bool, Error> {
+    let exists = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (SELECT 1 FROM dropbox WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM google_drive WHERE user_id = $1)
+            OR EXISTS (SELECT 1 FROM user_storage_providers WHERE user_id = $1)
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(exists)
+}
+
+/// Reports a scan as running as soon as it's requested, the scanner resets
+/// the count and updates its progress once it picks it up.
+pub async fn queue_scan(pool: &Pool<Postgres>, user_id: &str) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO library_scans (user_id, scanning, count, started_at)
+        VALUES ($1, TRUE, 0, NOW())
+        ON CONFLICT (user_id) DO UPDATE SET
+            scanning = TRUE,
+            started_at = NOW(),
+            xata_updatedat = NOW()
+        "#,
+    )
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Reports the scan queued by [`queue_scan`] as not running anymore, when no
+/// scanner took the request.
+pub async fn cancel_scan(pool: &Pool<Postgres>, user_id: &str) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        UPDATE library_scans
+        SET scanning = FALSE,
+            xata_updatedat = NOW()
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
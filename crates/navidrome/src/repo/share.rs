@@ -0,0 +1,138 @@
+use anyhow::Error;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+
+#[derive(sqlx::FromRow)]
+pub struct Share {
+    pub xata_id: String,
+    pub user_id: String,
+    pub description: Option<String>,
+    pub track_ids: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_visited_at: Option<DateTime<Utc>>,
+    pub visit_count: i32,
+    pub xata_createdat: DateTime<Utc>,
+}
+
+impl Share {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+const SHARE_SELECT: &str = r#"
+    SELECT xata_id, user_id, description, track_ids, expires_at, last_visited_at,
+           visit_count, xata_createdat
+    FROM navidrome_shares
+"#;
+
+pub async fn get_shares(pool: &Pool<Postgres>, user_id: &str) -> Result<Vec<Share>, Error> {
+    let rows: Vec<Share> = sqlx::query_as(&format!(
+        r#"
+        {SHARE_SELECT}
+        WHERE user_id = $1
+        ORDER BY xata_createdat DESC
+        "#,
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// A share of any user, looked up by the public share pages.
+pub async fn get_share(pool: &Pool<Postgres>, share_id: &str) -> Result<Option<Share>, Error> {
+    let row: Option<Share> = sqlx::query_as(&format!(
+        r#"
+        {SHARE_SELECT}
+        WHERE xata_id = $1
+        "#,
+    ))
+    .bind(share_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// Shares `track_ids` of `user_id`; returns the new share id, which is part
+/// of the public URL so it's random rather than a xata id.
+pub async fn create_share(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    track_ids: &[String],
+    description: Option<&str>,
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<String, Error> {
+    let share_id: String = sqlx::query_scalar(
+        r#"
+        INSERT INTO navidrome_shares (xata_id, user_id, track_ids, description, expires_at)
+        VALUES (replace(gen_random_uuid()::text, '-', ''), $1, $2, $3, $4)
+        RETURNING xata_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(track_ids)
+    .bind(description)
+    .bind(expires_at)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(share_id)
+}
+
+/// Replaces the description and expiry of a share of `user_id`; false when
+/// there's no such share.
+pub async fn update_share(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    share_id: &str,
+    description: Option<&str>,
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<bool, Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE navidrome_shares
+        SET description = $3, expires_at = $4, xata_updatedat = now()
+        WHERE xata_id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(share_id)
+    .bind(user_id)
+    .bind(description)
+    .bind(expires_at)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// False when `user_id` has no such share.
+pub async fn delete_share(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    share_id: &str,
+) -> Result<bool, Error> {
+    let result = sqlx::query(r#"DELETE FROM navidrome_shares WHERE xata_id = $1 AND user_id = $2"#)
+        .bind(share_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn record_visit(pool: &Pool<Postgres>, share_id: &str) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        UPDATE navidrome_shares
+        SET visit_count = visit_count + 1, last_visited_at = now()
+        WHERE xata_id = $1
+        "#,
+    )
+    .bind(share_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
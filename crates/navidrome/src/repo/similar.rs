@@ -0,0 +1,224 @@
+use anyhow::Error;
+use sqlx::{Pool, Postgres};
+
+/// Tracks and artists a similarity query starts from.
+#[derive(Debug, Default)]
+pub struct Seed {
+    pub track_ids: Vec<String>,
+    pub artist_ids: Vec<String>,
+}
+
+// Listeners considered when looking for co-listened tracks; the most active
+// ones carry the signal and it keeps the join bounded.
+const MAX_SEED_LISTENERS: i64 = 500;
+
+/// Resolves the `id` of getSimilarSongs, which can be a song, an album or an
+/// artist, into seed tracks and artists.
+pub async fn resolve_seed(pool: &Pool<Postgres>, id: &str) -> Result<Option<Seed>, Error> {
+    let track: Option<(String,)> =
+        sqlx::query_as(r#"SELECT xata_id FROM tracks WHERE xata_id = $1"#)
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+    if track.is_some() {
+        let artist_ids: Vec<(String,)> = sqlx::query_as(
+            r#"SELECT at3.artist_id FROM artist_tracks at3
+               JOIN artists ar ON at3.artist_id = ar.xata_id
+               JOIN tracks t ON at3.track_id = t.xata_id
+               WHERE at3.track_id = $1
+                 AND t.album_artist = ar.name"#,
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await?;
+        return Ok(Some(Seed {
+            track_ids: vec![id.to_string()],
+            artist_ids: artist_ids.into_iter().map(|(id,)| id).collect(),
+        }));
+    }
+
+    let album: Option<(String,)> =
+        sqlx::query_as(r#"SELECT xata_id FROM albums WHERE xata_id = $1"#)
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+    if album.is_some() {
+        let track_ids: Vec<(String,)> =
+            sqlx::query_as(r#"SELECT track_id FROM album_tracks WHERE album_id = $1"#)
+                .bind(id)
+                .fetch_all(pool)
+                .await?;
+        let artist_ids: Vec<(String,)> =
+            sqlx::query_as(r#"SELECT artist_id FROM artist_albums WHERE album_id = $1"#)
+                .bind(id)
+                .fetch_all(pool)
+                .await?;
+        return Ok(Some(Seed {
+            track_ids: track_ids.into_iter().map(|(id,)| id).collect(),
+            artist_ids: artist_ids.into_iter().map(|(id,)| id).collect(),
+        }));
+    }
+
+    let artist: Option<(String,)> =
+        sqlx::query_as(r#"SELECT xata_id FROM artists WHERE xata_id = $1"#)
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(artist.map(|(id,)| Seed {
+        track_ids: vec![],
+        artist_ids: vec![id],
+    }))
+}
+
+/// Tracks from the user's library played by the people who listen to the
+/// seed, scored by the number of those listeners.
+pub async fn get_co_listened_tracks(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    seed: &Seed,
+    limit: i64,
+) -> Result<Vec<(String, i64)>, Error> {
+    let rows: Vec<(String, i64)> = sqlx::query_as(
+        r#"
+        WITH seed_listeners AS (
+            SELECT user_id
+            FROM scrobbles
+            WHERE track_id = ANY($2) OR artist_id = ANY($3)
+            GROUP BY user_id
+            ORDER BY COUNT(*) DESC
+            LIMIT $4
+        )
+        SELECT s.track_id, COUNT(DISTINCT s.user_id) AS score
+        FROM scrobbles s
+        JOIN seed_listeners sl ON s.user_id = sl.user_id
+        WHERE s.track_id IS NOT NULL
+          AND s.track_id <> ALL($2)
+          AND EXISTS (
+            SELECT 1 FROM user_uploads uu
+            WHERE uu.track_id = s.track_id AND uu.user_id = $1
+          )
+        GROUP BY s.track_id
+        ORDER BY score DESC
+        LIMIT $5
+        "#,
+    )
+    .bind(user_id)
+    .bind(&seed.track_ids)
+    .bind(&seed.artist_ids)
+    .bind(MAX_SEED_LISTENERS)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Tracks from the user's library whose artist shares genres with the seed
+/// artists, scored by the number of shared genres.
+pub async fn get_genre_tracks(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    seed: &Seed,
+    limit: i64,
+) -> Result<Vec<(String, i64)>, Error> {
+    let rows: Vec<(String, i64)> = sqlx::query_as(
+        r#"
+        WITH seed_genres AS (
+            SELECT DISTINCT unnest(genres) AS genre
+            FROM artists
+            WHERE xata_id = ANY($2)
+        )
+        SELECT t.xata_id, COUNT(DISTINCT sg.genre) AS score
+        FROM user_uploads uu
+        JOIN tracks t ON t.xata_id = uu.track_id
+        JOIN artist_tracks at3 ON at3.track_id = t.xata_id
+        JOIN artists ar ON at3.artist_id = ar.xata_id AND t.album_artist = ar.name
+        JOIN seed_genres sg ON sg.genre = ANY(ar.genres)
+        WHERE uu.user_id = $1
+          AND t.xata_id <> ALL($3)
+        GROUP BY t.xata_id
+        ORDER BY score DESC, RANDOM()
+        LIMIT $4
+        "#,
+    )
+    .bind(user_id)
+    .bind(&seed.artist_ids)
+    .bind(&seed.track_ids)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Tracks from the user's library by the seed artists, scored by their play
+/// count across all users.
+pub async fn get_artist_tracks(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    seed: &Seed,
+    limit: i64,
+) -> Result<Vec<(String, i64)>, Error> {
+    let rows: Vec<(String, i64)> = sqlx::query_as(
+        r#"
+        SELECT t.xata_id,
+               COALESCE((SELECT SUM(ut.scrobbles) FROM user_tracks ut
+                         WHERE ut.track_id = t.xata_id), 0)::BIGINT AS score
+        FROM tracks t
+        WHERE t.xata_id <> ALL($3)
+          AND EXISTS (
+            SELECT 1 FROM user_uploads uu
+            WHERE uu.track_id = t.xata_id AND uu.user_id = $1
+          )
+          AND EXISTS (
+            SELECT 1 FROM artist_tracks at3
+            JOIN artists ar ON at3.artist_id = ar.xata_id
+            WHERE at3.track_id = t.xata_id
+              AND t.album_artist = ar.name
+              AND ar.xata_id = ANY($2)
+          )
+        ORDER BY score DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(user_id)
+    .bind(&seed.artist_ids)
+    .bind(&seed.track_ids)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Most played tracks of an artist in the user's library, by play count
+/// across all users.
+pub async fn get_top_track_ids(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    artist: &str,
+    limit: i64,
+) -> Result<Vec<String>, Error> {
+    let rows: Vec<(String, i64)> = sqlx::query_as(
+        r#"
+        SELECT t.xata_id,
+               COALESCE((SELECT SUM(ut.scrobbles) FROM user_tracks ut
+                         WHERE ut.track_id = t.xata_id), 0)::BIGINT AS score
+        FROM tracks t
+        WHERE (LOWER(t.artist) = LOWER($2) OR LOWER(t.album_artist) = LOWER($2))
+          AND EXISTS (
+            SELECT 1 FROM user_uploads uu
+            WHERE uu.track_id = t.xata_id AND uu.user_id = $1
+          )
+        ORDER BY score DESC, t.title ASC
+        LIMIT $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(artist)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|(id, _)| id).collect())
+}
@@ -1,7 +1,7 @@
 use anyhow::Error;
 use sqlx::{Pool, Postgres};
 
-use crate::xata::track::TrackWithUpload;
+use crate::xata::track::{TrackLyrics, TrackWithUpload};
 
 pub const TRACK_SELECT: &str = r#"
     SELECT
@@ -81,6 +81,9 @@ pub async fn get_tracks_by_album(
 #[derive(Debug, sqlx::FromRow, Clone)]
 pub struct StreamTrack {
     pub r2_key: String,
+    pub mime_type: String,
+    /// Average bit rate (kbps) of the file, from its size and duration.
+    pub bit_rate: Option<i32>,
     pub storage_provider_id: Option<String>,
     pub storage_endpoint: Option<String>,
     pub storage_region: Option<String>,
@@ -99,6 +102,8 @@ pub async fn get_stream_track_by_id(
         r#"
         SELECT
             u.r2_key,
+            u.mime_type,
+            (u.file_size::bigint * 8 / NULLIF(t.duration, 0))::int AS bit_rate,
             usp.xata_id     AS storage_provider_id,
             usp.endpoint    AS storage_endpoint,
             usp.region      AS storage_region,
@@ -107,6 +112,7 @@ pub async fn get_stream_track_by_id(
             usp.secret_key  AS storage_secret_key,
             usp.public_url  AS storage_public_url
         FROM user_uploads u
+        JOIN tracks t ON u.track_id = t.xata_id
         LEFT JOIN user_storage_providers usp ON u.storage_provider_id = usp.xata_id
         WHERE u.track_id = $1 AND u.user_id = $2
         "#,
@@ -293,3 +299,100 @@ pub async fn get_artist_id_for_track(
 
     Ok(row.map(|(id,)| id))
 }
+
+pub async fn get_track_lyrics(
+    pool: &Pool<Postgres>,
+    track_id: &str,
+    user_id: &str,
+) -> Result<Option<TrackLyrics>, Error> {
+    let row: Option<TrackLyrics> = sqlx::query_as(
+        r#"
+        SELECT tracks.xata_id, tracks.title, tracks.artist, tracks.lyrics, tracks.synced_lyrics
+        FROM tracks
+        JOIN user_uploads ON tracks.xata_id = user_uploads.track_id
+        WHERE tracks.xata_id = $1
+          AND user_uploads.user_id = $2
+        LIMIT 1
+        "#,
+    )
+    .bind(track_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// Looks up lyrics by artist and title (case insensitive) in the user's
+/// library, for the classic `getLyrics` endpoint.
+pub async fn find_track_lyrics(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    artist: Option<&str>,
+    title: Option<&str>,
+) -> Result<Option<TrackLyrics>, Error> {
+    let row: Option<TrackLyrics> = sqlx::query_as(
+        r#"
+        SELECT tracks.xata_id, tracks.title, tracks.artist, tracks.lyrics, tracks.synced_lyrics
+        FROM tracks
+        JOIN user_uploads ON tracks.xata_id = user_uploads.track_id
+        WHERE user_uploads.user_id = $1
+          AND ($2::text IS NULL OR LOWER(tracks.artist) = LOWER($2)
+               OR LOWER(tracks.album_artist) = LOWER($2))
+          AND ($3::text IS NULL OR LOWER(tracks.title) = LOWER($3))
+          AND (tracks.lyrics IS NOT NULL OR tracks.synced_lyrics IS NOT NULL)
+        LIMIT 1
+        "#,
+    )
+    .bind(user_id)
+    .bind(artist)
+    .bind(title)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// Recording MBIDs of the artist's tracks, to find the artist in the
+/// MusicBrainz recordings the scrobbler stored.
+pub async fn get_recording_mbids_for_artist(
+    pool: &Pool<Postgres>,
+    artist_id: &str,
+) -> Result<Vec<String>, Error> {
+    let rows: Vec<(String,)> = sqlx::query_as(
+        r#"SELECT DISTINCT t.mb_id FROM artist_tracks at3
+           JOIN artists ar ON at3.artist_id = ar.xata_id
+           JOIN tracks t ON at3.track_id = t.xata_id
+           WHERE at3.artist_id = $1
+             AND t.album_artist = ar.name
+             AND NULLIF(t.mb_id, '') IS NOT NULL
+           LIMIT 10"#,
+    )
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|(mbid,)| mbid).collect())
+}
+
+/// Same as [`get_recording_mbids_for_artist`], for the tracks of the album.
+pub async fn get_recording_mbids_for_album(
+    pool: &Pool<Postgres>,
+    album_id: &str,
+) -> Result<Vec<String>, Error> {
+    let rows: Vec<(String,)> = sqlx::query_as(
+        r#"SELECT DISTINCT t.mb_id FROM album_tracks at2
+           JOIN albums a ON at2.album_id = a.xata_id
+           JOIN tracks t ON at2.track_id = t.xata_id
+           WHERE at2.album_id = $1
+             AND t.album = a.title
+             AND t.album_artist = a.artist
+             AND NULLIF(t.mb_id, '') IS NOT NULL
+           LIMIT 10"#,
+    )
+    .bind(album_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|(mbid,)| mbid).collect())
+}
@@ -14,6 +14,17 @@ pub async fn get_user_did_by_id(
     Ok(row.map(|(did,)| did))
 }
 
+pub async fn get_user_handle_by_id(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+) -> Result<Option<String>, Error> {
+    let row: Option<(String,)> = sqlx::query_as(r#"SELECT handle FROM users WHERE xata_id = $1"#)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.map(|(handle,)| handle))
+}
+
 /// Resolve a user by handle without requiring an API key.
 ///
 /// Used by the internal (server-to-server) auth path: apps/api has already
@@ -11,6 +11,7 @@ pub fn ok(format: &str, data: Value) -> HttpResponse {
         "version": VERSION,
         "type": SERVER_TYPE,
         "serverVersion": SERVER_VERSION,
+        "openSubsonic": true,
     });
 
     if let (Some(obj), Some(inner)) = (base.as_object_mut(), data.as_object()) {
@@ -35,6 +36,7 @@ pub fn err(format: &str, code: u32, message: &str) -> HttpResponse {
         "version": VERSION,
         "type": SERVER_TYPE,
         "serverVersion": SERVER_VERSION,
+        "openSubsonic": true,
         "error": {
             "code": code,
             "message": message
@@ -9,7 +9,8 @@ pub fn public_url(r2_key: &str) -> String {
     format!("{}/{}", base.trim_end_matches('/'), key)
 }
 
-pub async fn presign_get(r2_key: &str, expires_secs: u32) -> Result<String, Error> {
+/// The bucket files uploaded to Rocksky are stored in.
+pub fn default_bucket() -> Result<Box<Bucket>, Error> {
     let region = Region::Custom {
         region: env::var("S3_REGION").unwrap_or_else(|_| "auto".to_string()),
         endpoint: env::var("S3_ENDPOINT").map_err(|_| Error::msg("S3_ENDPOINT is not set"))?,
@@ -28,27 +29,17 @@ pub async fn presign_get(r2_key: &str, expires_secs: u32) -> Result<String, Erro
 
     let bucket_name = env::var("S3_BUCKET").unwrap_or_else(|_| "rocksky-library".to_string());
 
-    let bucket = Bucket::new(&bucket_name, region, credentials)?.with_path_style();
-
-    let key = if r2_key.starts_with('/') {
-        r2_key.to_string()
-    } else {
-        format!("/{}", r2_key)
-    };
-
-    let url = bucket.presign_get(&key, expires_secs, None).await?;
-    Ok(url)
+    Ok(Bucket::new(&bucket_name, region, credentials)?.with_path_style())
 }
 
-pub async fn presign_get_with_creds(
-    r2_key: &str,
+/// A bucket the user brought, with decrypted credentials.
+pub fn bucket_with_creds(
     endpoint: &str,
     region: &str,
     bucket_name: &str,
     access_key_id: &str,
     secret_access_key: &str,
-    expires_secs: u32,
-) -> Result<String, Error> {
+) -> Result<Box<Bucket>, Error> {
     let region = Region::Custom {
         region: region.to_string(),
         endpoint: endpoint.to_string(),
@@ -62,15 +53,45 @@ pub async fn presign_get_with_creds(
         None,
     )?;
 
-    let bucket = Bucket::new(bucket_name, region, credentials)?.with_path_style();
+    Ok(Bucket::new(bucket_name, region, credentials)?.with_path_style())
+}
 
-    let key = if r2_key.starts_with('/') {
+/// Object path of `r2_key`, as rust-s3 expects it.
+pub fn object_path(r2_key: &str) -> String {
+    if r2_key.starts_with('/') {
         r2_key.to_string()
     } else {
         format!("/{}", r2_key)
-    };
+    }
+}
+
+pub async fn presign_get(r2_key: &str, expires_secs: u32) -> Result<String, Error> {
+    let bucket = default_bucket()?;
+    let url = bucket
+        .presign_get(object_path(r2_key), expires_secs, None)
+        .await?;
+    Ok(url)
+}
 
-    let url = bucket.presign_get(&key, expires_secs, None).await?;
+pub async fn presign_get_with_creds(
+    r2_key: &str,
+    endpoint: &str,
+    region: &str,
+    bucket_name: &str,
+    access_key_id: &str,
+    secret_access_key: &str,
+    expires_secs: u32,
+) -> Result<String, Error> {
+    let bucket = bucket_with_creds(
+        endpoint,
+        region,
+        bucket_name,
+        access_key_id,
+        secret_access_key,
+    )?;
+    let url = bucket
+        .presign_get(object_path(r2_key), expires_secs, None)
+        .await?;
     Ok(url)
 }
 
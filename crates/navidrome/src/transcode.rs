@@ -0,0 +1,351 @@
+use std::{collections::HashMap, env, process::Stdio};
+
+use anyhow::Error;
+use tokio::process::{Child, Command};
+
+/// Bit rates (kbps) renditions are encoded at, requested ones are rounded
+/// down to these so a handful of renditions per track gets cached.
+const BIT_RATES: [u32; 9] = [32, 48, 64, 96, 128, 160, 192, 256, 320];
+
+const DEFAULT_BIT_RATE: u32 = 192;
+
+/// The Subsonic `stream` parameters about transcoding.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StreamOptions {
+    pub max_bit_rate: Option<u32>,
+    pub format: Option<String>,
+    pub time_offset: Option<u32>,
+}
+
+impl StreamOptions {
+    pub fn from_params(params: &HashMap<String, String>) -> Self {
+        StreamOptions {
+            max_bit_rate: params.get("maxBitRate").and_then(|r| r.parse().ok()),
+            format: params.get("format").map(|f| f.to_lowercase()),
+            time_offset: params
+                .get("timeOffset")
+                .and_then(|o| o.parse::<f64>().ok())
+                .filter(|o| o.is_finite() && *o > 0.0)
+                .map(|o| o as u32),
+        }
+    }
+
+    fn offset(&self) -> u32 {
+        self.time_offset.unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Codec {
+    Mp3,
+    Opus,
+    Aac,
+}
+
+impl Codec {
+    fn from_format(format: &str) -> Option<Self> {
+        match format {
+            "mp3" => Some(Codec::Mp3),
+            "opus" | "ogg" => Some(Codec::Opus),
+            "aac" => Some(Codec::Aac),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Codec::Mp3 => "audio/mpeg",
+            Codec::Opus => "audio/ogg",
+            Codec::Aac => "audio/aac",
+        }
+    }
+
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Codec::Mp3 => "mp3",
+            Codec::Opus => "opus",
+            Codec::Aac => "aac",
+        }
+    }
+
+    /// Whether files of `mime` are already encoded with this codec.
+    fn encodes(&self, mime: &str) -> bool {
+        match self {
+            Codec::Mp3 => matches!(mime, "audio/mpeg" | "audio/mp3"),
+            // Uploads store .opus files as `audio/ogg`.
+            Codec::Opus => matches!(mime, "audio/ogg" | "audio/opus"),
+            Codec::Aac => matches!(mime, "audio/aac" | "audio/mp4" | "audio/x-m4a"),
+        }
+    }
+
+    fn encoder_args(&self) -> [&'static str; 4] {
+        match self {
+            Codec::Mp3 => ["-c:a", "libmp3lame", "-f", "mp3"],
+            Codec::Opus => ["-c:a", "libopus", "-f", "ogg"],
+            Codec::Aac => ["-c:a", "aac", "-f", "adts"],
+        }
+    }
+}
+
+/// A transcoded version of a track.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rendition {
+    pub codec: Codec,
+    pub bit_rate: u32,
+}
+
+impl Rendition {
+    /// Object key the rendition of the object `r2_key` is cached at, in the
+    /// same bucket.
+    pub fn key(&self, r2_key: &str) -> String {
+        format!(
+            "transcodes/{:x}/{}k.{}",
+            md5::compute(r2_key.trim_start_matches('/')),
+            self.bit_rate,
+            self.codec.suffix()
+        )
+    }
+}
+
+/// The rendition to stream for a file of type `mime` encoded at
+/// `source_bit_rate` (kbps), `None` when the original does.
+pub fn select_rendition(
+    options: &StreamOptions,
+    mime: &str,
+    source_bit_rate: Option<u32>,
+) -> Option<Rendition> {
+    if options.format.as_deref() == Some("raw") {
+        return None;
+    }
+
+    let max_bit_rate = options.max_bit_rate.filter(|rate| *rate > 0);
+    let seeking = options.offset() > 0;
+    let requested = options.format.as_deref().and_then(Codec::from_format);
+    // Lossy files gain nothing from a higher bit rate than their own.
+    let source_bit_rate = source_bit_rate.filter(|rate| *rate > 0 && !is_lossless(mime));
+    let within_limit =
+        max_bit_rate.is_none_or(|max| source_bit_rate.is_some_and(|rate| rate <= max));
+
+    let codec = match requested {
+        Some(codec) if codec.encodes(mime) && within_limit && !seeking => return None,
+        Some(codec) => codec,
+        // Lossy files are already small enough for the highest limits.
+        None if !is_lossless(mime)
+            && !seeking
+            && (within_limit || max_bit_rate.is_some_and(|rate| rate >= 320)) =>
+        {
+            return None
+        }
+        None if max_bit_rate.is_some() || seeking => Codec::Mp3,
+        None => return None,
+    };
+
+    let bit_rate = max_bit_rate.unwrap_or(DEFAULT_BIT_RATE);
+    Some(Rendition {
+        codec,
+        bit_rate: snap_bit_rate(source_bit_rate.map_or(bit_rate, |source| bit_rate.min(source))),
+    })
+}
+
+fn is_lossless(mime: &str) -> bool {
+    matches!(
+        mime,
+        "audio/flac" | "audio/wav" | "audio/x-wav" | "audio/aiff" | "audio/x-aiff"
+    )
+}
+
+/// The highest bit rate of [`BIT_RATES`] within `max`, `max` itself below
+/// the lowest one.
+fn snap_bit_rate(max: u32) -> u32 {
+    BIT_RATES
+        .iter()
+        .rev()
+        .find(|rate| **rate <= max)
+        .copied()
+        .unwrap_or(max)
+}
+
+fn ffmpeg_args(url: &str, rendition: &Rendition, offset: u32) -> Vec<String> {
+    let mut args: Vec<String> = vec!["-nostdin".into(), "-loglevel".into(), "error".into()];
+    if offset > 0 {
+        args.extend(["-ss".into(), offset.to_string()]);
+    }
+    args.extend([
+        "-i".into(),
+        url.into(),
+        "-map".into(),
+        "0:a:0".into(),
+        "-vn".into(),
+        "-b:a".into(),
+        format!("{}k", rendition.bit_rate),
+    ]);
+    args.extend(rendition.codec.encoder_args().map(String::from));
+    args.push("pipe:1".into());
+    args
+}
+
+/// Starts transcoding the audio at `url` from `offset` seconds, the output is
+/// read from the child's stdout. `FFMPEG_PATH` points to the ffmpeg binary
+/// when it isn't on the `PATH`.
+pub fn spawn(url: &str, rendition: &Rendition, options: &StreamOptions) -> Result<Child, Error> {
+    let ffmpeg = env::var("FFMPEG_PATH").unwrap_or_else(|_| "ffmpeg".to_string());
+    let child = Command::new(ffmpeg)
+        .args(ffmpeg_args(url, rendition, options.offset()))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .kill_on_drop(true)
+        .spawn()?;
+    Ok(child)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(
+        max_bit_rate: Option<u32>,
+        format: Option<&str>,
+        time_offset: Option<u32>,
+    ) -> StreamOptions {
+        StreamOptions {
+            max_bit_rate,
+            format: format.map(String::from),
+            time_offset,
+        }
+    }
+
+    #[test]
+    fn test_from_params() {
+        let params = HashMap::from([
+            ("maxBitRate".to_string(), "128".to_string()),
+            ("format".to_string(), "MP3".to_string()),
+            ("timeOffset".to_string(), "42.5".to_string()),
+        ]);
+        assert_eq!(
+            StreamOptions::from_params(&params),
+            options(Some(128), Some("mp3"), Some(42))
+        );
+        assert_eq!(
+            StreamOptions::from_params(&HashMap::new()),
+            StreamOptions::default()
+        );
+    }
+
+    #[test]
+    fn test_select_rendition() {
+        let rendition = |codec, bit_rate| Some(Rendition { codec, bit_rate });
+
+        assert_eq!(
+            select_rendition(&options(None, None, None), "audio/flac", None),
+            None
+        );
+        assert_eq!(
+            select_rendition(&options(Some(128), Some("raw"), None), "audio/flac", None),
+            None
+        );
+        assert_eq!(
+            select_rendition(&options(Some(128), None, None), "audio/flac", None),
+            rendition(Codec::Mp3, 128)
+        );
+        assert_eq!(
+            select_rendition(&options(Some(100), Some("opus"), None), "audio/flac", None),
+            rendition(Codec::Opus, 96)
+        );
+        assert_eq!(
+            select_rendition(&options(None, Some("opus"), None), "audio/flac", None),
+            rendition(Codec::Opus, 192)
+        );
+        assert_eq!(
+            select_rendition(&options(None, Some("mp3"), None), "audio/mpeg", None),
+            None
+        );
+        assert_eq!(
+            select_rendition(&options(None, Some("opus"), None), "audio/ogg", None),
+            None
+        );
+        assert_eq!(
+            select_rendition(&options(Some(320), None, None), "audio/mpeg", None),
+            None
+        );
+        assert_eq!(
+            select_rendition(&options(Some(128), None, None), "audio/mpeg", None),
+            rendition(Codec::Mp3, 128)
+        );
+        assert_eq!(
+            select_rendition(&options(None, None, Some(30)), "audio/mpeg", None),
+            rendition(Codec::Mp3, 192)
+        );
+    }
+
+    #[test]
+    fn test_select_rendition_within_source_bit_rate() {
+        let rendition = |codec, bit_rate| Some(Rendition { codec, bit_rate });
+
+        assert_eq!(
+            select_rendition(&options(Some(256), None, None), "audio/mpeg", Some(128)),
+            None
+        );
+        assert_eq!(
+            select_rendition(
+                &options(Some(256), Some("mp3"), None),
+                "audio/mpeg",
+                Some(128)
+            ),
+            None
+        );
+        assert_eq!(
+            select_rendition(&options(Some(96), None, None), "audio/mpeg", Some(128)),
+            rendition(Codec::Mp3, 96)
+        );
+        assert_eq!(
+            select_rendition(&options(None, Some("opus"), None), "audio/mpeg", Some(128)),
+            rendition(Codec::Opus, 128)
+        );
+        assert_eq!(
+            select_rendition(&options(None, None, Some(30)), "audio/mpeg", Some(128)),
+            rendition(Codec::Mp3, 128)
+        );
+        assert_eq!(
+            select_rendition(&options(Some(192), None, None), "audio/flac", Some(900)),
+            rendition(Codec::Mp3, 192)
+        );
+    }
+
+    #[test]
+    fn test_snap_bit_rate() {
+        assert_eq!(snap_bit_rate(16), 16);
+        assert_eq!(snap_bit_rate(32), 32);
+        assert_eq!(snap_bit_rate(56), 48);
+        assert_eq!(snap_bit_rate(128), 128);
+        assert_eq!(snap_bit_rate(200), 192);
+        assert_eq!(snap_bit_rate(1411), 320);
+    }
+
+    #[test]
+    fn test_rendition_key() {
+        let rendition = Rendition {
+            codec: Codec::Opus,
+            bit_rate: 96,
+        };
+        assert_eq!(
+            rendition.key("/music/song.flac"),
+            rendition.key("music/song.flac")
+        );
+        assert!(rendition.key("music/song.flac").starts_with("transcodes/"));
+        assert!(rendition.key("music/song.flac").ends_with("/96k.opus"));
+    }
+
+    #[test]
+    fn test_ffmpeg_args() {
+        let rendition = Rendition {
+            codec: Codec::Mp3,
+            bit_rate: 128,
+        };
+        assert_eq!(
+            ffmpeg_args("https://cdn/song.flac", &rendition, 30).join(" "),
+            "-nostdin -loglevel error -ss 30 -i https://cdn/song.flac -map 0:a:0 -vn -b:a 128k \
+             -c:a libmp3lame -f mp3 pipe:1"
+        );
+    }
+}
@@ -50,3 +50,12 @@ pub struct TrackWithUpload {
     pub storage_secret_key: Option<String>,
     pub storage_public_url: Option<String>,
 }
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct TrackLyrics {
+    pub xata_id: String,
+    pub title: String,
+    pub artist: String,
+    pub lyrics: Option<String>,
+    pub synced_lyrics: Option<String>,
+}
@@ -37,11 +37,6 @@ pub async fn start() -> Result<(), Error> {
 
     load_users(conn.clone(), &pool).await?;
 
-    sqlx::query(r#"
-      CREATE UNIQUE INDEX IF NOT EXISTS user_playlists_unique_index ON user_playlists (user_id, playlist_id)
-    "#)
-      .execute(&pool)
-      .await?;
     let conn = conn.clone();
 
     let addr = env::var("NATS_URL").unwrap_or_else(|_| "nats://localhost:4222".to_string());
@@ -9,7 +9,8 @@
 
 use once_cell::sync::Lazy;
 use rocksky_sdk::{
-    AlbumDraft, ArtistDraft, NowPlaying, RockskyAgent, ScrobbleDraft, ScrobbleMatch, SongDraft,
+    AlbumDraft, ArtistDraft, BatchWrite, NowPlaying, PlaylistDraft, RockskyAgent, ScrobbleDraft,
+    ScrobbleMatch, SongDraft,
 };
 use rustler::{Resource, ResourceArc};
 
@@ -44,6 +45,16 @@ fn envelope<T: serde::Serialize, E: std::fmt::Display>(r: Result<T, E>) -> Strin
     }
 }
 
+/// The AppView's response to the read query `nsid`, verbatim, in a
+/// `{"ok"|"error"}` envelope — the wire shape the Erlang/Elixir reads return.
+fn raw(base: &str, nsid: &str, params: &[(&str, String)]) -> String {
+    let pairs: Vec<(String, String)> = params
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.clone()))
+        .collect();
+    envelope(RT.block_on(appview(base).get(nsid, &pairs)))
+}
+
 fn parse<T: serde::de::DeserializeOwned>(json: &str) -> Result<T, String> {
     serde_json::from_str(json).map_err(|e| e.to_string())
 }
@@ -345,12 +356,12 @@ fn known_followers(base: String, actor: String, limit: u32, cursor: String) -> S
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn album(base: String, uri: String) -> String {
-    envelope(RT.block_on(appview(&base).album(&uri)))
+    raw(&base, "app.rocksky.album.getAlbum", &[("uri", uri)])
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn artist(base: String, uri: String) -> String {
-    envelope(RT.block_on(appview(&base).artist(&uri)))
+    raw(&base, "app.rocksky.artist.getArtist", &[("uri", uri)])
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
@@ -365,32 +376,72 @@ fn song(base: String, uri: String, mbid: String, isrc: String, spotify_id: Strin
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn actor_playlists(base: String, actor: String, limit: u32, offset: u32) -> String {
-    envelope(RT.block_on(appview(&base).actor_playlists(&actor, limit, offset)))
+    raw(
+        &base,
+        "app.rocksky.actor.getActorPlaylists",
+        &[
+            ("did", actor),
+            ("limit", limit.to_string()),
+            ("offset", offset.to_string()),
+        ],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn neighbours(base: String, actor: String) -> String {
-    envelope(RT.block_on(appview(&base).neighbours(&actor)))
+    raw(
+        &base,
+        "app.rocksky.actor.getActorNeighbours",
+        &[("did", actor)],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn compatibility(base: String, actor: String) -> String {
-    envelope(RT.block_on(appview(&base).compatibility(&actor)))
+    raw(
+        &base,
+        "app.rocksky.actor.getActorCompatibility",
+        &[("did", actor)],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn artist_listeners(base: String, uri: String, limit: u32, offset: u32) -> String {
-    envelope(RT.block_on(appview(&base).artist_listeners(&uri, limit, offset)))
+    raw(
+        &base,
+        "app.rocksky.artist.getArtistListeners",
+        &[
+            ("uri", uri),
+            ("limit", limit.to_string()),
+            ("offset", offset.to_string()),
+        ],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn artist_recent_listeners(base: String, uri: String, limit: u32, offset: u32) -> String {
-    envelope(RT.block_on(appview(&base).artist_recent_listeners(&uri, limit, offset)))
+    raw(
+        &base,
+        "app.rocksky.artist.getArtistRecentListeners",
+        &[
+            ("uri", uri),
+            ("limit", limit.to_string()),
+            ("offset", offset.to_string()),
+        ],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn song_recent_listeners(base: String, uri: String, limit: u32, offset: u32) -> String {
-    envelope(RT.block_on(appview(&base).song_recent_listeners(&uri, limit, offset)))
+    raw(
+        &base,
+        "app.rocksky.song.getSongRecentListeners",
+        &[
+            ("uri", uri),
+            ("limit", limit.to_string()),
+            ("offset", offset.to_string()),
+        ],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
@@ -427,32 +478,76 @@ fn feed_generator(base: String, feed_uri: String) -> String {
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn stories(base: String, size: u32, feed_uri: String, following: bool) -> String {
-    envelope(RT.block_on(appview(&base).stories(opt_u32(size), opt(&feed_uri), Some(following))))
+    let (size, feed, following) = (opt_u32(size), opt(&feed_uri), Some(following));
+    raw(
+        &base,
+        "app.rocksky.feed.getStories",
+        &[
+            ("size", size.map(|s| s.to_string()).unwrap_or_default()),
+            ("feed", feed.unwrap_or_default().to_string()),
+            (
+                "following",
+                following.map(|b| b.to_string()).unwrap_or_default(),
+            ),
+        ],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn recommendations(base: String, actor: String, limit: u32) -> String {
-    envelope(RT.block_on(appview(&base).recommendations(&actor, opt_u32(limit))))
+    let limit = opt_u32(limit);
+    raw(
+        &base,
+        "app.rocksky.feed.getRecommendations",
+        &[
+            ("did", actor),
+            ("limit", limit.map(|l| l.to_string()).unwrap_or_default()),
+        ],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn artist_recommendations(base: String, actor: String, limit: u32) -> String {
-    envelope(RT.block_on(appview(&base).artist_recommendations(&actor, opt_u32(limit))))
+    let limit = opt_u32(limit);
+    raw(
+        &base,
+        "app.rocksky.feed.getArtistRecommendations",
+        &[
+            ("did", actor),
+            ("limit", limit.map(|l| l.to_string()).unwrap_or_default()),
+        ],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn album_recommendations(base: String, actor: String, limit: u32) -> String {
-    envelope(RT.block_on(appview(&base).album_recommendations(&actor, opt_u32(limit))))
+    let limit = opt_u32(limit);
+    raw(
+        &base,
+        "app.rocksky.feed.getAlbumRecommendations",
+        &[
+            ("did", actor),
+            ("limit", limit.map(|l| l.to_string()).unwrap_or_default()),
+        ],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn stats(base: String, actor: String) -> String {
-    envelope(RT.block_on(appview(&base).stats(&actor)))
+    raw(&base, "app.rocksky.stats.getStats", &[("did", actor)])
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn wrapped(base: String, actor: String, year: u32) -> String {
-    envelope(RT.block_on(appview(&base).wrapped(&actor, opt_u32(year))))
+    let year = opt_u32(year);
+    raw(
+        &base,
+        "app.rocksky.stats.getWrapped",
+        &[
+            ("did", actor),
+            ("year", year.map(|y| y.to_string()).unwrap_or_default()),
+        ],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
@@ -477,42 +572,82 @@ fn spotify_currently_playing(base: String, actor: String) -> String {
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn playlists(base: String, limit: u32, offset: u32) -> String {
-    envelope(RT.block_on(appview(&base).playlists(limit, offset)))
+    raw(
+        &base,
+        "app.rocksky.playlist.getPlaylists",
+        &[("limit", limit.to_string()), ("offset", offset.to_string())],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn playlist(base: String, uri: String) -> String {
-    envelope(RT.block_on(appview(&base).playlist(&uri)))
+    raw(&base, "app.rocksky.playlist.getPlaylist", &[("uri", uri)])
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn album_shouts(base: String, uri: String, limit: u32, offset: u32) -> String {
-    envelope(RT.block_on(appview(&base).album_shouts(&uri, limit, offset)))
+    raw(
+        &base,
+        "app.rocksky.shout.getAlbumShouts",
+        &[
+            ("uri", uri),
+            ("limit", limit.to_string()),
+            ("offset", offset.to_string()),
+        ],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn artist_shouts(base: String, uri: String, limit: u32, offset: u32) -> String {
-    envelope(RT.block_on(appview(&base).artist_shouts(&uri, limit, offset)))
+    raw(
+        &base,
+        "app.rocksky.shout.getArtistShouts",
+        &[
+            ("uri", uri),
+            ("limit", limit.to_string()),
+            ("offset", offset.to_string()),
+        ],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn profile_shouts(base: String, actor: String, limit: u32, offset: u32) -> String {
-    envelope(RT.block_on(appview(&base).profile_shouts(&actor, limit, offset)))
+    raw(
+        &base,
+        "app.rocksky.shout.getProfileShouts",
+        &[
+            ("did", actor),
+            ("limit", limit.to_string()),
+            ("offset", offset.to_string()),
+        ],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn track_shouts(base: String, uri: String) -> String {
-    envelope(RT.block_on(appview(&base).track_shouts(&uri)))
+    raw(&base, "app.rocksky.shout.getTrackShouts", &[("uri", uri)])
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn shout_replies(base: String, uri: String, limit: u32, offset: u32) -> String {
-    envelope(RT.block_on(appview(&base).shout_replies(&uri, limit, offset)))
+    raw(
+        &base,
+        "app.rocksky.shout.getShoutReplies",
+        &[
+            ("uri", uri),
+            ("limit", limit.to_string()),
+            ("offset", offset.to_string()),
+        ],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
 fn audio_settings(base: String, actor: String) -> String {
-    envelope(RT.block_on(appview(&base).audio_settings(&actor)))
+    raw(
+        &base,
+        "app.rocksky.rockbox.getAudioSettings",
+        &[("did", actor)],
+    )
 }
 
 #[rustler::nif(schedule = "DirtyIo")]
@@ -641,6 +776,18 @@ fn agent_unfollow(agent: ResourceArc<AgentRes>, did: String) -> String {
     envelope(RT.block_on(agent.0.unfollow(&did)).map(|_| true))
 }
 
+/// Write many records in `applyWrites` transactions of up to 200 writes.
+/// `writes_json` is an array of `BatchWrite`s (`{"scrobble": {...}}`,
+/// `{"like": {"uri", "cid"}}`, `{"follow": did}`, ...). Returns the per-write
+/// outcomes and commit counts.
+#[rustler::nif(schedule = "DirtyIo")]
+fn agent_apply_batch(agent: ResourceArc<AgentRes>, writes_json: String) -> String {
+    match parse::<Vec<BatchWrite>>(&writes_json) {
+        Ok(w) => envelope(RT.block_on(agent.0.apply_batch(&w))),
+        Err(e) => envelope::<(), _>(Err(e)),
+    }
+}
+
 #[rustler::nif(schedule = "DirtyIo")]
 fn agent_shout(
     agent: ResourceArc<AgentRes>,
@@ -735,6 +882,69 @@ fn agent_clear_now_playing(agent: ResourceArc<AgentRes>) -> String {
     envelope(RT.block_on(agent.0.clear_now_playing()).map(|_| true))
 }
 
+/// Create a playlist from a `PlaylistDraft` (camelCase, only `name` required).
+/// Returns its `uri` and `cid`.
+#[rustler::nif(schedule = "DirtyIo")]
+fn agent_create_playlist(agent: ResourceArc<AgentRes>, draft_json: String) -> String {
+    match parse::<PlaylistDraft>(&draft_json) {
+        Ok(d) => envelope(RT.block_on(agent.0.create_playlist(&d))),
+        Err(e) => envelope::<(), _>(Err(e)),
+    }
+}
+
+/// Append a song to the end of a playlist, creating its song record first. A
+/// song already in the playlist is left where it is. Returns the item URI.
+#[rustler::nif(schedule = "DirtyIo")]
+fn agent_add_to_playlist(
+    agent: ResourceArc<AgentRes>,
+    playlist_uri: String,
+    song_json: String,
+) -> String {
+    match parse::<SongDraft>(&song_json) {
+        Ok(d) => envelope(RT.block_on(agent.0.add_to_playlist(&playlist_uri, &d))),
+        Err(e) => envelope::<(), _>(Err(e)),
+    }
+}
+
+/// Remove a song, by its song URI, from a playlist.
+#[rustler::nif(schedule = "DirtyIo")]
+fn agent_remove_from_playlist(
+    agent: ResourceArc<AgentRes>,
+    playlist_uri: String,
+    song_uri: String,
+) -> String {
+    envelope(
+        RT.block_on(agent.0.remove_from_playlist(&playlist_uri, &song_uri))
+            .map(|_| true),
+    )
+}
+
+/// Move the songs of `song_uris_json` (a JSON array of song URIs) to the front
+/// of a playlist, in that order.
+#[rustler::nif(schedule = "DirtyIo")]
+fn agent_reorder_playlist(
+    agent: ResourceArc<AgentRes>,
+    playlist_uri: String,
+    song_uris_json: String,
+) -> String {
+    match parse::<Vec<String>>(&song_uris_json) {
+        Ok(uris) => envelope(
+            RT.block_on(agent.0.reorder_playlist(&playlist_uri, &uris))
+                .map(|_| true),
+        ),
+        Err(e) => envelope::<(), _>(Err(e)),
+    }
+}
+
+/// Delete a playlist and its items.
+#[rustler::nif(schedule = "DirtyIo")]
+fn agent_delete_playlist(agent: ResourceArc<AgentRes>, playlist_uri: String) -> String {
+    envelope(
+        RT.block_on(agent.0.delete_playlist(&playlist_uri))
+            .map(|_| true),
+    )
+}
+
 // ---- dedup / jetstream (feature-gated) -----------------------------------
 
 /// Download the caller's repo and (re)build the local dedup index.
@@ -748,6 +958,7 @@ fn agent_sync_repo(agent: ResourceArc<AgentRes>) -> String {
                 "albums": s.albums,
                 "songs": s.songs,
                 "scrobbles": s.scrobbles,
+                "playlistItems": s.playlist_items,
                 "total": s.total(),
             }
         })
@@ -756,6 +967,44 @@ fn agent_sync_repo(agent: ResourceArc<AgentRes>) -> String {
     }
 }
 
+/// Scrobble a play, or keep it in the offline queue when the PDS is unreachable.
+/// Returns `{"sent": {...uris}}` or `{"queued": {"depth": n}}`.
+#[cfg(feature = "dedup")]
+#[rustler::nif(schedule = "DirtyIo")]
+fn agent_scrobble_or_queue(agent: ResourceArc<AgentRes>, draft_json: String) -> String {
+    match parse::<ScrobbleDraft>(&draft_json) {
+        Ok(d) => envelope(RT.block_on(agent.0.scrobble_or_queue(&d))),
+        Err(e) => envelope::<(), _>(Err(e)),
+    }
+}
+
+/// How many plays are waiting in the offline queue.
+#[cfg(feature = "dedup")]
+#[rustler::nif]
+fn agent_queue_depth(agent: ResourceArc<AgentRes>) -> String {
+    envelope(agent.0.queue_depth())
+}
+
+/// Replay the offline queue once, oldest play first. Returns the counts of plays
+/// sent, skipped as duplicates, dropped and still remaining.
+#[cfg(feature = "dedup")]
+#[rustler::nif(schedule = "DirtyIo")]
+fn agent_flush_queue(agent: ResourceArc<AgentRes>) -> String {
+    envelope(RT.block_on(agent.0.flush_queue()))
+}
+
+/// Replay the offline queue on a background task, backing off while the PDS
+/// stays unreachable, and return immediately (`{"ok": true}`).
+#[cfg(feature = "dedup")]
+#[rustler::nif]
+fn agent_replay_queue(agent: ResourceArc<AgentRes>) -> String {
+    let agent = agent.0.clone();
+    RT.spawn(async move {
+        let _ = agent.replay_queue().await;
+    });
+    envelope::<_, String>(Ok(true))
+}
+
 /// Start hydrating the dedup index from Jetstream on a background task and return
 /// immediately (`{"ok": true}`). The hydration runs for the life of the runtime.
 #[cfg(feature = "jetstream")]
@@ -55,8 +55,13 @@ async fn main() -> rocksky_sdk::Result<()> {
     // token — set ROCKSKY_TOKEN to try it. `library()` errors without one.
     if let Ok(token) = std::env::var("ROCKSKY_TOKEN") {
         let lib = av.with_token(token).library()?;
-        info!(genres = %lib.get_genres().await?, "library genres");
-        info!(albums = %lib.get_album_list("newest", Some(10), None, None, None, None).await?, "library albums");
+        info!(genres = lib.get_genres().await?.len(), "library genres");
+        for album in lib
+            .get_album_list("newest", Some(10), None, None, None, None)
+            .await?
+        {
+            info!(name = ?album.name, artist = ?album.artist, "library album");
+        }
     }
 
     Ok(())
@@ -25,13 +25,16 @@ use crate::app_rocksky::album::Album;
 use crate::app_rocksky::artist::Artist;
 use crate::app_rocksky::graph::follow::Follow;
 use crate::app_rocksky::like::Like;
+use crate::app_rocksky::playlist::Playlist;
+use crate::app_rocksky::playlist_item::PlaylistItem;
 use crate::app_rocksky::scrobble::Scrobble;
 use crate::app_rocksky::shout::{Gif, Shout};
-use crate::app_rocksky::song::Song;
+use crate::app_rocksky::song::{Song, SongViewBasic};
 use crate::appview::AppView;
 use crate::auth::{fetch_profile, rocksky_scopes, Profile};
 use crate::com_atproto::repo::strong_ref::StrongRef;
-use crate::error::{auth_err, Result, SdkError};
+use crate::dedup::{Identity, C_ALBUM, C_ARTIST, C_PLAYLIST, C_PLAYLIST_ITEM, C_SCROBBLE, C_SONG};
+use crate::error::{agent_err, auth_err, client_err, xrpc_err, Result, SdkError};
 
 /// The handle resolver backing the agent.
 type Resolver = JacquardResolver<reqwest::Client>;
@@ -175,6 +178,117 @@ pub struct ScrobbleResult {
     pub scrobble_uri: String,
 }
 
+/// The URI and CID of a record — the two halves of a
+/// `com.atproto.repo.strongRef` to it.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RecordRef {
+    pub uri: String,
+    pub cid: String,
+}
+
+/// One record write of a [`RockskyAgent::apply_batch`].
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchWrite {
+    /// A play, with the artist/album/song records it implies (as
+    /// [`scrobble`](RockskyAgent::scrobble)).
+    Scrobble(ScrobbleDraft),
+    Song(SongDraft),
+    Album(AlbumDraft),
+    Artist(ArtistDraft),
+    /// Like the record `uri`/`cid`.
+    Like {
+        uri: String,
+        cid: String,
+    },
+    /// Follow the account `did`.
+    Follow(String),
+}
+
+/// What [`RockskyAgent::apply_batch`] did with one [`BatchWrite`]. The URI is
+/// the record's own, for a play the scrobble's.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchOutcome {
+    /// Written by this batch.
+    Created(String),
+    /// Already in the repo, or written for an earlier write of the batch;
+    /// nothing was written for it.
+    Existing(String),
+    /// Its `applyWrites` transaction was rejected, nothing of it was written.
+    Failed(String),
+}
+
+/// The result of a [`RockskyAgent::apply_batch`].
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchReport {
+    /// One outcome per write, in the order given.
+    pub outcomes: Vec<BatchOutcome>,
+    /// `applyWrites` transactions committed.
+    pub commits: usize,
+    /// `applyWrites` transactions rejected.
+    pub failed_commits: usize,
+}
+
+impl BatchReport {
+    /// Whether every write landed (or already existed).
+    pub fn is_complete(&self) -> bool {
+        self.failed_commits == 0
+    }
+
+    /// The indices of the writes that failed, e.g. to retry just those.
+    pub fn failed(&self) -> Vec<usize> {
+        self.outcomes
+            .iter()
+            .enumerate()
+            .filter(|(_, o)| matches!(o, BatchOutcome::Failed(_)))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// User input for a playlist (`app.rocksky.playlist`). Only `name` is required.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlaylistDraft {
+    pub name: String,
+    pub description: Option<String>,
+    pub picture_url: Option<String>,
+    pub spotify_link: Option<String>,
+    pub tidal_link: Option<String>,
+    pub youtube_link: Option<String>,
+    pub apple_music_link: Option<String>,
+}
+
+/// What [`RockskyAgent::scrobble_or_queue`] did with a play: written now, or
+/// kept in the offline queue (`depth` plays are waiting, this one included).
+#[cfg(feature = "dedup")]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScrobbleOutcome {
+    Sent(ScrobbleResult),
+    Queued { depth: usize },
+}
+
+/// The result of one [`RockskyAgent::flush_queue`] pass.
+#[cfg(feature = "dedup")]
+#[derive(Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlushReport {
+    /// Plays written to the PDS.
+    pub sent: usize,
+    /// Plays the dedup index already had (written before the connection
+    /// dropped, or by another client), removed without writing.
+    pub duplicates: usize,
+    /// Plays the PDS rejected for good (e.g. an invalid record), removed so
+    /// they don't block the rest of the queue.
+    pub dropped: usize,
+    /// Plays still queued, because the PDS became unreachable again or failed
+    /// in a way that may not be permanent.
+    pub remaining: usize,
+}
+
 /// The artist a scrobble implies: identity is the **album artist** (matching the
 /// server's hash), so albums stay grouped under one artist regardless of the
 /// per-track artist.
@@ -201,6 +315,57 @@ fn album_draft_for(draft: &ScrobbleDraft) -> AlbumDraft {
     }
 }
 
+/// The `track` snapshot a playlist item carries: the song's metadata plus the
+/// URI of its `app.rocksky.song` record.
+fn playlist_track_for(draft: &SongDraft, song_uri: &str) -> Result<SongViewBasic> {
+    Ok(SongViewBasic {
+        title: Some(draft.title.clone().into()),
+        artist: Some(draft.artist.clone().into()),
+        album: Some(draft.album.clone().into()),
+        album_artist: Some(draft.album_artist.clone().into()),
+        album_art: draft.album_art_url.as_deref().and_then(parse_uri),
+        duration: Some(draft.duration_ms),
+        track_number: draft.track_number,
+        disc_number: draft.disc_number,
+        isrc: draft.isrc.clone().map(Into::into),
+        mbid: draft.mbid.clone().map(Into::into),
+        tags: non_empty_tags(&draft.tags),
+        sha256: Some(crate::dedup::song_hash(&draft.title, &draft.artist, &draft.album).into()),
+        uri: Some(AtUri::new_owned(song_uri).map_err(|e| SdkError::Auth(format!("uri: {e}")))?),
+        ..Default::default()
+    })
+}
+
+/// The item order after moving the songs of `wanted` to the front of the
+/// playlist, in that order; the other items keep their relative order after
+/// them. `items` are `(rkey, song_uri, order)`. Returns only the items whose
+/// order changes, as `(rkey, new_order)`.
+fn reorder_plan(items: &[(String, String, i64)], wanted: &[String]) -> Vec<(String, i64)> {
+    let mut current: Vec<&(String, String, i64)> = items.iter().collect();
+    current.sort_by(|a, b| a.2.cmp(&b.2).then_with(|| a.0.cmp(&b.0)));
+
+    let mut placed: Vec<&(String, String, i64)> = Vec::with_capacity(current.len());
+    for song in wanted {
+        for item in &current {
+            if &item.1 == song && !placed.iter().any(|p| p.0 == item.0) {
+                placed.push(item);
+            }
+        }
+    }
+    for item in &current {
+        if !placed.iter().any(|p| p.0 == item.0) {
+            placed.push(item);
+        }
+    }
+
+    placed
+        .into_iter()
+        .enumerate()
+        .filter(|(i, item)| item.2 != *i as i64)
+        .map(|(i, item)| (item.0.clone(), i as i64))
+        .collect()
+}
+
 /// The song a scrobble implies: the track's own fields verbatim.
 fn song_draft_for(draft: &ScrobbleDraft) -> SongDraft {
     SongDraft {
@@ -224,6 +389,83 @@ fn song_draft_for(draft: &ScrobbleDraft) -> SongDraft {
     }
 }
 
+/// The `app.rocksky.artist` record of an artist.
+fn artist_record(draft: &ArtistDraft) -> Artist {
+    Artist::new()
+        .name(draft.name.clone())
+        .created_at(Datetime::now())
+        .maybe_picture_url(draft.picture_url.as_deref().and_then(parse_uri))
+        .maybe_bio(draft.bio.clone().map(Into::into))
+        .maybe_tags(non_empty_tags(&draft.tags))
+        .build()
+}
+
+/// The `app.rocksky.album` record of an album.
+fn album_record(draft: &AlbumDraft) -> Album {
+    Album::new()
+        .title(draft.title.clone())
+        .artist(draft.artist.clone())
+        .created_at(Datetime::now())
+        .maybe_album_art_url(draft.album_art_url.as_deref().and_then(parse_uri))
+        .maybe_year(draft.year)
+        .maybe_release_date(draft.release_date.as_deref().and_then(parse_datetime))
+        .maybe_genre(draft.genre.clone().map(Into::into))
+        .maybe_tags(non_empty_tags(&draft.tags))
+        .maybe_spotify_link(draft.spotify_link.as_deref().and_then(parse_uri))
+        .build()
+}
+
+/// The `app.rocksky.song` record of a track.
+fn song_record(draft: &SongDraft) -> Song {
+    Song::new()
+        .title(draft.title.clone())
+        .artist(draft.artist.clone())
+        .album(draft.album.clone())
+        .album_artist(draft.album_artist.clone())
+        .duration(draft.duration_ms)
+        .created_at(Datetime::now())
+        .maybe_album_art_url(draft.album_art_url.as_deref().and_then(parse_uri))
+        .maybe_track_number(draft.track_number)
+        .maybe_disc_number(draft.disc_number)
+        .maybe_year(draft.year)
+        .maybe_release_date(draft.release_date.as_deref().and_then(parse_datetime))
+        .maybe_genre(draft.genre.clone().map(Into::into))
+        .maybe_tags(non_empty_tags(&draft.tags))
+        .maybe_composer(draft.composer.clone().map(Into::into))
+        .maybe_label(draft.label.clone().map(Into::into))
+        .maybe_mbid(draft.mbid.clone().map(Into::into))
+        .maybe_isrc(draft.isrc.clone().map(Into::into))
+        .maybe_spotify_link(draft.spotify_link.as_deref().and_then(parse_uri))
+        .build()
+}
+
+/// The `app.rocksky.scrobble` record of a play at `created_at`.
+fn scrobble_record(draft: &ScrobbleDraft, created_at: Datetime) -> Scrobble {
+    Scrobble::new()
+        .title(draft.title.clone())
+        .artist(draft.artist.clone())
+        .album(draft.album.clone())
+        .album_artist(draft.album_artist.clone())
+        .duration(draft.duration_ms)
+        .created_at(created_at)
+        .maybe_album_art_url(draft.album_art_url.as_deref().and_then(parse_uri))
+        .maybe_track_number(draft.track_number)
+        .maybe_disc_number(draft.disc_number)
+        .maybe_year(draft.year)
+        .maybe_release_date(draft.release_date.as_deref().and_then(parse_datetime))
+        .maybe_genre(draft.genre.clone().map(Into::into))
+        .maybe_tags(non_empty_tags(&draft.tags))
+        .maybe_composer(draft.composer.clone().map(Into::into))
+        .maybe_label(draft.label.clone().map(Into::into))
+        .maybe_mbid(draft.mbid.clone().map(Into::into))
+        .maybe_isrc(draft.isrc.clone().map(Into::into))
+        .maybe_spotify_link(draft.spotify_link.as_deref().and_then(parse_uri))
+        .maybe_youtube_link(draft.youtube_link.as_deref().and_then(parse_uri))
+        .maybe_tidal_link(draft.tidal_link.as_deref().and_then(parse_uri))
+        .maybe_apple_music_link(draft.apple_music_link.as_deref().and_then(parse_uri))
+        .build()
+}
+
 /// The track for the actor's now-playing status (`app.rocksky.actor.status`).
 #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase", default)]
@@ -371,7 +613,7 @@ impl RockskyAgent {
 
     /// The caller's DID, or [`SdkError::NotAuthenticated`] if not logged in.
     #[cfg_attr(not(feature = "dedup"), allow(dead_code))]
-    fn did(&self) -> Result<String> {
+    pub(crate) fn did(&self) -> Result<String> {
         self.profile()
             .map(|p| p.did)
             .ok_or(SdkError::NotAuthenticated)
@@ -554,16 +796,16 @@ impl RockskyAgent {
             let agent = Agent::from(self.resume_oauth().await?);
             XrpcClient::send(&agent, req)
                 .await
-                .map_err(|e| SdkError::Auth(format!("service auth request failed: {e:?}")))?
+                .map_err(|e| client_err("service auth request", e))?
         } else {
             let agent = self.credential_agent().await?;
             XrpcClient::send(&agent, req)
                 .await
-                .map_err(|e| SdkError::Auth(format!("service auth request failed: {e:?}")))?
+                .map_err(|e| client_err("service auth request", e))?
         };
         let out = resp
             .parse::<SmolStr>()
-            .map_err(|e| SdkError::Auth(format!("service auth decode: {e:?}")))?;
+            .map_err(|e| xrpc_err("service auth", e))?;
         Ok(out.token.to_string())
     }
 
@@ -607,6 +849,15 @@ impl RockskyAgent {
     /// Create a record on the user's PDS, resuming whichever session exists.
     /// Returns the new record's `at://` URI.
     async fn create<R>(&self, record: R, what: &'static str) -> Result<String>
+    where
+        R: jacquard_common::types::collection::Collection + serde::Serialize,
+    {
+        Ok(self.create_ref(record, what).await?.uri)
+    }
+
+    /// [`create`](Self::create), returning the new record's URI and CID — what
+    /// a strongRef to it needs.
+    async fn create_ref<R>(&self, record: R, what: &'static str) -> Result<RecordRef>
     where
         R: jacquard_common::types::collection::Collection + serde::Serialize,
     {
@@ -616,15 +867,18 @@ impl RockskyAgent {
             Agent::from(session)
                 .create_record(record, None)
                 .await
-                .map_err(|e| SdkError::Auth(format!("{what}: {e:?}")))?
+                .map_err(|e| agent_err(what, e))?
         } else {
             self.credential_agent()
                 .await?
                 .create_record(record, None)
                 .await
-                .map_err(|e| SdkError::Auth(format!("{what}: {e:?}")))?
+                .map_err(|e| agent_err(what, e))?
         };
-        Ok(out.uri.to_string())
+        Ok(RecordRef {
+            uri: out.uri.to_string(),
+            cid: out.cid.to_string(),
+        })
     }
 
     /// Put (upsert) a record at `rkey`, resuming whichever session exists.
@@ -638,13 +892,13 @@ impl RockskyAgent {
             Agent::from(session)
                 .put_record(rkey, record)
                 .await
-                .map_err(|e| SdkError::Auth(format!("{what}: {e:?}")))?;
+                .map_err(|e| agent_err(what, e))?;
         } else {
             self.credential_agent()
                 .await?
                 .put_record(rkey, record)
                 .await
-                .map_err(|e| SdkError::Auth(format!("{what}: {e:?}")))?;
+                .map_err(|e| agent_err(what, e))?;
         }
         Ok(())
     }
@@ -662,13 +916,13 @@ impl RockskyAgent {
             Agent::from(self.resume_oauth().await?)
                 .delete_record::<R>(rk)
                 .await
-                .map_err(|e| SdkError::Auth(format!("{what}: {e:?}")))?;
+                .map_err(|e| agent_err(what, e))?;
         } else {
             self.credential_agent()
                 .await?
                 .delete_record::<R>(rk)
                 .await
-                .map_err(|e| SdkError::Auth(format!("{what}: {e:?}")))?;
+                .map_err(|e| agent_err(what, e))?;
         }
         Ok(())
     }
@@ -681,6 +935,21 @@ impl RockskyAgent {
         collection: &str,
         pred: impl Fn(&serde_json::Value) -> bool,
     ) -> Result<Vec<String>> {
+        Ok(self
+            .records_where(collection, pred)
+            .await?
+            .into_iter()
+            .map(|r| r.rkey)
+            .collect())
+    }
+
+    /// Every record in `collection` whose JSON body satisfies `pred`, with its
+    /// URI and CID. Paginates the caller's repo.
+    async fn records_where(
+        &self,
+        collection: &str,
+        pred: impl Fn(&serde_json::Value) -> bool,
+    ) -> Result<Vec<ListedRecord>> {
         let did = self
             .profile()
             .map(|p| p.did)
@@ -688,10 +957,27 @@ impl RockskyAgent {
         let _guard = self.auth_lock.lock().await;
         if self.is_oauth() {
             let agent = Agent::from(self.resume_oauth().await?);
-            list_rkeys_where(&agent, &did, collection, pred).await
+            list_records_where(&agent, &did, collection, pred).await
         } else {
             let agent = self.credential_agent().await?;
-            list_rkeys_where(&agent, &did, collection, pred).await
+            list_records_where(&agent, &did, collection, pred).await
+        }
+    }
+
+    /// The record `rkey` of `collection` in the caller's repo, with its URI and
+    /// CID. [`SdkError::RecordNotFound`] when there's none.
+    async fn get_record(&self, collection: &str, rkey: &str) -> Result<ListedRecord> {
+        let did = self
+            .profile()
+            .map(|p| p.did)
+            .ok_or(SdkError::NotAuthenticated)?;
+        let _guard = self.auth_lock.lock().await;
+        if self.is_oauth() {
+            let agent = Agent::from(self.resume_oauth().await?);
+            get_record(&agent, &did, collection, rkey).await
+        } else {
+            let agent = self.credential_agent().await?;
+            get_record(&agent, &did, collection, rkey).await
         }
     }
 }
@@ -712,6 +998,13 @@ impl RockskyAgent {
     ///
     /// [`scrobble`]: RockskyAgent::scrobble
     pub async fn scrobble_match(&self, input: &ScrobbleMatch) -> Result<ScrobbleResult> {
+        let draft = self.match_draft(input).await;
+        self.scrobble(&draft).await
+    }
+
+    /// The [`ScrobbleDraft`] for a [`ScrobbleMatch`]: the matched metadata, or
+    /// a minimal draft from the input when the match comes back empty.
+    async fn match_draft(&self, input: &ScrobbleMatch) -> ScrobbleDraft {
         let ScrobbleMatch {
             title,
             artist,
@@ -766,7 +1059,7 @@ impl RockskyAgent {
         };
         // "Scrobbled at" — Unix seconds; the draft defaults to now when None.
         draft.timestamp = *timestamp;
-        self.scrobble(&draft).await
+        draft
     }
 
     /// Scrobble a play — the full fan-out, mirroring how Rocksky's indexer
@@ -813,29 +1106,7 @@ impl RockskyAgent {
                 return Ok(uri);
             }
         }
-        let record = Scrobble::new()
-            .title(draft.title.clone())
-            .artist(draft.artist.clone())
-            .album(draft.album.clone())
-            .album_artist(draft.album_artist.clone())
-            .duration(draft.duration_ms)
-            .created_at(created_at)
-            .maybe_album_art_url(draft.album_art_url.as_deref().and_then(parse_uri))
-            .maybe_track_number(draft.track_number)
-            .maybe_disc_number(draft.disc_number)
-            .maybe_year(draft.year)
-            .maybe_release_date(draft.release_date.as_deref().and_then(parse_datetime))
-            .maybe_genre(draft.genre.clone().map(Into::into))
-            .maybe_tags(non_empty_tags(&draft.tags))
-            .maybe_composer(draft.composer.clone().map(Into::into))
-            .maybe_label(draft.label.clone().map(Into::into))
-            .maybe_mbid(draft.mbid.clone().map(Into::into))
-            .maybe_isrc(draft.isrc.clone().map(Into::into))
-            .maybe_spotify_link(draft.spotify_link.as_deref().and_then(parse_uri))
-            .maybe_youtube_link(draft.youtube_link.as_deref().and_then(parse_uri))
-            .maybe_tidal_link(draft.tidal_link.as_deref().and_then(parse_uri))
-            .maybe_apple_music_link(draft.apple_music_link.as_deref().and_then(parse_uri))
-            .build();
+        let record = scrobble_record(draft, created_at);
         let uri = self.create(record, "create scrobble").await?;
         #[cfg(feature = "dedup")]
         if let Some(idx) = &self.dedup {
@@ -856,26 +1127,7 @@ impl RockskyAgent {
                 return Ok(uri);
             }
         }
-        let record = Song::new()
-            .title(draft.title.clone())
-            .artist(draft.artist.clone())
-            .album(draft.album.clone())
-            .album_artist(draft.album_artist.clone())
-            .duration(draft.duration_ms)
-            .created_at(Datetime::now())
-            .maybe_album_art_url(draft.album_art_url.as_deref().and_then(parse_uri))
-            .maybe_track_number(draft.track_number)
-            .maybe_disc_number(draft.disc_number)
-            .maybe_year(draft.year)
-            .maybe_release_date(draft.release_date.as_deref().and_then(parse_datetime))
-            .maybe_genre(draft.genre.clone().map(Into::into))
-            .maybe_tags(non_empty_tags(&draft.tags))
-            .maybe_composer(draft.composer.clone().map(Into::into))
-            .maybe_label(draft.label.clone().map(Into::into))
-            .maybe_mbid(draft.mbid.clone().map(Into::into))
-            .maybe_isrc(draft.isrc.clone().map(Into::into))
-            .maybe_spotify_link(draft.spotify_link.as_deref().and_then(parse_uri))
-            .build();
+        let record = song_record(draft);
         let uri = self.create(record, "create song").await?;
         #[cfg(feature = "dedup")]
         if let Some(idx) = &self.dedup {
@@ -896,17 +1148,7 @@ impl RockskyAgent {
                 return Ok(uri);
             }
         }
-        let record = Album::new()
-            .title(draft.title.clone())
-            .artist(draft.artist.clone())
-            .created_at(Datetime::now())
-            .maybe_album_art_url(draft.album_art_url.as_deref().and_then(parse_uri))
-            .maybe_year(draft.year)
-            .maybe_release_date(draft.release_date.as_deref().and_then(parse_datetime))
-            .maybe_genre(draft.genre.clone().map(Into::into))
-            .maybe_tags(non_empty_tags(&draft.tags))
-            .maybe_spotify_link(draft.spotify_link.as_deref().and_then(parse_uri))
-            .build();
+        let record = album_record(draft);
         let uri = self.create(record, "create album").await?;
         #[cfg(feature = "dedup")]
         if let Some(idx) = &self.dedup {
@@ -927,13 +1169,7 @@ impl RockskyAgent {
                 return Ok(uri);
             }
         }
-        let record = Artist::new()
-            .name(draft.name.clone())
-            .created_at(Datetime::now())
-            .maybe_picture_url(draft.picture_url.as_deref().and_then(parse_uri))
-            .maybe_bio(draft.bio.clone().map(Into::into))
-            .maybe_tags(non_empty_tags(&draft.tags))
-            .build();
+        let record = artist_record(draft);
         let uri = self.create(record, "create artist").await?;
         #[cfg(feature = "dedup")]
         if let Some(idx) = &self.dedup {
@@ -1095,6 +1331,201 @@ impl RockskyAgent {
     }
 }
 
+/// Playlist authoring. A playlist is an `app.rocksky.playlist` record; each of
+/// its songs is an `app.rocksky.playlistItem` whose `subject` is a strongRef to
+/// the playlist and whose `track` snapshots the song, in `order`.
+impl RockskyAgent {
+    /// Create a playlist. Returns its URI and CID.
+    pub async fn create_playlist(&self, draft: &PlaylistDraft) -> Result<RecordRef> {
+        let record = Playlist::new()
+            .name(draft.name.clone())
+            .created_at(Datetime::now())
+            .maybe_description(draft.description.clone().map(Into::into))
+            .maybe_picture_url(draft.picture_url.as_deref().and_then(parse_uri))
+            .maybe_spotify_link(draft.spotify_link.clone().map(Into::into))
+            .maybe_tidal_link(draft.tidal_link.clone().map(Into::into))
+            .maybe_youtube_link(draft.youtube_link.clone().map(Into::into))
+            .maybe_apple_music_link(draft.apple_music_link.clone().map(Into::into))
+            .build();
+        self.create_ref(record, "create playlist").await
+    }
+
+    /// Append a song to the end of the playlist at `playlist_uri`, creating its
+    /// `app.rocksky.song` record first (deduped like any song). Returns the
+    /// item URI. With a dedup store this is idempotent: when the song is
+    /// already in the playlist, its existing item URI is returned and nothing
+    /// is written. Without one, every call writes a new song record, which no
+    /// existing item points at, so adding a song twice adds it twice.
+    pub async fn add_to_playlist(&self, playlist_uri: &str, song: &SongDraft) -> Result<String> {
+        let song_uri = self.create_song(song).await?;
+        let items = self.playlist_entries(playlist_uri).await?;
+        if let Some(existing) = items.iter().find(|i| i.song_uri == song_uri) {
+            return Ok(existing.uri.clone());
+        }
+
+        let playlist = self.playlist_ref(playlist_uri).await?;
+        let order = items.iter().map(|i| i.order).max().map_or(0, |o| o + 1);
+        let record = PlaylistItem::new()
+            .subject(strong_ref(&playlist.uri, &playlist.cid)?)
+            .track(playlist_track_for(song, &song_uri)?)
+            .order(order)
+            .created_at(Datetime::now())
+            .build();
+        let uri = self.create(record, "create playlist item").await?;
+        #[cfg(feature = "dedup")]
+        if let Some(idx) = &self.dedup {
+            let did = self.did()?;
+            idx.record_playlist_item(&did, playlist_uri, &song_uri, &uri, order)?;
+        }
+        Ok(uri)
+    }
+
+    /// Remove a song (by its `app.rocksky.song` URI) from the playlist. The
+    /// other items keep their order; gaps are harmless.
+    pub async fn remove_from_playlist(&self, playlist_uri: &str, song_uri: &str) -> Result<()> {
+        let items = self.listed_playlist_entries(playlist_uri).await?;
+        for item in items.iter().filter(|i| i.song_uri == song_uri) {
+            self.delete_playlist_item(&item.rkey).await?;
+        }
+        Ok(())
+    }
+
+    /// Move the songs of `song_uris` to the front of the playlist, in that
+    /// order; songs not listed keep their relative order after them. Passing
+    /// every song gives the full new order. Only the items whose position
+    /// changes are rewritten.
+    pub async fn reorder_playlist(&self, playlist_uri: &str, song_uris: &[String]) -> Result<()> {
+        let items = self.listed_playlist_entries(playlist_uri).await?;
+        let current: Vec<(String, String, i64)> = items
+            .iter()
+            .map(|i| (i.rkey.clone(), i.song_uri.clone(), i.order))
+            .collect();
+        for (rkey, order) in reorder_plan(&current, song_uris) {
+            let Some(item) = items.iter().find(|i| i.rkey == rkey) else {
+                continue;
+            };
+            let value = match &item.value {
+                Some(value) => value.clone(),
+                None => self.get_record(C_PLAYLIST_ITEM, &rkey).await?.value,
+            };
+            let mut record: PlaylistItem = serde_json::from_value(value)?;
+            record.order = order;
+            let rk: RecordKey<Rkey> = rkey
+                .parse()
+                .map_err(|e| SdkError::Auth(format!("rkey: {e}")))?;
+            self.put(rk, record, "reorder playlist").await?;
+            #[cfg(feature = "dedup")]
+            if let Some(idx) = &self.dedup {
+                let did = self.did()?;
+                idx.record_playlist_item(&did, playlist_uri, &item.song_uri, &item.uri, order)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete a playlist: its items, then the playlist record itself.
+    pub async fn delete_playlist(&self, playlist_uri: &str) -> Result<()> {
+        for item in self.listed_playlist_entries(playlist_uri).await? {
+            self.delete_playlist_item(&item.rkey).await?;
+        }
+        let rkey = playlist_uri
+            .rsplit('/')
+            .next()
+            .ok_or_else(|| SdkError::Other(format!("invalid playlist uri: {playlist_uri}")))?;
+        self.delete::<Playlist>(rkey, "delete playlist").await
+    }
+
+    /// The items of the playlist at `playlist_uri`, in no particular order.
+    /// Read from the dedup store when configured, which misses items written
+    /// by other clients; else listed from the PDS like
+    /// [`Self::listed_playlist_entries`].
+    async fn playlist_entries(&self, playlist_uri: &str) -> Result<Vec<PlaylistEntry>> {
+        #[cfg(feature = "dedup")]
+        if let Some(idx) = &self.dedup {
+            let did = self.did()?;
+            return Ok(idx
+                .playlist_items(&did, playlist_uri)?
+                .into_iter()
+                .filter_map(|i| {
+                    Some(PlaylistEntry {
+                        rkey: i.uri.rsplit('/').next()?.to_string(),
+                        uri: i.uri,
+                        song_uri: i.song_uri,
+                        order: i.order.unwrap_or_default(),
+                        value: None,
+                    })
+                })
+                .collect());
+        }
+        self.listed_playlist_entries(playlist_uri).await
+    }
+
+    /// The items of the playlist at `playlist_uri` as the PDS has them, in no
+    /// particular order — what deleting or reordering must act on, since the
+    /// dedup store only knows the items this agent wrote. The store, when
+    /// configured, is reconciled with the listing on the way.
+    async fn listed_playlist_entries(&self, playlist_uri: &str) -> Result<Vec<PlaylistEntry>> {
+        let items: Vec<PlaylistEntry> = self
+            .records_where(C_PLAYLIST_ITEM, |v| subject_uri_is(v, playlist_uri))
+            .await?
+            .into_iter()
+            .map(|r| PlaylistEntry {
+                song_uri: item_song_uri(&r.value).unwrap_or_default().to_string(),
+                order: item_order(&r.value).unwrap_or_default(),
+                uri: r.uri,
+                rkey: r.rkey,
+                value: Some(r.value),
+            })
+            .collect();
+        #[cfg(feature = "dedup")]
+        if let Some(idx) = &self.dedup {
+            let did = self.did()?;
+            for stale in idx.playlist_items(&did, playlist_uri)? {
+                if !items.iter().any(|i| i.uri == stale.uri) {
+                    if let Some(rkey) = stale.uri.rsplit('/').next() {
+                        idx.forget(&did, C_PLAYLIST_ITEM, rkey)?;
+                    }
+                }
+            }
+            for item in items.iter().filter(|i| !i.song_uri.is_empty()) {
+                idx.record_playlist_item(
+                    &did,
+                    playlist_uri,
+                    &item.song_uri,
+                    &item.uri,
+                    item.order,
+                )?;
+            }
+        }
+        Ok(items)
+    }
+
+    /// The current URI + CID of the caller's playlist at `playlist_uri`, for
+    /// the strongRef its items point at.
+    async fn playlist_ref(&self, playlist_uri: &str) -> Result<RecordRef> {
+        let did = self.did()?;
+        let rkey = playlist_uri
+            .strip_prefix(&format!("at://{did}/{C_PLAYLIST}/"))
+            .ok_or(SdkError::RecordNotFound)?;
+        let record = self.get_record(C_PLAYLIST, rkey).await?;
+        Ok(RecordRef {
+            uri: record.uri,
+            cid: record.cid,
+        })
+    }
+
+    async fn delete_playlist_item(&self, rkey: &str) -> Result<()> {
+        self.delete::<PlaylistItem>(rkey, "delete playlist item")
+            .await?;
+        #[cfg(feature = "dedup")]
+        if let Some(idx) = &self.dedup {
+            let did = self.did()?;
+            idx.forget(&did, C_PLAYLIST_ITEM, rkey)?;
+        }
+        Ok(())
+    }
+}
+
 /// Convert a user-facing [`ShoutGif`] into the generated
 /// `app.rocksky.shout.defs#gif` record embed. Errors when `url` is not a valid
 /// URI.
@@ -1178,17 +1609,545 @@ impl RockskyAgent {
             let agent = Agent::from(self.resume_oauth().await?);
             XrpcClient::send(&agent, req)
                 .await
-                .map_err(|e| SdkError::Auth(format!("getRepo: {e:?}")))?
+                .map_err(|e| client_err("getRepo", e))?
         } else {
             let agent = self.credential_agent().await?;
             XrpcClient::send(&agent, req)
                 .await
-                .map_err(|e| SdkError::Auth(format!("getRepo: {e:?}")))?
+                .map_err(|e| client_err("getRepo", e))?
         };
         Ok(resp.buffer().to_vec())
     }
 }
 
+/// First wait of [`RockskyAgent::replay_queue`] after a failed flush, doubled
+/// per failure up to [`REPLAY_BACKOFF_MAX`].
+#[cfg(feature = "dedup")]
+const REPLAY_BACKOFF: std::time::Duration = std::time::Duration::from_secs(2);
+#[cfg(feature = "dedup")]
+const REPLAY_BACKOFF_MAX: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// The wait before replay attempt `failures + 1`.
+#[cfg(feature = "dedup")]
+fn replay_backoff(failures: u32) -> std::time::Duration {
+    REPLAY_BACKOFF
+        .saturating_mul(1 << failures.min(16))
+        .min(REPLAY_BACKOFF_MAX)
+}
+
+/// Offline store-and-forward for scrobbles (the `dedup` feature). Plays that
+/// can't reach the PDS are persisted in the dedup store and replayed later, in
+/// the order they happened, deduped against the index so a play that did land
+/// before the connection dropped isn't written twice.
+#[cfg(feature = "dedup")]
+impl RockskyAgent {
+    /// [`scrobble`](Self::scrobble), but a play that fails only because the PDS
+    /// or AppView is unreachable (see [`SdkError::is_transient`]) is queued
+    /// instead of lost. Any queued backlog is flushed first so plays land in
+    /// order; while it can't be, the new play joins the queue behind it. Other
+    /// errors are returned as usual. Requires a dedup store.
+    pub async fn scrobble_or_queue(&self, draft: &ScrobbleDraft) -> Result<ScrobbleOutcome> {
+        let idx = self.queue_index()?;
+        let did = self.did()?;
+        // Pin the play time now, so a replay dedups on the same second.
+        let mut draft = draft.clone();
+        draft
+            .timestamp
+            .get_or_insert_with(|| chrono::Utc::now().timestamp());
+
+        if idx.queue_depth(&did)? > 0 && self.flush_queue().await?.remaining > 0 {
+            idx.enqueue_scrobble(&did, &draft)?;
+            return Ok(ScrobbleOutcome::Queued {
+                depth: idx.queue_depth(&did)?,
+            });
+        }
+        match self.scrobble(&draft).await {
+            Ok(result) => Ok(ScrobbleOutcome::Sent(result)),
+            Err(e) if e.is_transient() => {
+                tracing::warn!(error = %e, "scrobble failed, queued for replay");
+                idx.enqueue_scrobble(&did, &draft)?;
+                Ok(ScrobbleOutcome::Queued {
+                    depth: idx.queue_depth(&did)?,
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// [`scrobble_match`](Self::scrobble_match) with the offline queue of
+    /// [`scrobble_or_queue`](Self::scrobble_or_queue). When the AppView is
+    /// unreachable too, the minimal title/artist/album draft is what's queued.
+    pub async fn scrobble_match_or_queue(&self, input: &ScrobbleMatch) -> Result<ScrobbleOutcome> {
+        let draft = self.match_draft(input).await;
+        self.scrobble_or_queue(&draft).await
+    }
+
+    /// How many plays of the logged-in account are waiting in the offline
+    /// queue.
+    pub fn queue_depth(&self) -> Result<usize> {
+        self.queue_index()?.queue_depth(&self.did()?)
+    }
+
+    /// Replay the offline queue once, oldest play first. A play whose
+    /// `(song, second)` is already in the index is dropped as a duplicate, and
+    /// one the PDS rejects for good (see [`SdkError::is_permanent`]) is
+    /// dropped too. The pass stops at the first other failure — including a
+    /// local [store](SdkError::Store) one — leaving it and everything after it
+    /// queued. Errors only when the session itself is unusable.
+    pub async fn flush_queue(&self) -> Result<FlushReport> {
+        let idx = self.queue_index()?;
+        let did = self.did()?;
+        let queued = idx.queued_scrobbles(&did)?;
+        let mut report = FlushReport::default();
+
+        for (i, entry) in queued.iter().enumerate() {
+            let d = &entry.draft;
+            let secs = d.timestamp.unwrap_or_default();
+            if idx
+                .scrobble_uri(&did, &d.title, &d.artist, &d.album, secs)?
+                .is_some()
+            {
+                idx.dequeue_scrobble(entry)?;
+                report.duplicates += 1;
+                continue;
+            }
+            match self.scrobble(d).await {
+                Ok(_) => report.sent += 1,
+                Err(e @ (SdkError::NotAuthenticated | SdkError::SessionExpired)) => return Err(e),
+                Err(e @ SdkError::Pds { .. }) if e.is_permanent() => {
+                    tracing::warn!(error = %e, title = %d.title, "dropping queued scrobble");
+                    report.dropped += 1;
+                }
+                Err(e) => {
+                    tracing::debug!(error = %e, "offline queue not flushed");
+                    report.remaining = queued.len() - i;
+                    return Ok(report);
+                }
+            }
+            idx.dequeue_scrobble(entry)?;
+        }
+        Ok(report)
+    }
+
+    /// Flush the offline queue until it's empty, waiting with exponential
+    /// backoff (2 s doubling up to 5 min) while the PDS stays unreachable.
+    /// Returns the combined report. Meant for a background task once a play
+    /// has been queued: `tokio::spawn(async move { agent.replay_queue().await })`.
+    pub async fn replay_queue(&self) -> Result<FlushReport> {
+        let mut total = FlushReport::default();
+        let mut failures = 0;
+        loop {
+            let report = self.flush_queue().await?;
+            total.sent += report.sent;
+            total.duplicates += report.duplicates;
+            total.dropped += report.dropped;
+            total.remaining = report.remaining;
+            if report.remaining == 0 {
+                return Ok(total);
+            }
+            // Progress means the connection is back; only stalls back off.
+            if report.sent + report.duplicates + report.dropped > 0 {
+                failures = 0;
+            }
+            tokio::time::sleep(replay_backoff(failures)).await;
+            failures += 1;
+        }
+    }
+
+    /// Import a listening-history export — Spotify extended streaming history,
+    /// a Last.fm CSV/JSON dump or a ListenBrainz export, told apart by `name`
+    /// and contents — into the repo. Plays the dedup index already has are
+    /// skipped; the rest are written in rate-limited `applyWrites` batches,
+    /// resuming from `config`'s progress file. See [`crate::import`].
+    pub async fn import_history(
+        &self,
+        name: &str,
+        input: &str,
+        config: &crate::import::ImportConfig,
+    ) -> Result<crate::import::ImportReport> {
+        let format = crate::import::ImportFormat::detect(name, input)
+            .ok_or_else(|| SdkError::Other(format!("unrecognized export format: {name}")))?;
+        let plays = crate::import::parse(format, input)?;
+        crate::import::run(self, name, plays, config).await
+    }
+
+    /// The dedup store the queue lives in.
+    fn queue_index(&self) -> Result<Arc<crate::dedup::RepoIndex>> {
+        self.dedup
+            .clone()
+            .ok_or_else(|| SdkError::Other("no dedup store configured".into()))
+    }
+}
+
+/// The most writes a PDS accepts in one `com.atproto.repo.applyWrites`.
+pub(crate) const MAX_APPLY_WRITES: usize = 200;
+
+/// One create of an `applyWrites` transaction, with the dedup identity it's
+/// indexed under once written (none for likes and follows).
+struct BatchCreate {
+    collection: &'static str,
+    value: jacquard_common::types::value::Data,
+    identity: Option<Identity>,
+}
+
+/// Serialize a record for an `applyWrites` create.
+fn batch_create<R: serde::Serialize>(
+    collection: &'static str,
+    record: &R,
+    identity: Option<Identity>,
+) -> Result<BatchCreate> {
+    let value = jacquard_common::types::value::to_data(record)
+        .map_err(|e| SdkError::Other(format!("serialize {collection}: {e}")))?;
+    Ok(BatchCreate {
+        collection,
+        value,
+        identity,
+    })
+}
+
+/// The records a write creates, its own last.
+fn batch_creates(write: &BatchWrite) -> Result<Vec<BatchCreate>> {
+    let artist = |d: &ArtistDraft| {
+        batch_create(
+            C_ARTIST,
+            &artist_record(d),
+            Some(Identity::Artist(d.name.clone())),
+        )
+    };
+    let album = |d: &AlbumDraft| {
+        let identity = Identity::Album(d.title.clone(), d.artist.clone());
+        batch_create(C_ALBUM, &album_record(d), Some(identity))
+    };
+    let song = |d: &SongDraft| {
+        let identity = Identity::Song(d.title.clone(), d.artist.clone(), d.album.clone());
+        batch_create(C_SONG, &song_record(d), Some(identity))
+    };
+    Ok(match write {
+        BatchWrite::Scrobble(d) => {
+            let created_at = match d.timestamp {
+                Some(ts) => datetime_from_unix(ts)?,
+                None => Datetime::now(),
+            };
+            let identity = Identity::Scrobble(
+                d.title.clone(),
+                d.artist.clone(),
+                d.album.clone(),
+                created_at.timestamp(),
+            );
+            vec![
+                artist(&artist_draft_for(d))?,
+                album(&album_draft_for(d))?,
+                song(&song_draft_for(d))?,
+                batch_create(C_SCROBBLE, &scrobble_record(d, created_at), Some(identity))?,
+            ]
+        }
+        BatchWrite::Song(d) => vec![song(d)?],
+        BatchWrite::Album(d) => vec![album(d)?],
+        BatchWrite::Artist(d) => vec![artist(d)?],
+        BatchWrite::Like { uri, cid } => {
+            let record = Like::new()
+                .subject(strong_ref(uri, cid)?)
+                .created_at(Datetime::now())
+                .build();
+            vec![batch_create("app.rocksky.like", &record, None)?]
+        }
+        BatchWrite::Follow(did) => {
+            let subject = Did::new_owned(did).map_err(|e| SdkError::Auth(format!("did: {e}")))?;
+            let record = Follow::new()
+                .subject(subject)
+                .created_at(Datetime::now())
+                .build();
+            vec![batch_create("app.rocksky.graph.follow", &record, None)?]
+        }
+    })
+}
+
+/// The records to create for a batch of writes, deduped against the index
+/// and within the batch.
+#[derive(Default)]
+struct BatchPlan {
+    creates: Vec<BatchCreate>,
+    /// Per write: the range of `creates` it adds, and its own record.
+    writes: Vec<(std::ops::Range<usize>, Target)>,
+}
+
+/// Where the record a write stands for comes from.
+enum Target {
+    /// Already in the repo.
+    Existing(String),
+    /// `creates[i]`, added by this write or an earlier one.
+    Create(usize),
+}
+
+/// How the `applyWrites` transaction writing a chunk of a [`BatchPlan`] went.
+struct ChunkResult {
+    /// The creates of the chunk.
+    creates: std::ops::Range<usize>,
+    /// The created records, in the order of the creates.
+    result: Result<Vec<RecordRef>>,
+}
+
+/// Split the writes of `plan` into chunks of at most [`MAX_APPLY_WRITES`]
+/// creates, never splitting a write's creates across two chunks. Returns the
+/// ranges of writes.
+fn chunk_plan(plan: &BatchPlan) -> Vec<std::ops::Range<usize>> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut ops = 0;
+    for (i, (creates, _)) in plan.writes.iter().enumerate() {
+        if ops + creates.len() > MAX_APPLY_WRITES {
+            chunks.push(start..i);
+            start = i;
+            ops = 0;
+        }
+        ops += creates.len();
+    }
+    if start < plan.writes.len() {
+        chunks.push(start..plan.writes.len());
+    }
+    chunks
+}
+
+/// Batched writes through `com.atproto.repo.applyWrites`: one commit, and one
+/// rate-limited request, for up to [`MAX_APPLY_WRITES`] records.
+impl RockskyAgent {
+    /// Write `writes` in as few `applyWrites` transactions as the 200-write
+    /// limit allows, instead of one `createRecord` each. Like the single verbs,
+    /// artists/albums/songs/plays the dedup index already has (or that an
+    /// earlier write of the batch creates) aren't written again.
+    ///
+    /// A transaction is all-or-nothing, so a rejected one fails just its own
+    /// writes; the others still go through, and the report says which is
+    /// which. The index learns the records of each committed transaction in
+    /// one local transaction too. Once the session itself is unusable the
+    /// remaining transactions aren't attempted. Errors only when the batch
+    /// can't be built (no session, an invalid like/follow subject).
+    pub async fn apply_batch(&self, writes: &[BatchWrite]) -> Result<BatchReport> {
+        let did = self.did()?;
+        let plan = self.plan_batch(&did, writes)?;
+        let chunks = self.write_plan(&did, &plan).await;
+
+        let mut created: Vec<Option<std::result::Result<String, String>>> =
+            plan.creates.iter().map(|_| None).collect();
+        let mut report = BatchReport::default();
+        for chunk in &chunks {
+            match &chunk.result {
+                Ok(refs) => {
+                    report.commits += 1;
+                    for (i, r) in chunk.creates.clone().zip(refs) {
+                        created[i] = Some(Ok(r.uri.clone()));
+                    }
+                }
+                Err(e) => {
+                    report.failed_commits += 1;
+                    for i in chunk.creates.clone() {
+                        created[i] = Some(Err(e.to_string()));
+                    }
+                }
+            }
+        }
+        report.outcomes = plan
+            .writes
+            .iter()
+            .map(|(own, target)| match target {
+                Target::Existing(uri) => BatchOutcome::Existing(uri.clone()),
+                Target::Create(i) => match created[*i].clone() {
+                    Some(Ok(uri)) if own.contains(i) => BatchOutcome::Created(uri),
+                    Some(Ok(uri)) => BatchOutcome::Existing(uri),
+                    Some(Err(e)) => BatchOutcome::Failed(e),
+                    None => BatchOutcome::Failed("not written".into()),
+                },
+            })
+            .collect();
+        Ok(report)
+    }
+
+    /// Write the plays of `drafts` as one `applyWrites` transaction (see
+    /// [`apply_batch`](Self::apply_batch)), returning the transaction's error
+    /// as is. Returns how many scrobbles were written. Errors when the plays
+    /// need more than [`MAX_APPLY_WRITES`] writes.
+    #[cfg_attr(not(feature = "dedup"), allow(dead_code))]
+    pub(crate) async fn apply_scrobble_batch(&self, drafts: &[ScrobbleDraft]) -> Result<usize> {
+        let did = self.did()?;
+        let writes: Vec<BatchWrite> = drafts.iter().cloned().map(BatchWrite::Scrobble).collect();
+        let plan = self.plan_batch(&did, &writes)?;
+        if plan.creates.len() > MAX_APPLY_WRITES {
+            return Err(SdkError::Other(format!(
+                "batch of {} writes exceeds the applyWrites limit of {MAX_APPLY_WRITES}",
+                plan.creates.len()
+            )));
+        }
+        let mut written = 0;
+        for chunk in self.write_plan(&did, &plan).await {
+            chunk.result?;
+            written += plan.creates[chunk.creates]
+                .iter()
+                .filter(|c| c.collection == C_SCROBBLE)
+                .count();
+        }
+        Ok(written)
+    }
+
+    /// Build the creates of `writes`, skipping the records the index has or
+    /// an earlier write already creates.
+    fn plan_batch(&self, did: &str, writes: &[BatchWrite]) -> Result<BatchPlan> {
+        let mut plan = BatchPlan::default();
+        let mut planned = std::collections::HashMap::new();
+        for write in writes {
+            let start = plan.creates.len();
+            let mut target = None;
+            for create in batch_creates(write)? {
+                target = Some(match &create.identity {
+                    Some(identity) => match planned.get(&identity.key()) {
+                        Some(&i) => Target::Create(i),
+                        None => match self.indexed_uri(did, identity)? {
+                            Some(uri) => Target::Existing(uri),
+                            None => {
+                                planned.insert(identity.key(), plan.creates.len());
+                                plan.creates.push(create);
+                                Target::Create(plan.creates.len() - 1)
+                            }
+                        },
+                    },
+                    None => {
+                        plan.creates.push(create);
+                        Target::Create(plan.creates.len() - 1)
+                    }
+                });
+            }
+            let target = target.ok_or_else(|| SdkError::Other("empty batch write".into()))?;
+            plan.writes.push((start..plan.creates.len(), target));
+        }
+        Ok(plan)
+    }
+
+    /// The URI the dedup index has for `identity` (never any, without one).
+    fn indexed_uri(&self, did: &str, identity: &Identity) -> Result<Option<String>> {
+        #[cfg(feature = "dedup")]
+        if let Some(idx) = &self.dedup {
+            return idx.identity_uri(did, identity);
+        }
+        let _ = (did, identity);
+        Ok(None)
+    }
+
+    /// Send the creates of `plan`, one `applyWrites` per chunk, indexing each
+    /// committed chunk. Stops sending once the session is unusable; the
+    /// chunks left fail with that error.
+    async fn write_plan(&self, did: &str, plan: &BatchPlan) -> Vec<ChunkResult> {
+        let mut results = Vec::new();
+        let mut fatal: Option<String> = None;
+        for writes in chunk_plan(plan) {
+            let creates = plan.writes[writes.start].0.start..plan.writes[writes.end - 1].0.end;
+            if creates.is_empty() {
+                results.push(ChunkResult {
+                    creates,
+                    result: Ok(Vec::new()),
+                });
+                continue;
+            }
+            let result = match &fatal {
+                Some(e) => Err(SdkError::Auth(e.clone())),
+                None => {
+                    self.apply_creates(did, &plan.creates[creates.clone()])
+                        .await
+                }
+            };
+            match &result {
+                Ok(refs) => self.index_created(did, &plan.creates[creates.clone()], refs),
+                Err(e @ (SdkError::NotAuthenticated | SdkError::SessionExpired)) => {
+                    fatal = Some(e.to_string());
+                }
+                Err(e) => tracing::warn!(error = %e, "applyWrites transaction rejected"),
+            }
+            results.push(ChunkResult { creates, result });
+        }
+        results
+    }
+
+    /// Index the records of a committed transaction, all at once. The index
+    /// is only a cache of the repo, so failing to update it is logged, not
+    /// returned: the next [`sync_repo`](Self::sync_repo) catches up.
+    fn index_created(&self, did: &str, creates: &[BatchCreate], refs: &[RecordRef]) {
+        #[cfg(feature = "dedup")]
+        if let Some(idx) = &self.dedup {
+            let records: Vec<(&Identity, &str)> = creates
+                .iter()
+                .zip(refs)
+                .filter_map(|(c, r)| Some((c.identity.as_ref()?, r.uri.as_str())))
+                .collect();
+            if let Err(e) = idx.record_all(did, &records) {
+                tracing::warn!(error = %e, "failed to index batched records");
+            }
+        }
+        let _ = (did, creates, refs);
+    }
+
+    /// Send `creates` as one `applyWrites` call. Returns the created records,
+    /// in the order of `creates`.
+    async fn apply_creates(&self, did: &str, creates: &[BatchCreate]) -> Result<Vec<RecordRef>> {
+        use jacquard::api::com_atproto::repo::apply_writes::{
+            ApplyWrites, ApplyWritesOutputResultsItem, ApplyWritesWritesItem, Create,
+        };
+        use smol_str::SmolStr;
+
+        let writes = creates
+            .iter()
+            .map(|c| {
+                Ok(ApplyWritesWritesItem::Create(Box::new(Create {
+                    collection: Nsid::new_owned(c.collection).map_err(auth_err)?,
+                    rkey: None,
+                    value: c.value.clone(),
+                    extra_data: None,
+                })))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let req = ApplyWrites::<SmolStr> {
+            repo: AtIdentifier::new_owned(did).map_err(auth_err)?,
+            swap_commit: None,
+            validate: None,
+            writes,
+            extra_data: None,
+        };
+
+        let _guard = self.auth_lock.lock().await;
+        let resp = if self.is_oauth() {
+            let agent = Agent::from(self.resume_oauth().await?);
+            XrpcClient::send(&agent, req)
+                .await
+                .map_err(|e| client_err("applyWrites", e))?
+        } else {
+            let agent = self.credential_agent().await?;
+            XrpcClient::send(&agent, req)
+                .await
+                .map_err(|e| client_err("applyWrites", e))?
+        };
+        let out = resp
+            .parse::<SmolStr>()
+            .map_err(|e| xrpc_err("applyWrites", e))?;
+        let results: Vec<RecordRef> = out
+            .results
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|r| match r {
+                ApplyWritesOutputResultsItem::CreateResult(c) => Some(RecordRef {
+                    uri: c.uri.to_string(),
+                    cid: c.cid.to_string(),
+                }),
+                _ => None,
+            })
+            .collect();
+        if results.len() != creates.len() {
+            return Err(SdkError::Other(format!(
+                "applyWrites returned {} results for {} writes",
+                results.len(),
+                creates.len()
+            )));
+        }
+        Ok(results)
+    }
+}
+
 /// The `self` record key used by singleton records (now-playing status).
 fn self_rkey() -> Result<RecordKey<Rkey>> {
     "self"
@@ -1213,6 +2172,18 @@ fn subject_uri_is(v: &serde_json::Value, uri: &str) -> bool {
         == Some(uri)
 }
 
+/// The song URI (`track.uri`) of a playlist item record.
+fn item_song_uri(v: &serde_json::Value) -> Option<&str> {
+    v.get("track")
+        .and_then(|t| t.get("uri"))
+        .and_then(|u| u.as_str())
+}
+
+/// The `order` of a playlist item record.
+fn item_order(v: &serde_json::Value) -> Option<i64> {
+    v.get("order").and_then(serde_json::Value::as_i64)
+}
+
 /// Parse a URL string into a lexicon `UriValue`, dropping anything invalid.
 fn parse_uri(s: &str) -> Option<UriValue> {
     UriValue::new_owned(s).ok()
@@ -1239,22 +2210,78 @@ fn non_empty_tags(tags: &[String]) -> Option<Vec<jacquard_common::DefaultStr>> {
     }
 }
 
-/// List (paginated) the rkeys of every record in `did`'s `collection` whose JSON
-/// body satisfies `pred`. Generic over the session agent (credential or OAuth),
-/// both of which are `XrpcClient`s.
-async fn list_rkeys_where<A: XrpcClient + Sync>(
+/// A record listed from the caller's repo.
+struct ListedRecord {
+    uri: String,
+    rkey: String,
+    cid: String,
+    value: serde_json::Value,
+}
+
+/// An item of a playlist being edited. `value` is its record when it was
+/// listed from the repo, `None` when it came from the dedup store.
+struct PlaylistEntry {
+    uri: String,
+    rkey: String,
+    song_uri: String,
+    order: i64,
+    value: Option<serde_json::Value>,
+}
+
+/// Fetch the record `rkey` of `did`'s `collection`. Generic over the session
+/// agent like [`list_records_where`].
+async fn get_record<A: XrpcClient + Sync>(
+    agent: &A,
+    did: &str,
+    collection: &str,
+    rkey: &str,
+) -> Result<ListedRecord> {
+    use jacquard::api::com_atproto::repo::get_record::{GetRecord, GetRecordError};
+    use jacquard_common::xrpc::XrpcError;
+    use smol_str::SmolStr;
+
+    let req = GetRecord::<SmolStr> {
+        cid: None,
+        collection: Nsid::<SmolStr>::new_owned(collection).map_err(auth_err)?,
+        repo: AtIdentifier::<SmolStr>::new_owned(did).map_err(auth_err)?,
+        rkey: rkey
+            .parse()
+            .map_err(|e| SdkError::Auth(format!("rkey: {e}")))?,
+    };
+    let resp = XrpcClient::send(agent, req)
+        .await
+        .map_err(|e| client_err("getRecord", e))?;
+    let out = resp.parse::<SmolStr>().map_err(|e| match e {
+        XrpcError::Xrpc(GetRecordError::RecordNotFound(_)) => SdkError::RecordNotFound,
+        e => xrpc_err("getRecord", e),
+    })?;
+    let cid = out
+        .cid
+        .ok_or_else(|| SdkError::Other("getRecord returned no cid".into()))?;
+    Ok(ListedRecord {
+        uri: out.uri.to_string(),
+        rkey: rkey.to_string(),
+        cid: cid.to_string(),
+        value: serde_json::to_value(&out.value)?,
+    })
+}
+
+/// List (paginated) every record in `did`'s `collection` whose JSON body
+/// satisfies `pred`. Generic over the session agent (credential or OAuth), both
+/// of which are `XrpcClient`s.
+async fn list_records_where<A: XrpcClient + Sync>(
     agent: &A,
     did: &str,
     collection: &str,
     pred: impl Fn(&serde_json::Value) -> bool,
-) -> Result<Vec<String>> {
+) -> Result<Vec<ListedRecord>> {
     use jacquard::api::com_atproto::repo::list_records::ListRecords;
     use smol_str::SmolStr;
 
     let repo = AtIdentifier::<SmolStr>::new_owned(did).map_err(auth_err)?;
     let collection = Nsid::<SmolStr>::new_owned(collection).map_err(auth_err)?;
 
-    let mut rkeys = Vec::new();
+    let mut records = Vec::new();
     let mut cursor: Option<SmolStr> = None;
     loop {
         let req = ListRecords::<SmolStr> {
@@ -1266,21 +2293,26 @@ async fn list_rkeys_where<A: XrpcClient + Sync>(
         };
         let resp = XrpcClient::send(agent, req)
             .await
-            .map_err(|e| SdkError::Auth(format!("listRecords: {e:?}")))?;
+            .map_err(|e| client_err("listRecords", e))?;
         let page = resp
             .parse::<SmolStr>()
-            .map_err(|e| SdkError::Auth(format!("listRecords decode: {e:?}")))?;
+            .map_err(|e| xrpc_err("listRecords", e))?;
 
         for rec in &page.records {
-            let matches = serde_json::to_value(&rec.value)
-                .ok()
-                .map(|v| pred(&v))
-                .unwrap_or(false);
-            if matches {
-                let uri = rec.uri.to_string();
-                if let Some(rkey) = uri.rsplit('/').next() {
-                    rkeys.push(rkey.to_string());
-                }
+            let Ok(value) = serde_json::to_value(&rec.value) else {
+                continue;
+            };
+            if !pred(&value) {
+                continue;
+            }
+            let uri = rec.uri.to_string();
+            if let Some(rkey) = uri.rsplit('/').next() {
+                records.push(ListedRecord {
+                    rkey: rkey.to_string(),
+                    cid: rec.cid.to_string(),
+                    uri,
+                    value,
+                });
             }
         }
 
@@ -1289,7 +2321,7 @@ async fn list_rkeys_where<A: XrpcClient + Sync>(
             _ => break,
         }
     }
-    Ok(rkeys)
+    Ok(records)
 }
 
 #[cfg(test)]
@@ -1359,4 +2391,128 @@ mod tests {
         assert_eq!(s.composer.as_deref(), Some("Composer C"));
         assert_eq!(s.label.as_deref(), Some("Label L"));
     }
+
+    #[cfg(feature = "dedup")]
+    #[test]
+    fn replay_backoff_doubles_up_to_the_cap() {
+        assert_eq!(replay_backoff(0), REPLAY_BACKOFF);
+        assert_eq!(replay_backoff(1), REPLAY_BACKOFF * 2);
+        assert_eq!(replay_backoff(3), REPLAY_BACKOFF * 8);
+        assert_eq!(replay_backoff(8), REPLAY_BACKOFF_MAX);
+        assert_eq!(replay_backoff(u32::MAX), REPLAY_BACKOFF_MAX);
+    }
+
+    fn items(list: &[(&str, &str, i64)]) -> Vec<(String, String, i64)> {
+        list.iter()
+            .map(|(rkey, song, order)| (rkey.to_string(), song.to_string(), *order))
+            .collect()
+    }
+
+    #[test]
+    fn reorder_moves_wanted_songs_to_the_front() {
+        let current = items(&[("a", "s1", 0), ("b", "s2", 1), ("c", "s3", 2)]);
+        assert_eq!(
+            reorder_plan(&current, &["s3".into(), "s1".into()]),
+            vec![
+                ("c".to_string(), 0),
+                ("a".to_string(), 1),
+                ("b".to_string(), 2)
+            ]
+        );
+        // Already in that order -> nothing to rewrite.
+        assert!(reorder_plan(&current, &["s1".into(), "s2".into()]).is_empty());
+    }
+
+    #[test]
+    fn reorder_closes_gaps_and_ignores_unknown_songs() {
+        // Gaps left by removals; "s9" isn't in the playlist.
+        let current = items(&[("b", "s2", 5), ("a", "s1", 2)]);
+        assert_eq!(
+            reorder_plan(&current, &["s9".into(), "s2".into()]),
+            vec![("b".to_string(), 0), ("a".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn playlist_track_links_the_song_record() {
+        let song = song_draft_for(&full_draft());
+        let track = playlist_track_for(&song, "at://did:plc:test/app.rocksky.song/s1").unwrap();
+        assert_eq!(
+            track.uri.map(|u| u.to_string()).as_deref(),
+            Some("at://did:plc:test/app.rocksky.song/s1")
+        );
+        assert_eq!(track.title.as_deref(), Some("Song A"));
+        assert_eq!(track.duration, Some(210_000));
+        assert_eq!(
+            track.sha256.as_deref(),
+            Some(crate::dedup::song_hash("Song A", "Track Artist", "Album A").as_str())
+        );
+    }
+
+    fn play(artist: &str, timestamp: i64) -> BatchWrite {
+        BatchWrite::Scrobble(ScrobbleDraft {
+            artist: artist.into(),
+            album_artist: artist.into(),
+            timestamp: Some(timestamp),
+            ..full_draft()
+        })
+    }
+
+    #[test]
+    fn batch_plan_creates_shared_records_once() {
+        let agent = RockskyAgent::new("/nonexistent/session.json");
+        let writes = [
+            play("Artist X", 1),
+            play("Artist X", 2),
+            BatchWrite::Artist(ArtistDraft {
+                name: "ARTIST X".into(),
+                ..Default::default()
+            }),
+            BatchWrite::Follow("did:plc:friend".into()),
+        ];
+        let plan = agent.plan_batch("did:plc:test", &writes).unwrap();
+
+        let collections: Vec<_> = plan.creates.iter().map(|c| c.collection).collect();
+        assert_eq!(
+            collections,
+            [
+                C_ARTIST,
+                C_ALBUM,
+                C_SONG,
+                C_SCROBBLE,
+                C_SCROBBLE,
+                "app.rocksky.graph.follow"
+            ]
+        );
+        let ranges: Vec<_> = plan.writes.iter().map(|(r, _)| r.clone()).collect();
+        assert_eq!(ranges, [0..4, 4..5, 5..5, 5..6]);
+        // The artist write resolves to the record the first play creates.
+        assert!(matches!(plan.writes[2].1, Target::Create(0)));
+        assert!(matches!(plan.writes[1].1, Target::Create(4)));
+    }
+
+    #[test]
+    fn batch_chunks_respect_the_applywrites_limit() {
+        let agent = RockskyAgent::new("/nonexistent/session.json");
+        // Every play brings a new artist, album, song and scrobble.
+        let writes: Vec<_> = (0..60).map(|i| play(&format!("Artist {i}"), i)).collect();
+        let plan = agent.plan_batch("did:plc:test", &writes).unwrap();
+        assert_eq!(plan.creates.len(), 240);
+        assert_eq!(chunk_plan(&plan), [0..50, 50..60]);
+    }
+
+    #[test]
+    fn batch_report_lists_failed_writes() {
+        let report = BatchReport {
+            outcomes: vec![
+                BatchOutcome::Created("at://a".into()),
+                BatchOutcome::Failed("rejected".into()),
+                BatchOutcome::Existing("at://a".into()),
+            ],
+            commits: 1,
+            failed_commits: 1,
+        };
+        assert!(!report.is_complete());
+        assert_eq!(report.failed(), [1]);
+    }
 }
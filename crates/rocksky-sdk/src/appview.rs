@@ -167,6 +167,8 @@ impl AppView {
     /// JSON response back. Every named method on this client is sugar over this,
     /// so `get` reaches queries that have no dedicated wrapper (and any added
     /// server-side later). Empty-valued params are dropped before the request.
+    /// It is also the raw counterpart of the typed methods, for fields the wire
+    /// types below don't model yet.
     ///
     /// ```no_run
     /// # async fn f(av: &rocksky_sdk::AppView) -> rocksky_sdk::Result<()> {
@@ -568,21 +570,23 @@ impl AppView {
         Ok(out.followers)
     }
 
-    // ---- detail lookups & the long tail (raw JSON) ----------------------
+    // ---- detail lookups & the long tail ---------------------------------
     //
-    // These return the AppView's JSON verbatim (`serde_json::Value`): their
-    // shapes are bespoke (nested tracklists, charts, wrapped, now-playing,
-    // recommendations, shouts, playlists) and not worth freezing into structs.
-    // Reach anything else via [`AppView::get`].
-
-    /// A single album with its tracklist (`app.rocksky.album.getAlbum`).
-    pub async fn album(&self, uri: &str) -> Result<serde_json::Value> {
+    // Typed where the lexicons define a view (albums, artists, playlists,
+    // neighbours, stats, wrapped, stories, recommendations, shouts, audio
+    // settings). The rest return the AppView's JSON verbatim
+    // (`serde_json::Value`): their shapes are bespoke (charts, now-playing,
+    // provider matches) and not worth freezing into structs. Reach anything
+    // else — or the raw form of a typed method — via [`AppView::get`].
+
+    /// A single album with its tags and tracklist (`app.rocksky.album.getAlbum`).
+    pub async fn album(&self, uri: &str) -> Result<AlbumDetail> {
         self.query("app.rocksky.album.getAlbum", &[("uri", uri.to_string())])
             .await
     }
 
     /// A single artist with detail (`app.rocksky.artist.getArtist`).
-    pub async fn artist(&self, uri: &str) -> Result<serde_json::Value> {
+    pub async fn artist(&self, uri: &str) -> Result<ArtistDetail> {
         self.query("app.rocksky.artist.getArtist", &[("uri", uri.to_string())])
             .await
     }
@@ -638,35 +642,42 @@ impl AppView {
         actor: &str,
         limit: u32,
         offset: u32,
-    ) -> Result<serde_json::Value> {
-        self.query(
-            "app.rocksky.actor.getActorPlaylists",
-            &[
-                ("did", actor.to_string()),
-                ("limit", limit.to_string()),
-                ("offset", offset.to_string()),
-            ],
-        )
-        .await
+    ) -> Result<Vec<PlaylistView>> {
+        let out: PlaylistsOutput = self
+            .query(
+                "app.rocksky.actor.getActorPlaylists",
+                &[
+                    ("did", actor.to_string()),
+                    ("limit", limit.to_string()),
+                    ("offset", offset.to_string()),
+                ],
+            )
+            .await?;
+        Ok(out.playlists)
     }
 
     /// Actors with similar taste to `actor` (`app.rocksky.actor.getActorNeighbours`).
-    pub async fn neighbours(&self, actor: &str) -> Result<serde_json::Value> {
-        self.query(
-            "app.rocksky.actor.getActorNeighbours",
-            &[("did", actor.to_string())],
-        )
-        .await
+    pub async fn neighbours(&self, actor: &str) -> Result<Vec<NeighbourView>> {
+        let out: NeighboursOutput = self
+            .query(
+                "app.rocksky.actor.getActorNeighbours",
+                &[("did", actor.to_string())],
+            )
+            .await?;
+        Ok(out.neighbours)
     }
 
     /// Music compatibility between the viewer and `actor`
-    /// (`app.rocksky.actor.getActorCompatibility`, auth-gated).
-    pub async fn compatibility(&self, actor: &str) -> Result<serde_json::Value> {
-        self.query(
-            "app.rocksky.actor.getActorCompatibility",
-            &[("did", actor.to_string())],
-        )
-        .await
+    /// (`app.rocksky.actor.getActorCompatibility`, auth-gated). `None` when the
+    /// server has nothing to compare.
+    pub async fn compatibility(&self, actor: &str) -> Result<Option<CompatibilityView>> {
+        let out: CompatibilityOutput = self
+            .query(
+                "app.rocksky.actor.getActorCompatibility",
+                &[("did", actor.to_string())],
+            )
+            .await?;
+        Ok(out.compatibility)
     }
 
     /// An artist's all-time listeners (`app.rocksky.artist.getArtistListeners`).
@@ -675,16 +686,18 @@ impl AppView {
         uri: &str,
         limit: u32,
         offset: u32,
-    ) -> Result<serde_json::Value> {
-        self.query(
-            "app.rocksky.artist.getArtistListeners",
-            &[
-                ("uri", uri.to_string()),
-                ("limit", limit.to_string()),
-                ("offset", offset.to_string()),
-            ],
-        )
-        .await
+    ) -> Result<Vec<ListenerView>> {
+        let out: ListenersOutput<ListenerView> = self
+            .query(
+                "app.rocksky.artist.getArtistListeners",
+                &[
+                    ("uri", uri.to_string()),
+                    ("limit", limit.to_string()),
+                    ("offset", offset.to_string()),
+                ],
+            )
+            .await?;
+        Ok(out.listeners)
     }
 
     /// An artist's recent listeners (`app.rocksky.artist.getArtistRecentListeners`).
@@ -693,16 +706,18 @@ impl AppView {
         uri: &str,
         limit: u32,
         offset: u32,
-    ) -> Result<serde_json::Value> {
-        self.query(
-            "app.rocksky.artist.getArtistRecentListeners",
-            &[
-                ("uri", uri.to_string()),
-                ("limit", limit.to_string()),
-                ("offset", offset.to_string()),
-            ],
-        )
-        .await
+    ) -> Result<Vec<RecentListenerView>> {
+        let out: ListenersOutput<RecentListenerView> = self
+            .query(
+                "app.rocksky.artist.getArtistRecentListeners",
+                &[
+                    ("uri", uri.to_string()),
+                    ("limit", limit.to_string()),
+                    ("offset", offset.to_string()),
+                ],
+            )
+            .await?;
+        Ok(out.listeners)
     }
 
     /// A song's recent listeners (`app.rocksky.song.getSongRecentListeners`).
@@ -711,16 +726,18 @@ impl AppView {
         uri: &str,
         limit: u32,
         offset: u32,
-    ) -> Result<serde_json::Value> {
-        self.query(
-            "app.rocksky.song.getSongRecentListeners",
-            &[
-                ("uri", uri.to_string()),
-                ("limit", limit.to_string()),
-                ("offset", offset.to_string()),
-            ],
-        )
-        .await
+    ) -> Result<Vec<RecentListenerView>> {
+        let out: ListenersOutput<RecentListenerView> = self
+            .query(
+                "app.rocksky.song.getSongRecentListeners",
+                &[
+                    ("uri", uri.to_string()),
+                    ("limit", limit.to_string()),
+                    ("offset", offset.to_string()),
+                ],
+            )
+            .await?;
+        Ok(out.listeners)
     }
 
     /// A scrobble time-series chart (`app.rocksky.charts.getScrobblesChart`). Scope
@@ -776,19 +793,21 @@ impl AppView {
         size: Option<u32>,
         feed: Option<&str>,
         following: Option<bool>,
-    ) -> Result<serde_json::Value> {
-        self.query(
-            "app.rocksky.feed.getStories",
-            &[
-                ("size", size.map(|s| s.to_string()).unwrap_or_default()),
-                ("feed", feed.unwrap_or_default().to_string()),
-                (
-                    "following",
-                    following.map(|b| b.to_string()).unwrap_or_default(),
-                ),
-            ],
-        )
-        .await
+    ) -> Result<Vec<StoryView>> {
+        let out: StoriesOutput = self
+            .query(
+                "app.rocksky.feed.getStories",
+                &[
+                    ("size", size.map(|s| s.to_string()).unwrap_or_default()),
+                    ("feed", feed.unwrap_or_default().to_string()),
+                    (
+                        "following",
+                        following.map(|b| b.to_string()).unwrap_or_default(),
+                    ),
+                ],
+            )
+            .await?;
+        Ok(out.stories)
     }
 
     /// Track recommendations for `actor` (`app.rocksky.feed.getRecommendations`).
@@ -796,7 +815,7 @@ impl AppView {
         &self,
         actor: &str,
         limit: Option<u32>,
-    ) -> Result<serde_json::Value> {
+    ) -> Result<Recommendations> {
         self.query(
             "app.rocksky.feed.getRecommendations",
             &[
@@ -812,7 +831,7 @@ impl AppView {
         &self,
         actor: &str,
         limit: Option<u32>,
-    ) -> Result<serde_json::Value> {
+    ) -> Result<ArtistRecommendations> {
         self.query(
             "app.rocksky.feed.getArtistRecommendations",
             &[
@@ -828,7 +847,7 @@ impl AppView {
         &self,
         actor: &str,
         limit: Option<u32>,
-    ) -> Result<serde_json::Value> {
+    ) -> Result<AlbumRecommendations> {
         self.query(
             "app.rocksky.feed.getAlbumRecommendations",
             &[
@@ -840,13 +859,13 @@ impl AppView {
     }
 
     /// An actor's aggregate stats (`app.rocksky.stats.getStats`).
-    pub async fn stats(&self, actor: &str) -> Result<serde_json::Value> {
+    pub async fn stats(&self, actor: &str) -> Result<StatsView> {
         self.query("app.rocksky.stats.getStats", &[("did", actor.to_string())])
             .await
     }
 
     /// An actor's year-in-review (`app.rocksky.stats.getWrapped`).
-    pub async fn wrapped(&self, actor: &str, year: Option<u32>) -> Result<serde_json::Value> {
+    pub async fn wrapped(&self, actor: &str, year: Option<u32>) -> Result<WrappedView> {
         self.query(
             "app.rocksky.stats.getWrapped",
             &[
@@ -898,16 +917,18 @@ impl AppView {
     }
 
     /// The playlist catalog (`app.rocksky.playlist.getPlaylists`).
-    pub async fn playlists(&self, limit: u32, offset: u32) -> Result<serde_json::Value> {
-        self.query(
-            "app.rocksky.playlist.getPlaylists",
-            &[("limit", limit.to_string()), ("offset", offset.to_string())],
-        )
-        .await
+    pub async fn playlists(&self, limit: u32, offset: u32) -> Result<Vec<PlaylistView>> {
+        let out: PlaylistsOutput = self
+            .query(
+                "app.rocksky.playlist.getPlaylists",
+                &[("limit", limit.to_string()), ("offset", offset.to_string())],
+            )
+            .await?;
+        Ok(out.playlists)
     }
 
-    /// A single playlist with its items (`app.rocksky.playlist.getPlaylist`).
-    pub async fn playlist(&self, uri: &str) -> Result<serde_json::Value> {
+    /// A single playlist with its tracks (`app.rocksky.playlist.getPlaylist`).
+    pub async fn playlist(&self, uri: &str) -> Result<PlaylistDetail> {
         self.query(
             "app.rocksky.playlist.getPlaylist",
             &[("uri", uri.to_string())],
@@ -916,13 +937,8 @@ impl AppView {
     }
 
     /// Shouts on an album (`app.rocksky.shout.getAlbumShouts`).
-    pub async fn album_shouts(
-        &self,
-        uri: &str,
-        limit: u32,
-        offset: u32,
-    ) -> Result<serde_json::Value> {
-        self.query(
+    pub async fn album_shouts(&self, uri: &str, limit: u32, offset: u32) -> Result<Vec<ShoutView>> {
+        self.shouts(
             "app.rocksky.shout.getAlbumShouts",
             &[
                 ("uri", uri.to_string()),
@@ -939,8 +955,8 @@ impl AppView {
         uri: &str,
         limit: u32,
         offset: u32,
-    ) -> Result<serde_json::Value> {
-        self.query(
+    ) -> Result<Vec<ShoutView>> {
+        self.shouts(
             "app.rocksky.shout.getArtistShouts",
             &[
                 ("uri", uri.to_string()),
@@ -957,8 +973,8 @@ impl AppView {
         actor: &str,
         limit: u32,
         offset: u32,
-    ) -> Result<serde_json::Value> {
-        self.query(
+    ) -> Result<Vec<ShoutView>> {
+        self.shouts(
             "app.rocksky.shout.getProfileShouts",
             &[
                 ("did", actor.to_string()),
@@ -970,8 +986,8 @@ impl AppView {
     }
 
     /// Shouts on a track (`app.rocksky.shout.getTrackShouts`).
-    pub async fn track_shouts(&self, uri: &str) -> Result<serde_json::Value> {
-        self.query(
+    pub async fn track_shouts(&self, uri: &str) -> Result<Vec<ShoutView>> {
+        self.shouts(
             "app.rocksky.shout.getTrackShouts",
             &[("uri", uri.to_string())],
         )
@@ -984,8 +1000,8 @@ impl AppView {
         uri: &str,
         limit: u32,
         offset: u32,
-    ) -> Result<serde_json::Value> {
-        self.query(
+    ) -> Result<Vec<ShoutView>> {
+        self.shouts(
             "app.rocksky.shout.getShoutReplies",
             &[
                 ("uri", uri.to_string()),
@@ -996,8 +1012,13 @@ impl AppView {
         .await
     }
 
+    async fn shouts(&self, nsid: &str, params: &[(&str, String)]) -> Result<Vec<ShoutView>> {
+        let out: ShoutsOutput = self.query(nsid, params).await?;
+        Ok(out.shouts)
+    }
+
     /// An actor's Rockbox EQ / audio settings (`app.rocksky.rockbox.getAudioSettings`).
-    pub async fn audio_settings(&self, actor: &str) -> Result<serde_json::Value> {
+    pub async fn audio_settings(&self, actor: &str) -> Result<AudioSettings> {
         self.query(
             "app.rocksky.rockbox.getAudioSettings",
             &[("did", actor.to_string())],
@@ -1350,40 +1371,828 @@ pub struct UpdateSeenResult {
     pub unread_count: i64,
 }
 
-// ---- output envelopes ----------------------------------------------------
+/// `app.rocksky.album.defs#albumViewDetailed` — an album with its tags and
+/// tracklist.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlbumDetail {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub artist_uri: Option<String>,
+    #[serde(default)]
+    pub year: Option<u32>,
+    #[serde(default)]
+    pub album_art: Option<String>,
+    #[serde(default)]
+    pub release_date: Option<String>,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub play_count: Option<u64>,
+    #[serde(default)]
+    pub unique_listeners: Option<u64>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub tracks: Vec<SongView>,
+}
 
-#[derive(Deserialize)]
-struct ScrobblesOutput {
+/// `app.rocksky.artist.defs#artistViewDetailed`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtistDetail {
     #[serde(default)]
-    scrobbles: Vec<ScrobbleView>,
+    pub id: Option<String>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub picture: Option<String>,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub play_count: Option<u64>,
+    #[serde(default)]
+    pub unique_listeners: Option<u64>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
-#[derive(Deserialize)]
-struct AlbumsOutput {
+/// `app.rocksky.artist.defs#songViewBasic` — a listener's most-played song.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListenerSong {
     #[serde(default)]
-    albums: Vec<AlbumView>,
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub play_count: Option<u64>,
 }
 
-#[derive(Deserialize)]
-struct ArtistsOutput {
+/// `app.rocksky.artist.defs#listenerViewBasic`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListenerView {
     #[serde(default)]
-    artists: Vec<ArtistView>,
+    pub id: Option<String>,
+    #[serde(default)]
+    pub did: Option<String>,
+    #[serde(default)]
+    pub handle: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub most_listened_song: Option<ListenerSong>,
+    #[serde(default)]
+    pub total_plays: Option<u64>,
+    #[serde(default)]
+    pub rank: Option<u32>,
 }
 
-#[derive(Deserialize)]
-struct TracksOutput {
+/// `app.rocksky.artist.defs#recentListenerView` (and the identical
+/// `app.rocksky.song.defs#recentListenerView`).
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentListenerView {
     #[serde(default)]
-    tracks: Vec<SongView>,
+    pub id: Option<String>,
+    #[serde(default)]
+    pub did: Option<String>,
+    #[serde(default)]
+    pub handle: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub avatar: Option<String>,
+    /// When the listener last scrobbled the artist / song.
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub scrobble_uri: Option<String>,
 }
 
-#[derive(Deserialize)]
-struct FollowsOutput {
+/// `app.rocksky.actor.defs#neighbourViewBasic` — an actor with similar taste.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NeighbourView {
     #[serde(default)]
-    follows: Vec<ProfileView>,
+    pub user_id: Option<String>,
+    #[serde(default)]
+    pub did: Option<String>,
+    #[serde(default)]
+    pub handle: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub shared_artists_count: Option<u64>,
+    /// The share of the actor's artists this neighbour also listens to, `0..=1`.
+    /// The lexicon says integer; the server sends a fraction.
+    #[serde(default)]
+    pub similarity_score: Option<f64>,
+    #[serde(default)]
+    pub top_shared_artist_names: Vec<String>,
+    #[serde(default)]
+    pub top_shared_artists_details: Vec<ArtistView>,
 }
 
-#[derive(Deserialize)]
-struct FollowersOutput {
+/// `app.rocksky.actor.defs#artistViewBasic` — an artist both actors listen to,
+/// with each side's rank.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedArtist {
     #[serde(default)]
-    followers: Vec<ProfileView>,
+    pub id: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub picture: Option<String>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub user1_rank: Option<u32>,
+    #[serde(default)]
+    pub user2_rank: Option<u32>,
+    #[serde(default)]
+    pub weight: Option<f64>,
+}
+
+/// `app.rocksky.actor.defs#compatibilityViewBasic`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilityView {
+    #[serde(default)]
+    pub compatibility_level: Option<u32>,
+    /// Rounded to one decimal by the server.
+    #[serde(default)]
+    pub compatibility_percentage: Option<f64>,
+    #[serde(default)]
+    pub shared_artists: Option<u64>,
+    #[serde(default)]
+    pub top_shared_artist_names: Vec<String>,
+    #[serde(default)]
+    pub top_shared_detailed_artists: Vec<SharedArtist>,
+    #[serde(default)]
+    pub user1_artist_count: Option<u64>,
+    #[serde(default)]
+    pub user2_artist_count: Option<u64>,
+}
+
+/// `app.rocksky.stats.defs#statsView` — an actor's totals.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsView {
+    #[serde(default)]
+    pub scrobbles: u64,
+    #[serde(default)]
+    pub artists: u64,
+    #[serde(default)]
+    pub loved_tracks: u64,
+    #[serde(default)]
+    pub albums: u64,
+    #[serde(default)]
+    pub tracks: u64,
+}
+
+/// `app.rocksky.stats.defs#wrappedView` — an actor's year in review.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WrappedView {
+    #[serde(default)]
+    pub year: Option<u32>,
+    #[serde(default)]
+    pub total_scrobbles: Option<u64>,
+    #[serde(default)]
+    pub total_listening_time_minutes: Option<u64>,
+    #[serde(default)]
+    pub top_artists: Vec<WrappedArtist>,
+    #[serde(default)]
+    pub top_tracks: Vec<WrappedTrack>,
+    #[serde(default)]
+    pub top_albums: Vec<WrappedAlbum>,
+    #[serde(default)]
+    pub top_genres: Vec<WrappedGenreCount>,
+    #[serde(default)]
+    pub scrobbles_per_month: Vec<WrappedMonthCount>,
+    #[serde(default)]
+    pub most_active_day: Option<WrappedDayCount>,
+    /// Hour of day (0–23, UTC) with the most scrobbles.
+    #[serde(default)]
+    pub most_active_hour: Option<u32>,
+    #[serde(default)]
+    pub new_artists_count: Option<u64>,
+    /// Longest run of consecutive days with a scrobble.
+    #[serde(default)]
+    pub longest_streak: Option<u32>,
+    #[serde(default)]
+    pub first_scrobble: Option<WrappedMilestone>,
+    #[serde(default)]
+    pub last_scrobble: Option<WrappedMilestone>,
+}
+
+/// `app.rocksky.stats.defs#wrappedArtist`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WrappedArtist {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub picture: Option<String>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub play_count: Option<u64>,
+}
+
+/// `app.rocksky.stats.defs#wrappedTrack`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WrappedTrack {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub album_art: Option<String>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub artist_uri: Option<String>,
+    #[serde(default)]
+    pub album_uri: Option<String>,
+    #[serde(default)]
+    pub play_count: Option<u64>,
+}
+
+/// `app.rocksky.stats.defs#wrappedAlbum`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WrappedAlbum {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub album_art: Option<String>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub play_count: Option<u64>,
+}
+
+/// `app.rocksky.stats.defs#wrappedGenreCount`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WrappedGenreCount {
+    #[serde(default)]
+    pub genre: String,
+    #[serde(default)]
+    pub count: u64,
+}
+
+/// `app.rocksky.stats.defs#wrappedMonthCount`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WrappedMonthCount {
+    /// 1–12.
+    #[serde(default)]
+    pub month: u32,
+    #[serde(default)]
+    pub count: u64,
+}
+
+/// `app.rocksky.stats.defs#wrappedDayCount`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WrappedDayCount {
+    /// `YYYY-MM-DD`.
+    #[serde(default)]
+    pub date: String,
+    #[serde(default)]
+    pub count: u64,
+}
+
+/// `app.rocksky.stats.defs#wrappedMilestone` — the first / last scrobble of the year.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WrappedMilestone {
+    #[serde(default)]
+    pub track_title: Option<String>,
+    #[serde(default)]
+    pub artist_name: Option<String>,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub track_uri: Option<String>,
+}
+
+/// `app.rocksky.feed.defs#storyView` — an actor's latest scrobble in the stories row.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryView {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub did: Option<String>,
+    #[serde(default)]
+    pub handle: Option<String>,
+    #[serde(default)]
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub album: Option<String>,
+    #[serde(default)]
+    pub album_artist: Option<String>,
+    #[serde(default)]
+    pub album_art: Option<String>,
+    #[serde(default)]
+    pub track_id: Option<String>,
+    #[serde(default)]
+    pub track_uri: Option<String>,
+    #[serde(default)]
+    pub artist_uri: Option<String>,
+    #[serde(default)]
+    pub album_uri: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// `app.rocksky.feed.defs#recommendationView` — a recommended track.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendationView {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub album: Option<String>,
+    #[serde(default)]
+    pub album_art: Option<String>,
+    #[serde(default)]
+    pub track_uri: Option<String>,
+    #[serde(default)]
+    pub artist_uri: Option<String>,
+    #[serde(default)]
+    pub album_uri: Option<String>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    #[serde(default)]
+    pub recommendation_score: Option<f64>,
+    /// Which signal produced the pick (e.g. neighbours, genre).
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub likes_count: Option<u64>,
+}
+
+/// `app.rocksky.feed.defs#recommendedArtistView`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendedArtistView {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub picture: Option<String>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    #[serde(default)]
+    pub recommendation_score: Option<f64>,
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+/// `app.rocksky.feed.defs#recommendedAlbumView`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendedAlbumView {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub artist_uri: Option<String>,
+    #[serde(default)]
+    pub year: Option<u32>,
+    #[serde(default)]
+    pub album_art: Option<String>,
+    #[serde(default)]
+    pub recommendation_score: Option<f64>,
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+/// `app.rocksky.feed.defs#recommendationsView`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recommendations {
+    #[serde(default)]
+    pub recommendations: Vec<RecommendationView>,
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+/// `app.rocksky.feed.defs#recommendedArtistsView`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtistRecommendations {
+    #[serde(default)]
+    pub artists: Vec<RecommendedArtistView>,
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+/// `app.rocksky.feed.defs#recommendedAlbumsView`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlbumRecommendations {
+    #[serde(default)]
+    pub albums: Vec<RecommendedAlbumView>,
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+/// `app.rocksky.playlist.defs#playlistViewBasic`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistView {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub cover_image_url: Option<String>,
+    #[serde(default)]
+    pub curator_did: Option<String>,
+    #[serde(default)]
+    pub curator_handle: Option<String>,
+    #[serde(default)]
+    pub curator_name: Option<String>,
+    #[serde(default)]
+    pub curator_avatar_url: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub track_count: Option<u32>,
+}
+
+/// `app.rocksky.playlist.defs#playlistViewDetailed` — a playlist with its tracks.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistDetail {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub cover_image_url: Option<String>,
+    #[serde(default)]
+    pub curator_did: Option<String>,
+    #[serde(default)]
+    pub curator_handle: Option<String>,
+    #[serde(default)]
+    pub curator_name: Option<String>,
+    #[serde(default)]
+    pub curator_avatar_url: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub tracks: Vec<SongView>,
+}
+
+/// A shout (comment) as returned by the `app.rocksky.shout.get*Shouts` and
+/// `getShoutReplies` queries.
+///
+/// Modeled on what the AppView actually sends rather than
+/// `app.rocksky.shout.defs#shoutView`: the body is `content` (the def's
+/// `message` is accepted as an alias) and album / artist / profile / track
+/// shouts carry `likes` and, for a signed-in viewer, `liked`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShoutView {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default, alias = "message")]
+    pub content: Option<String>,
+    /// The at-uri of the shout this one replies to.
+    #[serde(default)]
+    pub parent: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub likes: Option<u64>,
+    #[serde(default)]
+    pub liked: Option<bool>,
+    #[serde(default)]
+    pub author: Option<ShoutAuthor>,
+}
+
+/// `app.rocksky.shout.defs#author`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShoutAuthor {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub did: Option<String>,
+    #[serde(default)]
+    pub handle: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub avatar: Option<String>,
+}
+
+/// `app.rocksky.rockbox.defs#settingsView` — an actor's Rockbox audio settings.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioSettings {
+    #[serde(default)]
+    pub crossfade: Option<CrossfadeSettings>,
+    #[serde(default)]
+    pub equalizer: Option<EqualizerSettings>,
+    #[serde(default)]
+    pub replay_gain: Option<ReplayGainSettings>,
+    #[serde(default)]
+    pub tone: Option<ToneSettings>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+/// `app.rocksky.rockbox.defs#crossfadeSettings`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrossfadeSettings {
+    #[serde(default)]
+    pub mode: Option<String>,
+    #[serde(default)]
+    pub fade_in_delay: Option<i64>,
+    #[serde(default)]
+    pub fade_in_duration: Option<i64>,
+    #[serde(default)]
+    pub fade_out_delay: Option<i64>,
+    #[serde(default)]
+    pub fade_out_duration: Option<i64>,
+    #[serde(default)]
+    pub fade_out_mix_mode: Option<String>,
+}
+
+/// `app.rocksky.rockbox.defs#equalizerSettings`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EqualizerSettings {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub precut: Option<i64>,
+    #[serde(default)]
+    pub bands: Vec<EqualizerBand>,
+}
+
+/// `app.rocksky.rockbox.defs#equalizerBand`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EqualizerBand {
+    #[serde(default)]
+    pub frequency: i64,
+    #[serde(default)]
+    pub gain: i64,
+    #[serde(default)]
+    pub q: i64,
+}
+
+/// `app.rocksky.rockbox.defs#replayGainSettings`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayGainSettings {
+    #[serde(default)]
+    pub mode: Option<String>,
+    #[serde(default)]
+    pub preamp: Option<i64>,
+    #[serde(default)]
+    pub prevent_clipping: Option<bool>,
+}
+
+/// `app.rocksky.rockbox.defs#toneSettings`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToneSettings {
+    #[serde(default)]
+    pub bass: Option<i64>,
+    #[serde(default)]
+    pub treble: Option<i64>,
+    #[serde(default)]
+    pub balance: Option<i64>,
+    #[serde(default)]
+    pub channels: Option<String>,
+}
+
+// ---- output envelopes ----------------------------------------------------
+
+#[derive(Deserialize)]
+struct ScrobblesOutput {
+    #[serde(default)]
+    scrobbles: Vec<ScrobbleView>,
+}
+
+#[derive(Deserialize)]
+struct AlbumsOutput {
+    #[serde(default)]
+    albums: Vec<AlbumView>,
+}
+
+#[derive(Deserialize)]
+struct ArtistsOutput {
+    #[serde(default)]
+    artists: Vec<ArtistView>,
+}
+
+#[derive(Deserialize)]
+struct TracksOutput {
+    #[serde(default)]
+    tracks: Vec<SongView>,
+}
+
+#[derive(Deserialize)]
+struct FollowsOutput {
+    #[serde(default)]
+    follows: Vec<ProfileView>,
+}
+
+#[derive(Deserialize)]
+struct FollowersOutput {
+    #[serde(default)]
+    followers: Vec<ProfileView>,
+}
+
+#[derive(Deserialize)]
+struct PlaylistsOutput {
+    #[serde(default)]
+    playlists: Vec<PlaylistView>,
+}
+
+#[derive(Deserialize)]
+struct NeighboursOutput {
+    #[serde(default)]
+    neighbours: Vec<NeighbourView>,
+}
+
+#[derive(Deserialize)]
+struct CompatibilityOutput {
+    #[serde(default)]
+    compatibility: Option<CompatibilityView>,
+}
+
+#[derive(Deserialize)]
+struct ListenersOutput<T> {
+    #[serde(default = "Vec::new")]
+    listeners: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct StoriesOutput {
+    #[serde(default)]
+    stories: Vec<StoryView>,
+}
+
+#[derive(Deserialize)]
+struct ShoutsOutput {
+    #[serde(default)]
+    shouts: Vec<ShoutView>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn neighbours_and_compatibility_accept_fractional_scores() {
+        let out: NeighboursOutput = serde_json::from_value(json!({
+            "neighbours": [{
+                "userId": "u1",
+                "did": "did:plc:bob",
+                "handle": "bob.test",
+                "sharedArtistsCount": 12,
+                "similarityScore": 0.42,
+                "topSharedArtistNames": ["Kate Bush"],
+                "topSharedArtistsDetails": [{ "id": "a1", "name": "Kate Bush" }]
+            }]
+        }))
+        .unwrap();
+        let n = &out.neighbours[0];
+        assert_eq!(n.similarity_score, Some(0.42));
+        assert_eq!(
+            n.top_shared_artists_details[0].name.as_deref(),
+            Some("Kate Bush")
+        );
+
+        let out: CompatibilityOutput = serde_json::from_value(json!({
+            "compatibility": {
+                "compatibilityLevel": 4,
+                "compatibilityPercentage": 37.5,
+                "topSharedDetailedArtists": [{ "name": "Björk", "user1Rank": 1, "user2Rank": 3, "weight": 0.25 }]
+            }
+        }))
+        .unwrap();
+        let c = out.compatibility.unwrap();
+        assert_eq!(c.compatibility_percentage, Some(37.5));
+        assert_eq!(c.top_shared_detailed_artists[0].weight, Some(0.25));
+    }
+
+    #[test]
+    fn shouts_decode_the_served_shape() {
+        let out: ShoutsOutput = serde_json::from_value(json!({
+            "shouts": [
+                {
+                    "id": "s1",
+                    "content": "great record",
+                    "createdAt": "2026-01-01T00:00:00.000Z",
+                    "parent": null,
+                    "uri": "at://did:plc:bob/app.rocksky.shout/1",
+                    "likes": 3,
+                    "liked": true,
+                    "author": { "did": "did:plc:bob", "handle": "bob.test", "displayName": "Bob" }
+                },
+                { "id": "s2", "message": "per the lexicon" }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(out.shouts[0].content.as_deref(), Some("great record"));
+        assert_eq!(out.shouts[0].likes, Some(3));
+        assert_eq!(
+            out.shouts[0]
+                .author
+                .as_ref()
+                .unwrap()
+                .display_name
+                .as_deref(),
+            Some("Bob")
+        );
+        assert_eq!(out.shouts[1].content.as_deref(), Some("per the lexicon"));
+    }
+
+    #[test]
+    fn wrapped_decodes_nested_views() {
+        let w: WrappedView = serde_json::from_value(json!({
+            "year": 2025,
+            "totalScrobbles": 4200,
+            "topTracks": [{ "title": "Hounds of Love", "artist": "Kate Bush", "playCount": 88 }],
+            "scrobblesPerMonth": [{ "month": 1, "count": 300 }],
+            "mostActiveDay": { "date": "2025-03-14", "count": 61 },
+            "firstScrobble": { "trackTitle": "Cloudbusting", "artistName": "Kate Bush" }
+        }))
+        .unwrap();
+        assert_eq!(w.year, Some(2025));
+        assert_eq!(w.top_tracks[0].play_count, Some(88));
+        assert_eq!(w.most_active_day.unwrap().count, 61);
+        assert_eq!(
+            w.first_scrobble.unwrap().track_title.as_deref(),
+            Some("Cloudbusting")
+        );
+        assert!(w.top_artists.is_empty());
+    }
 }
@@ -17,7 +17,14 @@
 //!   did + album_hash                 -> existing app.rocksky.album  at-uri
 //!   did + artist_hash                -> existing app.rocksky.artist at-uri
 //!   did + song_hash + unix_seconds   -> existing app.rocksky.scrobble at-uri
+//!   did + playlist_uri + song_uri    -> existing app.rocksky.playlistItem at-uri
 //! ```
+//!
+//! The same database also holds the offline scrobble queue: plays that couldn't
+//! reach the PDS, keyed by `(did, unix_seconds, seq)` so each account's plays
+//! replay in the order they happened (see [`RockskyAgent::scrobble_or_queue`]).
+//!
+//! [`RockskyAgent::scrobble_or_queue`]: crate::RockskyAgent::scrobble_or_queue
 
 use sha2::{Digest, Sha256};
 
@@ -49,19 +56,58 @@ pub fn artist_hash(album_artist: &str) -> String {
     sha256_lower(album_artist)
 }
 
-/// The Rocksky collection NSIDs this index tracks. Only referenced by the
-/// feature-gated index module.
-#[cfg_attr(not(feature = "dedup"), allow(dead_code))]
+/// The Rocksky collection NSIDs this index tracks.
 pub(crate) const C_ARTIST: &str = "app.rocksky.artist";
-#[cfg_attr(not(feature = "dedup"), allow(dead_code))]
 pub(crate) const C_ALBUM: &str = "app.rocksky.album";
-#[cfg_attr(not(feature = "dedup"), allow(dead_code))]
 pub(crate) const C_SONG: &str = "app.rocksky.song";
-#[cfg_attr(not(feature = "dedup"), allow(dead_code))]
 pub(crate) const C_SCROBBLE: &str = "app.rocksky.scrobble";
+pub(crate) const C_PLAYLIST: &str = "app.rocksky.playlist";
+pub(crate) const C_PLAYLIST_ITEM: &str = "app.rocksky.playlistItem";
+
+/// The dedup identity of a record the SDK creates — what the index keys it
+/// on. Used by batched writes to look records up and index them in bulk.
+#[derive(Clone, Debug)]
+pub(crate) enum Identity {
+    /// An artist, by name (the album artist).
+    Artist(String),
+    /// An album, by title and album artist.
+    Album(String, String),
+    /// A song, by title, artist and album.
+    Song(String, String, String),
+    /// A play of a song (title, artist, album) at a Unix second.
+    Scrobble(String, String, String, i64),
+}
+
+impl Identity {
+    /// A key unique per identity, case-insensitive like the hashes, to dedup
+    /// records within a batch.
+    pub(crate) fn key(&self) -> String {
+        match self {
+            Identity::Artist(name) => format!("artist:{}", artist_hash(name)),
+            Identity::Album(title, artist) => format!("album:{}", album_hash(title, artist)),
+            Identity::Song(title, artist, album) => {
+                format!("song:{}", song_hash(title, artist, album))
+            }
+            Identity::Scrobble(title, artist, album, secs) => {
+                format!("scrobble:{}:{secs}", song_hash(title, artist, album))
+            }
+        }
+    }
+
+    /// The collection the record lives in.
+    #[cfg_attr(not(feature = "dedup"), allow(dead_code))]
+    pub(crate) fn collection(&self) -> &'static str {
+        match self {
+            Identity::Artist(_) => C_ARTIST,
+            Identity::Album(..) => C_ALBUM,
+            Identity::Song(..) => C_SONG,
+            Identity::Scrobble(..) => C_SCROBBLE,
+        }
+    }
+}
 
 #[cfg(feature = "dedup")]
-pub use index::{IndexStats, RepoIndex};
+pub use index::{IndexStats, PlaylistItemEntry, QueuedScrobble, RepoIndex};
 
 #[cfg(feature = "dedup")]
 mod index {
@@ -71,18 +117,28 @@ mod index {
 
     use ipld_core::cid::Cid;
     use ipld_core::ipld::Ipld;
-    use redb::{Database, Durability, TableDefinition};
+    use redb::{Database, Durability, ReadableTable, ReadableTableMetadata, TableDefinition};
     use serde::Deserialize;
 
-    use super::{album_hash, artist_hash, song_hash, C_ALBUM, C_ARTIST, C_SCROBBLE, C_SONG};
+    use super::{
+        album_hash, artist_hash, song_hash, Identity, C_ALBUM, C_ARTIST, C_PLAYLIST_ITEM,
+        C_SCROBBLE, C_SONG,
+    };
+    use crate::agent::ScrobbleDraft;
     use crate::error::{Result, SdkError};
 
     /// The single key→value table backing the whole index (keys are the
     /// NUL-separated byte strings below; values are at-uris or small metadata).
     const TABLE: TableDefinition<&[u8], &[u8]> = TableDefinition::new("index");
 
+    /// The offline scrobble queue: `(did, unix_secs, seq)` -> JSON
+    /// [`ScrobbleDraft`]. redb orders tuple keys field by field, so an account's
+    /// plays are one range, in play order; `seq` keeps two plays queued in the
+    /// same second distinct.
+    const QUEUE: TableDefinition<(&str, i64, u64), &[u8]> = TableDefinition::new("scrobble_queue");
+
     fn db_err<E: core::fmt::Display>(e: E) -> SdkError {
-        SdkError::Other(format!("dedup index: {e}"))
+        SdkError::Store(e.to_string())
     }
 
     /// What a [`RepoIndex::index_car`] pass added.
@@ -92,12 +148,13 @@ mod index {
         pub albums: usize,
         pub songs: usize,
         pub scrobbles: usize,
+        pub playlist_items: usize,
     }
 
     impl IndexStats {
         /// Total records indexed in this pass.
         pub fn total(&self) -> usize {
-            self.artists + self.albums + self.songs + self.scrobbles
+            self.artists + self.albums + self.songs + self.scrobbles + self.playlist_items
         }
     }
 
@@ -111,9 +168,11 @@ mod index {
     // Key layout (NUL-separated so segments can't collide):
     //   "<did>\0<collection>\0<hash>"                -> at-uri   (song/album/artist)
     //   "<did>\0scrobble\0<song_hash>\0<unix_secs>"  -> at-uri   (scrobble)
+    //   "<did>\0playlistItem\0<playlist>\0<song>"     -> at-uri\0order (playlist item)
     //   "\0rk\0<did>\0<collection>\0<rkey>"          -> primary key above (reverse, for deletes)
     //   "\0meta\0rev\0<did>"                          -> commit rev  (CAR incremental cursor)
     //   "\0meta\0cursor\0<did>"                       -> time_us     (jetstream cursor)
+    //   "\0meta\0queue_depth\0<did>"                  -> plays queued for the did
     const SEP: char = '\u{0}';
 
     fn ident_key(did: &str, collection: &str, hash: &str) -> Vec<u8> {
@@ -124,6 +183,42 @@ mod index {
         format!("{did}{SEP}scrobble{SEP}{song_hash}{SEP}{unix_secs}").into_bytes()
     }
 
+    fn identity_key(did: &str, identity: &Identity) -> Vec<u8> {
+        match identity {
+            Identity::Artist(name) => ident_key(did, C_ARTIST, &artist_hash(name)),
+            Identity::Album(title, artist) => ident_key(did, C_ALBUM, &album_hash(title, artist)),
+            Identity::Song(title, artist, album) => {
+                ident_key(did, C_SONG, &song_hash(title, artist, album))
+            }
+            Identity::Scrobble(title, artist, album, secs) => {
+                scrobble_key(did, &song_hash(title, artist, album), *secs)
+            }
+        }
+    }
+
+    fn playlist_item_key(did: &str, playlist_uri: &str, song_uri: &str) -> Vec<u8> {
+        format!("{did}{SEP}playlistItem{SEP}{playlist_uri}{SEP}{song_uri}").into_bytes()
+    }
+
+    /// The prefix of the keys of every item of the playlist `playlist_uri`.
+    fn playlist_items_prefix(did: &str, playlist_uri: &str) -> Vec<u8> {
+        format!("{did}{SEP}playlistItem{SEP}{playlist_uri}{SEP}").into_bytes()
+    }
+
+    /// A playlist item value: its at-uri and its `order`.
+    fn playlist_item_value(uri: &str, order: i64) -> Vec<u8> {
+        format!("{uri}{SEP}{order}").into_bytes()
+    }
+
+    /// The at-uri and `order` of a playlist item value. Items indexed before
+    /// their order was kept have the at-uri alone.
+    fn split_playlist_item_value(value: &str) -> (&str, Option<i64>) {
+        match value.split_once(SEP) {
+            Some((uri, order)) => (uri, order.parse().ok()),
+            None => (value, None),
+        }
+    }
+
     /// Reverse index: `rkey` -> the primary key above, so a delete event (which
     /// carries only the rkey, not the record) can find and drop the entry.
     fn rk_key(did: &str, collection: &str, rkey: &str) -> Vec<u8> {
@@ -138,6 +233,100 @@ mod index {
         format!("{SEP}meta{SEP}cursor{SEP}{did}").into_bytes()
     }
 
+    fn queue_seq_key() -> Vec<u8> {
+        format!("{SEP}meta{SEP}queue_seq").into_bytes()
+    }
+
+    fn queue_depth_key(did: &str) -> Vec<u8> {
+        format!("{SEP}meta{SEP}queue_depth{SEP}{did}").into_bytes()
+    }
+
+    /// Store `depth` as the number of plays queued for `did`.
+    fn put_queue_depth(t: &mut redb::Table<&[u8], &[u8]>, did: &str, depth: u64) -> Result<()> {
+        let key = queue_depth_key(did);
+        if depth == 0 {
+            t.remove(key.as_slice()).map_err(db_err)?;
+        } else {
+            t.insert(key.as_slice(), depth.to_string().as_bytes())
+                .map_err(db_err)?;
+        }
+        Ok(())
+    }
+
+    /// Number of plays queued for `did`, from an open table.
+    fn read_queue_depth(
+        t: &impl ReadableTable<&'static [u8], &'static [u8]>,
+        did: &str,
+    ) -> Result<u64> {
+        Ok(t.get(queue_depth_key(did).as_slice())
+            .map_err(db_err)?
+            .and_then(|v| std::str::from_utf8(v.value()).ok()?.parse::<u64>().ok())
+            .unwrap_or(0))
+    }
+
+    /// A play waiting in the offline queue. Its draft's `timestamp` is always
+    /// set — it's pinned at enqueue time so a replay dedups against the same
+    /// `(song, second)` as the original attempt.
+    #[derive(Clone, Debug)]
+    pub struct QueuedScrobble {
+        key: (i64, u64),
+        pub did: String,
+        pub draft: ScrobbleDraft,
+    }
+
+    /// An item of a playlist, as indexed.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct PlaylistItemEntry {
+        /// The at-uri of the `app.rocksky.playlistItem` record.
+        pub uri: String,
+        /// The at-uri of the `app.rocksky.song` it holds.
+        pub song_uri: String,
+        /// Its position; `None` for items indexed before it was kept.
+        pub order: Option<i64>,
+    }
+
+    /// Index a playlist item and its reverse rkey mapping into an open table,
+    /// dropping the entry it had before an update moved it.
+    fn insert_playlist_item(
+        t: &mut redb::Table<&[u8], &[u8]>,
+        did: &str,
+        playlist_uri: &str,
+        song_uri: &str,
+        uri: &str,
+        order: i64,
+    ) -> Result<()> {
+        let primary = playlist_item_key(did, playlist_uri, song_uri);
+        if let Some(rkey) = uri.rsplit('/').next() {
+            let rk = rk_key(did, C_PLAYLIST_ITEM, rkey);
+            let previous = t
+                .insert(rk.as_slice(), primary.as_slice())
+                .map_err(db_err)?
+                .map(|v| v.value().to_vec());
+            if let Some(previous) = previous.filter(|p| *p != primary) {
+                t.remove(previous.as_slice()).map_err(db_err)?;
+            }
+        }
+        t.insert(
+            primary.as_slice(),
+            playlist_item_value(uri, order).as_slice(),
+        )
+        .map_err(db_err)?;
+        Ok(())
+    }
+
+    /// The playlist, song and `order` of a playlistItem record, from field
+    /// accessors for `subject.uri`, `track.uri` and `order`.
+    fn playlist_item_fields<'a>(
+        field: impl Fn(&str) -> Option<&'a str>,
+        order: Option<i64>,
+    ) -> Option<(&'a str, &'a str, i64)> {
+        Some((
+            field("subject")?,
+            field("track")?,
+            order.unwrap_or_default(),
+        ))
+    }
+
     /// The primary index key for a record, from field accessors. `None` when the
     /// record lacks the fields needed to identify it, or isn't a tracked type.
     fn primary_key_for<'a>(
@@ -170,14 +359,34 @@ mod index {
         }
     }
 
+    /// Insert `primary -> uri` and the reverse rkey mapping (rkey taken from
+    /// the uri's last segment) into an open table.
+    fn insert_primary(
+        t: &mut redb::Table<&[u8], &[u8]>,
+        did: &str,
+        collection: &str,
+        primary: &[u8],
+        uri: &str,
+    ) -> Result<()> {
+        if let Some(rkey) = uri.rsplit('/').next() {
+            t.insert(rk_key(did, collection, rkey).as_slice(), primary)
+                .map_err(db_err)?;
+        }
+        t.insert(primary, uri.as_bytes()).map_err(db_err)?;
+        Ok(())
+    }
+
     impl RepoIndex {
         /// Open (creating if needed) the index at `path`.
         pub fn open(path: impl AsRef<Path>) -> Result<Self> {
             let db = Database::create(path).map_err(db_err)?;
-            // Create the table up front so read transactions never race a
+            // Create the tables up front so read transactions never race a
             // not-yet-created table on a fresh index.
             let w = db.begin_write().map_err(db_err)?;
-            w.open_table(TABLE).map_err(db_err)?;
+            {
+                w.open_table(TABLE).map_err(db_err)?;
+                w.open_table(QUEUE).map_err(db_err)?;
+            }
             w.commit().map_err(db_err)?;
             Ok(Self { db })
         }
@@ -231,6 +440,55 @@ mod index {
             self.get_str(&key)
         }
 
+        /// The at-uri of the caller's existing item for `song_uri` in the
+        /// playlist `playlist_uri`, if any — so re-adding a song is a no-op.
+        pub fn playlist_item_uri(
+            &self,
+            did: &str,
+            playlist_uri: &str,
+            song_uri: &str,
+        ) -> Result<Option<String>> {
+            Ok(self
+                .get_str(&playlist_item_key(did, playlist_uri, song_uri))?
+                .map(|v| split_playlist_item_value(&v).0.to_string()))
+        }
+
+        /// The caller's items of the playlist `playlist_uri`, in no particular
+        /// order.
+        pub fn playlist_items(
+            &self,
+            did: &str,
+            playlist_uri: &str,
+        ) -> Result<Vec<PlaylistItemEntry>> {
+            let prefix = playlist_items_prefix(did, playlist_uri);
+            let r = self.db.begin_read().map_err(db_err)?;
+            let t = r.open_table(TABLE).map_err(db_err)?;
+            let mut items = Vec::new();
+            for entry in t.range(prefix.as_slice()..).map_err(db_err)? {
+                let (k, v) = entry.map_err(db_err)?;
+                let Some(song_uri) = k.value().strip_prefix(prefix.as_slice()) else {
+                    break;
+                };
+                let value = String::from_utf8_lossy(v.value());
+                let (uri, order) = split_playlist_item_value(&value);
+                items.push(PlaylistItemEntry {
+                    uri: uri.to_string(),
+                    song_uri: String::from_utf8_lossy(song_uri).into_owned(),
+                    order,
+                });
+            }
+            Ok(items)
+        }
+
+        /// The at-uri of the caller's existing record with this identity, if any.
+        pub(crate) fn identity_uri(
+            &self,
+            did: &str,
+            identity: &Identity,
+        ) -> Result<Option<String>> {
+            self.get_str(&identity_key(did, identity))
+        }
+
         fn get_ident(&self, did: &str, collection: &str, hash: &str) -> Result<Option<String>> {
             self.get_str(&ident_key(did, collection, hash))
         }
@@ -297,6 +555,52 @@ mod index {
             )
         }
 
+        pub(crate) fn record_playlist_item(
+            &self,
+            did: &str,
+            playlist_uri: &str,
+            song_uri: &str,
+            uri: &str,
+            order: i64,
+        ) -> Result<()> {
+            let w = self.db.begin_write().map_err(db_err)?;
+            {
+                let mut t = w.open_table(TABLE).map_err(db_err)?;
+                insert_playlist_item(&mut t, did, playlist_uri, song_uri, uri, order)?;
+            }
+            w.commit().map_err(db_err)
+        }
+
+        /// Index the records one `applyWrites` created, in a single transaction:
+        /// like the commit itself, either all of them are indexed or none.
+        pub(crate) fn record_all(&self, did: &str, records: &[(&Identity, &str)]) -> Result<()> {
+            let w = self.db.begin_write().map_err(db_err)?;
+            {
+                let mut t = w.open_table(TABLE).map_err(db_err)?;
+                for (identity, uri) in records {
+                    let primary = identity_key(did, identity);
+                    insert_primary(&mut t, did, identity.collection(), &primary, uri)?;
+                }
+            }
+            w.commit().map_err(db_err)
+        }
+
+        /// Drop the entry of a record deleted from the repo, found through its
+        /// reverse rkey mapping. A no-op for records never indexed.
+        pub(crate) fn forget(&self, did: &str, collection: &str, rkey: &str) -> Result<()> {
+            let rk = rk_key(did, collection, rkey);
+            if let Some(primary) = self.get_bytes(&rk)? {
+                let w = self.db.begin_write().map_err(db_err)?;
+                {
+                    let mut t = w.open_table(TABLE).map_err(db_err)?;
+                    t.remove(primary.as_slice()).map_err(db_err)?;
+                    t.remove(rk.as_slice()).map_err(db_err)?;
+                }
+                w.commit().map_err(db_err)?;
+            }
+            Ok(())
+        }
+
         /// Write a primary key -> uri mapping plus its reverse rkey mapping (rkey
         /// taken from the uri's last segment), so a later delete can find it.
         fn put_primary(
@@ -309,12 +613,7 @@ mod index {
             let w = self.db.begin_write().map_err(db_err)?;
             {
                 let mut t = w.open_table(TABLE).map_err(db_err)?;
-                if let Some(rkey) = uri.rsplit('/').next() {
-                    t.insert(rk_key(did, collection, rkey).as_slice(), primary.as_slice())
-                        .map_err(db_err)?;
-                }
-                t.insert(primary.as_slice(), uri.as_bytes())
-                    .map_err(db_err)?;
+                insert_primary(&mut t, did, collection, &primary, uri)?;
             }
             w.commit().map_err(db_err)
         }
@@ -345,6 +644,98 @@ mod index {
             w.commit().map_err(db_err)
         }
 
+        // ---- offline scrobble queue ----
+
+        /// Persist a play that couldn't be written, for a later
+        /// [`RockskyAgent::flush_queue`](crate::RockskyAgent::flush_queue). A
+        /// draft without a `timestamp` is pinned to now. Durable, unlike the
+        /// index entries — a queued play exists nowhere else.
+        pub fn enqueue_scrobble(&self, did: &str, draft: &ScrobbleDraft) -> Result<QueuedScrobble> {
+            let mut draft = draft.clone();
+            let secs = *draft
+                .timestamp
+                .get_or_insert_with(|| chrono::Utc::now().timestamp());
+            let value = serde_json::to_vec(&draft)?;
+            let w = self.db.begin_write().map_err(db_err)?;
+            let seq = {
+                let mut t = w.open_table(TABLE).map_err(db_err)?;
+                let seq = t
+                    .get(queue_seq_key().as_slice())
+                    .map_err(db_err)?
+                    .and_then(|v| std::str::from_utf8(v.value()).ok()?.parse::<u64>().ok())
+                    .map_or(0, |s| s + 1);
+                t.insert(queue_seq_key().as_slice(), seq.to_string().as_bytes())
+                    .map_err(db_err)?;
+                let depth = read_queue_depth(&t, did)?;
+                put_queue_depth(&mut t, did, depth + 1)?;
+                let mut q = w.open_table(QUEUE).map_err(db_err)?;
+                q.insert((did, secs, seq), value.as_slice())
+                    .map_err(db_err)?;
+                seq
+            };
+            w.commit().map_err(db_err)?;
+            Ok(QueuedScrobble {
+                key: (secs, seq),
+                did: did.to_string(),
+                draft,
+            })
+        }
+
+        /// The plays queued for `did`, oldest first.
+        pub fn queued_scrobbles(&self, did: &str) -> Result<Vec<QueuedScrobble>> {
+            let r = self.db.begin_read().map_err(db_err)?;
+            let q = r.open_table(QUEUE).map_err(db_err)?;
+            let mut out = Vec::new();
+            let range = q
+                .range((did, i64::MIN, 0)..=(did, i64::MAX, u64::MAX))
+                .map_err(db_err)?;
+            for entry in range {
+                let (k, v) = entry.map_err(db_err)?;
+                let (_, secs, seq) = k.value();
+                out.push(QueuedScrobble {
+                    key: (secs, seq),
+                    did: did.to_string(),
+                    draft: serde_json::from_slice(v.value())?,
+                });
+            }
+            Ok(out)
+        }
+
+        /// How many plays are queued for `did`, from a counter kept alongside
+        /// the queue.
+        pub fn queue_depth(&self, did: &str) -> Result<usize> {
+            let r = self.db.begin_read().map_err(db_err)?;
+            let t = r.open_table(TABLE).map_err(db_err)?;
+            Ok(read_queue_depth(&t, did)? as usize)
+        }
+
+        /// How many plays are queued across every account.
+        pub fn queue_len(&self) -> Result<u64> {
+            let r = self.db.begin_read().map_err(db_err)?;
+            let q = r.open_table(QUEUE).map_err(db_err)?;
+            q.len().map_err(db_err)
+        }
+
+        /// Drop a play from the queue once it's been written (or found to be a
+        /// duplicate).
+        pub fn dequeue_scrobble(&self, entry: &QueuedScrobble) -> Result<()> {
+            let w = self.db.begin_write().map_err(db_err)?;
+            {
+                let mut q = w.open_table(QUEUE).map_err(db_err)?;
+                // Dropping an entry twice must not count it twice.
+                let (secs, seq) = entry.key;
+                if q.remove((entry.did.as_str(), secs, seq))
+                    .map_err(db_err)?
+                    .is_some()
+                {
+                    let mut t = w.open_table(TABLE).map_err(db_err)?;
+                    let depth = read_queue_depth(&t, &entry.did)?;
+                    put_queue_depth(&mut t, &entry.did, depth.saturating_sub(1))?;
+                }
+            }
+            w.commit().map_err(db_err)
+        }
+
         /// Apply a single Jetstream commit event to the index. `create`/`update`
         /// upsert the record's identity (and its reverse rkey mapping); `delete`
         /// drops the entry via the reverse mapping. Records of untracked
@@ -358,6 +749,22 @@ mod index {
             record: Option<&serde_json::Value>,
         ) -> Result<()> {
             match operation {
+                "create" | "update" if collection == C_PLAYLIST_ITEM => {
+                    let Some(rec) = record else { return Ok(()) };
+                    let Some((playlist_uri, song_uri, order)) = playlist_item_fields(
+                        |k| rec.get(k)?.get("uri")?.as_str(),
+                        rec.get("order").and_then(|v| v.as_i64()),
+                    ) else {
+                        return Ok(());
+                    };
+                    let uri = format!("at://{did}/{collection}/{rkey}");
+                    let w = self.db.begin_write().map_err(db_err)?;
+                    {
+                        let mut t = w.open_table(TABLE).map_err(db_err)?;
+                        insert_playlist_item(&mut t, did, playlist_uri, song_uri, &uri, order)?;
+                    }
+                    w.commit().map_err(db_err)?;
+                }
                 "create" | "update" => {
                     let Some(rec) = record else { return Ok(()) };
                     let Some(primary) =
@@ -376,25 +783,14 @@ mod index {
                     }
                     w.commit().map_err(db_err)?;
                 }
-                "delete" => {
-                    let rk = rk_key(did, collection, rkey);
-                    if let Some(primary) = self.get_bytes(&rk)? {
-                        let w = self.db.begin_write().map_err(db_err)?;
-                        {
-                            let mut t = w.open_table(TABLE).map_err(db_err)?;
-                            t.remove(primary.as_slice()).map_err(db_err)?;
-                            t.remove(rk.as_slice()).map_err(db_err)?;
-                        }
-                        w.commit().map_err(db_err)?;
-                    }
-                }
+                "delete" => self.forget(did, collection, rkey)?,
                 _ => {}
             }
             Ok(())
         }
 
         /// Ingest a repo CAR (full or an incremental `since=` diff) for `did`,
-        /// indexing every song/album/artist/scrobble it contains. Records are
+        /// indexing every song/album/artist/scrobble/playlist item it contains. Records are
         /// batched into a single write. Returns what was added.
         ///
         /// The MST walk tolerates missing CIDs, so a `since=` diff — which only
@@ -424,7 +820,10 @@ mod index {
                     let Some((collection, rkey)) = path.split_once('/') else {
                         continue;
                     };
-                    if !matches!(collection, C_ARTIST | C_ALBUM | C_SONG | C_SCROBBLE) {
+                    if !matches!(
+                        collection,
+                        C_ARTIST | C_ALBUM | C_SONG | C_SCROBBLE | C_PLAYLIST_ITEM
+                    ) {
                         continue;
                     }
                     let Some(bytes) = blocks.get(value_cid) else {
@@ -433,6 +832,21 @@ mod index {
                     let Ok(rec): core::result::Result<Ipld, _> = decode(bytes) else {
                         continue;
                     };
+                    if collection == C_PLAYLIST_ITEM {
+                        let order = match field(&rec, "order") {
+                            Some(Ipld::Integer(o)) => i64::try_from(*o).ok(),
+                            _ => None,
+                        };
+                        if let Some((playlist_uri, song_uri, order)) = playlist_item_fields(
+                            |k| field(&rec, k).and_then(|v| str_field(v, "uri")),
+                            order,
+                        ) {
+                            let uri = format!("at://{did}/{collection}/{rkey}");
+                            insert_playlist_item(&mut t, did, playlist_uri, song_uri, &uri, order)?;
+                            stats.playlist_items += 1;
+                        }
+                        continue;
+                    }
                     let Some(primary) = primary_key_for(did, collection, |k| str_field(&rec, k))
                     else {
                         continue;
@@ -589,6 +1003,14 @@ mod index {
         }
     }
 
+    /// Read a field from a decoded dag-cbor record map.
+    fn field<'a>(rec: &'a Ipld, key: &str) -> Option<&'a Ipld> {
+        match rec {
+            Ipld::Map(m) => m.get(key),
+            _ => None,
+        }
+    }
+
     /// Read a string field from a decoded dag-cbor record map.
     fn str_field<'a>(rec: &'a Ipld, key: &str) -> Option<&'a str> {
         match rec {
@@ -615,6 +1037,7 @@ mod tests {
     //! a lookup miss means the write verb will publish, a hit means it reuses
     //! the existing URI and writes nothing. All on a temp redb file; no PDS.
     use super::*;
+    use crate::agent::ScrobbleDraft;
 
     const DID: &str = "did:plc:test";
     const OTHER: &str = "did:plc:other";
@@ -736,4 +1159,245 @@ mod tests {
         // Another user's repo doesn't shadow this one.
         assert!(idx.artist_uri(OTHER, "Album Artist").unwrap().is_none());
     }
+
+    #[test]
+    fn playlist_item_dedup_keyed_on_playlist_and_song() {
+        let (idx, _dir) = tmp_index();
+        let playlist = "at://did:plc:test/app.rocksky.playlist/p1";
+        let song = "at://did:plc:test/app.rocksky.song/s1";
+        assert!(idx
+            .playlist_item_uri(DID, playlist, song)
+            .unwrap()
+            .is_none());
+
+        let uri = "at://did:plc:test/app.rocksky.playlistItem/i1";
+        idx.record_playlist_item(DID, playlist, song, uri, 0)
+            .unwrap();
+        assert_eq!(
+            idx.playlist_item_uri(DID, playlist, song)
+                .unwrap()
+                .as_deref(),
+            Some(uri)
+        );
+        // The same song in another playlist is a distinct item (miss).
+        assert!(idx
+            .playlist_item_uri(DID, "at://did:plc:test/app.rocksky.playlist/p2", song)
+            .unwrap()
+            .is_none());
+
+        // Removing the item (by rkey, as a delete carries) re-allows adding it.
+        idx.forget(DID, C_PLAYLIST_ITEM, "i1").unwrap();
+        assert!(idx
+            .playlist_item_uri(DID, playlist, song)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn playlist_items_are_listed_with_their_order() {
+        let (idx, _dir) = tmp_index();
+        let playlist = "at://did:plc:test/app.rocksky.playlist/p1";
+        idx.record_playlist_item(
+            DID,
+            playlist,
+            "at://did:plc:test/app.rocksky.song/s1",
+            "at://did:plc:test/app.rocksky.playlistItem/i1",
+            0,
+        )
+        .unwrap();
+        idx.record_playlist_item(
+            DID,
+            playlist,
+            "at://did:plc:test/app.rocksky.song/s2",
+            "at://did:plc:test/app.rocksky.playlistItem/i2",
+            1,
+        )
+        .unwrap();
+        // Neither another playlist sharing the prefix nor another repo leaks in.
+        idx.record_playlist_item(
+            DID,
+            "at://did:plc:test/app.rocksky.playlist/p10",
+            "at://did:plc:test/app.rocksky.song/s3",
+            "at://did:plc:test/app.rocksky.playlistItem/i3",
+            0,
+        )
+        .unwrap();
+        idx.record_playlist_item(
+            OTHER,
+            playlist,
+            "at://did:plc:test/app.rocksky.song/s4",
+            "at://did:plc:other/app.rocksky.playlistItem/i4",
+            0,
+        )
+        .unwrap();
+
+        let mut items = idx.playlist_items(DID, playlist).unwrap();
+        items.sort_by_key(|i| i.order);
+        assert_eq!(
+            items,
+            [
+                PlaylistItemEntry {
+                    uri: "at://did:plc:test/app.rocksky.playlistItem/i1".into(),
+                    song_uri: "at://did:plc:test/app.rocksky.song/s1".into(),
+                    order: Some(0),
+                },
+                PlaylistItemEntry {
+                    uri: "at://did:plc:test/app.rocksky.playlistItem/i2".into(),
+                    song_uri: "at://did:plc:test/app.rocksky.song/s2".into(),
+                    order: Some(1),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn playlist_items_follow_jetstream_commits() {
+        let (idx, _dir) = tmp_index();
+        let playlist = "at://did:plc:test/app.rocksky.playlist/p1";
+        let item = |song: &str, order: i64| {
+            serde_json::json!({
+                "subject": { "uri": playlist, "cid": "bafy" },
+                "track": { "uri": song },
+                "order": order,
+            })
+        };
+        let s1 = "at://did:plc:test/app.rocksky.song/s1";
+        let s2 = "at://did:plc:test/app.rocksky.song/s2";
+        idx.apply_commit(DID, C_PLAYLIST_ITEM, "create", "i1", Some(&item(s1, 0)))
+            .unwrap();
+        assert_eq!(idx.playlist_items(DID, playlist).unwrap()[0].order, Some(0));
+
+        // A reorder moves the item; an update swapping the song drops the old
+        // entry rather than leaving both.
+        idx.apply_commit(DID, C_PLAYLIST_ITEM, "update", "i1", Some(&item(s1, 3)))
+            .unwrap();
+        assert_eq!(idx.playlist_items(DID, playlist).unwrap()[0].order, Some(3));
+        idx.apply_commit(DID, C_PLAYLIST_ITEM, "update", "i1", Some(&item(s2, 3)))
+            .unwrap();
+        let items = idx.playlist_items(DID, playlist).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].song_uri, s2);
+
+        idx.apply_commit(DID, C_PLAYLIST_ITEM, "delete", "i1", None)
+            .unwrap();
+        assert!(idx.playlist_items(DID, playlist).unwrap().is_empty());
+    }
+
+    #[test]
+    fn record_all_indexes_a_batch_like_single_writes() {
+        let (idx, _dir) = tmp_index();
+        let artist = Identity::Artist("Album Artist".into());
+        let play = Identity::Scrobble("Song A".into(), "Track Artist".into(), "Album A".into(), 7);
+        idx.record_all(
+            DID,
+            &[
+                (&artist, "at://did:plc:test/app.rocksky.artist/a1"),
+                (&play, "at://did:plc:test/app.rocksky.scrobble/s1"),
+            ],
+        )
+        .unwrap();
+
+        // Same keys as the per-record lookups, case-insensitively.
+        assert_eq!(
+            idx.artist_uri(DID, "ALBUM ARTIST").unwrap().as_deref(),
+            Some("at://did:plc:test/app.rocksky.artist/a1")
+        );
+        assert_eq!(
+            idx.scrobble_uri(DID, "Song A", "Track Artist", "Album A", 7)
+                .unwrap()
+                .as_deref(),
+            Some("at://did:plc:test/app.rocksky.scrobble/s1")
+        );
+        assert!(idx.identity_uri(OTHER, &artist).unwrap().is_none());
+
+        // The reverse rkey mappings are there for deletes.
+        idx.forget(DID, C_SCROBBLE, "s1").unwrap();
+        assert!(idx.identity_uri(DID, &play).unwrap().is_none());
+        assert!(idx.identity_uri(DID, &artist).unwrap().is_some());
+    }
+
+    fn draft(title: &str, timestamp: Option<i64>) -> ScrobbleDraft {
+        ScrobbleDraft {
+            title: title.into(),
+            artist: "Track Artist".into(),
+            album: "Album A".into(),
+            album_artist: "Album Artist".into(),
+            timestamp,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn queue_replays_in_play_order() {
+        let (idx, _dir) = tmp_index();
+        idx.enqueue_scrobble(DID, &draft("Later", Some(1_700_000_100)))
+            .unwrap();
+        idx.enqueue_scrobble(DID, &draft("Earlier", Some(1_700_000_000)))
+            .unwrap();
+        // Same second as "Later" but queued after it.
+        idx.enqueue_scrobble(DID, &draft("Later too", Some(1_700_000_100)))
+            .unwrap();
+
+        let titles: Vec<String> = idx
+            .queued_scrobbles(DID)
+            .unwrap()
+            .into_iter()
+            .map(|q| q.draft.title)
+            .collect();
+        assert_eq!(titles, ["Earlier", "Later", "Later too"]);
+    }
+
+    #[test]
+    fn queue_pins_the_timestamp_at_enqueue() {
+        let (idx, _dir) = tmp_index();
+        let before = chrono::Utc::now().timestamp();
+        let queued = idx.enqueue_scrobble(DID, &draft("Song A", None)).unwrap();
+        let pinned = queued.draft.timestamp.unwrap();
+        assert!(pinned >= before);
+        // What comes back out is the pinned draft, not "now" at replay time.
+        assert_eq!(
+            idx.queued_scrobbles(DID).unwrap()[0].draft.timestamp,
+            Some(pinned)
+        );
+    }
+
+    #[test]
+    fn queue_is_scoped_per_did_and_dequeues() {
+        let (idx, _dir) = tmp_index();
+        let mine = idx
+            .enqueue_scrobble(DID, &draft("Song A", Some(1)))
+            .unwrap();
+        idx.enqueue_scrobble(OTHER, &draft("Song B", Some(2)))
+            .unwrap();
+        assert_eq!(idx.queue_depth(DID).unwrap(), 1);
+        assert_eq!(idx.queue_len().unwrap(), 2);
+        let others: Vec<String> = idx
+            .queued_scrobbles(OTHER)
+            .unwrap()
+            .into_iter()
+            .map(|q| q.draft.title)
+            .collect();
+        assert_eq!(others, ["Song B"]);
+
+        idx.dequeue_scrobble(&mine).unwrap();
+        assert_eq!(idx.queue_depth(DID).unwrap(), 0);
+        assert_eq!(idx.queue_depth(OTHER).unwrap(), 1);
+
+        idx.dequeue_scrobble(&mine).unwrap();
+        assert_eq!(idx.queue_depth(DID).unwrap(), 0);
+        assert_eq!(idx.queue_depth(OTHER).unwrap(), 1);
+    }
+
+    #[test]
+    fn queue_survives_reopening() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("idx.redb");
+        {
+            let idx = RepoIndex::open(&path).unwrap();
+            idx.enqueue_scrobble(DID, &draft("Song A", Some(1)))
+                .unwrap();
+        }
+        let idx = RepoIndex::open(&path).unwrap();
+        assert_eq!(idx.queue_depth(DID).unwrap(), 1);
+    }
 }
@@ -1,6 +1,8 @@
 //! The SDK's error type. A library must not leak `anyhow` to its callers, so the
 //! app's `anyhow::Result` becomes a typed [`SdkError`] here.
 
+use jacquard::common::xrpc::XrpcError;
+
 /// Errors returned by the Rocksky SDK.
 #[derive(thiserror::Error, Debug)]
 pub enum SdkError {
@@ -31,6 +33,20 @@ pub enum SdkError {
     #[error("auth: {0}")]
     Auth(String),
 
+    /// A call to the user's PDS failed. `failure` says why, so callers can
+    /// tell an unreachable PDS from a rejected write; `message` is the
+    /// jacquard error and its causes.
+    #[error("{context}: {message}")]
+    Pds {
+        context: String,
+        failure: PdsFailure,
+        message: String,
+    },
+
+    /// The local dedup store (the redb index and offline queue) failed.
+    #[error("dedup index: {0}")]
+    Store(String),
+
     #[error(transparent)]
     Http(#[from] reqwest::Error),
 
@@ -45,6 +61,168 @@ pub enum SdkError {
     Other(String),
 }
 
+impl SdkError {
+    /// Whether the operation may succeed if simply retried later — the PDS or
+    /// AppView was unreachable, timed out or answered with a 5xx / 429.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            SdkError::Http(e) => {
+                e.is_connect()
+                    || e.is_timeout()
+                    || e.is_request()
+                    || e.status()
+                        .is_some_and(|s| s.is_server_error() || s.as_u16() == 429)
+            }
+            SdkError::AppView { status, .. } => *status >= 500 || *status == 429,
+            SdkError::Io(_) => true,
+            SdkError::Pds { failure, .. } => match failure {
+                PdsFailure::Transport | PdsFailure::IdentityResolution => true,
+                PdsFailure::Status(status) => *status >= 500 || *status == 429,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether the operation is known to fail again as is: the PDS rejected
+    /// the request itself, or it couldn't even be built. Errors that are
+    /// neither this nor [transient](Self::is_transient) can't be classified;
+    /// a failing local [store](SdkError::Store) is one of them.
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            SdkError::Pds { failure, .. } => match failure {
+                PdsFailure::Rejected | PdsFailure::InvalidRequest => true,
+                PdsFailure::Status(status) => {
+                    (400..500).contains(status) && ![401, 408, 429].contains(status)
+                }
+                _ => false,
+            },
+            SdkError::AppView { status, .. } => {
+                (400..500).contains(status) && ![401, 408, 429].contains(status)
+            }
+            SdkError::RecordNotFound | SdkError::Json(_) | SdkError::Other(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Why a call to the user's PDS failed (see [`SdkError::Pds`]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PdsFailure {
+    /// Connecting to the PDS, or sending or reading, failed or timed out.
+    Transport,
+    /// Resolving the account's identity (handle, DID document) failed.
+    IdentityResolution,
+    /// The PDS answered with this non-success HTTP status.
+    Status(u16),
+    /// The PDS rejected the request with one of the endpoint's own errors.
+    Rejected,
+    /// The request couldn't be built or encoded.
+    InvalidRequest,
+    /// The session was refused.
+    Auth,
+    /// Anything else, e.g. an answer that couldn't be decoded.
+    Unknown,
+}
+
+impl PdsFailure {
+    fn of_client(e: &jacquard::common::error::ClientError) -> Self {
+        use jacquard::common::error::ClientErrorKind;
+        match e.kind() {
+            ClientErrorKind::Transport => PdsFailure::Transport,
+            ClientErrorKind::IdentityResolution => PdsFailure::IdentityResolution,
+            ClientErrorKind::Http { status } => PdsFailure::Status(status.as_u16()),
+            ClientErrorKind::Auth(_) => PdsFailure::Auth,
+            ClientErrorKind::InvalidRequest(_) | ClientErrorKind::Encode(_) => {
+                PdsFailure::InvalidRequest
+            }
+            _ => PdsFailure::Unknown,
+        }
+    }
+
+    fn of_xrpc<E: std::error::Error>(e: &XrpcError<E>) -> Self {
+        match e {
+            XrpcError::Xrpc(_) => PdsFailure::Rejected,
+            XrpcError::Auth(_) => PdsFailure::Auth,
+            XrpcError::Generic(generic) => PdsFailure::Status(generic.http_status.as_u16()),
+            _ => PdsFailure::Unknown,
+        }
+    }
+
+    fn of_agent(e: &jacquard::client::AgentError) -> Self {
+        use jacquard::api::com_atproto::repo::{
+            create_record::CreateRecordError, delete_record::DeleteRecordError,
+            put_record::PutRecordError,
+        };
+        use jacquard::client::AgentErrorKind;
+        use jacquard::common::xrpc::GenericError;
+
+        if let Some(client) = e.client_error() {
+            return PdsFailure::of_client(client);
+        }
+        // The record verbs box the `XrpcError` of their endpoint.
+        if let Some(xrpc) = e.source_downcast::<XrpcError<CreateRecordError>>() {
+            return PdsFailure::of_xrpc(xrpc);
+        }
+        if let Some(xrpc) = e.source_downcast::<XrpcError<PutRecordError>>() {
+            return PdsFailure::of_xrpc(xrpc);
+        }
+        if let Some(xrpc) = e.source_downcast::<XrpcError<DeleteRecordError>>() {
+            return PdsFailure::of_xrpc(xrpc);
+        }
+        if let Some(xrpc) = e.source_downcast::<XrpcError<GenericError>>() {
+            return PdsFailure::of_xrpc(xrpc);
+        }
+        match e.kind() {
+            AgentErrorKind::Auth(_) | AgentErrorKind::NoSession => PdsFailure::Auth,
+            AgentErrorKind::SubOperation { .. } | AgentErrorKind::RecordOperation { .. } => {
+                PdsFailure::Rejected
+            }
+            _ => PdsFailure::Unknown,
+        }
+    }
+}
+
+/// `e` and its causes, as `error: cause: cause`.
+fn error_chain(e: &dyn std::error::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = e.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// A jacquard transport error of the call described by `context`.
+pub(crate) fn client_err(context: &str, e: jacquard::common::error::ClientError) -> SdkError {
+    SdkError::Pds {
+        context: context.to_string(),
+        failure: PdsFailure::of_client(&e),
+        message: error_chain(&e),
+    }
+}
+
+/// The error answer of the XRPC call described by `context`.
+pub(crate) fn xrpc_err<E: std::error::Error>(context: &str, e: XrpcError<E>) -> SdkError {
+    SdkError::Pds {
+        context: context.to_string(),
+        failure: PdsFailure::of_xrpc(&e),
+        message: error_chain(&e),
+    }
+}
+
+/// A failed jacquard record verb (create / put / delete), `context` being
+/// what it was for.
+pub(crate) fn agent_err(context: &str, e: jacquard::client::AgentError) -> SdkError {
+    SdkError::Pds {
+        context: context.to_string(),
+        failure: PdsFailure::of_agent(&e),
+        message: error_chain(&e),
+    }
+}
+
 /// The SDK's result alias.
 pub type Result<T> = core::result::Result<T, SdkError>;
 
@@ -52,3 +230,46 @@ pub type Result<T> = core::result::Result<T, SdkError>;
 pub(crate) fn auth_err<E: core::fmt::Display>(e: E) -> SdkError {
     SdkError::Auth(e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jacquard::common::error::ClientError;
+
+    #[test]
+    fn classifies_pds_errors_by_kind() {
+        let unreachable = client_err(
+            "applyWrites",
+            ClientError::transport(std::io::Error::from(std::io::ErrorKind::ConnectionRefused)),
+        );
+        assert!(unreachable.is_transient());
+        assert!(!unreachable.is_permanent());
+
+        let overloaded = client_err(
+            "applyWrites",
+            ClientError::http(reqwest::StatusCode::SERVICE_UNAVAILABLE, None),
+        );
+        assert!(overloaded.is_transient());
+
+        let invalid = client_err(
+            "applyWrites",
+            ClientError::http(reqwest::StatusCode::BAD_REQUEST, None),
+        );
+        assert!(invalid.is_permanent());
+        assert!(!invalid.is_transient());
+        assert!(
+            invalid.to_string().starts_with("applyWrites: "),
+            "{invalid}"
+        );
+
+        // Neither retried in a loop nor given up on.
+        let undecodable = client_err("applyWrites", ClientError::decode("truncated body"));
+        assert!(!undecodable.is_transient());
+        assert!(!undecodable.is_permanent());
+
+        // A local storage failure says nothing about the play itself.
+        let store = SdkError::Store("I/O error".into());
+        assert!(!store.is_transient());
+        assert!(!store.is_permanent());
+    }
+}
@@ -0,0 +1,1035 @@
+//! Import listening history from other services into the user's repo.
+//!
+//! Three export formats are understood, all normalized into [`ScrobbleDraft`]s:
+//!
+//! - **Spotify** extended streaming history (`Streaming_History_Audio_*.json`,
+//!   from *Privacy → Download your data*). Plays shorter than 30 s and podcast
+//!   episodes are skipped, as Spotify itself doesn't count them.
+//! - **Last.fm** scrobble dumps: the CSV of the common exporters (with a
+//!   `uts,utc_time,artist,…` header, or the headerless `artist,album,track,date`
+//!   one) and the JSON pages of `user.getRecentTracks`.
+//! - **ListenBrainz** listen exports (JSON Lines, or a JSON array).
+//!
+//! [`run`] then writes the plays in `com.atproto.repo.applyWrites` batches, one
+//! request per [`ImportConfig::batch_size`] plays with a pause in between to
+//! stay under the PDS rate limits. Plays the dedup index already has are
+//! skipped, and the progress through each source is saved after every batch,
+//! so an interrupted import picks up where it stopped.
+//!
+//! Gated behind the `dedup` feature.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::agent::{RockskyAgent, ScrobbleDraft, MAX_APPLY_WRITES};
+use crate::dedup::song_hash;
+use crate::error::Result;
+
+/// Spotify counts a stream as a play from 30 seconds on.
+const MIN_SPOTIFY_PLAY_MS: i64 = 30_000;
+
+/// Each play writes at most four records: its artist, album, song and scrobble.
+const MAX_WRITES_PER_PLAY: usize = 4;
+
+/// The export formats [`parse`] understands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Spotify's `Streaming_History_Audio_*.json`.
+    SpotifyExtended,
+    /// A Last.fm scrobble CSV.
+    LastfmCsv,
+    /// Last.fm `user.getRecentTracks` JSON pages.
+    LastfmJson,
+    /// A ListenBrainz listen export.
+    ListenBrainz,
+}
+
+impl ImportFormat {
+    /// Guess the format of an export from its file name and contents. `None`
+    /// when it's none of the supported ones.
+    pub fn detect(name: &str, input: &str) -> Option<Self> {
+        let name = name
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or(name)
+            .to_lowercase();
+        if name.ends_with(".csv") {
+            return Some(ImportFormat::LastfmCsv);
+        }
+        if name.starts_with("streaming_history_audio") || input.contains("\"ms_played\"") {
+            return Some(ImportFormat::SpotifyExtended);
+        }
+        if input.contains("\"listened_at\"") {
+            return Some(ImportFormat::ListenBrainz);
+        }
+        if input.contains("\"uts\"") || input.contains("\"recenttracks\"") {
+            return Some(ImportFormat::LastfmJson);
+        }
+        None
+    }
+}
+
+/// Parse an export of `format` into plays, in file order.
+pub fn parse(format: ImportFormat, input: &str) -> Result<Vec<ScrobbleDraft>> {
+    match format {
+        ImportFormat::SpotifyExtended => parse_spotify_extended(input),
+        ImportFormat::LastfmCsv => parse_lastfm_csv(input),
+        ImportFormat::LastfmJson => parse_lastfm_json(input),
+        ImportFormat::ListenBrainz => parse_listenbrainz(input),
+    }
+}
+
+/// One stream of Spotify's extended streaming history.
+#[derive(Deserialize)]
+struct SpotifyStream {
+    /// When the stream *ended*, RFC 3339.
+    ts: String,
+    #[serde(default)]
+    ms_played: i64,
+    master_metadata_track_name: Option<String>,
+    master_metadata_album_artist_name: Option<String>,
+    master_metadata_album_album_name: Option<String>,
+    spotify_track_uri: Option<String>,
+}
+
+/// Parse a `Streaming_History_Audio_*.json` file. The play time is when the
+/// stream started; Spotify records when it ended.
+pub fn parse_spotify_extended(input: &str) -> Result<Vec<ScrobbleDraft>> {
+    let streams: Vec<SpotifyStream> = serde_json::from_str(input)?;
+    let mut plays = Vec::new();
+    for s in streams {
+        let (Some(title), Some(artist)) = (
+            non_empty(s.master_metadata_track_name),
+            non_empty(s.master_metadata_album_artist_name),
+        ) else {
+            continue;
+        };
+        if s.ms_played < MIN_SPOTIFY_PLAY_MS {
+            continue;
+        }
+        let Some(ended) = parse_rfc3339(&s.ts) else {
+            continue;
+        };
+        plays.push(ScrobbleDraft {
+            title,
+            album_artist: artist.clone(),
+            artist,
+            album: s.master_metadata_album_album_name.unwrap_or_default(),
+            spotify_link: s
+                .spotify_track_uri
+                .as_deref()
+                .and_then(|uri| uri.strip_prefix("spotify:track:"))
+                .map(|id| format!("https://open.spotify.com/track/{id}")),
+            timestamp: Some(ended - s.ms_played / 1000),
+            ..Default::default()
+        });
+    }
+    Ok(plays)
+}
+
+/// Parse a Last.fm scrobble CSV: either with a header naming the `uts` (or
+/// `utc_time`), `artist`, `album`, `track` and optional `*_mbid` columns, or
+/// headerless `artist,album,track,date` rows dated `31 Jan 2021 18:04` (UTC).
+pub fn parse_lastfm_csv(input: &str) -> Result<Vec<ScrobbleDraft>> {
+    let mut rows = csv_rows(input).into_iter().peekable();
+    let header: Option<Vec<String>> = match rows.peek() {
+        Some(first) if first.iter().any(|c| c == "uts" || c == "utc_time") => rows
+            .next()
+            .map(|h| h.iter().map(|c| c.to_lowercase()).collect()),
+        _ => None,
+    };
+    let column = |name: &str| {
+        header
+            .as_ref()
+            .and_then(|h| h.iter().position(|c| c == name))
+    };
+    let (uts, utc_time) = (column("uts"), column("utc_time"));
+    let (artist, album, track) = match &header {
+        Some(_) => (column("artist"), column("album"), column("track")),
+        None => (Some(0), Some(1), Some(2)),
+    };
+    let track_mbid = column("track_mbid");
+
+    let mut plays = Vec::new();
+    for row in rows {
+        let field = |i: Option<usize>| {
+            i.and_then(|i| row.get(i))
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let (Some(title), Some(artist)) = (field(track), field(artist)) else {
+            continue;
+        };
+        let timestamp = match &header {
+            Some(_) => field(uts)
+                .and_then(|t| t.parse().ok())
+                .or_else(|| field(utc_time).and_then(|t| parse_lastfm_date(&t))),
+            None => field(Some(3)).and_then(|t| parse_lastfm_date(&t)),
+        };
+        let Some(timestamp) = timestamp else {
+            continue;
+        };
+        plays.push(ScrobbleDraft {
+            title,
+            album_artist: artist.clone(),
+            artist,
+            album: field(album).unwrap_or_default(),
+            mbid: field(track_mbid),
+            timestamp: Some(timestamp),
+            ..Default::default()
+        });
+    }
+    Ok(plays)
+}
+
+/// Parse Last.fm `user.getRecentTracks` JSON: a single response, an array of
+/// response pages, or a plain array of tracks. The currently playing track has
+/// no date and is skipped.
+pub fn parse_lastfm_json(input: &str) -> Result<Vec<ScrobbleDraft>> {
+    let doc: Value = serde_json::from_str(input)?;
+    let mut tracks = Vec::new();
+    collect_lastfm_tracks(&doc, &mut tracks);
+
+    let mut plays = Vec::new();
+    for t in tracks {
+        let Some(timestamp) = t
+            .pointer("/date/uts")
+            .and_then(|u| u.as_str().and_then(|s| s.parse().ok()).or(u.as_i64()))
+        else {
+            continue;
+        };
+        let (Some(title), Some(artist)) = (
+            non_empty(t.get("name").and_then(Value::as_str).map(String::from)),
+            lastfm_text(t.get("artist")),
+        ) else {
+            continue;
+        };
+        plays.push(ScrobbleDraft {
+            title,
+            album_artist: artist.clone(),
+            artist,
+            album: lastfm_text(t.get("album")).unwrap_or_default(),
+            mbid: non_empty(t.get("mbid").and_then(Value::as_str).map(String::from)),
+            timestamp: Some(timestamp),
+            ..Default::default()
+        });
+    }
+    Ok(plays)
+}
+
+fn collect_lastfm_tracks<'a>(doc: &'a Value, out: &mut Vec<&'a Value>) {
+    match doc {
+        Value::Array(items) => items.iter().for_each(|i| collect_lastfm_tracks(i, out)),
+        Value::Object(o) if o.contains_key("recenttracks") => {
+            collect_lastfm_tracks(&o["recenttracks"], out)
+        }
+        Value::Object(o) => match o.get("track") {
+            Some(Value::Array(tracks)) => out.extend(tracks),
+            Some(track @ Value::Object(_)) => out.push(track),
+            _ if o.contains_key("name") => out.push(doc),
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+/// Last.fm names artists and albums either `{"#text": …}` (recent tracks) or
+/// `{"name": …}` (extended responses).
+fn lastfm_text(v: Option<&Value>) -> Option<String> {
+    let v = v?;
+    let text = v
+        .as_str()
+        .or_else(|| v.get("#text").and_then(Value::as_str))
+        .or_else(|| v.get("name").and_then(Value::as_str))?;
+    non_empty(Some(text.to_string()))
+}
+
+/// Parse a ListenBrainz export: one listen per line (JSON Lines), or a JSON
+/// array of listens.
+pub fn parse_listenbrainz(input: &str) -> Result<Vec<ScrobbleDraft>> {
+    let listens: Vec<Value> = if input.trim_start().starts_with('[') {
+        serde_json::from_str(input)?
+    } else {
+        input
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?
+    };
+
+    let mut plays = Vec::new();
+    for l in listens {
+        let Some(timestamp) = l.get("listened_at").and_then(Value::as_i64) else {
+            continue;
+        };
+        let meta = &l["track_metadata"];
+        let text = |key: &str| non_empty(meta.get(key).and_then(Value::as_str).map(String::from));
+        let (Some(title), Some(artist)) = (text("track_name"), text("artist_name")) else {
+            continue;
+        };
+        let info = &meta["additional_info"];
+        let info_str =
+            |key: &str| non_empty(info.get(key).and_then(Value::as_str).map(String::from));
+        let info_int = |key: &str| {
+            info.get(key)
+                .and_then(|v| v.as_i64().or_else(|| v.as_str()?.parse().ok()))
+        };
+        plays.push(ScrobbleDraft {
+            title,
+            album_artist: artist.clone(),
+            artist,
+            album: text("release_name").unwrap_or_default(),
+            duration_ms: info_int("duration_ms")
+                .or_else(|| info_int("duration").map(|s| s * 1000))
+                .unwrap_or_default(),
+            track_number: info_int("tracknumber"),
+            mbid: info_str("recording_mbid").or_else(|| {
+                non_empty(
+                    meta.pointer("/mbid_mapping/recording_mbid")
+                        .and_then(Value::as_str)
+                        .map(String::from),
+                )
+            }),
+            isrc: info_str("isrc"),
+            spotify_link: info_str("spotify_id"),
+            timestamp: Some(timestamp),
+            ..Default::default()
+        });
+    }
+    Ok(plays)
+}
+
+/// Split CSV `input` into rows of fields (RFC 4180: quoted fields may hold
+/// commas, newlines and `""`-escaped quotes). Blank lines are dropped.
+fn csv_rows(input: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = input.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                if row.iter().any(|f| !f.is_empty()) {
+                    rows.push(std::mem::take(&mut row));
+                }
+                row.clear();
+            }
+            c => field.push(c),
+        }
+    }
+    row.push(field);
+    if row.iter().any(|f| !f.is_empty()) {
+        rows.push(row);
+    }
+    rows
+}
+
+fn parse_rfc3339(s: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|d| d.timestamp())
+}
+
+/// `31 Jan 2021 18:04`, the date format of Last.fm's own pages and exports.
+fn parse_lastfm_date(s: &str) -> Option<i64> {
+    chrono::NaiveDateTime::parse_from_str(s.trim(), "%d %b %Y %H:%M")
+        .ok()
+        .map(|d| d.and_utc().timestamp())
+        .or_else(|| parse_rfc3339(s.trim()))
+}
+
+fn non_empty(s: Option<String>) -> Option<String> {
+    s.map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+/// Configuration for [`run`].
+#[derive(Clone, Debug)]
+pub struct ImportConfig {
+    /// Plays per `applyWrites` request. Capped so a batch never exceeds the
+    /// 200-write limit, even when every play brings new artist/album/song
+    /// records along.
+    pub batch_size: usize,
+    /// Pause between requests, to stay under the PDS write rate limit.
+    pub pause: Duration,
+    /// How often a batch that failed transiently is retried (with doubling
+    /// backoff from [`pause`](Self::pause)) before the import gives up.
+    pub max_retries: u32,
+    /// Where progress is saved after every batch. Without one an interrupted
+    /// import starts over, relying on the dedup index to skip what was written.
+    pub progress_path: Option<PathBuf>,
+}
+
+impl Default for ImportConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: MAX_APPLY_WRITES / MAX_WRITES_PER_PLAY,
+            pause: Duration::from_secs(1),
+            max_retries: 5,
+            progress_path: None,
+        }
+    }
+}
+
+impl ImportConfig {
+    /// Override the plays per request (capped at 50).
+    pub fn batch_size(mut self, plays: usize) -> Self {
+        self.batch_size = plays;
+        self
+    }
+
+    /// Override the pause between requests.
+    pub fn pause(mut self, pause: Duration) -> Self {
+        self.pause = pause;
+        self
+    }
+
+    /// Override the retries of a transiently failing batch.
+    pub fn max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Save progress to `path`, to resume an interrupted import.
+    pub fn progress_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.progress_path = Some(path.into());
+        self
+    }
+
+    fn plays_per_batch(&self) -> usize {
+        self.batch_size
+            .clamp(1, MAX_APPLY_WRITES / MAX_WRITES_PER_PLAY)
+    }
+}
+
+/// How far each source has been imported: the number of its plays, in play
+/// order, already handled. Persisted as JSON.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportProgress {
+    pub sources: BTreeMap<String, usize>,
+}
+
+impl ImportProgress {
+    /// Load the progress at `path`; empty when the file doesn't exist yet.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Save to `path`, atomically (write then rename) so a crash mid-save
+    /// can't lose it.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+}
+
+/// What an import did.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Plays read from the export.
+    pub parsed: usize,
+    /// Plays skipped because an earlier run already handled them.
+    pub resumed: usize,
+    /// Plays skipped as already in the repo, or repeated within the export.
+    pub duplicates: usize,
+    /// Scrobbles written.
+    pub written: usize,
+}
+
+/// Import `plays` from the export named `source` (the key its progress is
+/// saved under) into `agent`'s repo. Plays are written oldest first, in batches of
+/// [`ImportConfig::batch_size`]. Stops with an error once a batch keeps
+/// failing; progress up to the last written batch is kept.
+pub async fn run(
+    agent: &RockskyAgent,
+    source: &str,
+    mut plays: Vec<ScrobbleDraft>,
+    config: &ImportConfig,
+) -> Result<ImportReport> {
+    let did = agent.did()?;
+    let index = agent.dedup_index();
+    let mut progress = match &config.progress_path {
+        Some(path) => ImportProgress::load(path)?,
+        None => ImportProgress::default(),
+    };
+
+    plays.retain(|p| p.timestamp.is_some());
+    plays.sort_by_key(|p| p.timestamp);
+    let mut report = ImportReport {
+        parsed: plays.len(),
+        ..Default::default()
+    };
+    let mut done = progress
+        .sources
+        .get(source)
+        .copied()
+        .unwrap_or_default()
+        .min(plays.len());
+    report.resumed = done;
+
+    let mut seen = HashSet::new();
+    let mut first_batch = true;
+    while done < plays.len() {
+        // Fill a batch with plays that aren't in the repo yet.
+        let mut batch = Vec::new();
+        let mut end = done;
+        while end < plays.len() && batch.len() < config.plays_per_batch() {
+            let p = &plays[end];
+            end += 1;
+            let secs = p.timestamp.unwrap_or_default();
+            let fresh = seen.insert((song_hash(&p.title, &p.artist, &p.album), secs));
+            let indexed = match &index {
+                Some(idx) => idx
+                    .scrobble_uri(&did, &p.title, &p.artist, &p.album, secs)?
+                    .is_some(),
+                None => false,
+            };
+            if fresh && !indexed {
+                batch.push(p.clone());
+            } else {
+                report.duplicates += 1;
+            }
+        }
+
+        if !batch.is_empty() {
+            if !first_batch {
+                tokio::time::sleep(config.pause).await;
+            }
+            first_batch = false;
+            report.written += write_with_retries(agent, &batch, config).await?;
+        }
+
+        done = end;
+        progress.sources.insert(source.to_string(), done);
+        if let Some(path) = &config.progress_path {
+            progress.save(path)?;
+        }
+    }
+    Ok(report)
+}
+
+async fn write_with_retries(
+    agent: &RockskyAgent,
+    batch: &[ScrobbleDraft],
+    config: &ImportConfig,
+) -> Result<usize> {
+    let mut retries = 0;
+    loop {
+        match agent.apply_scrobble_batch(batch).await {
+            Err(e) if e.is_transient() && retries < config.max_retries => {
+                let delay = config.pause.saturating_mul(1 << retries.min(8));
+                tracing::warn!(error = %e, ?delay, "import batch failed, retrying");
+                tokio::time::sleep(delay).await;
+                retries += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::Profile;
+    use crate::error::{PdsFailure, SdkError};
+    use jacquard::client::{AtpSession, FileAuthStore};
+    use jacquard::common::session::{SessionKey, SessionStore};
+    use jacquard::types::string::Did;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::tcp::OwnedReadHalf;
+    use tokio::net::TcpListener;
+
+    const DID: &str = "did:plc:importer";
+    const HANDLE: &str = "importer.test";
+    const CID: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+    /// A local stand-in for the PDS: an XRPC server committing
+    /// `com.atproto.repo.applyWrites` requests into memory. It can drop the
+    /// first few connections, as an unreachable PDS would, leave the last
+    /// result out of its answers, or reject every write.
+    struct LocalPds {
+        url: String,
+        /// The body of every committed `applyWrites` request.
+        requests: Arc<Mutex<Vec<Value>>>,
+        unreachable: Arc<AtomicU32>,
+        short_results: Arc<AtomicBool>,
+        rejecting: Arc<AtomicBool>,
+    }
+
+    impl LocalPds {
+        async fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let pds = LocalPds {
+                url,
+                requests: Arc::default(),
+                unreachable: Arc::default(),
+                short_results: Arc::default(),
+                rejecting: Arc::default(),
+            };
+
+            let requests = pds.requests.clone();
+            let unreachable = pds.unreachable.clone();
+            let short_results = pds.short_results.clone();
+            let rejecting = pds.rejecting.clone();
+            tokio::spawn(async move {
+                let mut rkey = 0;
+                loop {
+                    let (stream, _) = listener.accept().await.unwrap();
+                    let dropped = unreachable
+                        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                        .is_ok();
+                    if dropped {
+                        continue;
+                    }
+                    let (reader, mut writer) = stream.into_split();
+                    let (path, body) = read_request(reader).await;
+                    let (status, answer) = if rejecting.load(Ordering::SeqCst) {
+                        (
+                            "400 Bad Request",
+                            serde_json::json!({
+                                "error": "InvalidRequest",
+                                "message": "Invalid app.rocksky.scrobble record",
+                            }),
+                        )
+                    } else if path == "/xrpc/com.atproto.repo.applyWrites" {
+                        let mut results: Vec<Value> = body["writes"]
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|write| {
+                                rkey += 1;
+                                serde_json::json!({
+                                    "$type": "com.atproto.repo.applyWrites#createResult",
+                                    "uri": format!("at://{DID}/{}/{rkey}", write["collection"].as_str().unwrap()),
+                                    "cid": CID,
+                                })
+                            })
+                            .collect();
+                        if short_results.load(Ordering::SeqCst) {
+                            results.pop();
+                        }
+                        requests.lock().unwrap().push(body);
+                        ("200 OK", serde_json::json!({ "results": results }))
+                    } else {
+                        (
+                            "404 Not Found",
+                            serde_json::json!({ "error": "MethodNotImplemented" }),
+                        )
+                    };
+                    let answer = answer.to_string();
+                    writer
+                        .write_all(
+                            format!(
+                                "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{answer}",
+                                answer.len()
+                            )
+                            .as_bytes(),
+                        )
+                        .await
+                        .unwrap();
+                }
+            });
+            pds
+        }
+
+        /// An agent logged in to this PDS, with a dedup index under `dir`.
+        async fn agent(&self, dir: &Path) -> RockskyAgent {
+            let session_path = dir.join("session.json");
+            let session: AtpSession = serde_json::from_value(serde_json::json!({
+                "access_jwt": "access",
+                "refresh_jwt": "refresh",
+                "did": DID,
+                "handle": HANDLE,
+                "pds": self.url,
+            }))
+            .unwrap();
+            FileAuthStore::new(&session_path)
+                .set(
+                    SessionKey::new(Did::new_owned(DID).unwrap(), "session"),
+                    session,
+                )
+                .await
+                .unwrap();
+            Profile {
+                did: DID.into(),
+                handle: HANDLE.into(),
+                display_name: None,
+                pds: Some(self.url.clone()),
+                method: "password".into(),
+            }
+            .save(&session_path)
+            .unwrap();
+            RockskyAgent::builder()
+                .session_store(session_path)
+                .dedup_store(dir.join("idx.redb"))
+                .build()
+                .unwrap()
+        }
+
+        /// The records committed per request, as `(collection, title)` pairs.
+        fn committed(&self) -> Vec<Vec<(String, String)>> {
+            self.requests
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|request| {
+                    request["writes"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|write| {
+                            let title = write["value"]["title"].as_str().unwrap_or_default();
+                            (
+                                write["collection"].as_str().unwrap().to_string(),
+                                title.to_string(),
+                            )
+                        })
+                        .collect()
+                })
+                .collect()
+        }
+    }
+
+    /// Read one HTTP request, returning its path and JSON body.
+    async fn read_request(stream: OwnedReadHalf) -> (String, Value) {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+        let path = line.split(' ').nth(1).unwrap_or_default().to_string();
+        let mut length = 0;
+        loop {
+            line.clear();
+            reader.read_line(&mut line).await.unwrap();
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).await.unwrap();
+        (path, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    fn play(title: &str, timestamp: i64) -> ScrobbleDraft {
+        ScrobbleDraft {
+            title: title.into(),
+            artist: "Artist".into(),
+            album: "Album".into(),
+            album_artist: "Artist".into(),
+            timestamp: Some(timestamp),
+            ..Default::default()
+        }
+    }
+
+    fn fast() -> ImportConfig {
+        ImportConfig::default().pause(Duration::ZERO)
+    }
+
+    #[test]
+    fn parses_spotify_extended_history() {
+        let input = r#"[
+          {"ts": "2024-03-01T12:03:00Z", "ms_played": 180000,
+           "master_metadata_track_name": "Chaser",
+           "master_metadata_album_artist_name": "Calibro 35",
+           "master_metadata_album_album_name": "Jazzploitation",
+           "spotify_track_uri": "spotify:track:4uLU6hMCjMI75M1A2tKUQC"},
+          {"ts": "2024-03-01T12:04:00Z", "ms_played": 5000,
+           "master_metadata_track_name": "Skipped",
+           "master_metadata_album_artist_name": "Calibro 35",
+           "master_metadata_album_album_name": "Jazzploitation"},
+          {"ts": "2024-03-01T13:00:00Z", "ms_played": 1800000,
+           "master_metadata_track_name": null, "episode_name": "A podcast"}
+        ]"#;
+        let plays = parse(ImportFormat::SpotifyExtended, input).unwrap();
+        assert_eq!(plays.len(), 1);
+        assert_eq!(plays[0].title, "Chaser");
+        assert_eq!(plays[0].album_artist, "Calibro 35");
+        // Started three minutes before the stream ended.
+        assert_eq!(plays[0].timestamp, Some(1_709_294_400));
+        assert_eq!(
+            plays[0].spotify_link.as_deref(),
+            Some("https://open.spotify.com/track/4uLU6hMCjMI75M1A2tKUQC")
+        );
+    }
+
+    #[test]
+    fn parses_lastfm_csv_with_and_without_header() {
+        let with_header = "uts,utc_time,artist,artist_mbid,album,album_mbid,track,track_mbid\n\
+            1709294400,\"01 Mar 2024, 12:00\",Calibro 35,,Jazzploitation,,Chaser,abc\n";
+        let plays = parse_lastfm_csv(with_header).unwrap();
+        assert_eq!(plays.len(), 1);
+        assert_eq!(plays[0].timestamp, Some(1_709_294_400));
+        assert_eq!(plays[0].mbid.as_deref(), Some("abc"));
+
+        let headerless = "\u{feff}Calibro 35,Jazzploitation,Chaser,01 Mar 2024 12:00\r\n\
+            \"Crosby, Stills & Nash\",\"Crosby, Stills & Nash\",\"Wooden \"\"Ships\"\"\",01 Mar 2024 12:05\r\n\
+            \r\n";
+        let plays = parse_lastfm_csv(headerless).unwrap();
+        assert_eq!(plays.len(), 2);
+        assert_eq!(plays[0].timestamp, Some(1_709_294_400));
+        assert_eq!(plays[1].artist, "Crosby, Stills & Nash");
+        assert_eq!(plays[1].title, "Wooden \"Ships\"");
+        assert_eq!(plays[1].timestamp, Some(1_709_294_700));
+    }
+
+    #[test]
+    fn parses_lastfm_json_pages() {
+        let input = r##"[{"recenttracks": {"track": [
+          {"name": "Now", "artist": {"#text": "Calibro 35"}, "album": {"#text": "X"},
+           "@attr": {"nowplaying": "true"}},
+          {"name": "Chaser", "mbid": "", "artist": {"#text": "Calibro 35"},
+           "album": {"#text": "Jazzploitation"}, "date": {"uts": "1709294400"}}
+        ]}}, {"track": [
+          {"name": "Notte", "artist": {"name": "Calibro 35"}, "album": {"#text": ""},
+           "date": {"uts": "1709294700"}}
+        ]}]"##;
+        let plays = parse_lastfm_json(input).unwrap();
+        assert_eq!(plays.len(), 2);
+        assert_eq!(plays[0].album, "Jazzploitation");
+        assert_eq!(plays[0].mbid, None);
+        assert_eq!(plays[1].artist, "Calibro 35");
+        assert_eq!(plays[1].timestamp, Some(1_709_294_700));
+    }
+
+    #[test]
+    fn parses_listenbrainz_lines_and_arrays() {
+        let line = r#"{"listened_at": 1709294400, "track_metadata": {"artist_name": "Calibro 35", "track_name": "Chaser", "release_name": "Jazzploitation", "additional_info": {"duration_ms": 182320, "isrc": "ITX001", "tracknumber": "3"}, "mbid_mapping": {"recording_mbid": "rec-1"}}}"#;
+        let jsonl = format!("{line}\n\n{line}\n");
+        let plays = parse_listenbrainz(&jsonl).unwrap();
+        assert_eq!(plays.len(), 2);
+        assert_eq!(plays[0].duration_ms, 182_320);
+        assert_eq!(plays[0].track_number, Some(3));
+        assert_eq!(plays[0].mbid.as_deref(), Some("rec-1"));
+        assert_eq!(plays[0].isrc.as_deref(), Some("ITX001"));
+
+        let array = parse_listenbrainz(&format!("[{line}]")).unwrap();
+        assert_eq!(array.len(), 1);
+    }
+
+    #[test]
+    fn detects_formats() {
+        assert_eq!(
+            ImportFormat::detect("export/Streaming_History_Audio_2023.json", "[]"),
+            Some(ImportFormat::SpotifyExtended)
+        );
+        assert_eq!(
+            ImportFormat::detect("scrobbles.csv", ""),
+            Some(ImportFormat::LastfmCsv)
+        );
+        assert_eq!(
+            ImportFormat::detect("listens.jsonl", r#"{"listened_at": 1}"#),
+            Some(ImportFormat::ListenBrainz)
+        );
+        assert_eq!(
+            ImportFormat::detect("page1.json", r#"{"recenttracks": {}}"#),
+            Some(ImportFormat::LastfmJson)
+        );
+        assert_eq!(ImportFormat::detect("notes.txt", "hello"), None);
+    }
+
+    #[tokio::test]
+    async fn imports_in_batches_skipping_duplicates() {
+        let pds = LocalPds::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let agent = pds.agent(dir.path()).await;
+        let index = agent.dedup_index().unwrap();
+        // Already in the repo.
+        index
+            .record_scrobble(
+                DID,
+                "b",
+                "Artist",
+                "Album",
+                20,
+                "at://x/app.rocksky.scrobble/b",
+            )
+            .unwrap();
+        let plays = vec![
+            play("c", 30),
+            play("a", 10),
+            play("b", 20),
+            play("a", 10),
+            play("d", 40),
+        ];
+
+        let report = run(&agent, "export", plays, &fast().batch_size(2))
+            .await
+            .unwrap();
+        assert_eq!(
+            report,
+            ImportReport {
+                parsed: 5,
+                resumed: 0,
+                duplicates: 2,
+                written: 3,
+            }
+        );
+
+        let request = pds.requests.lock().unwrap()[0].clone();
+        assert_eq!(request["repo"], DID);
+        assert_eq!(
+            request["writes"][0]["$type"],
+            "com.atproto.repo.applyWrites#create"
+        );
+        // The artist and album are created once, with the first batch; the
+        // second finds them in the index.
+        let committed = pds.committed();
+        let collections: Vec<Vec<&str>> = committed
+            .iter()
+            .map(|writes| writes.iter().map(|(c, _)| c.as_str()).collect())
+            .collect();
+        assert_eq!(
+            collections,
+            [
+                vec![
+                    "app.rocksky.artist",
+                    "app.rocksky.album",
+                    "app.rocksky.song",
+                    "app.rocksky.scrobble",
+                    "app.rocksky.song",
+                    "app.rocksky.scrobble",
+                ],
+                vec!["app.rocksky.song", "app.rocksky.scrobble"],
+            ]
+        );
+        let titles: Vec<&str> = committed
+            .iter()
+            .flatten()
+            .filter(|(c, _)| c == "app.rocksky.scrobble")
+            .map(|(_, title)| title.as_str())
+            .collect();
+        assert_eq!(titles, ["a", "c", "d"]);
+
+        let uri = index
+            .scrobble_uri(DID, "d", "Artist", "Album", 40)
+            .unwrap()
+            .unwrap();
+        assert_eq!(uri, format!("at://{DID}/app.rocksky.scrobble/8"));
+    }
+
+    #[tokio::test]
+    async fn resumes_from_saved_progress() {
+        let pds = LocalPds::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let agent = pds.agent(dir.path()).await;
+        let config = fast()
+            .batch_size(2)
+            .progress_path(dir.path().join("progress.json"));
+        let plays: Vec<_> = (0..5).map(|i| play(&format!("t{i}"), i)).collect();
+
+        // A first run that got through the first batch only.
+        ImportProgress {
+            sources: BTreeMap::from([("export".to_string(), 2)]),
+        }
+        .save(dir.path().join("progress.json"))
+        .unwrap();
+
+        let report = run(&agent, "export", plays.clone(), &config).await.unwrap();
+        assert_eq!(report.resumed, 2);
+        assert_eq!(report.written, 3);
+        assert_eq!(pds.requests.lock().unwrap().len(), 2);
+        let progress = ImportProgress::load(dir.path().join("progress.json")).unwrap();
+        assert_eq!(progress.sources["export"], 5);
+
+        // Everything's done: a rerun writes nothing.
+        let report = run(&agent, "export", plays, &config).await.unwrap();
+        assert_eq!(report.resumed, 5);
+        assert_eq!(report.written, 0);
+        assert_eq!(pds.requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn retries_transient_failures_then_gives_up() {
+        let pds = LocalPds::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let agent = pds.agent(dir.path()).await;
+
+        pds.unreachable.store(2, Ordering::SeqCst);
+        let report = run(&agent, "export", vec![play("a", 1)], &fast())
+            .await
+            .unwrap();
+        assert_eq!(report.written, 1);
+        assert_eq!(pds.requests.lock().unwrap().len(), 1);
+
+        pds.unreachable.store(10, Ordering::SeqCst);
+        let err = run(&agent, "export", vec![play("b", 2)], &fast().max_retries(1))
+            .await
+            .unwrap_err();
+        assert!(err.is_transient(), "{err}");
+        assert!(!err.is_permanent(), "{err}");
+        assert_eq!(pds.unreachable.load(Ordering::SeqCst), 8);
+    }
+
+    #[tokio::test]
+    async fn rejected_writes_fail_permanently() {
+        let pds = LocalPds::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let agent = pds.agent(dir.path()).await;
+
+        pds.rejecting.store(true, Ordering::SeqCst);
+        let err = run(&agent, "export", vec![play("a", 1)], &fast())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                SdkError::Pds {
+                    failure: PdsFailure::Rejected,
+                    ..
+                }
+            ),
+            "{err}"
+        );
+        assert!(err.is_permanent(), "{err}");
+        assert!(!err.is_transient(), "{err}");
+        assert!(err.to_string().contains("InvalidRequest"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn rejects_answers_missing_results() {
+        let pds = LocalPds::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let agent = pds.agent(dir.path()).await;
+
+        pds.short_results.store(true, Ordering::SeqCst);
+        let err = run(&agent, "export", vec![play("a", 1)], &fast())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SdkError::Other(_)), "{err}");
+        // Nothing is indexed, so a rerun writes the play again.
+        let index = agent.dedup_index().unwrap();
+        assert_eq!(
+            index.scrobble_uri(DID, "a", "Artist", "Album", 1).unwrap(),
+            None
+        );
+        assert_eq!(pds.requests.lock().unwrap().len(), 1);
+    }
+}
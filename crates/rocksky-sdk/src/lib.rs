@@ -70,6 +70,8 @@ pub mod dedup;
 pub mod error;
 pub mod facets;
 pub mod filter;
+#[cfg(feature = "dedup")]
+pub mod import;
 #[cfg(feature = "jetstream")]
 pub mod jetstream;
 pub mod library;
@@ -80,22 +82,35 @@ pub mod remote_controller;
 pub mod remote_player;
 
 pub use agent::{
-    AlbumDraft, ArtistDraft, NowPlaying, RockskyAgent, RockskyAgentBuilder, ScrobbleDraft,
-    ScrobbleMatch, ScrobbleResult, ShoutGif, SongDraft,
+    AlbumDraft, ArtistDraft, BatchOutcome, BatchReport, BatchWrite, NowPlaying, PlaylistDraft,
+    RecordRef, RockskyAgent, RockskyAgentBuilder, ScrobbleDraft, ScrobbleMatch, ScrobbleResult,
+    ShoutGif, SongDraft,
 };
+#[cfg(feature = "dedup")]
+pub use agent::{FlushReport, ScrobbleOutcome};
 pub use appview::{
-    AlbumView, AppView, ArtistView, DateInterval, FeedItem, FeedView, GlobalStats,
-    NotificationActor, NotificationList, NotificationView, ProfileView, ScrobbleView,
-    SearchResults, SongView, UnreadCount, UpdateSeenResult,
+    AlbumDetail, AlbumRecommendations, AlbumView, AppView, ArtistDetail, ArtistRecommendations,
+    ArtistView, AudioSettings, CompatibilityView, DateInterval, FeedItem, FeedView, GlobalStats,
+    ListenerView, NeighbourView, NotificationActor, NotificationList, NotificationView,
+    PlaylistDetail, PlaylistView, ProfileView, RecentListenerView, Recommendations, ScrobbleView,
+    SearchResults, ShoutView, SongView, StatsView, StoryView, UnreadCount, UpdateSeenResult,
+    WrappedView,
 };
 pub use auth::Profile;
 #[cfg(feature = "dedup")]
-pub use dedup::{IndexStats, RepoIndex};
-pub use error::{Result, SdkError};
+pub use dedup::{IndexStats, QueuedScrobble, RepoIndex};
+pub use error::{PdsFailure, Result, SdkError};
 pub use filter::{Filter, FilterValue};
+#[cfg(feature = "dedup")]
+pub use import::{ImportConfig, ImportFormat, ImportProgress, ImportReport};
 #[cfg(feature = "jetstream")]
 pub use jetstream::JetstreamConfig;
-pub use library::Library;
+pub use library::{
+    Library, LibraryAlbum, LibraryAlbumInfo, LibraryArtist, LibraryArtistInfo, LibraryDirectory,
+    LibraryGenre, LibraryIndex, LibraryIndexes, LibraryLicense, LibraryLyrics, LibraryMusicFolder,
+    LibraryNowPlaying, LibraryPlayQueue, LibraryPlaylist, LibraryRadioStation, LibraryScanStatus,
+    LibrarySearchResult, LibrarySong, LibraryStatus, LibraryUser,
+};
 #[cfg(feature = "remote-player")]
 pub use remote_controller::{RemoteController, RemoteControllerConfig, RemoteDevice, RemoteEvent};
 #[cfg(feature = "remote-player")]
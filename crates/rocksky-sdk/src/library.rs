@@ -6,10 +6,14 @@
 //! [`crate::AppView::library`] errors when the client has none). Both the GET
 //! and POST helpers always send `Authorization: Bearer <token>`.
 //!
-//! Outputs are the raw JSON payloads returned by the AppView (the library
-//! lexicons are intentionally loose), so each method returns [`serde_json::Value`].
+//! The library lexicons leave their outputs open: the AppView forwards
+//! navidrome's `subsonic-response` payload as-is. Each named method decodes the
+//! relevant element of that payload into the Subsonic-shaped types at the
+//! bottom of this file (`getAlbum` → `album`, `getAlbumList` → `albumList2.album`,
+//! …). [`Library::get`] / [`Library::post`] stay raw for anything else.
 
 use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::error::{Result, SdkError};
@@ -87,6 +91,28 @@ impl Library {
         self.decode(nsid, res).await
     }
 
+    /// [`Library::query`], decoding the element at `path` of the payload.
+    async fn query_at<T: DeserializeOwned + Default>(
+        &self,
+        nsid: &str,
+        params: Vec<(&str, String)>,
+        path: &[&str],
+    ) -> Result<T> {
+        let payload = self.query(nsid, params).await?;
+        pluck(nsid, payload, path)
+    }
+
+    /// [`Library::procedure`], decoding the element at `path` of the payload.
+    async fn procedure_at<T: DeserializeOwned + Default>(
+        &self,
+        nsid: &str,
+        body: Value,
+        path: &[&str],
+    ) -> Result<T> {
+        let payload = self.procedure(nsid, body).await?;
+        pluck(nsid, payload, path)
+    }
+
     /// Escape hatch — call any authenticated library **query** by nsid. Every
     /// named query method is sugar over this, so it reaches endpoints without a
     /// dedicated wrapper. Empty-valued params are dropped.
@@ -105,71 +131,87 @@ impl Library {
     }
 
     /// Query `app.rocksky.library.ping` (auth required).
-    pub async fn ping(&self) -> Result<Value> {
-        self.query("app.rocksky.library.ping", Vec::new()).await
+    pub async fn ping(&self) -> Result<LibraryStatus> {
+        self.query_at("app.rocksky.library.ping", Vec::new(), &[])
+            .await
     }
 
     /// Query `app.rocksky.library.getLicense` (auth required).
-    pub async fn get_license(&self) -> Result<Value> {
-        self.query("app.rocksky.library.getLicense", Vec::new())
+    pub async fn get_license(&self) -> Result<LibraryLicense> {
+        self.query_at("app.rocksky.library.getLicense", Vec::new(), &["license"])
             .await
     }
 
     /// Query `app.rocksky.library.getMusicFolders` (auth required).
-    pub async fn get_music_folders(&self) -> Result<Value> {
-        self.query("app.rocksky.library.getMusicFolders", Vec::new())
-            .await
+    pub async fn get_music_folders(&self) -> Result<Vec<LibraryMusicFolder>> {
+        self.query_at(
+            "app.rocksky.library.getMusicFolders",
+            Vec::new(),
+            &["musicFolders", "musicFolder"],
+        )
+        .await
     }
 
     /// Query `app.rocksky.library.getScanStatus` (auth required).
-    pub async fn get_scan_status(&self) -> Result<Value> {
-        self.query("app.rocksky.library.getScanStatus", Vec::new())
-            .await
+    pub async fn get_scan_status(&self) -> Result<LibraryScanStatus> {
+        self.query_at(
+            "app.rocksky.library.getScanStatus",
+            Vec::new(),
+            &["scanStatus"],
+        )
+        .await
     }
 
     /// Query `app.rocksky.library.startScan` (auth required).
-    pub async fn start_scan(&self) -> Result<Value> {
-        self.query("app.rocksky.library.startScan", Vec::new())
+    pub async fn start_scan(&self) -> Result<LibraryScanStatus> {
+        self.query_at("app.rocksky.library.startScan", Vec::new(), &["scanStatus"])
             .await
     }
 
     /// Query `app.rocksky.library.getUser` (auth required).
-    pub async fn get_user(&self) -> Result<Value> {
-        self.query("app.rocksky.library.getUser", Vec::new()).await
+    pub async fn get_user(&self) -> Result<LibraryUser> {
+        self.query_at("app.rocksky.library.getUser", Vec::new(), &["user"])
+            .await
     }
 
     /// Query `app.rocksky.library.getArtists` (auth required).
-    pub async fn get_artists(&self) -> Result<Value> {
-        self.query("app.rocksky.library.getArtists", Vec::new())
+    pub async fn get_artists(&self) -> Result<LibraryIndexes> {
+        self.query_at("app.rocksky.library.getArtists", Vec::new(), &["artists"])
             .await
     }
 
     /// Query `app.rocksky.library.getIndexes` (auth required).
-    pub async fn get_indexes(&self) -> Result<Value> {
-        self.query("app.rocksky.library.getIndexes", Vec::new())
+    pub async fn get_indexes(&self) -> Result<LibraryIndexes> {
+        self.query_at("app.rocksky.library.getIndexes", Vec::new(), &["indexes"])
             .await
     }
 
     /// Query `app.rocksky.library.getArtist` (auth required).
-    pub async fn get_artist(&self, id: &str) -> Result<Value> {
+    pub async fn get_artist(&self, id: &str) -> Result<LibraryArtist> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("id", id.to_string()));
-        self.query("app.rocksky.library.getArtist", params).await
+        self.query_at("app.rocksky.library.getArtist", params, &["artist"])
+            .await
     }
 
     /// Query `app.rocksky.library.getArtistInfo` (auth required).
-    pub async fn get_artist_info(&self, id: &str) -> Result<Value> {
+    pub async fn get_artist_info(&self, id: &str) -> Result<LibraryArtistInfo> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("id", id.to_string()));
-        self.query("app.rocksky.library.getArtistInfo", params)
-            .await
+        self.query_at(
+            "app.rocksky.library.getArtistInfo",
+            params,
+            &["artistInfo2"],
+        )
+        .await
     }
 
     /// Query `app.rocksky.library.getAlbum` (auth required).
-    pub async fn get_album(&self, id: &str) -> Result<Value> {
+    pub async fn get_album(&self, id: &str) -> Result<LibraryAlbum> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("id", id.to_string()));
-        self.query("app.rocksky.library.getAlbum", params).await
+        self.query_at("app.rocksky.library.getAlbum", params, &["album"])
+            .await
     }
 
     /// Query `app.rocksky.library.getAlbumList` (auth required).
@@ -181,7 +223,7 @@ impl Library {
         from_year: Option<i64>,
         to_year: Option<i64>,
         genre: Option<&str>,
-    ) -> Result<Value> {
+    ) -> Result<Vec<LibraryAlbum>> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("type", r#type.to_string()));
         if let Some(v) = size {
@@ -199,21 +241,28 @@ impl Library {
         if let Some(v) = genre {
             params.push(("genre", v.to_string()));
         }
-        self.query("app.rocksky.library.getAlbumList", params).await
+        self.query_at(
+            "app.rocksky.library.getAlbumList",
+            params,
+            &["albumList2", "album"],
+        )
+        .await
     }
 
     /// Query `app.rocksky.library.getAlbumInfo` (auth required).
-    pub async fn get_album_info(&self, id: &str) -> Result<Value> {
+    pub async fn get_album_info(&self, id: &str) -> Result<LibraryAlbumInfo> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("id", id.to_string()));
-        self.query("app.rocksky.library.getAlbumInfo", params).await
+        self.query_at("app.rocksky.library.getAlbumInfo", params, &["albumInfo2"])
+            .await
     }
 
     /// Query `app.rocksky.library.getSong` (auth required).
-    pub async fn get_song(&self, id: &str) -> Result<Value> {
+    pub async fn get_song(&self, id: &str) -> Result<LibrarySong> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("id", id.to_string()));
-        self.query("app.rocksky.library.getSong", params).await
+        self.query_at("app.rocksky.library.getSong", params, &["song"])
+            .await
     }
 
     /// Query `app.rocksky.library.getRandomSongs` (auth required).
@@ -223,7 +272,7 @@ impl Library {
         genre: Option<&str>,
         from_year: Option<i64>,
         to_year: Option<i64>,
-    ) -> Result<Value> {
+    ) -> Result<Vec<LibrarySong>> {
         let mut params: Vec<(&str, String)> = Vec::new();
         if let Some(v) = size {
             params.push(("size", v.to_string()));
@@ -237,8 +286,12 @@ impl Library {
         if let Some(v) = to_year {
             params.push(("toYear", v.to_string()));
         }
-        self.query("app.rocksky.library.getRandomSongs", params)
-            .await
+        self.query_at(
+            "app.rocksky.library.getRandomSongs",
+            params,
+            &["randomSongs", "song"],
+        )
+        .await
     }
 
     /// Query `app.rocksky.library.getSongsByGenre` (auth required).
@@ -247,7 +300,7 @@ impl Library {
         genre: &str,
         count: Option<i64>,
         offset: Option<i64>,
-    ) -> Result<Value> {
+    ) -> Result<Vec<LibrarySong>> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("genre", genre.to_string()));
         if let Some(v) = count {
@@ -256,33 +309,58 @@ impl Library {
         if let Some(v) = offset {
             params.push(("offset", v.to_string()));
         }
-        self.query("app.rocksky.library.getSongsByGenre", params)
-            .await
+        self.query_at(
+            "app.rocksky.library.getSongsByGenre",
+            params,
+            &["songsByGenre", "song"],
+        )
+        .await
     }
 
     /// Query `app.rocksky.library.getSimilarSongs` (auth required).
-    pub async fn get_similar_songs(&self, id: &str, count: Option<i64>) -> Result<Value> {
+    pub async fn get_similar_songs(
+        &self,
+        id: &str,
+        count: Option<i64>,
+    ) -> Result<Vec<LibrarySong>> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("id", id.to_string()));
         if let Some(v) = count {
             params.push(("count", v.to_string()));
         }
-        self.query("app.rocksky.library.getSimilarSongs", params)
-            .await
+        self.query_at(
+            "app.rocksky.library.getSimilarSongs",
+            params,
+            &["similarSongs2", "song"],
+        )
+        .await
     }
 
     /// Query `app.rocksky.library.getTopSongs` (auth required).
-    pub async fn get_top_songs(&self, artist: &str, count: Option<i64>) -> Result<Value> {
+    pub async fn get_top_songs(
+        &self,
+        artist: &str,
+        count: Option<i64>,
+    ) -> Result<Vec<LibrarySong>> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("artist", artist.to_string()));
         if let Some(v) = count {
             params.push(("count", v.to_string()));
         }
-        self.query("app.rocksky.library.getTopSongs", params).await
+        self.query_at(
+            "app.rocksky.library.getTopSongs",
+            params,
+            &["topSongs", "song"],
+        )
+        .await
     }
 
     /// Query `app.rocksky.library.getLyrics` (auth required).
-    pub async fn get_lyrics(&self, artist: Option<&str>, title: Option<&str>) -> Result<Value> {
+    pub async fn get_lyrics(
+        &self,
+        artist: Option<&str>,
+        title: Option<&str>,
+    ) -> Result<LibraryLyrics> {
         let mut params: Vec<(&str, String)> = Vec::new();
         if let Some(v) = artist {
             params.push(("artist", v.to_string()));
@@ -290,21 +368,30 @@ impl Library {
         if let Some(v) = title {
             params.push(("title", v.to_string()));
         }
-        self.query("app.rocksky.library.getLyrics", params).await
+        self.query_at("app.rocksky.library.getLyrics", params, &["lyrics"])
+            .await
     }
 
     /// Query `app.rocksky.library.getMusicDirectory` (auth required).
-    pub async fn get_music_directory(&self, id: &str) -> Result<Value> {
+    pub async fn get_music_directory(&self, id: &str) -> Result<LibraryDirectory> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("id", id.to_string()));
-        self.query("app.rocksky.library.getMusicDirectory", params)
-            .await
+        self.query_at(
+            "app.rocksky.library.getMusicDirectory",
+            params,
+            &["directory"],
+        )
+        .await
     }
 
     /// Query `app.rocksky.library.getGenres` (auth required).
-    pub async fn get_genres(&self) -> Result<Value> {
-        self.query("app.rocksky.library.getGenres", Vec::new())
-            .await
+    pub async fn get_genres(&self) -> Result<Vec<LibraryGenre>> {
+        self.query_at(
+            "app.rocksky.library.getGenres",
+            Vec::new(),
+            &["genres", "genre"],
+        )
+        .await
     }
 
     /// Query `app.rocksky.library.search` (auth required).
@@ -317,7 +404,7 @@ impl Library {
         album_offset: Option<i64>,
         song_count: Option<i64>,
         song_offset: Option<i64>,
-    ) -> Result<Value> {
+    ) -> Result<LibrarySearchResult> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("query", query.to_string()));
         if let Some(v) = artist_count {
@@ -338,12 +425,13 @@ impl Library {
         if let Some(v) = song_offset {
             params.push(("songOffset", v.to_string()));
         }
-        self.query("app.rocksky.library.search", params).await
+        self.query_at("app.rocksky.library.search", params, &["searchResult3"])
+            .await
     }
 
     /// Query `app.rocksky.library.getStarred` (auth required).
-    pub async fn get_starred(&self) -> Result<Value> {
-        self.query("app.rocksky.library.getStarred", Vec::new())
+    pub async fn get_starred(&self) -> Result<LibrarySearchResult> {
+        self.query_at("app.rocksky.library.getStarred", Vec::new(), &["starred2"])
             .await
     }
 
@@ -353,7 +441,7 @@ impl Library {
         id: &str,
         album_id: Option<&str>,
         artist_id: Option<&str>,
-    ) -> Result<Value> {
+    ) -> Result<LibraryStatus> {
         let mut body = serde_json::Map::new();
         body.insert("id".into(), Value::String(id.to_string()));
         if let Some(v) = album_id {
@@ -362,7 +450,7 @@ impl Library {
         if let Some(v) = artist_id {
             body.insert("artistId".into(), Value::String(v.to_string()));
         }
-        self.procedure("app.rocksky.library.star", Value::Object(body))
+        self.procedure_at("app.rocksky.library.star", Value::Object(body), &[])
             .await
     }
 
@@ -372,7 +460,7 @@ impl Library {
         id: &str,
         album_id: Option<&str>,
         artist_id: Option<&str>,
-    ) -> Result<Value> {
+    ) -> Result<LibraryStatus> {
         let mut body = serde_json::Map::new();
         body.insert("id".into(), Value::String(id.to_string()));
         if let Some(v) = album_id {
@@ -381,29 +469,38 @@ impl Library {
         if let Some(v) = artist_id {
             body.insert("artistId".into(), Value::String(v.to_string()));
         }
-        self.procedure("app.rocksky.library.unstar", Value::Object(body))
+        self.procedure_at("app.rocksky.library.unstar", Value::Object(body), &[])
             .await
     }
 
     /// Query `app.rocksky.library.getPlaylists` (auth required).
-    pub async fn get_playlists(&self) -> Result<Value> {
-        self.query("app.rocksky.library.getPlaylists", Vec::new())
-            .await
+    pub async fn get_playlists(&self) -> Result<Vec<LibraryPlaylist>> {
+        self.query_at(
+            "app.rocksky.library.getPlaylists",
+            Vec::new(),
+            &["playlists", "playlist"],
+        )
+        .await
     }
 
     /// Query `app.rocksky.library.getPlaylist` (auth required).
-    pub async fn get_playlist(&self, id: &str) -> Result<Value> {
+    pub async fn get_playlist(&self, id: &str) -> Result<LibraryPlaylist> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("id", id.to_string()));
-        self.query("app.rocksky.library.getPlaylist", params).await
+        self.query_at("app.rocksky.library.getPlaylist", params, &["playlist"])
+            .await
     }
 
     /// Call the procedure `app.rocksky.library.createPlaylist` (auth required).
-    pub async fn create_playlist(&self, name: &str) -> Result<Value> {
+    pub async fn create_playlist(&self, name: &str) -> Result<LibraryPlaylist> {
         let mut body = serde_json::Map::new();
         body.insert("name".into(), Value::String(name.to_string()));
-        self.procedure("app.rocksky.library.createPlaylist", Value::Object(body))
-            .await
+        self.procedure_at(
+            "app.rocksky.library.createPlaylist",
+            Value::Object(body),
+            &["playlist"],
+        )
+        .await
     }
 
     /// Call the procedure `app.rocksky.library.updatePlaylist` (auth required).
@@ -414,7 +511,7 @@ impl Library {
         comment: Option<&str>,
         song_id_to_add: Option<&str>,
         song_index_to_remove: Option<i64>,
-    ) -> Result<Value> {
+    ) -> Result<LibraryStatus> {
         let mut body = serde_json::Map::new();
         body.insert("playlistId".into(), Value::String(playlist_id.to_string()));
         if let Some(v) = name {
@@ -429,31 +526,39 @@ impl Library {
         if let Some(v) = song_index_to_remove {
             body.insert("songIndexToRemove".into(), Value::Number(v.into()));
         }
-        self.procedure("app.rocksky.library.updatePlaylist", Value::Object(body))
-            .await
+        self.procedure_at(
+            "app.rocksky.library.updatePlaylist",
+            Value::Object(body),
+            &[],
+        )
+        .await
     }
 
     /// Call the procedure `app.rocksky.library.deletePlaylist` (auth required).
-    pub async fn delete_playlist(&self, id: &str) -> Result<Value> {
+    pub async fn delete_playlist(&self, id: &str) -> Result<LibraryStatus> {
         let mut body = serde_json::Map::new();
         body.insert("id".into(), Value::String(id.to_string()));
-        self.procedure("app.rocksky.library.deletePlaylist", Value::Object(body))
-            .await
+        self.procedure_at(
+            "app.rocksky.library.deletePlaylist",
+            Value::Object(body),
+            &[],
+        )
+        .await
     }
 
     /// Call the procedure `app.rocksky.library.deleteSong` (auth required).
-    pub async fn delete_song(&self, id: &str) -> Result<Value> {
+    pub async fn delete_song(&self, id: &str) -> Result<LibraryStatus> {
         let mut body = serde_json::Map::new();
         body.insert("id".into(), Value::String(id.to_string()));
-        self.procedure("app.rocksky.library.deleteSong", Value::Object(body))
+        self.procedure_at("app.rocksky.library.deleteSong", Value::Object(body), &[])
             .await
     }
 
     /// Call the procedure `app.rocksky.library.deleteAlbum` (auth required).
-    pub async fn delete_album(&self, id: &str) -> Result<Value> {
+    pub async fn delete_album(&self, id: &str) -> Result<LibraryStatus> {
         let mut body = serde_json::Map::new();
         body.insert("id".into(), Value::String(id.to_string()));
-        self.procedure("app.rocksky.library.deleteAlbum", Value::Object(body))
+        self.procedure_at("app.rocksky.library.deleteAlbum", Value::Object(body), &[])
             .await
     }
 
@@ -463,7 +568,7 @@ impl Library {
         id: &str,
         time: Option<i64>,
         submission: Option<bool>,
-    ) -> Result<Value> {
+    ) -> Result<LibraryStatus> {
         let mut body = serde_json::Map::new();
         body.insert("id".into(), Value::String(id.to_string()));
         if let Some(v) = time {
@@ -472,28 +577,40 @@ impl Library {
         if let Some(v) = submission {
             body.insert("submission".into(), Value::Bool(v));
         }
-        self.procedure("app.rocksky.library.scrobble", Value::Object(body))
+        self.procedure_at("app.rocksky.library.scrobble", Value::Object(body), &[])
             .await
     }
 
     /// Call the procedure `app.rocksky.library.updateNowPlaying` (auth required).
-    pub async fn update_now_playing(&self, id: &str) -> Result<Value> {
+    pub async fn update_now_playing(&self, id: &str) -> Result<LibraryStatus> {
         let mut body = serde_json::Map::new();
         body.insert("id".into(), Value::String(id.to_string()));
-        self.procedure("app.rocksky.library.updateNowPlaying", Value::Object(body))
-            .await
+        self.procedure_at(
+            "app.rocksky.library.updateNowPlaying",
+            Value::Object(body),
+            &[],
+        )
+        .await
     }
 
     /// Query `app.rocksky.library.getNowPlaying` (auth required).
-    pub async fn get_now_playing(&self) -> Result<Value> {
-        self.query("app.rocksky.library.getNowPlaying", Vec::new())
-            .await
+    pub async fn get_now_playing(&self) -> Result<Vec<LibraryNowPlaying>> {
+        self.query_at(
+            "app.rocksky.library.getNowPlaying",
+            Vec::new(),
+            &["nowPlaying", "entry"],
+        )
+        .await
     }
 
     /// Query `app.rocksky.library.getPlayQueue` (auth required).
-    pub async fn get_play_queue(&self) -> Result<Value> {
-        self.query("app.rocksky.library.getPlayQueue", Vec::new())
-            .await
+    pub async fn get_play_queue(&self) -> Result<LibraryPlayQueue> {
+        self.query_at(
+            "app.rocksky.library.getPlayQueue",
+            Vec::new(),
+            &["playQueue"],
+        )
+        .await
     }
 
     /// Call the procedure `app.rocksky.library.savePlayQueue` (auth required).
@@ -502,7 +619,7 @@ impl Library {
         id: Option<&str>,
         current: Option<&str>,
         position: Option<i64>,
-    ) -> Result<Value> {
+    ) -> Result<LibraryStatus> {
         let mut body = serde_json::Map::new();
         if let Some(v) = id {
             body.insert("id".into(), Value::String(v.to_string()));
@@ -513,8 +630,12 @@ impl Library {
         if let Some(v) = position {
             body.insert("position".into(), Value::Number(v.into()));
         }
-        self.procedure("app.rocksky.library.savePlayQueue", Value::Object(body))
-            .await
+        self.procedure_at(
+            "app.rocksky.library.savePlayQueue",
+            Value::Object(body),
+            &[],
+        )
+        .await
     }
 
     /// Resolve a media/art URL from `app.rocksky.library.getStreamUrl` (auth required).
@@ -523,7 +644,7 @@ impl Library {
         id: &str,
         max_bit_rate: Option<i64>,
         format: Option<&str>,
-    ) -> Result<Value> {
+    ) -> Result<String> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("id", id.to_string()));
         if let Some(v) = max_bit_rate {
@@ -532,31 +653,512 @@ impl Library {
         if let Some(v) = format {
             params.push(("format", v.to_string()));
         }
-        self.query("app.rocksky.library.getStreamUrl", params).await
+        self.query_at("app.rocksky.library.getStreamUrl", params, &["url"])
+            .await
     }
 
     /// Resolve a media/art URL from `app.rocksky.library.getDownloadUrl` (auth required).
-    pub async fn get_download_url(&self, id: &str) -> Result<Value> {
+    pub async fn get_download_url(&self, id: &str) -> Result<String> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("id", id.to_string()));
-        self.query("app.rocksky.library.getDownloadUrl", params)
+        self.query_at("app.rocksky.library.getDownloadUrl", params, &["url"])
             .await
     }
 
     /// Resolve a media/art URL from `app.rocksky.library.getCoverArtUrl` (auth required).
-    pub async fn get_cover_art_url(&self, id: &str, size: Option<i64>) -> Result<Value> {
+    pub async fn get_cover_art_url(&self, id: &str, size: Option<i64>) -> Result<String> {
         let mut params: Vec<(&str, String)> = Vec::new();
         params.push(("id", id.to_string()));
         if let Some(v) = size {
             params.push(("size", v.to_string()));
         }
-        self.query("app.rocksky.library.getCoverArtUrl", params)
+        self.query_at("app.rocksky.library.getCoverArtUrl", params, &["url"])
             .await
     }
 
     /// Query `app.rocksky.library.getInternetRadioStations` (auth required).
-    pub async fn get_internet_radio_stations(&self) -> Result<Value> {
-        self.query("app.rocksky.library.getInternetRadioStations", Vec::new())
-            .await
+    pub async fn get_internet_radio_stations(&self) -> Result<Vec<LibraryRadioStation>> {
+        self.query_at(
+            "app.rocksky.library.getInternetRadioStations",
+            Vec::new(),
+            &["internetRadioStations", "internetRadioStation"],
+        )
+        .await
+    }
+}
+
+/// Decode the element at `path` inside a Subsonic payload. navidrome omits
+/// empty lists and objects, so a missing (or `null`) element decodes as the
+/// type's default rather than an error.
+fn pluck<T: DeserializeOwned + Default>(
+    nsid: &str,
+    mut payload: Value,
+    path: &[&str],
+) -> Result<T> {
+    for key in path {
+        payload = match payload.get_mut(*key) {
+            Some(v) => v.take(),
+            None => return Ok(T::default()),
+        };
+    }
+    if payload.is_null() {
+        return Ok(T::default());
+    }
+    serde_json::from_value(payload).map_err(|e| SdkError::Other(format!("decode {nsid}: {e}")))
+}
+
+// ---- wire types ----------------------------------------------------------
+//
+// Subsonic / OpenSubsonic response elements as emitted by navidrome (JSON
+// `f=json` form). Ids are opaque strings except music-folder and player ids,
+// which Subsonic defines as integers.
+
+/// The bare `subsonic-response` envelope — what `ping` and the acknowledge-only
+/// procedures (`star`, `scrobble`, `deleteSong`, …) return.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryStatus {
+    /// `"ok"` — failures surface as [`SdkError::AppView`] instead.
+    #[serde(default)]
+    pub status: Option<String>,
+    /// The Subsonic API version.
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub r#type: Option<String>,
+    #[serde(default)]
+    pub server_version: Option<String>,
+    #[serde(default)]
+    pub open_subsonic: Option<bool>,
+}
+
+/// `license`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryLicense {
+    #[serde(default)]
+    pub valid: bool,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub license_expires: Option<String>,
+    #[serde(default)]
+    pub trial_expires: Option<String>,
+}
+
+/// `musicFolder`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryMusicFolder {
+    #[serde(default)]
+    pub id: i64,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// `scanStatus`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryScanStatus {
+    #[serde(default)]
+    pub scanning: bool,
+    /// Files scanned so far.
+    #[serde(default)]
+    pub count: Option<u64>,
+    #[serde(default)]
+    pub folder_count: Option<u64>,
+    #[serde(default)]
+    pub last_scan: Option<String>,
+}
+
+/// `user` — the caller's Subsonic account and its roles.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryUser {
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub scrobbling_enabled: bool,
+    #[serde(default)]
+    pub admin_role: bool,
+    #[serde(default)]
+    pub settings_role: bool,
+    #[serde(default)]
+    pub download_role: bool,
+    #[serde(default)]
+    pub upload_role: bool,
+    #[serde(default)]
+    pub playlist_role: bool,
+    #[serde(default)]
+    pub cover_art_role: bool,
+    #[serde(default)]
+    pub comment_role: bool,
+    #[serde(default)]
+    pub podcast_role: bool,
+    #[serde(default)]
+    pub stream_role: bool,
+    #[serde(default)]
+    pub jukebox_role: bool,
+    #[serde(default)]
+    pub share_role: bool,
+    #[serde(default)]
+    pub video_conversion_role: bool,
+    /// Ids of the music folders the user may access.
+    #[serde(default)]
+    pub folder: Vec<i64>,
+}
+
+/// `artists` / `indexes` — artists bucketed by initial.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryIndexes {
+    #[serde(default)]
+    pub ignored_articles: Option<String>,
+    /// Only set on `getIndexes`.
+    #[serde(default)]
+    pub last_modified: Option<i64>,
+    #[serde(default)]
+    pub index: Vec<LibraryIndex>,
+}
+
+/// `index` — one initial's bucket of artists.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryIndex {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub artist: Vec<LibraryArtist>,
+}
+
+/// `artist` (`ArtistID3`). `album` is filled only by `getArtist`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryArtist {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub cover_art: Option<String>,
+    #[serde(default)]
+    pub artist_image_url: Option<String>,
+    #[serde(default)]
+    pub album_count: Option<u32>,
+    #[serde(default)]
+    pub starred: Option<String>,
+    #[serde(default)]
+    pub album: Vec<LibraryAlbum>,
+}
+
+/// `artistInfo2`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryArtistInfo {
+    #[serde(default)]
+    pub biography: Option<String>,
+    #[serde(default)]
+    pub music_brainz_id: Option<String>,
+    #[serde(default)]
+    pub last_fm_url: Option<String>,
+    #[serde(default)]
+    pub small_image_url: Option<String>,
+    #[serde(default)]
+    pub medium_image_url: Option<String>,
+    #[serde(default)]
+    pub large_image_url: Option<String>,
+    #[serde(default)]
+    pub similar_artist: Vec<LibraryArtist>,
+}
+
+/// `album` (`AlbumID3`). `song` is filled only by `getAlbum`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryAlbum {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub artist_id: Option<String>,
+    #[serde(default)]
+    pub cover_art: Option<String>,
+    #[serde(default)]
+    pub song_count: Option<u32>,
+    /// Total length in seconds.
+    #[serde(default)]
+    pub duration: Option<u64>,
+    #[serde(default)]
+    pub play_count: Option<u64>,
+    #[serde(default)]
+    pub year: Option<i32>,
+    #[serde(default)]
+    pub genre: Option<String>,
+    #[serde(default)]
+    pub created: Option<String>,
+    #[serde(default)]
+    pub starred: Option<String>,
+    #[serde(default)]
+    pub song: Vec<LibrarySong>,
+}
+
+/// `albumInfo2`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryAlbumInfo {
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub music_brainz_id: Option<String>,
+    #[serde(default)]
+    pub last_fm_url: Option<String>,
+    #[serde(default)]
+    pub small_image_url: Option<String>,
+    #[serde(default)]
+    pub medium_image_url: Option<String>,
+    #[serde(default)]
+    pub large_image_url: Option<String>,
+}
+
+/// `song` / `child` / `entry` — a track, or a folder when `is_dir` is set.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibrarySong {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub parent: Option<String>,
+    #[serde(default)]
+    pub is_dir: bool,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub album: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub album_id: Option<String>,
+    #[serde(default)]
+    pub artist_id: Option<String>,
+    #[serde(default)]
+    pub track: Option<u32>,
+    #[serde(default)]
+    pub disc_number: Option<u32>,
+    #[serde(default)]
+    pub year: Option<i32>,
+    #[serde(default)]
+    pub genre: Option<String>,
+    #[serde(default)]
+    pub cover_art: Option<String>,
+    /// File size in bytes.
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub suffix: Option<String>,
+    /// Length in seconds.
+    #[serde(default)]
+    pub duration: Option<u64>,
+    #[serde(default)]
+    pub bit_rate: Option<u32>,
+    #[serde(default)]
+    pub sampling_rate: Option<u32>,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub play_count: Option<u64>,
+    #[serde(default)]
+    pub music_brainz_id: Option<String>,
+    #[serde(default)]
+    pub created: Option<String>,
+    #[serde(default)]
+    pub starred: Option<String>,
+    /// `"music"` for tracks.
+    #[serde(default)]
+    pub r#type: Option<String>,
+}
+
+/// `nowPlaying.entry` — a track someone is playing, with who and where.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryNowPlaying {
+    #[serde(flatten)]
+    pub song: LibrarySong,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub minutes_ago: Option<u32>,
+    #[serde(default)]
+    pub player_id: Option<i64>,
+    #[serde(default)]
+    pub player_name: Option<String>,
+}
+
+/// `directory` — a music-directory level and its children.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryDirectory {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub parent: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub starred: Option<String>,
+    #[serde(default)]
+    pub child: Vec<LibrarySong>,
+}
+
+/// `genre`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryGenre {
+    /// The genre name.
+    #[serde(default)]
+    pub value: String,
+    #[serde(default)]
+    pub song_count: Option<u64>,
+    #[serde(default)]
+    pub album_count: Option<u64>,
+}
+
+/// `searchResult3` / `starred2` — matching (or starred) artists, albums and songs.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibrarySearchResult {
+    #[serde(default)]
+    pub artist: Vec<LibraryArtist>,
+    #[serde(default)]
+    pub album: Vec<LibraryAlbum>,
+    #[serde(default)]
+    pub song: Vec<LibrarySong>,
+}
+
+/// `playlist`. `entry` is filled only by `getPlaylist`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryPlaylist {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub public: bool,
+    #[serde(default)]
+    pub song_count: Option<u32>,
+    /// Total length in seconds.
+    #[serde(default)]
+    pub duration: Option<u64>,
+    #[serde(default)]
+    pub cover_art: Option<String>,
+    #[serde(default)]
+    pub created: Option<String>,
+    #[serde(default)]
+    pub changed: Option<String>,
+    #[serde(default)]
+    pub entry: Vec<LibrarySong>,
+}
+
+/// `lyrics` — plain (unsynced) lyrics; empty when none are known.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryLyrics {
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+/// `playQueue` — the saved play queue; empty when none was saved.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryPlayQueue {
+    #[serde(default)]
+    pub entry: Vec<LibrarySong>,
+    /// Id of the current track.
+    #[serde(default)]
+    pub current: Option<String>,
+    /// Position within the current track, in milliseconds.
+    #[serde(default)]
+    pub position: Option<i64>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub changed: Option<String>,
+    /// The client that saved the queue.
+    #[serde(default)]
+    pub changed_by: Option<String>,
+}
+
+/// `internetRadioStation`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryRadioStation {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub stream_url: Option<String>,
+    #[serde(default)]
+    pub home_page_url: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pluck_decodes_nested_elements() {
+        let payload = json!({
+            "status": "ok",
+            "version": "1.16.1",
+            "albumList2": { "album": [{ "id": "al1", "name": "Hounds of Love", "songCount": 12, "year": 1985 }] }
+        });
+        let albums: Vec<LibraryAlbum> =
+            pluck("getAlbumList", payload.clone(), &["albumList2", "album"]).unwrap();
+        assert_eq!(albums[0].id, "al1");
+        assert_eq!(albums[0].year, Some(1985));
+
+        let status: LibraryStatus = pluck("ping", payload, &[]).unwrap();
+        assert_eq!(status.status.as_deref(), Some("ok"));
+    }
+
+    #[test]
+    fn pluck_defaults_omitted_elements() {
+        let payload = json!({ "status": "ok", "randomSongs": {}, "playQueue": {} });
+        let songs: Vec<LibrarySong> =
+            pluck("getRandomSongs", payload.clone(), &["randomSongs", "song"]).unwrap();
+        assert!(songs.is_empty());
+        let queue: LibraryPlayQueue = pluck("getPlayQueue", payload, &["playQueue"]).unwrap();
+        assert!(queue.current.is_none());
+    }
+
+    #[test]
+    fn now_playing_entries_carry_the_song_and_player() {
+        let payload = json!({
+            "nowPlaying": { "entry": [{
+                "id": "t1", "isDir": false, "title": "Cloudbusting", "duration": 283,
+                "username": "bob.test", "minutesAgo": 2, "playerId": 1
+            }] }
+        });
+        let entries: Vec<LibraryNowPlaying> =
+            pluck("getNowPlaying", payload, &["nowPlaying", "entry"]).unwrap();
+        assert_eq!(entries[0].song.title.as_deref(), Some("Cloudbusting"));
+        assert_eq!(entries[0].song.duration, Some(283));
+        assert_eq!(entries[0].player_id, Some(1));
     }
 }
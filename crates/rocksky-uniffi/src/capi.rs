@@ -14,7 +14,9 @@ use std::cell::RefCell;
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 
-use rocksky_sdk::{RockskyAgent, ScrobbleDraft, ScrobbleMatch};
+use rocksky_sdk::{
+    BatchWrite, PlaylistDraft, RockskyAgent, ScrobbleDraft, ScrobbleMatch, SongDraft,
+};
 
 use crate::RT;
 
@@ -459,12 +461,72 @@ pub unsafe extern "C" fn rocksky_agent_sync_repo(agent: *mut Agent) -> *mut c_ch
             "albums": s.albums,
             "songs": s.songs,
             "scrobbles": s.scrobbles,
+            "playlistItems": s.playlist_items,
             "total": s.total(),
         }))),
         Err(e) => respond::<()>(Err(e.to_string())),
     }
 }
 
+/// Scrobble a play, or keep it in the offline queue when the PDS is unreachable.
+/// `scrobble_json` is a `ScrobbleDraft` (camelCase). Returns
+/// `{"sent": {...}}` or `{"queued": {"depth": n}}`.
+///
+/// # Safety
+/// `agent` must be a live handle; `scrobble_json` a valid C string.
+#[cfg(feature = "dedup")]
+#[no_mangle]
+pub unsafe extern "C" fn rocksky_agent_scrobble_or_queue(
+    agent: *mut Agent,
+    scrobble_json: *const c_char,
+) -> *mut c_char {
+    let a = with_agent(agent);
+    match serde_json::from_str::<ScrobbleDraft>(&cstr(scrobble_json)) {
+        Ok(d) => respond(
+            RT.block_on(a.scrobble_or_queue(&d))
+                .map_err(|e| e.to_string()),
+        ),
+        Err(e) => respond::<()>(Err(e.to_string())),
+    }
+}
+
+/// How many plays are waiting in the offline queue.
+///
+/// # Safety
+/// `agent` must be a live handle from [`rocksky_agent_login`].
+#[cfg(feature = "dedup")]
+#[no_mangle]
+pub unsafe extern "C" fn rocksky_agent_queue_depth(agent: *mut Agent) -> *mut c_char {
+    respond(with_agent(agent).queue_depth().map_err(|e| e.to_string()))
+}
+
+/// Replay the offline queue once, oldest play first; returns the counts of
+/// plays sent, duplicates, dropped and remaining.
+///
+/// # Safety
+/// `agent` must be a live handle from [`rocksky_agent_login`].
+#[cfg(feature = "dedup")]
+#[no_mangle]
+pub unsafe extern "C" fn rocksky_agent_flush_queue(agent: *mut Agent) -> *mut c_char {
+    let a = with_agent(agent);
+    respond(RT.block_on(a.flush_queue()).map_err(|e| e.to_string()))
+}
+
+/// Replay the offline queue in the background, backing off while the PDS stays
+/// unreachable, and return immediately (`{"ok": true}`).
+///
+/// # Safety
+/// `agent` must be a live handle from [`rocksky_agent_login`].
+#[cfg(feature = "dedup")]
+#[no_mangle]
+pub unsafe extern "C" fn rocksky_agent_replay_queue(agent: *mut Agent) -> *mut c_char {
+    let a = (*agent).0.clone();
+    RT.spawn(async move {
+        let _ = a.replay_queue().await;
+    });
+    respond(Ok(true))
+}
+
 /// Keep the local dedup index hydrated from Jetstream in the background and
 /// return immediately (`{"ok": true}`). Runs for the life of the process.
 ///
@@ -506,6 +568,25 @@ pub unsafe extern "C" fn rocksky_agent_follow(
     respond(RT.block_on(a.follow(&cstr(did))).map_err(|e| e.to_string()))
 }
 
+/// Write many records in `applyWrites` transactions of up to 200 writes.
+/// `writes_json` is an array of `BatchWrite`s, e.g.
+/// `[{"scrobble": {…}}, {"like": {"uri": "…", "cid": "…"}}, {"follow": "did:…"}]`.
+/// Returns the per-write outcomes and commit counts.
+///
+/// # Safety
+/// `agent` must be a live handle; `writes_json` a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn rocksky_agent_apply_batch(
+    agent: *mut Agent,
+    writes_json: *const c_char,
+) -> *mut c_char {
+    let a = with_agent(agent);
+    match serde_json::from_str::<Vec<BatchWrite>>(&cstr(writes_json)) {
+        Ok(w) => respond(RT.block_on(a.apply_batch(&w)).map_err(|e| e.to_string())),
+        Err(e) => respond::<()>(Err(e.to_string())),
+    }
+}
+
 /// # Safety
 /// `agent` must be a live handle; the string args valid C strings.
 #[no_mangle]
@@ -584,6 +665,105 @@ pub unsafe extern "C" fn rocksky_agent_reply_shout_with_gif(
     )
 }
 
+/// Create a playlist. `playlist_json` is a `PlaylistDraft` (camelCase, only
+/// `name` required). Returns its `{"uri", "cid"}`.
+///
+/// # Safety
+/// `agent` must be a live handle; `playlist_json` a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn rocksky_agent_create_playlist(
+    agent: *mut Agent,
+    playlist_json: *const c_char,
+) -> *mut c_char {
+    let a = with_agent(agent);
+    match serde_json::from_str::<PlaylistDraft>(&cstr(playlist_json)) {
+        Ok(d) => respond(
+            RT.block_on(a.create_playlist(&d))
+                .map_err(|e| e.to_string()),
+        ),
+        Err(e) => respond::<()>(Err(e.to_string())),
+    }
+}
+
+/// Append a song to the end of a playlist, creating its song record first. A
+/// song already in the playlist is left where it is. `song_json` is a
+/// `SongDraft` (camelCase). Returns the item URI.
+///
+/// # Safety
+/// `agent` must be a live handle; the string args valid C strings.
+#[no_mangle]
+pub unsafe extern "C" fn rocksky_agent_add_to_playlist(
+    agent: *mut Agent,
+    playlist_uri: *const c_char,
+    song_json: *const c_char,
+) -> *mut c_char {
+    let a = with_agent(agent);
+    match serde_json::from_str::<SongDraft>(&cstr(song_json)) {
+        Ok(d) => respond(
+            RT.block_on(a.add_to_playlist(&cstr(playlist_uri), &d))
+                .map_err(|e| e.to_string()),
+        ),
+        Err(e) => respond::<()>(Err(e.to_string())),
+    }
+}
+
+/// Remove a song, by its song URI, from a playlist.
+///
+/// # Safety
+/// `agent` must be a live handle; the string args valid C strings.
+#[no_mangle]
+pub unsafe extern "C" fn rocksky_agent_remove_from_playlist(
+    agent: *mut Agent,
+    playlist_uri: *const c_char,
+    song_uri: *const c_char,
+) -> *mut c_char {
+    let a = with_agent(agent);
+    respond(
+        RT.block_on(a.remove_from_playlist(&cstr(playlist_uri), &cstr(song_uri)))
+            .map(|_| true)
+            .map_err(|e| e.to_string()),
+    )
+}
+
+/// Move the songs of `song_uris_json` (a JSON array of song URIs) to the front
+/// of a playlist, in that order.
+///
+/// # Safety
+/// `agent` must be a live handle; the string args valid C strings.
+#[no_mangle]
+pub unsafe extern "C" fn rocksky_agent_reorder_playlist(
+    agent: *mut Agent,
+    playlist_uri: *const c_char,
+    song_uris_json: *const c_char,
+) -> *mut c_char {
+    let a = with_agent(agent);
+    match serde_json::from_str::<Vec<String>>(&cstr(song_uris_json)) {
+        Ok(uris) => respond(
+            RT.block_on(a.reorder_playlist(&cstr(playlist_uri), &uris))
+                .map(|_| true)
+                .map_err(|e| e.to_string()),
+        ),
+        Err(e) => respond::<()>(Err(e.to_string())),
+    }
+}
+
+/// Delete a playlist and its items.
+///
+/// # Safety
+/// `agent` must be a live handle; `playlist_uri` a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn rocksky_agent_delete_playlist(
+    agent: *mut Agent,
+    playlist_uri: *const c_char,
+) -> *mut c_char {
+    let a = with_agent(agent);
+    respond(
+        RT.block_on(a.delete_playlist(&cstr(playlist_uri)))
+            .map(|_| true)
+            .map_err(|e| e.to_string()),
+    )
+}
+
 /// # Safety
 /// `agent` must be a live handle.
 #[no_mangle]
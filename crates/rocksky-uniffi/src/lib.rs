@@ -288,6 +288,54 @@ impl From<NowPlayingInput> for rocksky_sdk::NowPlaying {
     }
 }
 
+/// A playlist (`app.rocksky.playlist`). Only `name` is required.
+#[derive(Debug, Clone, Default, uniffi::Record)]
+pub struct PlaylistInput {
+    pub name: String,
+    #[uniffi(default = None)]
+    pub description: Option<String>,
+    #[uniffi(default = None)]
+    pub picture_url: Option<String>,
+    #[uniffi(default = None)]
+    pub spotify_link: Option<String>,
+    #[uniffi(default = None)]
+    pub tidal_link: Option<String>,
+    #[uniffi(default = None)]
+    pub youtube_link: Option<String>,
+    #[uniffi(default = None)]
+    pub apple_music_link: Option<String>,
+}
+
+impl From<PlaylistInput> for rocksky_sdk::PlaylistDraft {
+    fn from(p: PlaylistInput) -> Self {
+        rocksky_sdk::PlaylistDraft {
+            name: p.name,
+            description: p.description,
+            picture_url: p.picture_url,
+            spotify_link: p.spotify_link,
+            tidal_link: p.tidal_link,
+            youtube_link: p.youtube_link,
+            apple_music_link: p.apple_music_link,
+        }
+    }
+}
+
+/// The URI and CID of a record, the two halves of a strong reference to it.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct RecordRef {
+    pub uri: String,
+    pub cid: String,
+}
+
+impl From<rocksky_sdk::RecordRef> for RecordRef {
+    fn from(r: rocksky_sdk::RecordRef) -> Self {
+        RecordRef {
+            uri: r.uri,
+            cid: r.cid,
+        }
+    }
+}
+
 /// A GIF / sticker / clip to attach to a shout
 /// (`app.rocksky.shout.defs#gif`). Only `url` is required.
 #[derive(Debug, Clone, Default, uniffi::Record)]
@@ -318,6 +366,45 @@ impl From<ShoutGifInput> for rocksky_sdk::ShoutGif {
     }
 }
 
+/// One record write of `apply_batch`.
+#[derive(Debug, Clone, uniffi::Enum)]
+pub enum BatchWrite {
+    /// A play, with the artist/album/song records it implies.
+    Scrobble {
+        input: ScrobbleInput,
+    },
+    Song {
+        input: SongInput,
+    },
+    Album {
+        input: AlbumInput,
+    },
+    Artist {
+        input: ArtistInput,
+    },
+    Like {
+        uri: String,
+        cid: String,
+    },
+    Follow {
+        did: String,
+    },
+}
+
+impl From<BatchWrite> for rocksky_sdk::BatchWrite {
+    fn from(w: BatchWrite) -> Self {
+        use rocksky_sdk::BatchWrite as C;
+        match w {
+            BatchWrite::Scrobble { input } => C::Scrobble(input.into()),
+            BatchWrite::Song { input } => C::Song(input.into()),
+            BatchWrite::Album { input } => C::Album(input.into()),
+            BatchWrite::Artist { input } => C::Artist(input.into()),
+            BatchWrite::Like { uri, cid } => C::Like { uri, cid },
+            BatchWrite::Follow { did } => C::Follow(did),
+        }
+    }
+}
+
 // ---- output records (SDK -> host) ----------------------------------------
 
 /// The four record URIs a scrobble touches.
@@ -340,6 +427,90 @@ impl From<rocksky_sdk::ScrobbleResult> for ScrobbleResult {
     }
 }
 
+/// What `scrobble_or_queue` did with a play: `result` when it was written now,
+/// else `queued_depth` plays (this one included) are waiting offline.
+#[cfg(feature = "dedup")]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ScrobbleOutcome {
+    pub result: Option<ScrobbleResult>,
+    pub queued_depth: Option<u64>,
+}
+
+#[cfg(feature = "dedup")]
+impl From<rocksky_sdk::ScrobbleOutcome> for ScrobbleOutcome {
+    fn from(o: rocksky_sdk::ScrobbleOutcome) -> Self {
+        match o {
+            rocksky_sdk::ScrobbleOutcome::Sent(r) => ScrobbleOutcome {
+                result: Some(r.into()),
+                queued_depth: None,
+            },
+            rocksky_sdk::ScrobbleOutcome::Queued { depth } => ScrobbleOutcome {
+                result: None,
+                queued_depth: Some(depth as u64),
+            },
+        }
+    }
+}
+
+/// The result of one offline-queue flush.
+#[cfg(feature = "dedup")]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FlushReport {
+    pub sent: u64,
+    pub duplicates: u64,
+    pub dropped: u64,
+    pub remaining: u64,
+}
+
+#[cfg(feature = "dedup")]
+impl From<rocksky_sdk::FlushReport> for FlushReport {
+    fn from(r: rocksky_sdk::FlushReport) -> Self {
+        FlushReport {
+            sent: r.sent as u64,
+            duplicates: r.duplicates as u64,
+            dropped: r.dropped as u64,
+            remaining: r.remaining as u64,
+        }
+    }
+}
+
+/// What `apply_batch` did with one write: the URI of the record it stands
+/// for (the scrobble's, for a play), or why its transaction was rejected.
+#[derive(Debug, Clone, uniffi::Enum)]
+pub enum BatchOutcome {
+    Created { uri: String },
+    Existing { uri: String },
+    Failed { error: String },
+}
+
+impl From<rocksky_sdk::BatchOutcome> for BatchOutcome {
+    fn from(o: rocksky_sdk::BatchOutcome) -> Self {
+        match o {
+            rocksky_sdk::BatchOutcome::Created(uri) => BatchOutcome::Created { uri },
+            rocksky_sdk::BatchOutcome::Existing(uri) => BatchOutcome::Existing { uri },
+            rocksky_sdk::BatchOutcome::Failed(error) => BatchOutcome::Failed { error },
+        }
+    }
+}
+
+/// The result of `apply_batch`: one outcome per write, in order.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct BatchReport {
+    pub outcomes: Vec<BatchOutcome>,
+    pub commits: u64,
+    pub failed_commits: u64,
+}
+
+impl From<rocksky_sdk::BatchReport> for BatchReport {
+    fn from(r: rocksky_sdk::BatchReport) -> Self {
+        BatchReport {
+            outcomes: r.outcomes.into_iter().map(Into::into).collect(),
+            commits: r.commits as u64,
+            failed_commits: r.failed_commits as u64,
+        }
+    }
+}
+
 /// The locally-cached identity after login.
 #[derive(Debug, Clone, uniffi::Record)]
 pub struct Profile {
@@ -695,108 +866,972 @@ impl From<rocksky_sdk::appview::GlobalStats> for GlobalStats {
     }
 }
 
-// ---- read client ---------------------------------------------------------
-
-/// Unauthenticated read client over the public Rocksky AppView.
-#[derive(uniffi::Object)]
-pub struct AppView {
-    inner: rocksky_sdk::AppView,
+/// An album with its tags and tracklist (`app.rocksky.album.getAlbum`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct AlbumDetail {
+    pub id: Option<String>,
+    pub uri: Option<String>,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub artist_uri: Option<String>,
+    pub year: Option<u32>,
+    pub album_art: Option<String>,
+    pub release_date: Option<String>,
+    pub sha256: Option<String>,
+    pub play_count: Option<u64>,
+    pub unique_listeners: Option<u64>,
+    pub tags: Vec<String>,
+    pub tracks: Vec<SongView>,
 }
 
-#[uniffi::export]
-impl AppView {
-    /// `token`, when set, is sent as `Authorization: Bearer <token>` on every
-    /// read — needed only for auth-gated queries.
-    #[uniffi::constructor]
-    pub fn new(base: Option<String>, token: Option<String>) -> Arc<Self> {
-        let base = base.unwrap_or_else(|| rocksky_sdk::DEFAULT_APPVIEW.to_string());
-        let mut inner = rocksky_sdk::AppView::new(base);
-        if let Some(t) = token.filter(|t| !t.is_empty()) {
-            inner.set_token(Some(t));
+impl From<rocksky_sdk::appview::AlbumDetail> for AlbumDetail {
+    fn from(a: rocksky_sdk::appview::AlbumDetail) -> Self {
+        AlbumDetail {
+            id: a.id,
+            uri: a.uri,
+            title: a.title,
+            artist: a.artist,
+            artist_uri: a.artist_uri,
+            year: a.year,
+            album_art: a.album_art,
+            release_date: a.release_date,
+            sha256: a.sha256,
+            play_count: a.play_count,
+            unique_listeners: a.unique_listeners,
+            tags: a.tags,
+            tracks: a.tracks.into_iter().map(Into::into).collect(),
         }
-        Arc::new(Self { inner })
     }
+}
 
-    pub fn profile(&self, actor: String) -> Result<ProfileView, RockskyError> {
-        Ok(RT.block_on(self.inner.profile(&actor)).map_err(err)?.into())
-    }
+/// An artist with detail (`app.rocksky.artist.getArtist`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ArtistDetail {
+    pub id: Option<String>,
+    pub uri: Option<String>,
+    pub name: Option<String>,
+    pub picture: Option<String>,
+    pub sha256: Option<String>,
+    pub play_count: Option<u64>,
+    pub unique_listeners: Option<u64>,
+    pub tags: Vec<String>,
+}
 
-    pub fn scrobbles(
-        &self,
-        actor: String,
-        limit: u32,
-        offset: u32,
-    ) -> Result<Vec<ScrobbleView>, RockskyError> {
-        let out = RT
-            .block_on(self.inner.scrobbles(&actor, limit, offset))
-            .map_err(err)?;
-        Ok(out.into_iter().map(Into::into).collect())
+impl From<rocksky_sdk::appview::ArtistDetail> for ArtistDetail {
+    fn from(a: rocksky_sdk::appview::ArtistDetail) -> Self {
+        ArtistDetail {
+            id: a.id,
+            uri: a.uri,
+            name: a.name,
+            picture: a.picture,
+            sha256: a.sha256,
+            play_count: a.play_count,
+            unique_listeners: a.unique_listeners,
+            tags: a.tags,
+        }
     }
+}
 
-    pub fn songs(
-        &self,
-        actor: String,
-        limit: u32,
-        offset: u32,
-    ) -> Result<Vec<SongView>, RockskyError> {
-        let out = RT
-            .block_on(self.inner.songs(&actor, limit, offset))
-            .map_err(err)?;
-        Ok(out.into_iter().map(Into::into).collect())
+/// A listener's most-played song of an artist.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ListenerSong {
+    pub uri: Option<String>,
+    pub title: Option<String>,
+    pub play_count: Option<u64>,
+}
+
+impl From<rocksky_sdk::appview::ListenerSong> for ListenerSong {
+    fn from(l: rocksky_sdk::appview::ListenerSong) -> Self {
+        ListenerSong {
+            uri: l.uri,
+            title: l.title,
+            play_count: l.play_count,
+        }
     }
+}
 
-    pub fn albums(
-        &self,
-        actor: String,
-        limit: u32,
-        offset: u32,
-    ) -> Result<Vec<AlbumView>, RockskyError> {
-        let out = RT
-            .block_on(self.inner.albums(&actor, limit, offset))
-            .map_err(err)?;
-        Ok(out.into_iter().map(Into::into).collect())
+/// An artist's listener, ranked by plays (`app.rocksky.artist.getArtistListeners`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ListenerView {
+    pub id: Option<String>,
+    pub did: Option<String>,
+    pub handle: Option<String>,
+    pub display_name: Option<String>,
+    pub avatar: Option<String>,
+    pub most_listened_song: Option<ListenerSong>,
+    pub total_plays: Option<u64>,
+    pub rank: Option<u32>,
+}
+
+impl From<rocksky_sdk::appview::ListenerView> for ListenerView {
+    fn from(l: rocksky_sdk::appview::ListenerView) -> Self {
+        ListenerView {
+            id: l.id,
+            did: l.did,
+            handle: l.handle,
+            display_name: l.display_name,
+            avatar: l.avatar,
+            most_listened_song: l.most_listened_song.map(Into::into),
+            total_plays: l.total_plays,
+            rank: l.rank,
+        }
     }
+}
 
-    pub fn artists(
-        &self,
-        actor: String,
-        limit: u32,
-        offset: u32,
-    ) -> Result<Vec<ArtistView>, RockskyError> {
-        let out = RT
-            .block_on(self.inner.artists(&actor, limit, offset))
-            .map_err(err)?;
-        Ok(out.into_iter().map(Into::into).collect())
+/// Someone who recently played an artist or song.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct RecentListenerView {
+    pub id: Option<String>,
+    pub did: Option<String>,
+    pub handle: Option<String>,
+    pub display_name: Option<String>,
+    pub avatar: Option<String>,
+    pub timestamp: Option<String>,
+    pub scrobble_uri: Option<String>,
+}
+
+impl From<rocksky_sdk::appview::RecentListenerView> for RecentListenerView {
+    fn from(r: rocksky_sdk::appview::RecentListenerView) -> Self {
+        RecentListenerView {
+            id: r.id,
+            did: r.did,
+            handle: r.handle,
+            display_name: r.display_name,
+            avatar: r.avatar,
+            timestamp: r.timestamp,
+            scrobble_uri: r.scrobble_uri,
+        }
     }
+}
 
-    pub fn loved_songs(
-        &self,
-        actor: String,
-        limit: u32,
-        offset: u32,
-    ) -> Result<Vec<SongView>, RockskyError> {
-        let out = RT
-            .block_on(self.inner.loved_songs(&actor, limit, offset))
-            .map_err(err)?;
-        Ok(out.into_iter().map(Into::into).collect())
+/// An actor with similar taste (`app.rocksky.actor.getActorNeighbours`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct NeighbourView {
+    pub user_id: Option<String>,
+    pub did: Option<String>,
+    pub handle: Option<String>,
+    pub display_name: Option<String>,
+    pub avatar: Option<String>,
+    pub shared_artists_count: Option<u64>,
+    pub similarity_score: Option<f64>,
+    pub top_shared_artist_names: Vec<String>,
+    pub top_shared_artists_details: Vec<ArtistView>,
+}
+
+impl From<rocksky_sdk::appview::NeighbourView> for NeighbourView {
+    fn from(n: rocksky_sdk::appview::NeighbourView) -> Self {
+        NeighbourView {
+            user_id: n.user_id,
+            did: n.did,
+            handle: n.handle,
+            display_name: n.display_name,
+            avatar: n.avatar,
+            shared_artists_count: n.shared_artists_count,
+            similarity_score: n.similarity_score,
+            top_shared_artist_names: n.top_shared_artist_names,
+            top_shared_artists_details: n
+                .top_shared_artists_details
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        }
     }
+}
 
-    pub fn top_tracks(&self, limit: u32, offset: u32) -> Result<Vec<SongView>, RockskyError> {
-        let out = RT
-            .block_on(self.inner.top_tracks(limit, offset))
-            .map_err(err)?;
-        Ok(out.into_iter().map(Into::into).collect())
+/// An artist two actors share, with each side's rank.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct SharedArtist {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub picture: Option<String>,
+    pub uri: Option<String>,
+    pub user1_rank: Option<u32>,
+    pub user2_rank: Option<u32>,
+    pub weight: Option<f64>,
+}
+
+impl From<rocksky_sdk::appview::SharedArtist> for SharedArtist {
+    fn from(s: rocksky_sdk::appview::SharedArtist) -> Self {
+        SharedArtist {
+            id: s.id,
+            name: s.name,
+            picture: s.picture,
+            uri: s.uri,
+            user1_rank: s.user1_rank,
+            user2_rank: s.user2_rank,
+            weight: s.weight,
+        }
     }
+}
 
-    pub fn top_artists(&self, limit: u32, offset: u32) -> Result<Vec<ArtistView>, RockskyError> {
-        let out = RT
-            .block_on(self.inner.top_artists(limit, offset))
-            .map_err(err)?;
-        Ok(out.into_iter().map(Into::into).collect())
+/// Music compatibility between two actors (`app.rocksky.actor.getActorCompatibility`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct CompatibilityView {
+    pub compatibility_level: Option<u32>,
+    pub compatibility_percentage: Option<f64>,
+    pub shared_artists: Option<u64>,
+    pub top_shared_artist_names: Vec<String>,
+    pub top_shared_detailed_artists: Vec<SharedArtist>,
+    pub user1_artist_count: Option<u64>,
+    pub user2_artist_count: Option<u64>,
+}
+
+impl From<rocksky_sdk::appview::CompatibilityView> for CompatibilityView {
+    fn from(c: rocksky_sdk::appview::CompatibilityView) -> Self {
+        CompatibilityView {
+            compatibility_level: c.compatibility_level,
+            compatibility_percentage: c.compatibility_percentage,
+            shared_artists: c.shared_artists,
+            top_shared_artist_names: c.top_shared_artist_names,
+            top_shared_detailed_artists: c
+                .top_shared_detailed_artists
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            user1_artist_count: c.user1_artist_count,
+            user2_artist_count: c.user2_artist_count,
+        }
     }
+}
 
-    /// Top tracks over a typed [`DateInterval`].
-    pub fn top_tracks_interval(
+/// An actor's totals (`app.rocksky.stats.getStats`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct StatsView {
+    pub scrobbles: u64,
+    pub artists: u64,
+    pub loved_tracks: u64,
+    pub albums: u64,
+    pub tracks: u64,
+}
+
+impl From<rocksky_sdk::appview::StatsView> for StatsView {
+    fn from(s: rocksky_sdk::appview::StatsView) -> Self {
+        StatsView {
+            scrobbles: s.scrobbles,
+            artists: s.artists,
+            loved_tracks: s.loved_tracks,
+            albums: s.albums,
+            tracks: s.tracks,
+        }
+    }
+}
+
+/// An actor's year in review (`app.rocksky.stats.getWrapped`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct WrappedView {
+    pub year: Option<u32>,
+    pub total_scrobbles: Option<u64>,
+    pub total_listening_time_minutes: Option<u64>,
+    pub top_artists: Vec<WrappedArtist>,
+    pub top_tracks: Vec<WrappedTrack>,
+    pub top_albums: Vec<WrappedAlbum>,
+    pub top_genres: Vec<WrappedGenreCount>,
+    pub scrobbles_per_month: Vec<WrappedMonthCount>,
+    pub most_active_day: Option<WrappedDayCount>,
+    pub most_active_hour: Option<u32>,
+    pub new_artists_count: Option<u64>,
+    pub longest_streak: Option<u32>,
+    pub first_scrobble: Option<WrappedMilestone>,
+    pub last_scrobble: Option<WrappedMilestone>,
+}
+
+impl From<rocksky_sdk::appview::WrappedView> for WrappedView {
+    fn from(w: rocksky_sdk::appview::WrappedView) -> Self {
+        WrappedView {
+            year: w.year,
+            total_scrobbles: w.total_scrobbles,
+            total_listening_time_minutes: w.total_listening_time_minutes,
+            top_artists: w.top_artists.into_iter().map(Into::into).collect(),
+            top_tracks: w.top_tracks.into_iter().map(Into::into).collect(),
+            top_albums: w.top_albums.into_iter().map(Into::into).collect(),
+            top_genres: w.top_genres.into_iter().map(Into::into).collect(),
+            scrobbles_per_month: w.scrobbles_per_month.into_iter().map(Into::into).collect(),
+            most_active_day: w.most_active_day.map(Into::into),
+            most_active_hour: w.most_active_hour,
+            new_artists_count: w.new_artists_count,
+            longest_streak: w.longest_streak,
+            first_scrobble: w.first_scrobble.map(Into::into),
+            last_scrobble: w.last_scrobble.map(Into::into),
+        }
+    }
+}
+
+/// A top artist of the year.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct WrappedArtist {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub picture: Option<String>,
+    pub uri: Option<String>,
+    pub play_count: Option<u64>,
+}
+
+impl From<rocksky_sdk::appview::WrappedArtist> for WrappedArtist {
+    fn from(w: rocksky_sdk::appview::WrappedArtist) -> Self {
+        WrappedArtist {
+            id: w.id,
+            name: w.name,
+            picture: w.picture,
+            uri: w.uri,
+            play_count: w.play_count,
+        }
+    }
+}
+
+/// A top track of the year.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct WrappedTrack {
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album_art: Option<String>,
+    pub uri: Option<String>,
+    pub artist_uri: Option<String>,
+    pub album_uri: Option<String>,
+    pub play_count: Option<u64>,
+}
+
+impl From<rocksky_sdk::appview::WrappedTrack> for WrappedTrack {
+    fn from(w: rocksky_sdk::appview::WrappedTrack) -> Self {
+        WrappedTrack {
+            id: w.id,
+            title: w.title,
+            artist: w.artist,
+            album_art: w.album_art,
+            uri: w.uri,
+            artist_uri: w.artist_uri,
+            album_uri: w.album_uri,
+            play_count: w.play_count,
+        }
+    }
+}
+
+/// A top album of the year.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct WrappedAlbum {
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album_art: Option<String>,
+    pub uri: Option<String>,
+    pub play_count: Option<u64>,
+}
+
+impl From<rocksky_sdk::appview::WrappedAlbum> for WrappedAlbum {
+    fn from(w: rocksky_sdk::appview::WrappedAlbum) -> Self {
+        WrappedAlbum {
+            id: w.id,
+            title: w.title,
+            artist: w.artist,
+            album_art: w.album_art,
+            uri: w.uri,
+            play_count: w.play_count,
+        }
+    }
+}
+
+/// Scrobbles for one genre over the year.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct WrappedGenreCount {
+    pub genre: String,
+    pub count: u64,
+}
+
+impl From<rocksky_sdk::appview::WrappedGenreCount> for WrappedGenreCount {
+    fn from(w: rocksky_sdk::appview::WrappedGenreCount) -> Self {
+        WrappedGenreCount {
+            genre: w.genre,
+            count: w.count,
+        }
+    }
+}
+
+/// Scrobbles in one month (1–12).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct WrappedMonthCount {
+    pub month: u32,
+    pub count: u64,
+}
+
+impl From<rocksky_sdk::appview::WrappedMonthCount> for WrappedMonthCount {
+    fn from(w: rocksky_sdk::appview::WrappedMonthCount) -> Self {
+        WrappedMonthCount {
+            month: w.month,
+            count: w.count,
+        }
+    }
+}
+
+/// Scrobbles on one day (`YYYY-MM-DD`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct WrappedDayCount {
+    pub date: String,
+    pub count: u64,
+}
+
+impl From<rocksky_sdk::appview::WrappedDayCount> for WrappedDayCount {
+    fn from(w: rocksky_sdk::appview::WrappedDayCount) -> Self {
+        WrappedDayCount {
+            date: w.date,
+            count: w.count,
+        }
+    }
+}
+
+/// The first or last scrobble of the year.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct WrappedMilestone {
+    pub track_title: Option<String>,
+    pub artist_name: Option<String>,
+    pub timestamp: Option<String>,
+    pub track_uri: Option<String>,
+}
+
+impl From<rocksky_sdk::appview::WrappedMilestone> for WrappedMilestone {
+    fn from(w: rocksky_sdk::appview::WrappedMilestone) -> Self {
+        WrappedMilestone {
+            track_title: w.track_title,
+            artist_name: w.artist_name,
+            timestamp: w.timestamp,
+            track_uri: w.track_uri,
+        }
+    }
+}
+
+/// An entry of the stories row (`app.rocksky.feed.getStories`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct StoryView {
+    pub id: Option<String>,
+    pub uri: Option<String>,
+    pub did: Option<String>,
+    pub handle: Option<String>,
+    pub avatar: Option<String>,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub album_artist: Option<String>,
+    pub album_art: Option<String>,
+    pub track_id: Option<String>,
+    pub track_uri: Option<String>,
+    pub artist_uri: Option<String>,
+    pub album_uri: Option<String>,
+    pub created_at: Option<String>,
+}
+
+impl From<rocksky_sdk::appview::StoryView> for StoryView {
+    fn from(s: rocksky_sdk::appview::StoryView) -> Self {
+        StoryView {
+            id: s.id,
+            uri: s.uri,
+            did: s.did,
+            handle: s.handle,
+            avatar: s.avatar,
+            title: s.title,
+            artist: s.artist,
+            album: s.album,
+            album_artist: s.album_artist,
+            album_art: s.album_art,
+            track_id: s.track_id,
+            track_uri: s.track_uri,
+            artist_uri: s.artist_uri,
+            album_uri: s.album_uri,
+            created_at: s.created_at,
+        }
+    }
+}
+
+/// A recommended track.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct RecommendationView {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub album_art: Option<String>,
+    pub track_uri: Option<String>,
+    pub artist_uri: Option<String>,
+    pub album_uri: Option<String>,
+    pub genres: Vec<String>,
+    pub recommendation_score: Option<f64>,
+    pub source: Option<String>,
+    pub likes_count: Option<u64>,
+}
+
+impl From<rocksky_sdk::appview::RecommendationView> for RecommendationView {
+    fn from(r: rocksky_sdk::appview::RecommendationView) -> Self {
+        RecommendationView {
+            title: r.title,
+            artist: r.artist,
+            album: r.album,
+            album_art: r.album_art,
+            track_uri: r.track_uri,
+            artist_uri: r.artist_uri,
+            album_uri: r.album_uri,
+            genres: r.genres,
+            recommendation_score: r.recommendation_score,
+            source: r.source,
+            likes_count: r.likes_count,
+        }
+    }
+}
+
+/// A recommended artist.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct RecommendedArtistView {
+    pub id: Option<String>,
+    pub uri: Option<String>,
+    pub name: Option<String>,
+    pub picture: Option<String>,
+    pub genres: Vec<String>,
+    pub recommendation_score: Option<f64>,
+    pub source: Option<String>,
+}
+
+impl From<rocksky_sdk::appview::RecommendedArtistView> for RecommendedArtistView {
+    fn from(r: rocksky_sdk::appview::RecommendedArtistView) -> Self {
+        RecommendedArtistView {
+            id: r.id,
+            uri: r.uri,
+            name: r.name,
+            picture: r.picture,
+            genres: r.genres,
+            recommendation_score: r.recommendation_score,
+            source: r.source,
+        }
+    }
+}
+
+/// A recommended album.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct RecommendedAlbumView {
+    pub id: Option<String>,
+    pub uri: Option<String>,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub artist_uri: Option<String>,
+    pub year: Option<u32>,
+    pub album_art: Option<String>,
+    pub recommendation_score: Option<f64>,
+    pub source: Option<String>,
+}
+
+impl From<rocksky_sdk::appview::RecommendedAlbumView> for RecommendedAlbumView {
+    fn from(r: rocksky_sdk::appview::RecommendedAlbumView) -> Self {
+        RecommendedAlbumView {
+            id: r.id,
+            uri: r.uri,
+            title: r.title,
+            artist: r.artist,
+            artist_uri: r.artist_uri,
+            year: r.year,
+            album_art: r.album_art,
+            recommendation_score: r.recommendation_score,
+            source: r.source,
+        }
+    }
+}
+
+/// Track recommendations (`app.rocksky.feed.getRecommendations`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct Recommendations {
+    pub recommendations: Vec<RecommendationView>,
+    pub cursor: Option<String>,
+}
+
+impl From<rocksky_sdk::appview::Recommendations> for Recommendations {
+    fn from(r: rocksky_sdk::appview::Recommendations) -> Self {
+        Recommendations {
+            recommendations: r.recommendations.into_iter().map(Into::into).collect(),
+            cursor: r.cursor,
+        }
+    }
+}
+
+/// Artist recommendations (`app.rocksky.feed.getArtistRecommendations`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ArtistRecommendations {
+    pub artists: Vec<RecommendedArtistView>,
+    pub cursor: Option<String>,
+}
+
+impl From<rocksky_sdk::appview::ArtistRecommendations> for ArtistRecommendations {
+    fn from(a: rocksky_sdk::appview::ArtistRecommendations) -> Self {
+        ArtistRecommendations {
+            artists: a.artists.into_iter().map(Into::into).collect(),
+            cursor: a.cursor,
+        }
+    }
+}
+
+/// Album recommendations (`app.rocksky.feed.getAlbumRecommendations`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct AlbumRecommendations {
+    pub albums: Vec<RecommendedAlbumView>,
+    pub cursor: Option<String>,
+}
+
+impl From<rocksky_sdk::appview::AlbumRecommendations> for AlbumRecommendations {
+    fn from(a: rocksky_sdk::appview::AlbumRecommendations) -> Self {
+        AlbumRecommendations {
+            albums: a.albums.into_iter().map(Into::into).collect(),
+            cursor: a.cursor,
+        }
+    }
+}
+
+/// A playlist summary (`app.rocksky.playlist.getPlaylists`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct PlaylistView {
+    pub id: Option<String>,
+    pub uri: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub cover_image_url: Option<String>,
+    pub curator_did: Option<String>,
+    pub curator_handle: Option<String>,
+    pub curator_name: Option<String>,
+    pub curator_avatar_url: Option<String>,
+    pub created_at: Option<String>,
+    pub track_count: Option<u32>,
+}
+
+impl From<rocksky_sdk::appview::PlaylistView> for PlaylistView {
+    fn from(p: rocksky_sdk::appview::PlaylistView) -> Self {
+        PlaylistView {
+            id: p.id,
+            uri: p.uri,
+            title: p.title,
+            description: p.description,
+            cover_image_url: p.cover_image_url,
+            curator_did: p.curator_did,
+            curator_handle: p.curator_handle,
+            curator_name: p.curator_name,
+            curator_avatar_url: p.curator_avatar_url,
+            created_at: p.created_at,
+            track_count: p.track_count,
+        }
+    }
+}
+
+/// A playlist with its tracks (`app.rocksky.playlist.getPlaylist`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct PlaylistDetail {
+    pub id: Option<String>,
+    pub uri: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub cover_image_url: Option<String>,
+    pub curator_did: Option<String>,
+    pub curator_handle: Option<String>,
+    pub curator_name: Option<String>,
+    pub curator_avatar_url: Option<String>,
+    pub created_at: Option<String>,
+    pub tracks: Vec<SongView>,
+}
+
+impl From<rocksky_sdk::appview::PlaylistDetail> for PlaylistDetail {
+    fn from(p: rocksky_sdk::appview::PlaylistDetail) -> Self {
+        PlaylistDetail {
+            id: p.id,
+            uri: p.uri,
+            title: p.title,
+            description: p.description,
+            cover_image_url: p.cover_image_url,
+            curator_did: p.curator_did,
+            curator_handle: p.curator_handle,
+            curator_name: p.curator_name,
+            curator_avatar_url: p.curator_avatar_url,
+            created_at: p.created_at,
+            tracks: p.tracks.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A shout (comment) on an album, artist, profile or track.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ShoutView {
+    pub id: Option<String>,
+    pub uri: Option<String>,
+    pub content: Option<String>,
+    pub parent: Option<String>,
+    pub created_at: Option<String>,
+    pub likes: Option<u64>,
+    pub liked: Option<bool>,
+    pub author: Option<ShoutAuthor>,
+}
+
+impl From<rocksky_sdk::appview::ShoutView> for ShoutView {
+    fn from(s: rocksky_sdk::appview::ShoutView) -> Self {
+        ShoutView {
+            id: s.id,
+            uri: s.uri,
+            content: s.content,
+            parent: s.parent,
+            created_at: s.created_at,
+            likes: s.likes,
+            liked: s.liked,
+            author: s.author.map(Into::into),
+        }
+    }
+}
+
+/// The author of a shout.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ShoutAuthor {
+    pub id: Option<String>,
+    pub did: Option<String>,
+    pub handle: Option<String>,
+    pub display_name: Option<String>,
+    pub avatar: Option<String>,
+}
+
+impl From<rocksky_sdk::appview::ShoutAuthor> for ShoutAuthor {
+    fn from(s: rocksky_sdk::appview::ShoutAuthor) -> Self {
+        ShoutAuthor {
+            id: s.id,
+            did: s.did,
+            handle: s.handle,
+            display_name: s.display_name,
+            avatar: s.avatar,
+        }
+    }
+}
+
+/// An actor's Rockbox audio settings (`app.rocksky.rockbox.getAudioSettings`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct AudioSettings {
+    pub crossfade: Option<CrossfadeSettings>,
+    pub equalizer: Option<EqualizerSettings>,
+    pub replay_gain: Option<ReplayGainSettings>,
+    pub tone: Option<ToneSettings>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl From<rocksky_sdk::appview::AudioSettings> for AudioSettings {
+    fn from(a: rocksky_sdk::appview::AudioSettings) -> Self {
+        AudioSettings {
+            crossfade: a.crossfade.map(Into::into),
+            equalizer: a.equalizer.map(Into::into),
+            replay_gain: a.replay_gain.map(Into::into),
+            tone: a.tone.map(Into::into),
+            created_at: a.created_at,
+            updated_at: a.updated_at,
+        }
+    }
+}
+
+/// Rockbox crossfade settings.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct CrossfadeSettings {
+    pub mode: Option<String>,
+    pub fade_in_delay: Option<i64>,
+    pub fade_in_duration: Option<i64>,
+    pub fade_out_delay: Option<i64>,
+    pub fade_out_duration: Option<i64>,
+    pub fade_out_mix_mode: Option<String>,
+}
+
+impl From<rocksky_sdk::appview::CrossfadeSettings> for CrossfadeSettings {
+    fn from(c: rocksky_sdk::appview::CrossfadeSettings) -> Self {
+        CrossfadeSettings {
+            mode: c.mode,
+            fade_in_delay: c.fade_in_delay,
+            fade_in_duration: c.fade_in_duration,
+            fade_out_delay: c.fade_out_delay,
+            fade_out_duration: c.fade_out_duration,
+            fade_out_mix_mode: c.fade_out_mix_mode,
+        }
+    }
+}
+
+/// Rockbox equalizer settings.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct EqualizerSettings {
+    pub enabled: Option<bool>,
+    pub precut: Option<i64>,
+    pub bands: Vec<EqualizerBand>,
+}
+
+impl From<rocksky_sdk::appview::EqualizerSettings> for EqualizerSettings {
+    fn from(e: rocksky_sdk::appview::EqualizerSettings) -> Self {
+        EqualizerSettings {
+            enabled: e.enabled,
+            precut: e.precut,
+            bands: e.bands.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// One equalizer band.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct EqualizerBand {
+    pub frequency: i64,
+    pub gain: i64,
+    pub q: i64,
+}
+
+impl From<rocksky_sdk::appview::EqualizerBand> for EqualizerBand {
+    fn from(e: rocksky_sdk::appview::EqualizerBand) -> Self {
+        EqualizerBand {
+            frequency: e.frequency,
+            gain: e.gain,
+            q: e.q,
+        }
+    }
+}
+
+/// Rockbox ReplayGain settings.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ReplayGainSettings {
+    pub mode: Option<String>,
+    pub preamp: Option<i64>,
+    pub prevent_clipping: Option<bool>,
+}
+
+impl From<rocksky_sdk::appview::ReplayGainSettings> for ReplayGainSettings {
+    fn from(r: rocksky_sdk::appview::ReplayGainSettings) -> Self {
+        ReplayGainSettings {
+            mode: r.mode,
+            preamp: r.preamp,
+            prevent_clipping: r.prevent_clipping,
+        }
+    }
+}
+
+/// Rockbox tone settings.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ToneSettings {
+    pub bass: Option<i64>,
+    pub treble: Option<i64>,
+    pub balance: Option<i64>,
+    pub channels: Option<String>,
+}
+
+impl From<rocksky_sdk::appview::ToneSettings> for ToneSettings {
+    fn from(t: rocksky_sdk::appview::ToneSettings) -> Self {
+        ToneSettings {
+            bass: t.bass,
+            treble: t.treble,
+            balance: t.balance,
+            channels: t.channels,
+        }
+    }
+}
+
+// ---- read client ---------------------------------------------------------
+
+/// Unauthenticated read client over the public Rocksky AppView.
+#[derive(uniffi::Object)]
+pub struct AppView {
+    inner: rocksky_sdk::AppView,
+}
+
+impl AppView {
+    /// The raw JSON response of the read query `nsid`, as the JSON-string reads
+    /// return it. Their `_typed` counterparts decode the same response.
+    fn raw(&self, nsid: &str, params: &[(&str, String)]) -> Result<String, RockskyError> {
+        let pairs: Vec<(String, String)> = params
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+        json(RT.block_on(self.inner.get(nsid, &pairs)))
+    }
+}
+
+#[uniffi::export]
+impl AppView {
+    /// `token`, when set, is sent as `Authorization: Bearer <token>` on every
+    /// read — needed only for auth-gated queries.
+    #[uniffi::constructor]
+    pub fn new(base: Option<String>, token: Option<String>) -> Arc<Self> {
+        let base = base.unwrap_or_else(|| rocksky_sdk::DEFAULT_APPVIEW.to_string());
+        let mut inner = rocksky_sdk::AppView::new(base);
+        if let Some(t) = token.filter(|t| !t.is_empty()) {
+            inner.set_token(Some(t));
+        }
+        Arc::new(Self { inner })
+    }
+
+    pub fn profile(&self, actor: String) -> Result<ProfileView, RockskyError> {
+        Ok(RT.block_on(self.inner.profile(&actor)).map_err(err)?.into())
+    }
+
+    pub fn scrobbles(
+        &self,
+        actor: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<ScrobbleView>, RockskyError> {
+        let out = RT
+            .block_on(self.inner.scrobbles(&actor, limit, offset))
+            .map_err(err)?;
+        Ok(out.into_iter().map(Into::into).collect())
+    }
+
+    pub fn songs(
+        &self,
+        actor: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<SongView>, RockskyError> {
+        let out = RT
+            .block_on(self.inner.songs(&actor, limit, offset))
+            .map_err(err)?;
+        Ok(out.into_iter().map(Into::into).collect())
+    }
+
+    pub fn albums(
+        &self,
+        actor: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<AlbumView>, RockskyError> {
+        let out = RT
+            .block_on(self.inner.albums(&actor, limit, offset))
+            .map_err(err)?;
+        Ok(out.into_iter().map(Into::into).collect())
+    }
+
+    pub fn artists(
+        &self,
+        actor: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<ArtistView>, RockskyError> {
+        let out = RT
+            .block_on(self.inner.artists(&actor, limit, offset))
+            .map_err(err)?;
+        Ok(out.into_iter().map(Into::into).collect())
+    }
+
+    pub fn loved_songs(
+        &self,
+        actor: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<SongView>, RockskyError> {
+        let out = RT
+            .block_on(self.inner.loved_songs(&actor, limit, offset))
+            .map_err(err)?;
+        Ok(out.into_iter().map(Into::into).collect())
+    }
+
+    pub fn top_tracks(&self, limit: u32, offset: u32) -> Result<Vec<SongView>, RockskyError> {
+        let out = RT
+            .block_on(self.inner.top_tracks(limit, offset))
+            .map_err(err)?;
+        Ok(out.into_iter().map(Into::into).collect())
+    }
+
+    pub fn top_artists(&self, limit: u32, offset: u32) -> Result<Vec<ArtistView>, RockskyError> {
+        let out = RT
+            .block_on(self.inner.top_artists(limit, offset))
+            .map_err(err)?;
+        Ok(out.into_iter().map(Into::into).collect())
+    }
+
+    /// Top tracks over a typed [`DateInterval`].
+    pub fn top_tracks_interval(
         &self,
         limit: u32,
         offset: u32,
@@ -851,10 +1886,12 @@ impl AppView {
         filter: Option<String>,
     ) -> Result<Vec<ArtistView>, RockskyError> {
         let out = RT
-            .block_on(
-                self.inner
-                    .catalog_artists(limit, offset, genre.as_deref(), filter.as_deref()),
-            )
+            .block_on(self.inner.catalog_artists(
+                limit,
+                offset,
+                genre.as_deref(),
+                filter.as_deref(),
+            ))
             .map_err(err)?;
         Ok(out.into_iter().map(Into::into).collect())
     }
@@ -961,10 +1998,11 @@ impl AppView {
         Ok(RT.block_on(self.inner.global_stats()).map_err(err)?.into())
     }
 
-    // ---- raw JSON reads --------------------------------------------------
+    // ---- detail lookups & the long tail ---------------------------------
     //
-    // The bespoke long tail is returned as a JSON string; host languages parse
-    // it into their native map/dict. `get` reaches any read query by nsid.
+    // The long tail is returned as a JSON string; host languages parse it into
+    // their native map/dict. Detail, stats and social reads also have a
+    // `_typed` form returning records. `get` reaches any read query by nsid.
 
     /// Call any AppView read query by nsid; returns the raw JSON response.
     pub fn get(
@@ -994,12 +2032,24 @@ impl AppView {
         serde_json::to_string(&v).map_err(err)
     }
 
+    /// `app.rocksky.album.getAlbum` — returns the raw JSON payload.
     pub fn album(&self, uri: String) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.album(&uri)))
+        self.raw("app.rocksky.album.getAlbum", &[("uri", uri)])
     }
 
+    /// `app.rocksky.album.getAlbum`, decoded into typed values.
+    pub fn album_typed(&self, uri: String) -> Result<AlbumDetail, RockskyError> {
+        Ok(RT.block_on(self.inner.album(&uri)).map_err(err)?.into())
+    }
+
+    /// `app.rocksky.artist.getArtist` — returns the raw JSON payload.
     pub fn artist(&self, uri: String) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.artist(&uri)))
+        self.raw("app.rocksky.artist.getArtist", &[("uri", uri)])
+    }
+
+    /// `app.rocksky.artist.getArtist`, decoded into typed values.
+    pub fn artist_typed(&self, uri: String) -> Result<ArtistDetail, RockskyError> {
+        Ok(RT.block_on(self.inner.artist(&uri)).map_err(err)?.into())
     }
 
     /// Resolve full canonical metadata for a bare title + artist
@@ -1034,48 +2084,163 @@ impl AppView {
         )))
     }
 
+    /// `app.rocksky.actor.getActorPlaylists` — returns the raw JSON payload.
     pub fn actor_playlists(
         &self,
         actor: String,
         limit: u32,
         offset: u32,
     ) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.actor_playlists(&actor, limit, offset)))
+        self.raw(
+            "app.rocksky.actor.getActorPlaylists",
+            &[
+                ("did", actor),
+                ("limit", limit.to_string()),
+                ("offset", offset.to_string()),
+            ],
+        )
+    }
+
+    /// `app.rocksky.actor.getActorPlaylists`, decoded into typed values.
+    pub fn actor_playlists_typed(
+        &self,
+        actor: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<PlaylistView>, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.actor_playlists(&actor, limit, offset))
+            .map_err(err)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
+    /// `app.rocksky.actor.getActorNeighbours` — returns the raw JSON payload.
     pub fn neighbours(&self, actor: String) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.neighbours(&actor)))
+        self.raw("app.rocksky.actor.getActorNeighbours", &[("did", actor)])
+    }
+
+    /// `app.rocksky.actor.getActorNeighbours`, decoded into typed values.
+    pub fn neighbours_typed(&self, actor: String) -> Result<Vec<NeighbourView>, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.neighbours(&actor))
+            .map_err(err)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
+    /// `app.rocksky.actor.getActorCompatibility` — returns the raw JSON payload.
     pub fn compatibility(&self, actor: String) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.compatibility(&actor)))
+        self.raw("app.rocksky.actor.getActorCompatibility", &[("did", actor)])
+    }
+
+    /// `app.rocksky.actor.getActorCompatibility`, decoded into typed values.
+    pub fn compatibility_typed(
+        &self,
+        actor: String,
+    ) -> Result<Option<CompatibilityView>, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.compatibility(&actor))
+            .map_err(err)?
+            .map(Into::into))
     }
 
+    /// `app.rocksky.artist.getArtistListeners` — returns the raw JSON payload.
     pub fn artist_listeners(
         &self,
         uri: String,
         limit: u32,
         offset: u32,
     ) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.artist_listeners(&uri, limit, offset)))
+        self.raw(
+            "app.rocksky.artist.getArtistListeners",
+            &[
+                ("uri", uri),
+                ("limit", limit.to_string()),
+                ("offset", offset.to_string()),
+            ],
+        )
+    }
+
+    /// `app.rocksky.artist.getArtistListeners`, decoded into typed values.
+    pub fn artist_listeners_typed(
+        &self,
+        uri: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<ListenerView>, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.artist_listeners(&uri, limit, offset))
+            .map_err(err)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
+    /// `app.rocksky.artist.getArtistRecentListeners` — returns the raw JSON payload.
     pub fn artist_recent_listeners(
         &self,
         uri: String,
         limit: u32,
         offset: u32,
     ) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.artist_recent_listeners(&uri, limit, offset)))
+        self.raw(
+            "app.rocksky.artist.getArtistRecentListeners",
+            &[
+                ("uri", uri),
+                ("limit", limit.to_string()),
+                ("offset", offset.to_string()),
+            ],
+        )
+    }
+
+    /// `app.rocksky.artist.getArtistRecentListeners`, decoded into typed values.
+    pub fn artist_recent_listeners_typed(
+        &self,
+        uri: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<RecentListenerView>, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.artist_recent_listeners(&uri, limit, offset))
+            .map_err(err)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
+    /// `app.rocksky.song.getSongRecentListeners` — returns the raw JSON payload.
     pub fn song_recent_listeners(
         &self,
         uri: String,
         limit: u32,
         offset: u32,
     ) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.song_recent_listeners(&uri, limit, offset)))
+        self.raw(
+            "app.rocksky.song.getSongRecentListeners",
+            &[
+                ("uri", uri),
+                ("limit", limit.to_string()),
+                ("offset", offset.to_string()),
+            ],
+        )
+    }
+
+    /// `app.rocksky.song.getSongRecentListeners`, decoded into typed values.
+    pub fn song_recent_listeners_typed(
+        &self,
+        uri: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<RecentListenerView>, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.song_recent_listeners(&uri, limit, offset))
+            .map_err(err)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -1108,45 +2273,153 @@ impl AppView {
         json(RT.block_on(self.inner.feed_generator(&feed)))
     }
 
+    /// `app.rocksky.feed.getStories` — returns the raw JSON payload.
     pub fn stories(
         &self,
         size: Option<u32>,
         feed: Option<String>,
         following: Option<bool>,
     ) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.stories(size, feed.as_deref(), following)))
+        self.raw(
+            "app.rocksky.feed.getStories",
+            &[
+                ("size", size.map(|s| s.to_string()).unwrap_or_default()),
+                ("feed", feed.unwrap_or_default().to_string()),
+                (
+                    "following",
+                    following.map(|b| b.to_string()).unwrap_or_default(),
+                ),
+            ],
+        )
+    }
+
+    /// `app.rocksky.feed.getStories`, decoded into typed values.
+    pub fn stories_typed(
+        &self,
+        size: Option<u32>,
+        feed: Option<String>,
+        following: Option<bool>,
+    ) -> Result<Vec<StoryView>, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.stories(size, feed.as_deref(), following))
+            .map_err(err)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
+    /// `app.rocksky.feed.getRecommendations` — returns the raw JSON payload.
     pub fn recommendations(
         &self,
         actor: String,
         limit: Option<u32>,
     ) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.recommendations(&actor, limit)))
+        self.raw(
+            "app.rocksky.feed.getRecommendations",
+            &[
+                ("did", actor),
+                ("limit", limit.map(|l| l.to_string()).unwrap_or_default()),
+            ],
+        )
+    }
+
+    /// `app.rocksky.feed.getRecommendations`, decoded into typed values.
+    pub fn recommendations_typed(
+        &self,
+        actor: String,
+        limit: Option<u32>,
+    ) -> Result<Recommendations, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.recommendations(&actor, limit))
+            .map_err(err)?
+            .into())
     }
 
+    /// `app.rocksky.feed.getArtistRecommendations` — returns the raw JSON payload.
     pub fn artist_recommendations(
         &self,
         actor: String,
         limit: Option<u32>,
     ) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.artist_recommendations(&actor, limit)))
+        self.raw(
+            "app.rocksky.feed.getArtistRecommendations",
+            &[
+                ("did", actor),
+                ("limit", limit.map(|l| l.to_string()).unwrap_or_default()),
+            ],
+        )
+    }
+
+    /// `app.rocksky.feed.getArtistRecommendations`, decoded into typed values.
+    pub fn artist_recommendations_typed(
+        &self,
+        actor: String,
+        limit: Option<u32>,
+    ) -> Result<ArtistRecommendations, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.artist_recommendations(&actor, limit))
+            .map_err(err)?
+            .into())
     }
 
+    /// `app.rocksky.feed.getAlbumRecommendations` — returns the raw JSON payload.
     pub fn album_recommendations(
         &self,
         actor: String,
         limit: Option<u32>,
     ) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.album_recommendations(&actor, limit)))
+        self.raw(
+            "app.rocksky.feed.getAlbumRecommendations",
+            &[
+                ("did", actor),
+                ("limit", limit.map(|l| l.to_string()).unwrap_or_default()),
+            ],
+        )
+    }
+
+    /// `app.rocksky.feed.getAlbumRecommendations`, decoded into typed values.
+    pub fn album_recommendations_typed(
+        &self,
+        actor: String,
+        limit: Option<u32>,
+    ) -> Result<AlbumRecommendations, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.album_recommendations(&actor, limit))
+            .map_err(err)?
+            .into())
     }
 
+    /// `app.rocksky.stats.getStats` — returns the raw JSON payload.
     pub fn stats(&self, actor: String) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.stats(&actor)))
+        self.raw("app.rocksky.stats.getStats", &[("did", actor)])
+    }
+
+    /// `app.rocksky.stats.getStats`, decoded into typed values.
+    pub fn stats_typed(&self, actor: String) -> Result<StatsView, RockskyError> {
+        Ok(RT.block_on(self.inner.stats(&actor)).map_err(err)?.into())
     }
 
+    /// `app.rocksky.stats.getWrapped` — returns the raw JSON payload.
     pub fn wrapped(&self, actor: String, year: Option<u32>) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.wrapped(&actor, year)))
+        self.raw(
+            "app.rocksky.stats.getWrapped",
+            &[
+                ("did", actor),
+                ("year", year.map(|y| y.to_string()).unwrap_or_default()),
+            ],
+        )
+    }
+
+    /// `app.rocksky.stats.getWrapped`, decoded into typed values.
+    pub fn wrapped_typed(
+        &self,
+        actor: String,
+        year: Option<u32>,
+    ) -> Result<WrappedView, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.wrapped(&actor, year))
+            .map_err(err)?
+            .into())
     }
 
     pub fn mirror_sources(&self) -> Result<String, RockskyError> {
@@ -1174,56 +2447,192 @@ impl AppView {
         json(RT.block_on(self.inner.spotify_currently_playing(&actor)))
     }
 
+    /// `app.rocksky.playlist.getPlaylists` — returns the raw JSON payload.
     pub fn playlists(&self, limit: u32, offset: u32) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.playlists(limit, offset)))
+        self.raw(
+            "app.rocksky.playlist.getPlaylists",
+            &[("limit", limit.to_string()), ("offset", offset.to_string())],
+        )
+    }
+
+    /// `app.rocksky.playlist.getPlaylists`, decoded into typed values.
+    pub fn playlists_typed(
+        &self,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<PlaylistView>, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.playlists(limit, offset))
+            .map_err(err)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
+    /// `app.rocksky.playlist.getPlaylist` — returns the raw JSON payload.
     pub fn playlist(&self, uri: String) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.playlist(&uri)))
+        self.raw("app.rocksky.playlist.getPlaylist", &[("uri", uri)])
     }
 
+    /// `app.rocksky.playlist.getPlaylist`, decoded into typed values.
+    pub fn playlist_typed(&self, uri: String) -> Result<PlaylistDetail, RockskyError> {
+        Ok(RT.block_on(self.inner.playlist(&uri)).map_err(err)?.into())
+    }
+
+    /// `app.rocksky.shout.getAlbumShouts` — returns the raw JSON payload.
     pub fn album_shouts(
         &self,
         uri: String,
         limit: u32,
         offset: u32,
     ) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.album_shouts(&uri, limit, offset)))
+        self.raw(
+            "app.rocksky.shout.getAlbumShouts",
+            &[
+                ("uri", uri),
+                ("limit", limit.to_string()),
+                ("offset", offset.to_string()),
+            ],
+        )
+    }
+
+    /// `app.rocksky.shout.getAlbumShouts`, decoded into typed values.
+    pub fn album_shouts_typed(
+        &self,
+        uri: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<ShoutView>, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.album_shouts(&uri, limit, offset))
+            .map_err(err)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
+    /// `app.rocksky.shout.getArtistShouts` — returns the raw JSON payload.
     pub fn artist_shouts(
         &self,
         uri: String,
         limit: u32,
         offset: u32,
     ) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.artist_shouts(&uri, limit, offset)))
+        self.raw(
+            "app.rocksky.shout.getArtistShouts",
+            &[
+                ("uri", uri),
+                ("limit", limit.to_string()),
+                ("offset", offset.to_string()),
+            ],
+        )
+    }
+
+    /// `app.rocksky.shout.getArtistShouts`, decoded into typed values.
+    pub fn artist_shouts_typed(
+        &self,
+        uri: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<ShoutView>, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.artist_shouts(&uri, limit, offset))
+            .map_err(err)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// `app.rocksky.shout.getProfileShouts` — returns the raw JSON payload.
+    pub fn profile_shouts(
+        &self,
+        actor: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<String, RockskyError> {
+        self.raw(
+            "app.rocksky.shout.getProfileShouts",
+            &[
+                ("did", actor),
+                ("limit", limit.to_string()),
+                ("offset", offset.to_string()),
+            ],
+        )
     }
 
-    pub fn profile_shouts(
+    /// `app.rocksky.shout.getProfileShouts`, decoded into typed values.
+    pub fn profile_shouts_typed(
         &self,
         actor: String,
         limit: u32,
         offset: u32,
-    ) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.profile_shouts(&actor, limit, offset)))
+    ) -> Result<Vec<ShoutView>, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.profile_shouts(&actor, limit, offset))
+            .map_err(err)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
+    /// `app.rocksky.shout.getTrackShouts` — returns the raw JSON payload.
     pub fn track_shouts(&self, uri: String) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.track_shouts(&uri)))
+        self.raw("app.rocksky.shout.getTrackShouts", &[("uri", uri)])
+    }
+
+    /// `app.rocksky.shout.getTrackShouts`, decoded into typed values.
+    pub fn track_shouts_typed(&self, uri: String) -> Result<Vec<ShoutView>, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.track_shouts(&uri))
+            .map_err(err)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
+    /// `app.rocksky.shout.getShoutReplies` — returns the raw JSON payload.
     pub fn shout_replies(
         &self,
         uri: String,
         limit: u32,
         offset: u32,
     ) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.shout_replies(&uri, limit, offset)))
+        self.raw(
+            "app.rocksky.shout.getShoutReplies",
+            &[
+                ("uri", uri),
+                ("limit", limit.to_string()),
+                ("offset", offset.to_string()),
+            ],
+        )
+    }
+
+    /// `app.rocksky.shout.getShoutReplies`, decoded into typed values.
+    pub fn shout_replies_typed(
+        &self,
+        uri: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<ShoutView>, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.shout_replies(&uri, limit, offset))
+            .map_err(err)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
+    /// `app.rocksky.rockbox.getAudioSettings` — returns the raw JSON payload.
     pub fn audio_settings(&self, actor: String) -> Result<String, RockskyError> {
-        json(RT.block_on(self.inner.audio_settings(&actor)))
+        self.raw("app.rocksky.rockbox.getAudioSettings", &[("did", actor)])
+    }
+
+    /// `app.rocksky.rockbox.getAudioSettings`, decoded into typed values.
+    pub fn audio_settings_typed(&self, actor: String) -> Result<AudioSettings, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.audio_settings(&actor))
+            .map_err(err)?
+            .into())
     }
 
     pub fn apikeys(&self, limit: u32, offset: u32) -> Result<String, RockskyError> {
@@ -1367,6 +2776,17 @@ impl Agent {
         RT.block_on(self.inner.unfollow(&did)).map_err(err)
     }
 
+    /// Write many records in `applyWrites` transactions of up to 200 writes
+    /// instead of one request each. A rejected transaction fails only its own
+    /// writes; see the per-write outcomes.
+    pub fn apply_batch(&self, writes: Vec<BatchWrite>) -> Result<BatchReport, RockskyError> {
+        let writes: Vec<rocksky_sdk::BatchWrite> = writes.into_iter().map(Into::into).collect();
+        Ok(RT
+            .block_on(self.inner.apply_batch(&writes))
+            .map_err(err)?
+            .into())
+    }
+
     /// Post a shout on a subject. Returns the shout URI.
     pub fn shout(
         &self,
@@ -1447,6 +2867,51 @@ impl Agent {
     pub fn clear_now_playing(&self) -> Result<(), RockskyError> {
         RT.block_on(self.inner.clear_now_playing()).map_err(err)
     }
+
+    /// Create a playlist. Returns its URI and CID.
+    pub fn create_playlist(&self, input: PlaylistInput) -> Result<RecordRef, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.create_playlist(&input.into()))
+            .map_err(err)?
+            .into())
+    }
+
+    /// Append a song to the end of a playlist, creating its song record first.
+    /// A song already in the playlist is left where it is. Returns the item URI.
+    pub fn add_to_playlist(
+        &self,
+        playlist_uri: String,
+        song: SongInput,
+    ) -> Result<String, RockskyError> {
+        RT.block_on(self.inner.add_to_playlist(&playlist_uri, &song.into()))
+            .map_err(err)
+    }
+
+    /// Remove a song, by its song URI, from a playlist.
+    pub fn remove_from_playlist(
+        &self,
+        playlist_uri: String,
+        song_uri: String,
+    ) -> Result<(), RockskyError> {
+        RT.block_on(self.inner.remove_from_playlist(&playlist_uri, &song_uri))
+            .map_err(err)
+    }
+
+    /// Move the songs of `song_uris` to the front of a playlist, in that order.
+    pub fn reorder_playlist(
+        &self,
+        playlist_uri: String,
+        song_uris: Vec<String>,
+    ) -> Result<(), RockskyError> {
+        RT.block_on(self.inner.reorder_playlist(&playlist_uri, &song_uris))
+            .map_err(err)
+    }
+
+    /// Delete a playlist and its items.
+    pub fn delete_playlist(&self, playlist_uri: String) -> Result<(), RockskyError> {
+        RT.block_on(self.inner.delete_playlist(&playlist_uri))
+            .map_err(err)
+    }
 }
 
 /// Duplicate-prevention index operations (the `dedup` feature). Kept in a
@@ -1464,10 +2929,51 @@ impl Agent {
             "albums": s.albums,
             "songs": s.songs,
             "scrobbles": s.scrobbles,
+            "playlistItems": s.playlist_items,
             "total": s.total(),
         })
         .to_string())
     }
+
+    /// Scrobble a play, or keep it in the offline queue when the PDS is
+    /// unreachable. Queued plays are replayed in order by `flush_queue` /
+    /// `replay_queue`, or before the next `scrobble_or_queue`.
+    pub fn scrobble_or_queue(&self, input: ScrobbleInput) -> Result<ScrobbleOutcome, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.scrobble_or_queue(&input.into()))
+            .map_err(err)?
+            .into())
+    }
+
+    /// `scrobble_match`, with the offline queue of `scrobble_or_queue`.
+    pub fn scrobble_match_or_queue(
+        &self,
+        input: ScrobbleMatchInput,
+    ) -> Result<ScrobbleOutcome, RockskyError> {
+        Ok(RT
+            .block_on(self.inner.scrobble_match_or_queue(&input.into()))
+            .map_err(err)?
+            .into())
+    }
+
+    /// How many plays are waiting in the offline queue.
+    pub fn queue_depth(&self) -> Result<u64, RockskyError> {
+        Ok(self.inner.queue_depth().map_err(err)? as u64)
+    }
+
+    /// Replay the offline queue once, oldest play first.
+    pub fn flush_queue(&self) -> Result<FlushReport, RockskyError> {
+        Ok(RT.block_on(self.inner.flush_queue()).map_err(err)?.into())
+    }
+
+    /// Replay the offline queue in the background, backing off while the PDS
+    /// stays unreachable, and return immediately.
+    pub fn replay_queue(&self) {
+        let agent = self.inner.clone();
+        RT.spawn(async move {
+            let _ = agent.replay_queue().await;
+        });
+    }
 }
 
 /// Jetstream hydration (the `jetstream` feature). Separate export block so the
@@ -1977,14 +3483,540 @@ impl RemoteController {
 
 // ---- library client (auth-gated uploaded-music API) ----------------------
 
+/// The bare Subsonic response — what `ping` and acknowledge-only procedures return.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryStatus {
+    pub status: Option<String>,
+    pub version: Option<String>,
+    pub server_type: Option<String>,
+    pub server_version: Option<String>,
+    pub open_subsonic: Option<bool>,
+}
+
+impl From<rocksky_sdk::library::LibraryStatus> for LibraryStatus {
+    fn from(s: rocksky_sdk::library::LibraryStatus) -> Self {
+        LibraryStatus {
+            status: s.status,
+            version: s.version,
+            server_type: s.r#type,
+            server_version: s.server_version,
+            open_subsonic: s.open_subsonic,
+        }
+    }
+}
+
+/// The Subsonic license (`app.rocksky.library.getLicense`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryLicense {
+    pub valid: bool,
+    pub email: Option<String>,
+    pub license_expires: Option<String>,
+    pub trial_expires: Option<String>,
+}
+
+impl From<rocksky_sdk::library::LibraryLicense> for LibraryLicense {
+    fn from(l: rocksky_sdk::library::LibraryLicense) -> Self {
+        LibraryLicense {
+            valid: l.valid,
+            email: l.email,
+            license_expires: l.license_expires,
+            trial_expires: l.trial_expires,
+        }
+    }
+}
+
+/// A music folder (`app.rocksky.library.getMusicFolders`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryMusicFolder {
+    pub id: i64,
+    pub name: Option<String>,
+}
+
+impl From<rocksky_sdk::library::LibraryMusicFolder> for LibraryMusicFolder {
+    fn from(m: rocksky_sdk::library::LibraryMusicFolder) -> Self {
+        LibraryMusicFolder {
+            id: m.id,
+            name: m.name,
+        }
+    }
+}
+
+/// Library scan progress (`app.rocksky.library.getScanStatus`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryScanStatus {
+    pub scanning: bool,
+    pub count: Option<u64>,
+    pub folder_count: Option<u64>,
+    pub last_scan: Option<String>,
+}
+
+impl From<rocksky_sdk::library::LibraryScanStatus> for LibraryScanStatus {
+    fn from(s: rocksky_sdk::library::LibraryScanStatus) -> Self {
+        LibraryScanStatus {
+            scanning: s.scanning,
+            count: s.count,
+            folder_count: s.folder_count,
+            last_scan: s.last_scan,
+        }
+    }
+}
+
+/// The caller's Subsonic account and roles (`app.rocksky.library.getUser`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryUser {
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub scrobbling_enabled: bool,
+    pub admin_role: bool,
+    pub settings_role: bool,
+    pub download_role: bool,
+    pub upload_role: bool,
+    pub playlist_role: bool,
+    pub cover_art_role: bool,
+    pub comment_role: bool,
+    pub podcast_role: bool,
+    pub stream_role: bool,
+    pub jukebox_role: bool,
+    pub share_role: bool,
+    pub video_conversion_role: bool,
+    pub folder: Vec<i64>,
+}
+
+impl From<rocksky_sdk::library::LibraryUser> for LibraryUser {
+    fn from(u: rocksky_sdk::library::LibraryUser) -> Self {
+        LibraryUser {
+            username: u.username,
+            email: u.email,
+            scrobbling_enabled: u.scrobbling_enabled,
+            admin_role: u.admin_role,
+            settings_role: u.settings_role,
+            download_role: u.download_role,
+            upload_role: u.upload_role,
+            playlist_role: u.playlist_role,
+            cover_art_role: u.cover_art_role,
+            comment_role: u.comment_role,
+            podcast_role: u.podcast_role,
+            stream_role: u.stream_role,
+            jukebox_role: u.jukebox_role,
+            share_role: u.share_role,
+            video_conversion_role: u.video_conversion_role,
+            folder: u.folder,
+        }
+    }
+}
+
+/// Artists bucketed by initial (`getArtists` / `getIndexes`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryIndexes {
+    pub ignored_articles: Option<String>,
+    pub last_modified: Option<i64>,
+    pub index: Vec<LibraryIndex>,
+}
+
+impl From<rocksky_sdk::library::LibraryIndexes> for LibraryIndexes {
+    fn from(i: rocksky_sdk::library::LibraryIndexes) -> Self {
+        LibraryIndexes {
+            ignored_articles: i.ignored_articles,
+            last_modified: i.last_modified,
+            index: i.index.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// One initial's bucket of artists.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryIndex {
+    pub name: String,
+    pub artist: Vec<LibraryArtist>,
+}
+
+impl From<rocksky_sdk::library::LibraryIndex> for LibraryIndex {
+    fn from(i: rocksky_sdk::library::LibraryIndex) -> Self {
+        LibraryIndex {
+            name: i.name,
+            artist: i.artist.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// An uploaded-library artist; `album` is filled by `get_artist`.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryArtist {
+    pub id: String,
+    pub name: Option<String>,
+    pub cover_art: Option<String>,
+    pub artist_image_url: Option<String>,
+    pub album_count: Option<u32>,
+    pub starred: Option<String>,
+    pub album: Vec<LibraryAlbum>,
+}
+
+impl From<rocksky_sdk::library::LibraryArtist> for LibraryArtist {
+    fn from(a: rocksky_sdk::library::LibraryArtist) -> Self {
+        LibraryArtist {
+            id: a.id,
+            name: a.name,
+            cover_art: a.cover_art,
+            artist_image_url: a.artist_image_url,
+            album_count: a.album_count,
+            starred: a.starred,
+            album: a.album.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Biography, images and similar artists (`app.rocksky.library.getArtistInfo`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryArtistInfo {
+    pub biography: Option<String>,
+    pub music_brainz_id: Option<String>,
+    pub last_fm_url: Option<String>,
+    pub small_image_url: Option<String>,
+    pub medium_image_url: Option<String>,
+    pub large_image_url: Option<String>,
+    pub similar_artist: Vec<LibraryArtist>,
+}
+
+impl From<rocksky_sdk::library::LibraryArtistInfo> for LibraryArtistInfo {
+    fn from(a: rocksky_sdk::library::LibraryArtistInfo) -> Self {
+        LibraryArtistInfo {
+            biography: a.biography,
+            music_brainz_id: a.music_brainz_id,
+            last_fm_url: a.last_fm_url,
+            small_image_url: a.small_image_url,
+            medium_image_url: a.medium_image_url,
+            large_image_url: a.large_image_url,
+            similar_artist: a.similar_artist.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// An uploaded-library album; `song` is filled by `get_album`.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryAlbum {
+    pub id: String,
+    pub name: Option<String>,
+    pub artist: Option<String>,
+    pub artist_id: Option<String>,
+    pub cover_art: Option<String>,
+    pub song_count: Option<u32>,
+    pub duration: Option<u64>,
+    pub play_count: Option<u64>,
+    pub year: Option<i32>,
+    pub genre: Option<String>,
+    pub created: Option<String>,
+    pub starred: Option<String>,
+    pub song: Vec<LibrarySong>,
+}
+
+impl From<rocksky_sdk::library::LibraryAlbum> for LibraryAlbum {
+    fn from(a: rocksky_sdk::library::LibraryAlbum) -> Self {
+        LibraryAlbum {
+            id: a.id,
+            name: a.name,
+            artist: a.artist,
+            artist_id: a.artist_id,
+            cover_art: a.cover_art,
+            song_count: a.song_count,
+            duration: a.duration,
+            play_count: a.play_count,
+            year: a.year,
+            genre: a.genre,
+            created: a.created,
+            starred: a.starred,
+            song: a.song.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Album notes and images (`app.rocksky.library.getAlbumInfo`).
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryAlbumInfo {
+    pub notes: Option<String>,
+    pub music_brainz_id: Option<String>,
+    pub last_fm_url: Option<String>,
+    pub small_image_url: Option<String>,
+    pub medium_image_url: Option<String>,
+    pub large_image_url: Option<String>,
+}
+
+impl From<rocksky_sdk::library::LibraryAlbumInfo> for LibraryAlbumInfo {
+    fn from(a: rocksky_sdk::library::LibraryAlbumInfo) -> Self {
+        LibraryAlbumInfo {
+            notes: a.notes,
+            music_brainz_id: a.music_brainz_id,
+            last_fm_url: a.last_fm_url,
+            small_image_url: a.small_image_url,
+            medium_image_url: a.medium_image_url,
+            large_image_url: a.large_image_url,
+        }
+    }
+}
+
+/// An uploaded-library track, or a folder when `is_dir` is set.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibrarySong {
+    pub id: String,
+    pub parent: Option<String>,
+    pub is_dir: bool,
+    pub title: Option<String>,
+    pub album: Option<String>,
+    pub artist: Option<String>,
+    pub album_id: Option<String>,
+    pub artist_id: Option<String>,
+    pub track: Option<u32>,
+    pub disc_number: Option<u32>,
+    pub year: Option<i32>,
+    pub genre: Option<String>,
+    pub cover_art: Option<String>,
+    pub size: Option<u64>,
+    pub content_type: Option<String>,
+    pub suffix: Option<String>,
+    pub duration: Option<u64>,
+    pub bit_rate: Option<u32>,
+    pub sampling_rate: Option<u32>,
+    pub path: Option<String>,
+    pub play_count: Option<u64>,
+    pub music_brainz_id: Option<String>,
+    pub created: Option<String>,
+    pub starred: Option<String>,
+    pub media_type: Option<String>,
+}
+
+impl From<rocksky_sdk::library::LibrarySong> for LibrarySong {
+    fn from(s: rocksky_sdk::library::LibrarySong) -> Self {
+        LibrarySong {
+            id: s.id,
+            parent: s.parent,
+            is_dir: s.is_dir,
+            title: s.title,
+            album: s.album,
+            artist: s.artist,
+            album_id: s.album_id,
+            artist_id: s.artist_id,
+            track: s.track,
+            disc_number: s.disc_number,
+            year: s.year,
+            genre: s.genre,
+            cover_art: s.cover_art,
+            size: s.size,
+            content_type: s.content_type,
+            suffix: s.suffix,
+            duration: s.duration,
+            bit_rate: s.bit_rate,
+            sampling_rate: s.sampling_rate,
+            path: s.path,
+            play_count: s.play_count,
+            music_brainz_id: s.music_brainz_id,
+            created: s.created,
+            starred: s.starred,
+            media_type: s.r#type,
+        }
+    }
+}
+
+/// A track someone is playing, with who and where.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryNowPlaying {
+    pub song: LibrarySong,
+    pub username: Option<String>,
+    pub minutes_ago: Option<u32>,
+    pub player_id: Option<i64>,
+    pub player_name: Option<String>,
+}
+
+impl From<rocksky_sdk::library::LibraryNowPlaying> for LibraryNowPlaying {
+    fn from(n: rocksky_sdk::library::LibraryNowPlaying) -> Self {
+        LibraryNowPlaying {
+            song: n.song.into(),
+            username: n.username,
+            minutes_ago: n.minutes_ago,
+            player_id: n.player_id,
+            player_name: n.player_name,
+        }
+    }
+}
+
+/// A music-directory level and its children.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryDirectory {
+    pub id: String,
+    pub parent: Option<String>,
+    pub name: Option<String>,
+    pub starred: Option<String>,
+    pub child: Vec<LibrarySong>,
+}
+
+impl From<rocksky_sdk::library::LibraryDirectory> for LibraryDirectory {
+    fn from(d: rocksky_sdk::library::LibraryDirectory) -> Self {
+        LibraryDirectory {
+            id: d.id,
+            parent: d.parent,
+            name: d.name,
+            starred: d.starred,
+            child: d.child.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A genre with its song and album counts.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryGenre {
+    pub value: String,
+    pub song_count: Option<u64>,
+    pub album_count: Option<u64>,
+}
+
+impl From<rocksky_sdk::library::LibraryGenre> for LibraryGenre {
+    fn from(g: rocksky_sdk::library::LibraryGenre) -> Self {
+        LibraryGenre {
+            value: g.value,
+            song_count: g.song_count,
+            album_count: g.album_count,
+        }
+    }
+}
+
+/// Matching (or starred) artists, albums and songs.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibrarySearchResult {
+    pub artist: Vec<LibraryArtist>,
+    pub album: Vec<LibraryAlbum>,
+    pub song: Vec<LibrarySong>,
+}
+
+impl From<rocksky_sdk::library::LibrarySearchResult> for LibrarySearchResult {
+    fn from(s: rocksky_sdk::library::LibrarySearchResult) -> Self {
+        LibrarySearchResult {
+            artist: s.artist.into_iter().map(Into::into).collect(),
+            album: s.album.into_iter().map(Into::into).collect(),
+            song: s.song.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// An uploaded-library playlist; `entry` is filled by `get_playlist`.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryPlaylist {
+    pub id: String,
+    pub name: Option<String>,
+    pub comment: Option<String>,
+    pub owner: Option<String>,
+    pub public: bool,
+    pub song_count: Option<u32>,
+    pub duration: Option<u64>,
+    pub cover_art: Option<String>,
+    pub created: Option<String>,
+    pub changed: Option<String>,
+    pub entry: Vec<LibrarySong>,
+}
+
+impl From<rocksky_sdk::library::LibraryPlaylist> for LibraryPlaylist {
+    fn from(p: rocksky_sdk::library::LibraryPlaylist) -> Self {
+        LibraryPlaylist {
+            id: p.id,
+            name: p.name,
+            comment: p.comment,
+            owner: p.owner,
+            public: p.public,
+            song_count: p.song_count,
+            duration: p.duration,
+            cover_art: p.cover_art,
+            created: p.created,
+            changed: p.changed,
+            entry: p.entry.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Plain lyrics; empty when none are known.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryLyrics {
+    pub artist: Option<String>,
+    pub title: Option<String>,
+    pub value: Option<String>,
+}
+
+impl From<rocksky_sdk::library::LibraryLyrics> for LibraryLyrics {
+    fn from(l: rocksky_sdk::library::LibraryLyrics) -> Self {
+        LibraryLyrics {
+            artist: l.artist,
+            title: l.title,
+            value: l.value,
+        }
+    }
+}
+
+/// The saved play queue; empty when none was saved.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryPlayQueue {
+    pub entry: Vec<LibrarySong>,
+    pub current: Option<String>,
+    pub position: Option<i64>,
+    pub username: Option<String>,
+    pub changed: Option<String>,
+    pub changed_by: Option<String>,
+}
+
+impl From<rocksky_sdk::library::LibraryPlayQueue> for LibraryPlayQueue {
+    fn from(p: rocksky_sdk::library::LibraryPlayQueue) -> Self {
+        LibraryPlayQueue {
+            entry: p.entry.into_iter().map(Into::into).collect(),
+            current: p.current,
+            position: p.position,
+            username: p.username,
+            changed: p.changed,
+            changed_by: p.changed_by,
+        }
+    }
+}
+
+/// An internet radio station.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LibraryRadioStation {
+    pub id: String,
+    pub name: Option<String>,
+    pub stream_url: Option<String>,
+    pub home_page_url: Option<String>,
+}
+
+impl From<rocksky_sdk::library::LibraryRadioStation> for LibraryRadioStation {
+    fn from(r: rocksky_sdk::library::LibraryRadioStation) -> Self {
+        LibraryRadioStation {
+            id: r.id,
+            name: r.name,
+            stream_url: r.stream_url,
+            home_page_url: r.home_page_url,
+        }
+    }
+}
+
 /// Authenticated `app.rocksky.library.*` client. A non-empty access token is
 /// mandatory — [`Library::new`] errors without one, so no library call can be
-/// made unauthenticated. Methods return the raw JSON payload as a string.
+/// made unauthenticated. Methods return the raw JSON payload as a string, and
+/// their `_typed` forms return records; `get` / `post` reach any library nsid.
 #[derive(uniffi::Object)]
 pub struct Library {
     inner: rocksky_sdk::Library,
 }
 
+impl Library {
+    /// The raw JSON payload of the library query `nsid`, as the JSON-string
+    /// methods return it.
+    fn raw_get(&self, nsid: &str, params: Vec<(&str, String)>) -> Result<String, RockskyError> {
+        let pairs = params
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+        json(RT.block_on(self.inner.get(nsid, pairs)))
+    }
+
+    /// The raw JSON payload of the library procedure `nsid`.
+    fn raw_post(&self, nsid: &str, body: serde_json::Value) -> Result<String, RockskyError> {
+        json(RT.block_on(self.inner.post(nsid, body)))
+    }
+}
+
 #[uniffi::export]
 impl Library {
     /// Build against an AppView base (default when `None`) with the required
@@ -1996,86 +4028,145 @@ impl Library {
         Ok(Arc::new(Self { inner }))
     }
 
+    /// Call any library **query** by nsid; returns the raw JSON payload.
+    pub fn get(
+        &self,
+        nsid: String,
+        params: std::collections::HashMap<String, String>,
+    ) -> Result<String, RockskyError> {
+        let pairs: Vec<(String, String)> = params.into_iter().collect();
+        json(RT.block_on(self.inner.get(&nsid, pairs)))
+    }
+
+    /// Call any library **procedure** by nsid with a JSON body; returns the raw
+    /// JSON payload.
+    pub fn post(&self, nsid: String, body_json: String) -> Result<String, RockskyError> {
+        let body: serde_json::Value = serde_json::from_str(&body_json).map_err(err)?;
+        json(RT.block_on(self.inner.post(&nsid, body)))
+    }
+
     /// `app.rocksky.library.ping` — returns the raw JSON payload.
     pub fn ping(&self) -> Result<String, RockskyError> {
-        Ok(RT.block_on(self.inner.ping()).map_err(err)?.to_string())
+        self.raw_get("app.rocksky.library.ping", Vec::new())
+    }
+
+    /// `app.rocksky.library.ping`, decoded into typed values.
+    pub fn ping_typed(&self) -> Result<LibraryStatus, RockskyError> {
+        Ok(RT.block_on(self.inner.ping()).map_err(err)?.into())
     }
 
     /// `app.rocksky.library.getLicense` — returns the raw JSON payload.
     pub fn get_license(&self) -> Result<String, RockskyError> {
-        Ok(RT
-            .block_on(self.inner.get_license())
-            .map_err(err)?
-            .to_string())
+        self.raw_get("app.rocksky.library.getLicense", Vec::new())
+    }
+
+    /// `app.rocksky.library.getLicense`, decoded into typed values.
+    pub fn get_license_typed(&self) -> Result<LibraryLicense, RockskyError> {
+        Ok(RT.block_on(self.inner.get_license()).map_err(err)?.into())
     }
 
     /// `app.rocksky.library.getMusicFolders` — returns the raw JSON payload.
     pub fn get_music_folders(&self) -> Result<String, RockskyError> {
+        self.raw_get("app.rocksky.library.getMusicFolders", Vec::new())
+    }
+
+    /// `app.rocksky.library.getMusicFolders`, decoded into typed values.
+    pub fn get_music_folders_typed(&self) -> Result<Vec<LibraryMusicFolder>, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_music_folders())
             .map_err(err)?
-            .to_string())
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
     /// `app.rocksky.library.getScanStatus` — returns the raw JSON payload.
     pub fn get_scan_status(&self) -> Result<String, RockskyError> {
+        self.raw_get("app.rocksky.library.getScanStatus", Vec::new())
+    }
+
+    /// `app.rocksky.library.getScanStatus`, decoded into typed values.
+    pub fn get_scan_status_typed(&self) -> Result<LibraryScanStatus, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_scan_status())
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.startScan` — returns the raw JSON payload.
     pub fn start_scan(&self) -> Result<String, RockskyError> {
-        Ok(RT
-            .block_on(self.inner.start_scan())
-            .map_err(err)?
-            .to_string())
+        self.raw_get("app.rocksky.library.startScan", Vec::new())
+    }
+
+    /// `app.rocksky.library.startScan`, decoded into typed values.
+    pub fn start_scan_typed(&self) -> Result<LibraryScanStatus, RockskyError> {
+        Ok(RT.block_on(self.inner.start_scan()).map_err(err)?.into())
     }
 
     /// `app.rocksky.library.getUser` — returns the raw JSON payload.
     pub fn get_user(&self) -> Result<String, RockskyError> {
-        Ok(RT.block_on(self.inner.get_user()).map_err(err)?.to_string())
+        self.raw_get("app.rocksky.library.getUser", Vec::new())
+    }
+
+    /// `app.rocksky.library.getUser`, decoded into typed values.
+    pub fn get_user_typed(&self) -> Result<LibraryUser, RockskyError> {
+        Ok(RT.block_on(self.inner.get_user()).map_err(err)?.into())
     }
 
     /// `app.rocksky.library.getArtists` — returns the raw JSON payload.
     pub fn get_artists(&self) -> Result<String, RockskyError> {
-        Ok(RT
-            .block_on(self.inner.get_artists())
-            .map_err(err)?
-            .to_string())
+        self.raw_get("app.rocksky.library.getArtists", Vec::new())
+    }
+
+    /// `app.rocksky.library.getArtists`, decoded into typed values.
+    pub fn get_artists_typed(&self) -> Result<LibraryIndexes, RockskyError> {
+        Ok(RT.block_on(self.inner.get_artists()).map_err(err)?.into())
     }
 
     /// `app.rocksky.library.getIndexes` — returns the raw JSON payload.
     pub fn get_indexes(&self) -> Result<String, RockskyError> {
-        Ok(RT
-            .block_on(self.inner.get_indexes())
-            .map_err(err)?
-            .to_string())
+        self.raw_get("app.rocksky.library.getIndexes", Vec::new())
+    }
+
+    /// `app.rocksky.library.getIndexes`, decoded into typed values.
+    pub fn get_indexes_typed(&self) -> Result<LibraryIndexes, RockskyError> {
+        Ok(RT.block_on(self.inner.get_indexes()).map_err(err)?.into())
     }
 
     /// `app.rocksky.library.getArtist` — returns the raw JSON payload.
     pub fn get_artist(&self, id: String) -> Result<String, RockskyError> {
-        Ok(RT
-            .block_on(self.inner.get_artist(&id))
-            .map_err(err)?
-            .to_string())
+        let params = vec![("id", id)];
+        self.raw_get("app.rocksky.library.getArtist", params)
+    }
+
+    /// `app.rocksky.library.getArtist`, decoded into typed values.
+    pub fn get_artist_typed(&self, id: String) -> Result<LibraryArtist, RockskyError> {
+        Ok(RT.block_on(self.inner.get_artist(&id)).map_err(err)?.into())
     }
 
     /// `app.rocksky.library.getArtistInfo` — returns the raw JSON payload.
     pub fn get_artist_info(&self, id: String) -> Result<String, RockskyError> {
+        let params = vec![("id", id)];
+        self.raw_get("app.rocksky.library.getArtistInfo", params)
+    }
+
+    /// `app.rocksky.library.getArtistInfo`, decoded into typed values.
+    pub fn get_artist_info_typed(&self, id: String) -> Result<LibraryArtistInfo, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_artist_info(&id))
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.getAlbum` — returns the raw JSON payload.
     pub fn get_album(&self, id: String) -> Result<String, RockskyError> {
-        Ok(RT
-            .block_on(self.inner.get_album(&id))
-            .map_err(err)?
-            .to_string())
+        let params = vec![("id", id)];
+        self.raw_get("app.rocksky.library.getAlbum", params)
+    }
+
+    /// `app.rocksky.library.getAlbum`, decoded into typed values.
+    pub fn get_album_typed(&self, id: String) -> Result<LibraryAlbum, RockskyError> {
+        Ok(RT.block_on(self.inner.get_album(&id)).map_err(err)?.into())
     }
 
     /// `app.rocksky.library.getAlbumList` — returns the raw JSON payload.
@@ -2088,6 +4179,36 @@ impl Library {
         to_year: Option<i64>,
         genre: Option<String>,
     ) -> Result<String, RockskyError> {
+        let mut params: Vec<(&str, String)> = Vec::new();
+        params.push(("type", r#type));
+        if let Some(v) = size {
+            params.push(("size", v.to_string()));
+        }
+        if let Some(v) = offset {
+            params.push(("offset", v.to_string()));
+        }
+        if let Some(v) = from_year {
+            params.push(("fromYear", v.to_string()));
+        }
+        if let Some(v) = to_year {
+            params.push(("toYear", v.to_string()));
+        }
+        if let Some(v) = genre {
+            params.push(("genre", v.to_string()));
+        }
+        self.raw_get("app.rocksky.library.getAlbumList", params)
+    }
+
+    /// `app.rocksky.library.getAlbumList`, decoded into typed values.
+    pub fn get_album_list_typed(
+        &self,
+        r#type: String,
+        size: Option<i64>,
+        offset: Option<i64>,
+        from_year: Option<i64>,
+        to_year: Option<i64>,
+        genre: Option<String>,
+    ) -> Result<Vec<LibraryAlbum>, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_album_list(
                 &r#type,
@@ -2098,23 +4219,34 @@ impl Library {
                 genre.as_deref(),
             ))
             .map_err(err)?
-            .to_string())
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
     /// `app.rocksky.library.getAlbumInfo` — returns the raw JSON payload.
     pub fn get_album_info(&self, id: String) -> Result<String, RockskyError> {
+        let params = vec![("id", id)];
+        self.raw_get("app.rocksky.library.getAlbumInfo", params)
+    }
+
+    /// `app.rocksky.library.getAlbumInfo`, decoded into typed values.
+    pub fn get_album_info_typed(&self, id: String) -> Result<LibraryAlbumInfo, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_album_info(&id))
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.getSong` — returns the raw JSON payload.
     pub fn get_song(&self, id: String) -> Result<String, RockskyError> {
-        Ok(RT
-            .block_on(self.inner.get_song(&id))
-            .map_err(err)?
-            .to_string())
+        let params = vec![("id", id)];
+        self.raw_get("app.rocksky.library.getSong", params)
+    }
+
+    /// `app.rocksky.library.getSong`, decoded into typed values.
+    pub fn get_song_typed(&self, id: String) -> Result<LibrarySong, RockskyError> {
+        Ok(RT.block_on(self.inner.get_song(&id)).map_err(err)?.into())
     }
 
     /// `app.rocksky.library.getRandomSongs` — returns the raw JSON payload.
@@ -2125,13 +4257,39 @@ impl Library {
         from_year: Option<i64>,
         to_year: Option<i64>,
     ) -> Result<String, RockskyError> {
+        let mut params: Vec<(&str, String)> = Vec::new();
+        if let Some(v) = size {
+            params.push(("size", v.to_string()));
+        }
+        if let Some(v) = genre {
+            params.push(("genre", v.to_string()));
+        }
+        if let Some(v) = from_year {
+            params.push(("fromYear", v.to_string()));
+        }
+        if let Some(v) = to_year {
+            params.push(("toYear", v.to_string()));
+        }
+        self.raw_get("app.rocksky.library.getRandomSongs", params)
+    }
+
+    /// `app.rocksky.library.getRandomSongs`, decoded into typed values.
+    pub fn get_random_songs_typed(
+        &self,
+        size: Option<i64>,
+        genre: Option<String>,
+        from_year: Option<i64>,
+        to_year: Option<i64>,
+    ) -> Result<Vec<LibrarySong>, RockskyError> {
         Ok(RT
             .block_on(
                 self.inner
                     .get_random_songs(size, genre.as_deref(), from_year, to_year),
             )
             .map_err(err)?
-            .to_string())
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
     /// `app.rocksky.library.getSongsByGenre` — returns the raw JSON payload.
@@ -2141,10 +4299,30 @@ impl Library {
         count: Option<i64>,
         offset: Option<i64>,
     ) -> Result<String, RockskyError> {
+        let mut params: Vec<(&str, String)> = Vec::new();
+        params.push(("genre", genre));
+        if let Some(v) = count {
+            params.push(("count", v.to_string()));
+        }
+        if let Some(v) = offset {
+            params.push(("offset", v.to_string()));
+        }
+        self.raw_get("app.rocksky.library.getSongsByGenre", params)
+    }
+
+    /// `app.rocksky.library.getSongsByGenre`, decoded into typed values.
+    pub fn get_songs_by_genre_typed(
+        &self,
+        genre: String,
+        count: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<Vec<LibrarySong>, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_songs_by_genre(&genre, count, offset))
             .map_err(err)?
-            .to_string())
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
     /// `app.rocksky.library.getSimilarSongs` — returns the raw JSON payload.
@@ -2153,10 +4331,26 @@ impl Library {
         id: String,
         count: Option<i64>,
     ) -> Result<String, RockskyError> {
+        let mut params: Vec<(&str, String)> = Vec::new();
+        params.push(("id", id));
+        if let Some(v) = count {
+            params.push(("count", v.to_string()));
+        }
+        self.raw_get("app.rocksky.library.getSimilarSongs", params)
+    }
+
+    /// `app.rocksky.library.getSimilarSongs`, decoded into typed values.
+    pub fn get_similar_songs_typed(
+        &self,
+        id: String,
+        count: Option<i64>,
+    ) -> Result<Vec<LibrarySong>, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_similar_songs(&id, count))
             .map_err(err)?
-            .to_string())
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
     /// `app.rocksky.library.getTopSongs` — returns the raw JSON payload.
@@ -2165,10 +4359,26 @@ impl Library {
         artist: String,
         count: Option<i64>,
     ) -> Result<String, RockskyError> {
+        let mut params: Vec<(&str, String)> = Vec::new();
+        params.push(("artist", artist));
+        if let Some(v) = count {
+            params.push(("count", v.to_string()));
+        }
+        self.raw_get("app.rocksky.library.getTopSongs", params)
+    }
+
+    /// `app.rocksky.library.getTopSongs`, decoded into typed values.
+    pub fn get_top_songs_typed(
+        &self,
+        artist: String,
+        count: Option<i64>,
+    ) -> Result<Vec<LibrarySong>, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_top_songs(&artist, count))
             .map_err(err)?
-            .to_string())
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
     /// `app.rocksky.library.getLyrics` — returns the raw JSON payload.
@@ -2177,29 +4387,59 @@ impl Library {
         artist: Option<String>,
         title: Option<String>,
     ) -> Result<String, RockskyError> {
+        let mut params: Vec<(&str, String)> = Vec::new();
+        if let Some(v) = artist {
+            params.push(("artist", v.to_string()));
+        }
+        if let Some(v) = title {
+            params.push(("title", v.to_string()));
+        }
+        self.raw_get("app.rocksky.library.getLyrics", params)
+    }
+
+    /// `app.rocksky.library.getLyrics`, decoded into typed values.
+    pub fn get_lyrics_typed(
+        &self,
+        artist: Option<String>,
+        title: Option<String>,
+    ) -> Result<LibraryLyrics, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_lyrics(artist.as_deref(), title.as_deref()))
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.getMusicDirectory` — returns the raw JSON payload.
     pub fn get_music_directory(&self, id: String) -> Result<String, RockskyError> {
+        let params = vec![("id", id)];
+        self.raw_get("app.rocksky.library.getMusicDirectory", params)
+    }
+
+    /// `app.rocksky.library.getMusicDirectory`, decoded into typed values.
+    pub fn get_music_directory_typed(&self, id: String) -> Result<LibraryDirectory, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_music_directory(&id))
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.getGenres` — returns the raw JSON payload.
     pub fn get_genres(&self) -> Result<String, RockskyError> {
+        self.raw_get("app.rocksky.library.getGenres", Vec::new())
+    }
+
+    /// `app.rocksky.library.getGenres`, decoded into typed values.
+    pub fn get_genres_typed(&self) -> Result<Vec<LibraryGenre>, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_genres())
             .map_err(err)?
-            .to_string())
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
     /// `app.rocksky.library.search` — returns the raw JSON payload.
+    #[allow(clippy::too_many_arguments)]
     pub fn search(
         &self,
         query: String,
@@ -2210,6 +4450,41 @@ impl Library {
         song_count: Option<i64>,
         song_offset: Option<i64>,
     ) -> Result<String, RockskyError> {
+        let mut params: Vec<(&str, String)> = Vec::new();
+        params.push(("query", query));
+        if let Some(v) = artist_count {
+            params.push(("artistCount", v.to_string()));
+        }
+        if let Some(v) = artist_offset {
+            params.push(("artistOffset", v.to_string()));
+        }
+        if let Some(v) = album_count {
+            params.push(("albumCount", v.to_string()));
+        }
+        if let Some(v) = album_offset {
+            params.push(("albumOffset", v.to_string()));
+        }
+        if let Some(v) = song_count {
+            params.push(("songCount", v.to_string()));
+        }
+        if let Some(v) = song_offset {
+            params.push(("songOffset", v.to_string()));
+        }
+        self.raw_get("app.rocksky.library.search", params)
+    }
+
+    /// `app.rocksky.library.search`, decoded into typed values.
+    #[allow(clippy::too_many_arguments)]
+    pub fn search_typed(
+        &self,
+        query: String,
+        artist_count: Option<i64>,
+        artist_offset: Option<i64>,
+        album_count: Option<i64>,
+        album_offset: Option<i64>,
+        song_count: Option<i64>,
+        song_offset: Option<i64>,
+    ) -> Result<LibrarySearchResult, RockskyError> {
         Ok(RT
             .block_on(self.inner.search(
                 &query,
@@ -2221,15 +4496,17 @@ impl Library {
                 song_offset,
             ))
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.getStarred` — returns the raw JSON payload.
     pub fn get_starred(&self) -> Result<String, RockskyError> {
-        Ok(RT
-            .block_on(self.inner.get_starred())
-            .map_err(err)?
-            .to_string())
+        self.raw_get("app.rocksky.library.getStarred", Vec::new())
+    }
+
+    /// `app.rocksky.library.getStarred`, decoded into typed values.
+    pub fn get_starred_typed(&self) -> Result<LibrarySearchResult, RockskyError> {
+        Ok(RT.block_on(self.inner.get_starred()).map_err(err)?.into())
     }
 
     /// `app.rocksky.library.star` — returns the raw JSON payload.
@@ -2239,13 +4516,31 @@ impl Library {
         album_id: Option<String>,
         artist_id: Option<String>,
     ) -> Result<String, RockskyError> {
+        let mut body = serde_json::Map::new();
+        body.insert("id".into(), serde_json::Value::String(id));
+        if let Some(v) = album_id {
+            body.insert("albumId".into(), serde_json::Value::String(v.to_string()));
+        }
+        if let Some(v) = artist_id {
+            body.insert("artistId".into(), serde_json::Value::String(v.to_string()));
+        }
+        self.raw_post("app.rocksky.library.star", serde_json::Value::Object(body))
+    }
+
+    /// `app.rocksky.library.star`, decoded into typed values.
+    pub fn star_typed(
+        &self,
+        id: String,
+        album_id: Option<String>,
+        artist_id: Option<String>,
+    ) -> Result<LibraryStatus, RockskyError> {
         Ok(RT
             .block_on(
                 self.inner
                     .star(&id, album_id.as_deref(), artist_id.as_deref()),
             )
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.unstar` — returns the raw JSON payload.
@@ -2255,37 +4550,81 @@ impl Library {
         album_id: Option<String>,
         artist_id: Option<String>,
     ) -> Result<String, RockskyError> {
+        let mut body = serde_json::Map::new();
+        body.insert("id".into(), serde_json::Value::String(id));
+        if let Some(v) = album_id {
+            body.insert("albumId".into(), serde_json::Value::String(v.to_string()));
+        }
+        if let Some(v) = artist_id {
+            body.insert("artistId".into(), serde_json::Value::String(v.to_string()));
+        }
+        self.raw_post(
+            "app.rocksky.library.unstar",
+            serde_json::Value::Object(body),
+        )
+    }
+
+    /// `app.rocksky.library.unstar`, decoded into typed values.
+    pub fn unstar_typed(
+        &self,
+        id: String,
+        album_id: Option<String>,
+        artist_id: Option<String>,
+    ) -> Result<LibraryStatus, RockskyError> {
         Ok(RT
             .block_on(
                 self.inner
                     .unstar(&id, album_id.as_deref(), artist_id.as_deref()),
             )
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.getPlaylists` — returns the raw JSON payload.
     pub fn get_playlists(&self) -> Result<String, RockskyError> {
+        self.raw_get("app.rocksky.library.getPlaylists", Vec::new())
+    }
+
+    /// `app.rocksky.library.getPlaylists`, decoded into typed values.
+    pub fn get_playlists_typed(&self) -> Result<Vec<LibraryPlaylist>, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_playlists())
             .map_err(err)?
-            .to_string())
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
     /// `app.rocksky.library.getPlaylist` — returns the raw JSON payload.
     pub fn get_playlist(&self, id: String) -> Result<String, RockskyError> {
+        let params = vec![("id", id)];
+        self.raw_get("app.rocksky.library.getPlaylist", params)
+    }
+
+    /// `app.rocksky.library.getPlaylist`, decoded into typed values.
+    pub fn get_playlist_typed(&self, id: String) -> Result<LibraryPlaylist, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_playlist(&id))
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.createPlaylist` — returns the raw JSON payload.
     pub fn create_playlist(&self, name: String) -> Result<String, RockskyError> {
+        let mut body = serde_json::Map::new();
+        body.insert("name".into(), serde_json::Value::String(name));
+        self.raw_post(
+            "app.rocksky.library.createPlaylist",
+            serde_json::Value::Object(body),
+        )
+    }
+
+    /// `app.rocksky.library.createPlaylist`, decoded into typed values.
+    pub fn create_playlist_typed(&self, name: String) -> Result<LibraryPlaylist, RockskyError> {
         Ok(RT
             .block_on(self.inner.create_playlist(&name))
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.updatePlaylist` — returns the raw JSON payload.
@@ -2297,6 +4636,41 @@ impl Library {
         song_id_to_add: Option<String>,
         song_index_to_remove: Option<i64>,
     ) -> Result<String, RockskyError> {
+        let mut body = serde_json::Map::new();
+        body.insert("playlistId".into(), serde_json::Value::String(playlist_id));
+        if let Some(v) = name {
+            body.insert("name".into(), serde_json::Value::String(v.to_string()));
+        }
+        if let Some(v) = comment {
+            body.insert("comment".into(), serde_json::Value::String(v.to_string()));
+        }
+        if let Some(v) = song_id_to_add {
+            body.insert(
+                "songIdToAdd".into(),
+                serde_json::Value::String(v.to_string()),
+            );
+        }
+        if let Some(v) = song_index_to_remove {
+            body.insert(
+                "songIndexToRemove".into(),
+                serde_json::Value::Number(v.into()),
+            );
+        }
+        self.raw_post(
+            "app.rocksky.library.updatePlaylist",
+            serde_json::Value::Object(body),
+        )
+    }
+
+    /// `app.rocksky.library.updatePlaylist`, decoded into typed values.
+    pub fn update_playlist_typed(
+        &self,
+        playlist_id: String,
+        name: Option<String>,
+        comment: Option<String>,
+        song_id_to_add: Option<String>,
+        song_index_to_remove: Option<i64>,
+    ) -> Result<LibraryStatus, RockskyError> {
         Ok(RT
             .block_on(self.inner.update_playlist(
                 &playlist_id,
@@ -2306,31 +4680,61 @@ impl Library {
                 song_index_to_remove,
             ))
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.deletePlaylist` — returns the raw JSON payload.
     pub fn delete_playlist(&self, id: String) -> Result<String, RockskyError> {
+        let mut body = serde_json::Map::new();
+        body.insert("id".into(), serde_json::Value::String(id));
+        self.raw_post(
+            "app.rocksky.library.deletePlaylist",
+            serde_json::Value::Object(body),
+        )
+    }
+
+    /// `app.rocksky.library.deletePlaylist`, decoded into typed values.
+    pub fn delete_playlist_typed(&self, id: String) -> Result<LibraryStatus, RockskyError> {
         Ok(RT
             .block_on(self.inner.delete_playlist(&id))
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.deleteSong` — returns the raw JSON payload.
     pub fn delete_song(&self, id: String) -> Result<String, RockskyError> {
+        let mut body = serde_json::Map::new();
+        body.insert("id".into(), serde_json::Value::String(id));
+        self.raw_post(
+            "app.rocksky.library.deleteSong",
+            serde_json::Value::Object(body),
+        )
+    }
+
+    /// `app.rocksky.library.deleteSong`, decoded into typed values.
+    pub fn delete_song_typed(&self, id: String) -> Result<LibraryStatus, RockskyError> {
         Ok(RT
             .block_on(self.inner.delete_song(&id))
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.deleteAlbum` — returns the raw JSON payload.
     pub fn delete_album(&self, id: String) -> Result<String, RockskyError> {
+        let mut body = serde_json::Map::new();
+        body.insert("id".into(), serde_json::Value::String(id));
+        self.raw_post(
+            "app.rocksky.library.deleteAlbum",
+            serde_json::Value::Object(body),
+        )
+    }
+
+    /// `app.rocksky.library.deleteAlbum`, decoded into typed values.
+    pub fn delete_album_typed(&self, id: String) -> Result<LibraryStatus, RockskyError> {
         Ok(RT
             .block_on(self.inner.delete_album(&id))
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.scrobble` — returns the raw JSON payload.
@@ -2340,34 +4744,77 @@ impl Library {
         time: Option<i64>,
         submission: Option<bool>,
     ) -> Result<String, RockskyError> {
+        let mut body = serde_json::Map::new();
+        body.insert("id".into(), serde_json::Value::String(id));
+        if let Some(v) = time {
+            body.insert("time".into(), serde_json::Value::Number(v.into()));
+        }
+        if let Some(v) = submission {
+            body.insert("submission".into(), serde_json::Value::Bool(v));
+        }
+        self.raw_post(
+            "app.rocksky.library.scrobble",
+            serde_json::Value::Object(body),
+        )
+    }
+
+    /// `app.rocksky.library.scrobble`, decoded into typed values.
+    pub fn scrobble_typed(
+        &self,
+        id: String,
+        time: Option<i64>,
+        submission: Option<bool>,
+    ) -> Result<LibraryStatus, RockskyError> {
         Ok(RT
             .block_on(self.inner.scrobble(&id, time, submission))
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.updateNowPlaying` — returns the raw JSON payload.
     pub fn update_now_playing(&self, id: String) -> Result<String, RockskyError> {
+        let mut body = serde_json::Map::new();
+        body.insert("id".into(), serde_json::Value::String(id));
+        self.raw_post(
+            "app.rocksky.library.updateNowPlaying",
+            serde_json::Value::Object(body),
+        )
+    }
+
+    /// `app.rocksky.library.updateNowPlaying`, decoded into typed values.
+    pub fn update_now_playing_typed(&self, id: String) -> Result<LibraryStatus, RockskyError> {
         Ok(RT
             .block_on(self.inner.update_now_playing(&id))
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.getNowPlaying` — returns the raw JSON payload.
     pub fn get_now_playing(&self) -> Result<String, RockskyError> {
+        self.raw_get("app.rocksky.library.getNowPlaying", Vec::new())
+    }
+
+    /// `app.rocksky.library.getNowPlaying`, decoded into typed values.
+    pub fn get_now_playing_typed(&self) -> Result<Vec<LibraryNowPlaying>, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_now_playing())
             .map_err(err)?
-            .to_string())
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 
     /// `app.rocksky.library.getPlayQueue` — returns the raw JSON payload.
     pub fn get_play_queue(&self) -> Result<String, RockskyError> {
+        self.raw_get("app.rocksky.library.getPlayQueue", Vec::new())
+    }
+
+    /// `app.rocksky.library.getPlayQueue`, decoded into typed values.
+    pub fn get_play_queue_typed(&self) -> Result<LibraryPlayQueue, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_play_queue())
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.savePlayQueue` — returns the raw JSON payload.
@@ -2377,13 +4824,36 @@ impl Library {
         current: Option<String>,
         position: Option<i64>,
     ) -> Result<String, RockskyError> {
+        let mut body = serde_json::Map::new();
+        if let Some(v) = id {
+            body.insert("id".into(), serde_json::Value::String(v.to_string()));
+        }
+        if let Some(v) = current {
+            body.insert("current".into(), serde_json::Value::String(v.to_string()));
+        }
+        if let Some(v) = position {
+            body.insert("position".into(), serde_json::Value::Number(v.into()));
+        }
+        self.raw_post(
+            "app.rocksky.library.savePlayQueue",
+            serde_json::Value::Object(body),
+        )
+    }
+
+    /// `app.rocksky.library.savePlayQueue`, decoded into typed values.
+    pub fn save_play_queue_typed(
+        &self,
+        id: Option<String>,
+        current: Option<String>,
+        position: Option<i64>,
+    ) -> Result<LibraryStatus, RockskyError> {
         Ok(RT
             .block_on(
                 self.inner
                     .save_play_queue(id.as_deref(), current.as_deref(), position),
             )
             .map_err(err)?
-            .to_string())
+            .into())
     }
 
     /// `app.rocksky.library.getStreamUrl` — returns the raw JSON payload.
@@ -2393,36 +4863,76 @@ impl Library {
         max_bit_rate: Option<i64>,
         format: Option<String>,
     ) -> Result<String, RockskyError> {
-        Ok(RT
-            .block_on(
-                self.inner
-                    .get_stream_url(&id, max_bit_rate, format.as_deref()),
-            )
-            .map_err(err)?
-            .to_string())
+        let mut params: Vec<(&str, String)> = Vec::new();
+        params.push(("id", id));
+        if let Some(v) = max_bit_rate {
+            params.push(("maxBitRate", v.to_string()));
+        }
+        if let Some(v) = format {
+            params.push(("format", v.to_string()));
+        }
+        self.raw_get("app.rocksky.library.getStreamUrl", params)
+    }
+
+    /// `app.rocksky.library.getStreamUrl`, decoded into typed values.
+    pub fn get_stream_url_typed(
+        &self,
+        id: String,
+        max_bit_rate: Option<i64>,
+        format: Option<String>,
+    ) -> Result<String, RockskyError> {
+        RT.block_on(
+            self.inner
+                .get_stream_url(&id, max_bit_rate, format.as_deref()),
+        )
+        .map_err(err)
     }
 
     /// `app.rocksky.library.getDownloadUrl` — returns the raw JSON payload.
     pub fn get_download_url(&self, id: String) -> Result<String, RockskyError> {
-        Ok(RT
-            .block_on(self.inner.get_download_url(&id))
-            .map_err(err)?
-            .to_string())
+        let params = vec![("id", id)];
+        self.raw_get("app.rocksky.library.getDownloadUrl", params)
+    }
+
+    /// `app.rocksky.library.getDownloadUrl`, decoded into typed values.
+    pub fn get_download_url_typed(&self, id: String) -> Result<String, RockskyError> {
+        RT.block_on(self.inner.get_download_url(&id)).map_err(err)
     }
 
     /// `app.rocksky.library.getCoverArtUrl` — returns the raw JSON payload.
     pub fn get_cover_art_url(&self, id: String, size: Option<i64>) -> Result<String, RockskyError> {
-        Ok(RT
-            .block_on(self.inner.get_cover_art_url(&id, size))
-            .map_err(err)?
-            .to_string())
+        let mut params: Vec<(&str, String)> = Vec::new();
+        params.push(("id", id));
+        if let Some(v) = size {
+            params.push(("size", v.to_string()));
+        }
+        self.raw_get("app.rocksky.library.getCoverArtUrl", params)
+    }
+
+    /// `app.rocksky.library.getCoverArtUrl`, decoded into typed values.
+    pub fn get_cover_art_url_typed(
+        &self,
+        id: String,
+        size: Option<i64>,
+    ) -> Result<String, RockskyError> {
+        RT.block_on(self.inner.get_cover_art_url(&id, size))
+            .map_err(err)
     }
 
     /// `app.rocksky.library.getInternetRadioStations` — returns the raw JSON payload.
     pub fn get_internet_radio_stations(&self) -> Result<String, RockskyError> {
+        self.raw_get("app.rocksky.library.getInternetRadioStations", Vec::new())
+    }
+
+    /// `app.rocksky.library.getInternetRadioStations`, decoded into typed values.
+    pub fn get_internet_radio_stations_typed(
+        &self,
+    ) -> Result<Vec<LibraryRadioStation>, RockskyError> {
         Ok(RT
             .block_on(self.inner.get_internet_radio_stations())
             .map_err(err)?
-            .to_string())
+            .into_iter()
+            .map(Into::into)
+            .collect())
     }
 }
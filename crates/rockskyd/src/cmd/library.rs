@@ -0,0 +1,30 @@
+use anyhow::Error;
+use clap::ArgMatches;
+
+pub async fn scan(args: &ArgMatches) -> Result<(), Error> {
+    rocksky_library::cmd::scan::scan(
+        args.get_one::<String>("did").unwrap(),
+        args.get_one::<String>("path").map(String::as_str),
+        args.get_one::<String>("storage-provider")
+            .map(String::as_str),
+        args.get_one::<String>("prefix")
+            .map(String::as_str)
+            .unwrap_or_default(),
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn serve() -> Result<(), Error> {
+    rocksky_library::cmd::serve::serve().await?;
+    Ok(())
+}
+
+pub async fn watch(args: &ArgMatches) -> Result<(), Error> {
+    rocksky_library::cmd::watch::watch(
+        args.get_one::<String>("did").unwrap(),
+        args.get_one::<String>("path").unwrap(),
+    )
+    .await?;
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use clap::Command;
+use clap::{Arg, Command};
 use dotenv::dotenv;
 use tracing_subscriber::fmt::format::Format;
 
@@ -21,6 +21,39 @@ fn cli() -> Command {
                 .subcommand(Command::new("serve").about("Serve Rocksky Google Drive API")),
         )
         .subcommand(Command::new("jetstream").about("Start JetStream Subscriber Service"))
+        .subcommand(
+            Command::new("library")
+                .about("Local and S3 library related commands")
+                .subcommand(
+                    Command::new("scan")
+                        .about("Scan a local directory or an S3 bucket into a user's library")
+                        .arg(Arg::new("did").long("did").required(true).help("DID of the library owner"))
+                        .arg(
+                            Arg::new("path")
+                                .help("Local directory to scan")
+                                .required_unless_present("storage-provider"),
+                        )
+                        .arg(
+                            Arg::new("storage-provider")
+                                .long("storage-provider")
+                                .conflicts_with("path")
+                                .help("ID of the user's storage provider whose bucket to scan"),
+                        )
+                        .arg(
+                            Arg::new("prefix")
+                                .long("prefix")
+                                .requires("storage-provider")
+                                .help("Only scan the objects under this prefix"),
+                        ),
+                )
+                .subcommand(Command::new("serve").about("Run the library scans requested through the Subsonic API"))
+                .subcommand(
+                    Command::new("watch")
+                        .about("Keep a user's library in sync with a local directory as files change")
+                        .arg(Arg::new("did").long("did").required(true).help("DID of the library owner"))
+                        .arg(Arg::new("path").required(true).help("Local directory to watch")),
+                ),
+        )
         .subcommand(Command::new("mirror").about("Mirror plays from Last.fm, ListenBrainz, Teal.fm into Rocksky"))
         .subcommand(Command::new("navidrome").about("Start Navidrome-compatible API (Subsonic REST API)"))
         .subcommand(Command::new("playlist").about("Playlist related commands"))
@@ -62,6 +95,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             Some(("serve", _)) => cmd::googledrive::serve().await?,
             _ => println!("Unknown googledrive command"),
         },
+        Some(("library", sub_m)) => match sub_m.subcommand() {
+            Some(("scan", args)) => cmd::library::scan(args).await?,
+            Some(("serve", _)) => cmd::library::serve().await?,
+            Some(("watch", args)) => cmd::library::watch(args).await?,
+            _ => println!("Unknown library command"),
+        },
         Some(("jetstream", _)) => {
             cmd::jetstream::start_jetstream_service().await?;
         }
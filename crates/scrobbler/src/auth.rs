@@ -12,8 +12,14 @@ use crate::cache::Cache;
 use crate::repo;
 use crate::rocksky::ROCKSKY_API;
 use crate::signature::generate_signature;
+use crate::xata::api_key::ApiKey;
 use crate::xata::user::User;
 
+// Lifetime of Last.fm session keys, in seconds. Last.fm's don't expire, but
+// clients authenticate again when a key is rejected (error 9), so a yearly
+// renewal goes unnoticed. Deleting the API key revokes its sessions sooner.
+const SESSION_KEY_TTL: usize = 60 * 60 * 24 * 365;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub exp: usize,
@@ -73,6 +79,46 @@ pub async fn authenticate(
     Ok(())
 }
 
+/// Why [`verify_signature`] rejected a request.
+#[derive(Debug)]
+pub enum SignatureError {
+    /// No API key matches `api_key`.
+    InvalidApiKey,
+    /// `api_sig` doesn't match the parameters of the request.
+    InvalidSignature,
+    /// The API key couldn't be looked up.
+    Other(Error),
+}
+
+/// Checks the `api_sig` of a request that isn't tied to a session yet
+/// (`auth.*` methods) against the shared secret of its API key.
+pub async fn verify_signature(
+    pool: &Pool<Postgres>,
+    api_key: &str,
+    api_sig: &str,
+    form: &BTreeMap<String, String>,
+) -> Result<ApiKey, SignatureError> {
+    let user_apikey = repo::api_key::get_apikey_by_key(pool, api_key)
+        .await
+        .map_err(SignatureError::Other)?
+        .ok_or(SignatureError::InvalidApiKey)?;
+
+    let signature = generate_signature(form, &user_apikey.shared_secret);
+
+    if signature != api_sig {
+        return Err(SignatureError::InvalidSignature);
+    }
+
+    Ok(user_apikey)
+}
+
+/// The `authToken` accepted by `auth.getMobileSession`:
+/// `md5(username + md5(password))`.
+pub fn mobile_auth_token(username: &str, password: &str) -> String {
+    let password = format!("{:x}", md5::compute(password));
+    format!("{:x}", md5::compute(format!("{}{}", username, password)))
+}
+
 pub async fn extract_did(
     pool: &Pool<Postgres>,
     form: &BTreeMap<String, String>,
@@ -106,6 +152,28 @@ pub fn generate_token(did: &str) -> Result<String, Error> {
     .map_err(Into::into)
 }
 
+/// Generates a Last.fm session key, a JWT like the tokens above which lasts a
+/// year instead of an hour.
+pub fn generate_session_key(did: &str) -> Result<String, Error> {
+    if env::var("JWT_SECRET").is_err() {
+        return Err(Error::msg("JWT_SECRET is not set"));
+    }
+
+    let now = chrono::Utc::now().timestamp() as usize;
+    let claims = Claims {
+        exp: now + SESSION_KEY_TTL,
+        iat: now,
+        did: did.to_string(),
+    };
+
+    jsonwebtoken::encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(env::var("JWT_SECRET")?.as_ref()),
+    )
+    .map_err(Into::into)
+}
+
 pub fn decode_token(token: &str) -> Result<Claims, Error> {
     if env::var("JWT_SECRET").is_err() {
         return Err(Error::msg("JWT_SECRET is not set"));
@@ -196,4 +264,12 @@ mod tests {
 
         assert_eq!(claims.did, "did:plc:7vdlgi2bflelz7mmuxoqjfcr");
     }
+
+    #[test]
+    fn test_mobile_auth_token() {
+        assert_eq!(
+            mobile_auth_token("alice.bsky.social", "secret"),
+            "ca0cf88d829ffb1de339d276f50fe819"
+        );
+    }
 }
@@ -0,0 +1,159 @@
+use actix_web::HttpResponse;
+use anyhow::Error;
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+use std::collections::BTreeMap;
+
+use crate::{
+    auth::{generate_session_key, mobile_auth_token, verify_signature, SignatureError},
+    cache::Cache,
+    params::validate_required_params,
+    repo,
+    response::build_error,
+    xata::user::UserWithoutSecret,
+};
+
+// How long a token returned by `auth.getToken` can be exchanged for a
+// session, in seconds (same as Last.fm).
+const TOKEN_TTL: usize = 60 * 60;
+
+fn token_key(token: &str) -> String {
+    format!("lastfm:token:{}", token)
+}
+
+pub async fn handle_get_token(
+    form: BTreeMap<String, String>,
+    pool: &Pool<Postgres>,
+    cache: &Cache,
+) -> Result<HttpResponse, Error> {
+    let params = match validate_required_params(&form, &["api_key", "api_sig"]) {
+        Ok(params) => params,
+        Err(e) => return Ok(HttpResponse::BadRequest().json(build_error(6, &e.to_string()))),
+    };
+
+    if let Err(e) = verify_signature(pool, &params[0], &params[1], &form).await {
+        return Ok(signature_error(e));
+    }
+
+    let mut bytes = [0u8; 16];
+    rand::fill(&mut bytes[..]);
+    let token = hex::encode(bytes);
+
+    // API keys belong to a single Rocksky user, so the token is authorized
+    // as soon as it is issued and only has to be bound to the API key.
+    cache.setex(&token_key(&token), &params[0], TOKEN_TTL)?;
+
+    Ok(HttpResponse::Ok().json(json!({ "token": token })))
+}
+
+pub async fn handle_get_session(
+    form: BTreeMap<String, String>,
+    pool: &Pool<Postgres>,
+    cache: &Cache,
+) -> Result<HttpResponse, Error> {
+    let params = match validate_required_params(&form, &["api_key", "api_sig", "token"]) {
+        Ok(params) => params,
+        Err(e) => return Ok(HttpResponse::BadRequest().json(build_error(6, &e.to_string()))),
+    };
+
+    let apikey = match verify_signature(pool, &params[0], &params[1], &form).await {
+        Ok(apikey) => apikey,
+        Err(e) => return Ok(signature_error(e)),
+    };
+
+    match cache.get(&token_key(&params[2]))? {
+        Some(api_key) if api_key == apikey.api_key => {}
+        Some(_) => {
+            return Ok(HttpResponse::Forbidden().json(build_error(
+                14,
+                "Unauthorized Token - This token has not been authorized",
+            )))
+        }
+        None => {
+            return Ok(HttpResponse::Forbidden()
+                .json(build_error(15, "Token has expired or has not been issued")))
+        }
+    }
+    cache.del(&token_key(&params[2]))?;
+
+    let user = match repo::user::get_user_by_id(pool, &apikey.user_id).await? {
+        Some(user) => user,
+        None => return Ok(HttpResponse::Forbidden().json(build_error(10, "Invalid API key"))),
+    };
+
+    session_response(&user)
+}
+
+pub async fn handle_get_mobile_session(
+    form: BTreeMap<String, String>,
+    pool: &Pool<Postgres>,
+) -> Result<HttpResponse, Error> {
+    let params = match validate_required_params(&form, &["api_key", "api_sig", "username"]) {
+        Ok(params) => params,
+        Err(e) => return Ok(HttpResponse::BadRequest().json(build_error(6, &e.to_string()))),
+    };
+
+    let apikey = match verify_signature(pool, &params[0], &params[1], &form).await {
+        Ok(apikey) => apikey,
+        Err(e) => return Ok(signature_error(e)),
+    };
+
+    let user = match repo::user::get_user_by_id(pool, &apikey.user_id).await? {
+        Some(user) => user,
+        None => return Ok(HttpResponse::Forbidden().json(build_error(10, "Invalid API key"))),
+    };
+
+    // Rocksky has no passwords: the API key's shared secret is used instead,
+    // and the username is the handle or DID of the API key's owner.
+    let username = &params[2];
+    let authenticated = is_owner(&user, username)
+        && match (form.get("password"), form.get("authToken")) {
+            (Some(password), _) => *password == apikey.shared_secret,
+            (None, Some(auth_token)) => {
+                *auth_token == mobile_auth_token(username, &apikey.shared_secret)
+            }
+            (None, None) => {
+                return Ok(HttpResponse::BadRequest()
+                    .json(build_error(6, "Missing required parameter: password")))
+            }
+        };
+
+    if !authenticated {
+        return Ok(HttpResponse::Forbidden().json(build_error(
+            4,
+            "Authentication Failed - You do not have permissions to access the service",
+        )));
+    }
+
+    session_response(&user)
+}
+
+fn is_owner(user: &UserWithoutSecret, username: &str) -> bool {
+    user.handle.eq_ignore_ascii_case(username) || user.did == username
+}
+
+fn session_response(user: &UserWithoutSecret) -> Result<HttpResponse, Error> {
+    let session_key = generate_session_key(&user.did)?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "session": {
+            "name": user.handle,
+            "key": session_key,
+            "subscriber": 0
+        }
+    })))
+}
+
+fn signature_error(e: SignatureError) -> HttpResponse {
+    match e {
+        SignatureError::InvalidApiKey => {
+            HttpResponse::Forbidden().json(build_error(10, "Invalid API key"))
+        }
+        SignatureError::InvalidSignature => {
+            HttpResponse::Forbidden().json(build_error(13, "Invalid method signature supplied"))
+        }
+        SignatureError::Other(e) => {
+            HttpResponse::InternalServerError().json(build_error(16, &e.to_string()))
+        }
+    }
+}
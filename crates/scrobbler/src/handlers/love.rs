@@ -0,0 +1,61 @@
+use actix_web::HttpResponse;
+use anyhow::Error;
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+use std::collections::BTreeMap;
+
+use crate::{
+    handlers::authenticate_session, params::validate_required_params, repo, response::build_error,
+    rocksky, types::Track,
+};
+
+pub async fn handle_love(
+    form: BTreeMap<String, String>,
+    pool: &Pool<Postgres>,
+) -> Result<HttpResponse, Error> {
+    love(form, pool, true).await
+}
+
+pub async fn handle_unlove(
+    form: BTreeMap<String, String>,
+    pool: &Pool<Postgres>,
+) -> Result<HttpResponse, Error> {
+    love(form, pool, false).await
+}
+
+async fn love(
+    form: BTreeMap<String, String>,
+    pool: &Pool<Postgres>,
+    loved: bool,
+) -> Result<HttpResponse, Error> {
+    let params = match validate_required_params(&form, &["artist", "track"]) {
+        Ok(params) => params,
+        Err(e) => return Ok(HttpResponse::BadRequest().json(build_error(6, &e.to_string()))),
+    };
+
+    let did = match authenticate_session(pool, &form).await {
+        Ok(did) => did,
+        Err(response) => return Ok(response),
+    };
+
+    // Only tracks already known to Rocksky can be (un)loved, Last.fm clients
+    // don't send enough metadata to create one.
+    let track = match repo::track::get_track(pool, &params[1], &params[0]).await? {
+        Some(track) => track,
+        None => {
+            return Ok(HttpResponse::BadRequest().json(build_error(6, "Track not found")));
+        }
+    };
+
+    let result = if loved {
+        rocksky::like(&did, Track::from(track)).await
+    } else {
+        rocksky::unlike(&did, &track.sha256).await
+    };
+
+    if let Err(e) = result {
+        return Ok(HttpResponse::BadGateway().json(build_error(16, &e.to_string())));
+    }
+
+    Ok(HttpResponse::Ok().json(json!({})))
+}
@@ -1,5 +1,9 @@
 use actix_web::{get, post, web, HttpResponse, Responder};
 use anyhow::Error;
+use auth::{handle_get_mobile_session, handle_get_session, handle_get_token};
+use love::{handle_love, handle_unlove};
+use now_playing::handle_update_now_playing;
+use recent_tracks::handle_get_recent_tracks;
 use scrobble::handle_scrobble;
 use sqlx::{Pool, Postgres};
 use std::collections::BTreeMap;
@@ -8,10 +12,18 @@ use v1::authenticate::authenticate;
 use v1::nowplaying::nowplaying;
 use v1::submission::submission;
 
+use crate::auth::{self as session, decode_token};
 use crate::cache::Cache;
+use crate::events::Events;
 use crate::musicbrainz::client::MusicbrainzClient;
+use crate::params::validate_required_params;
+use crate::response::build_error;
 use crate::BANNER;
 
+pub mod auth;
+pub mod love;
+pub mod now_playing;
+pub mod recent_tracks;
 pub mod scrobble;
 pub mod v1;
 
@@ -58,8 +70,34 @@ pub async fn handle_submission(
 }
 
 #[get("/2.0")]
-pub async fn handle_get() -> impl Responder {
-    HttpResponse::Ok().body(BANNER)
+pub async fn handle_get(
+    data: web::Data<Arc<Pool<Postgres>>>,
+    cache: web::Data<Cache>,
+    params: web::Query<BTreeMap<String, String>>,
+    mb_client: web::Data<Arc<MusicbrainzClient>>,
+    events: web::Data<Arc<Events>>,
+) -> impl Responder {
+    if params.is_empty() {
+        return Ok(HttpResponse::Ok().body(BANNER));
+    }
+
+    let method = params.get("method").cloned().unwrap_or_default();
+    if is_write_method(&method) {
+        return Ok(HttpResponse::MethodNotAllowed().json(build_error(
+            3,
+            &format!("Invalid Method - {} must be called with POST", method),
+        )));
+    }
+    call_method(
+        &method,
+        data.get_ref(),
+        cache.get_ref(),
+        mb_client.get_ref(),
+        events.get_ref(),
+        params.into_inner(),
+    )
+    .await
+    .map_err(actix_web::error::ErrorInternalServerError)
 }
 
 #[post("/2.0")]
@@ -68,13 +106,15 @@ pub async fn handle_methods(
     cache: web::Data<Cache>,
     form: web::Form<BTreeMap<String, String>>,
     mb_client: web::Data<Arc<MusicbrainzClient>>,
+    events: web::Data<Arc<Events>>,
 ) -> impl Responder {
     let conn = data.get_ref();
     let cache = cache.get_ref();
     let mb_client = mb_client.get_ref();
+    let events = events.get_ref();
 
     let method = form.get("method").unwrap_or(&"".to_string()).to_string();
-    call_method(&method, conn, cache, mb_client, form.into_inner())
+    call_method(&method, conn, cache, mb_client, events, form.into_inner())
         .await
         .map_err(actix_web::error::ErrorInternalServerError)
 }
@@ -84,10 +124,71 @@ pub async fn call_method(
     pool: &Arc<Pool<Postgres>>,
     cache: &Cache,
     mb_client: &Arc<MusicbrainzClient>,
+    events: &Arc<Events>,
     form: BTreeMap<String, String>,
 ) -> Result<HttpResponse, Error> {
-    match method {
+    // Method names are case insensitive on Last.fm.
+    match method.to_lowercase().as_str() {
+        "auth.gettoken" => handle_get_token(form, pool, cache).await,
+        "auth.getsession" => handle_get_session(form, pool, cache).await,
+        "auth.getmobilesession" => handle_get_mobile_session(form, pool).await,
         "track.scrobble" => handle_scrobble(form, pool, cache, mb_client).await,
-        _ => Err(Error::msg(format!("Unsupported method: {}", method))),
+        "track.updatenowplaying" => handle_update_now_playing(form, pool, cache, events).await,
+        "track.love" => handle_love(form, pool).await,
+        "track.unlove" => handle_unlove(form, pool).await,
+        "user.getrecenttracks" => handle_get_recent_tracks(form, pool, cache).await,
+        _ => Ok(HttpResponse::BadRequest().json(build_error(
+            3,
+            &format!(
+                "Invalid Method - No method with that name in this package: {}",
+                method
+            ),
+        ))),
+    }
+}
+
+/// Methods which write data or take credentials, which Last.fm only accepts
+/// in POST requests.
+fn is_write_method(method: &str) -> bool {
+    matches!(
+        method.to_lowercase().as_str(),
+        "auth.getmobilesession"
+            | "track.scrobble"
+            | "track.updatenowplaying"
+            | "track.love"
+            | "track.unlove"
+    )
+}
+
+/// Authenticates a method called on behalf of a user (`api_key`, `api_sig`
+/// and `sk`) and returns the DID of the session's user.
+pub async fn authenticate_session(
+    pool: &Pool<Postgres>,
+    form: &BTreeMap<String, String>,
+) -> Result<String, HttpResponse> {
+    let params = validate_required_params(form, &["api_key", "api_sig", "sk"])
+        .map_err(|e| HttpResponse::BadRequest().json(build_error(6, &e.to_string())))?;
+
+    if let Err(e) = session::authenticate(pool, &params[0], &params[1], &params[2], form).await {
+        return Err(HttpResponse::Forbidden()
+            .json(build_error(9, &format!("Authentication failed: {}", e))));
+    }
+
+    decode_token(&params[2])
+        .map(|claims| claims.did)
+        .map_err(|e| HttpResponse::Forbidden().json(build_error(9, &e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_write_method() {
+        assert!(is_write_method("track.scrobble"));
+        assert!(is_write_method("track.updateNowPlaying"));
+        assert!(is_write_method("auth.getMobileSession"));
+        assert!(!is_write_method("auth.getToken"));
+        assert!(!is_write_method("user.getRecentTracks"));
     }
 }
@@ -0,0 +1,94 @@
+use actix_web::HttpResponse;
+use anyhow::Error;
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+use std::collections::BTreeMap;
+
+use crate::{
+    cache::Cache,
+    events::Events,
+    handlers::authenticate_session,
+    listenbrainz::core::playing_now::{playing_now_key, PLAYING_NOW_DEFAULT_TTL},
+    params::validate_required_params,
+    response::build_error,
+};
+
+pub async fn handle_update_now_playing(
+    form: BTreeMap<String, String>,
+    pool: &Pool<Postgres>,
+    cache: &Cache,
+    events: &Events,
+) -> Result<HttpResponse, Error> {
+    let params = match validate_required_params(&form, &["artist", "track"]) {
+        Ok(params) => params,
+        Err(e) => return Ok(HttpResponse::BadRequest().json(build_error(6, &e.to_string()))),
+    };
+
+    let did = match authenticate_session(pool, &form).await {
+        Ok(did) => did,
+        Err(response) => return Ok(response),
+    };
+
+    let (artist, track) = (&params[0], &params[1]);
+    let album = form.get("album").cloned().unwrap_or_default();
+    let album_artist = form.get("albumArtist").cloned().unwrap_or_default();
+    let mbid = form.get("mbid").filter(|mbid| !mbid.is_empty());
+    // Last.fm reports durations in seconds.
+    let duration_ms = form
+        .get("duration")
+        .and_then(|d| d.parse::<u64>().ok())
+        .map(|d| d * 1000)
+        .unwrap_or(0);
+
+    tracing::info!(did = %did, artist = %artist, track = %track, "Now playing");
+
+    events
+        .emit_song_changed(
+            &did,
+            json!({
+                "name": track,
+                "artist": artist,
+                "album": album,
+                "duration_ms": duration_ms,
+                "recording_mb_id": mbid,
+            }),
+        )
+        .await;
+
+    // Shared with ListenBrainz's `playing_now` so both APIs report the same
+    // track, hence the ListenBrainz track metadata format.
+    let metadata = json!({
+        "artist_name": artist,
+        "track_name": track,
+        "release_name": album,
+        "additional_info": {
+            "duration_ms": duration_ms,
+            "release_artist_name": album_artist,
+            "recording_mbid": mbid,
+            "tracknumber": form.get("trackNumber").and_then(|n| n.parse::<u32>().ok()),
+            "submission_client": "Last.fm",
+        },
+    });
+    let ttl = if duration_ms > 0 {
+        (duration_ms / 1000) as usize
+    } else {
+        PLAYING_NOW_DEFAULT_TTL
+    };
+    if let Err(e) = cache.setex(&playing_now_key(&did), &metadata.to_string(), ttl) {
+        tracing::error!(error = %e, "Failed to cache playing now");
+    }
+
+    if duration_ms > 0 {
+        events.schedule_song_stopped(did, duration_ms).await;
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "nowplaying": {
+            "artist": { "#text": artist, "corrected": "0" },
+            "track": { "#text": track, "corrected": "0" },
+            "album": { "#text": album, "corrected": "0" },
+            "albumArtist": { "#text": album_artist, "corrected": "0" },
+            "ignoredMessage": { "#text": "", "code": "0" }
+        }
+    })))
+}
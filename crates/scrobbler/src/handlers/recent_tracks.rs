@@ -0,0 +1,180 @@
+use actix_web::HttpResponse;
+use anyhow::Error;
+use chrono::DateTime;
+use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
+use std::collections::BTreeMap;
+
+use crate::{
+    cache::Cache,
+    listenbrainz::core::playing_now::playing_now_key,
+    params::validate_required_params,
+    repo::{self, listen::Listen},
+    response::build_error,
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+pub async fn handle_get_recent_tracks(
+    form: BTreeMap<String, String>,
+    pool: &Pool<Postgres>,
+    cache: &Cache,
+) -> Result<HttpResponse, Error> {
+    let params = match validate_required_params(&form, &["api_key", "user"]) {
+        Ok(params) => params,
+        Err(e) => return Ok(HttpResponse::BadRequest().json(build_error(6, &e.to_string()))),
+    };
+
+    if repo::api_key::get_apikey_by_key(pool, &params[0])
+        .await?
+        .is_none()
+    {
+        return Ok(HttpResponse::Forbidden().json(build_error(10, "Invalid API key")));
+    }
+
+    let (limit, page, from, to) = match parse_paging(&form) {
+        Ok(paging) => paging,
+        Err(e) => return Ok(HttpResponse::BadRequest().json(build_error(6, &e.to_string()))),
+    };
+    let extended = form.get("extended").is_some_and(|e| e == "1");
+
+    let user = match repo::user::get_user_by_name(pool, &params[1]).await? {
+        Some(user) => user,
+        None => return Ok(HttpResponse::NotFound().json(build_error(6, "User not found"))),
+    };
+
+    let total = repo::listen::count_recent_listens(pool, &user.xata_id, from, to).await?;
+    let listens =
+        repo::listen::get_recent_listens(pool, &user.xata_id, from, to, limit, (page - 1) * limit)
+            .await?;
+
+    let mut tracks = vec![];
+
+    // Like Last.fm, the track being played comes first on the first page, in
+    // addition to the `limit` scrobbles.
+    if page == 1 && to.is_none() {
+        if let Some(cached) = cache.get(&playing_now_key(&user.did))? {
+            let metadata: Value = serde_json::from_str(&cached)?;
+            tracks.push(now_playing_to_json(&metadata, extended));
+        }
+    }
+
+    tracks.extend(
+        listens
+            .iter()
+            .map(|listen| listen_to_json(listen, extended)),
+    );
+
+    Ok(HttpResponse::Ok().json(json!({
+        "recenttracks": {
+            "track": tracks,
+            "@attr": {
+                "user": user.handle,
+                "page": page.to_string(),
+                "perPage": limit.to_string(),
+                "totalPages": ((total + limit - 1) / limit).to_string(),
+                "total": total.to_string(),
+            }
+        }
+    })))
+}
+
+fn parse_paging(
+    form: &BTreeMap<String, String>,
+) -> Result<(i64, i64, Option<i64>, Option<i64>), Error> {
+    let parse = |name: &str| -> Result<Option<i64>, Error> {
+        form.get(name)
+            .map(|value| {
+                value
+                    .parse::<i64>()
+                    .map_err(|_| Error::msg(format!("Invalid parameter: {}", name)))
+            })
+            .transpose()
+    };
+
+    let limit = parse("limit")?.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(Error::msg(format!(
+            "limit must be between 1 and {}",
+            MAX_LIMIT
+        )));
+    }
+
+    let page = parse("page")?.unwrap_or(1);
+    if page < 1 {
+        return Err(Error::msg("page must be greater than 0"));
+    }
+
+    Ok((limit, page, parse("from")?, parse("to")?))
+}
+
+fn listen_to_json(listen: &Listen, extended: bool) -> Value {
+    let mut track = track_to_json(
+        &listen.artist,
+        &listen.title,
+        &listen.album,
+        listen.mb_id.as_deref(),
+        listen.album_art.as_deref(),
+        extended,
+    );
+    track["url"] = json!(listen.uri.as_deref().map(web_url).unwrap_or_default());
+    track["date"] = json!({
+        "uts": listen.listened_at.to_string(),
+        "#text": DateTime::from_timestamp(listen.listened_at, 0)
+            .map(|date| date.format("%d %b %Y, %H:%M").to_string())
+            .unwrap_or_default(),
+    });
+    track
+}
+
+fn now_playing_to_json(metadata: &Value, extended: bool) -> Value {
+    let mut track = track_to_json(
+        metadata["artist_name"].as_str().unwrap_or_default(),
+        metadata["track_name"].as_str().unwrap_or_default(),
+        metadata["release_name"].as_str().unwrap_or_default(),
+        metadata["additional_info"]["recording_mbid"].as_str(),
+        None,
+        extended,
+    );
+    track["url"] = json!("");
+    track["@attr"] = json!({ "nowplaying": "true" });
+    track
+}
+
+fn track_to_json(
+    artist: &str,
+    title: &str,
+    album: &str,
+    mbid: Option<&str>,
+    album_art: Option<&str>,
+    extended: bool,
+) -> Value {
+    let artist = if extended {
+        json!({ "name": artist, "mbid": "", "url": "", "image": [] })
+    } else {
+        json!({ "#text": artist, "mbid": "" })
+    };
+
+    let images = ["small", "medium", "large", "extralarge"]
+        .iter()
+        .map(|size| json!({ "size": size, "#text": album_art.unwrap_or_default() }))
+        .collect::<Vec<_>>();
+
+    json!({
+        "artist": artist,
+        "streamable": "0",
+        "image": images,
+        "mbid": mbid.unwrap_or_default(),
+        "album": { "#text": album, "mbid": "" },
+        "name": title,
+    })
+}
+
+/// Maps the AT-URI of a scrobble to its page on rocksky.app.
+fn web_url(uri: &str) -> String {
+    format!(
+        "https://rocksky.app/{}",
+        uri.trim_start_matches("at://").replace("app.rocksky.", "")
+    )
+}
@@ -109,6 +109,20 @@ pub async fn run() -> Result<(), Error> {
             .service(listenbrainz::handlers::handle_get_artists)
             .service(listenbrainz::handlers::handle_get_recordings)
             .service(listenbrainz::handlers::handle_get_release_groups)
+            .service(listenbrainz::handlers::handle_get_releases)
+            .service(listenbrainz::handlers::handle_get_listening_activity)
+            .service(listenbrainz::handlers::handle_get_daily_activity)
+            .service(listenbrainz::handlers::handle_get_artist_activity)
+            .service(listenbrainz::handlers::handle_get_artist_map)
+            .service(listenbrainz::handlers::handle_get_year_in_music)
+            .service(listenbrainz::handlers::handle_get_artist_listeners)
+            .service(listenbrainz::handlers::handle_get_release_group_listeners)
+            .service(listenbrainz::handlers::handle_get_sitewide_artists)
+            .service(listenbrainz::handlers::handle_get_sitewide_releases)
+            .service(listenbrainz::handlers::handle_get_sitewide_recordings)
+            .service(listenbrainz::handlers::handle_get_sitewide_release_groups)
+            .service(listenbrainz::handlers::handle_get_sitewide_listening_activity)
+            .service(listenbrainz::handlers::handle_get_sitewide_artist_map)
             .service(handlers::index)
             .service(handlers::handle_get)
     })
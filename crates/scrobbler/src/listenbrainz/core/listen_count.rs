@@ -1,12 +1,24 @@
+use actix_web::HttpResponse;
 use anyhow::Error;
-use serde_json::{json, Value};
+use serde_json::json;
+use sqlx::{Pool, Postgres};
 
-pub async fn get_listen_count(_user_name: &str) -> Result<Value, Error> {
-    let count = json!({
+use crate::{listenbrainz::response::user_not_found, repo};
+
+pub async fn get_listen_count(
+    pool: &Pool<Postgres>,
+    user_name: &str,
+) -> Result<HttpResponse, Error> {
+    let user = match repo::user::get_user_by_name(pool, user_name).await? {
+        Some(user) => user,
+        None => return Ok(user_not_found(user_name)),
+    };
+
+    let count = repo::listen::get_listen_count(pool, &user.xata_id).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
       "payload": {
-        "count": 407,
+        "count": count,
       }
-    });
-
-    Ok(count)
+    })))
 }
@@ -1,17 +1,118 @@
+use actix_web::HttpResponse;
 use anyhow::Error;
 use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
 
-pub async fn get_listens(_user_name: &str) -> Result<Value, Error> {
-    let listens = json!({
+use crate::{
+    listenbrainz::{
+        response::{bad_request, parse_count, user_not_found, MAX_ITEMS_PER_GET},
+        types::GetListensParams,
+    },
+    repo::{self, listen::Listen},
+};
+
+pub async fn get_listens(
+    pool: &Pool<Postgres>,
+    user_name: &str,
+    params: &GetListensParams,
+) -> Result<HttpResponse, Error> {
+    if params.min_ts.is_some_and(|ts| ts < 0) || params.max_ts.is_some_and(|ts| ts < 0) {
+        return Ok(bad_request("min_ts and max_ts must be positive"));
+    }
+
+    if params.min_ts.is_some() && params.max_ts.is_some() {
+        return Ok(bad_request(
+            "You may only specify max_ts or min_ts, not both.",
+        ));
+    }
+
+    let count = match parse_count(params.count, MAX_ITEMS_PER_GET) {
+        Ok(count) => count,
+        Err(response) => return Ok(response),
+    };
+
+    let user = match repo::user::get_user_by_name(pool, user_name).await? {
+        Some(user) => user,
+        None => return Ok(user_not_found(user_name)),
+    };
+
+    let listens =
+        repo::listen::get_listens(pool, &user.xata_id, params.min_ts, params.max_ts, count).await?;
+    let bounds = repo::listen::get_listen_bounds(pool, &user.xata_id).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
       "payload": {
-        "count": 25,
-        "latest_listen_ts": 1748276948,
-        "listens": vec![
-          json!({})
-        ],
-        "oldest_listen_ts": 1747917399,
-        "user_id": "tsiry"
+        "count": listens.len(),
+        "listens": listens
+            .iter()
+            .map(|listen| listen_to_json(listen, &user.handle))
+            .collect::<Vec<_>>(),
+        "latest_listen_ts": bounds.latest_listen_ts.unwrap_or(0),
+        "oldest_listen_ts": bounds.oldest_listen_ts.unwrap_or(0),
+        "user_id": user.handle,
       }
+    })))
+}
+
+pub fn listen_to_json(listen: &Listen, user_name: &str) -> Value {
+    json!({
+      "listened_at": listen.listened_at,
+      "inserted_at": listen.inserted_at,
+      "recording_msid": listen.xata_id,
+      "user_name": user_name,
+      "track_metadata": track_metadata(listen),
+    })
+}
+
+pub fn track_metadata(listen: &Listen) -> Value {
+    let mut metadata = json!({
+      "artist_name": listen.artist,
+      "track_name": listen.title,
+      "release_name": listen.album,
+      "additional_info": {
+        "duration_ms": listen.duration,
+        "release_artist_name": listen.album_artist,
+        "recording_mbid": listen.mb_id,
+        "isrc": listen.isrc,
+        "tracknumber": listen.track_number,
+        "spotify_id": listen.spotify_link,
+        "origin_url": listen.uri,
+        "submission_client": "Rocksky",
+      },
     });
-    Ok(listens)
+
+    if let Some(mb_id) = &listen.mb_id {
+        metadata["mbid_mapping"] = json!({
+          "recording_mbid": mb_id,
+          "recording_name": listen.title,
+          "release_mbid": null,
+          "artist_mbids": [],
+        });
+    }
+
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use sqlx::postgres::PgPoolOptions;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_listens_rejects_min_and_max_ts() {
+        // Never connects: the parameters are rejected before any query.
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/rocksky")
+            .unwrap();
+        let params = GetListensParams {
+            min_ts: Some(1_700_000_000),
+            max_ts: Some(1_800_000_000),
+            count: None,
+        };
+
+        let response = get_listens(&pool, "user", &params).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
 }
@@ -1,31 +1,58 @@
+use actix_web::HttpResponse;
 use anyhow::Error;
 use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
 
-pub async fn get_playing_now(user_name: &str) -> Result<Value, Error> {
-    let playing_now = json!({
-        "payload": {
-            "count": 1,
-            "listens": [
-                {
-                    "playing_now": true,
-                    "track_metadata": {
-                        "additional_info": {
-                            "duration": 170,
-                            "music_service_name": "Spotify",
-                            "origin_url": "https://open.spotify.com/track/26haJjtanhamtf25RXH0MO",
-                            "spotify_id": "https://open.spotify.com/track/26haJjtanhamtf25RXH0MO",
-                            "submission_client": "Web Scrobbler",
-                            "submission_client_version": "3.14.0"
-                        },
-                        "artist_name": "Martin Solveig, ALMA",
-                        "release_name": "All Stars",
-                        "track_name": "All Stars"
-                    }
-                }
-            ],
-            "playing_now": true,
-            "user_id": user_name
-        }
-    });
-    Ok(playing_now)
+use crate::{
+    cache::Cache,
+    listenbrainz::{core::listens::track_metadata, response::user_not_found},
+    repo,
+};
+
+// How long a `playing_now` submission without a duration is reported as
+// playing, in seconds.
+pub const PLAYING_NOW_DEFAULT_TTL: usize = 10 * 60;
+
+/// Cache key under which `playing_now` submissions are kept until the track
+/// is expected to end.
+pub fn playing_now_key(did: &str) -> String {
+    format!("listenbrainz:playing_now:{}", did)
+}
+
+pub async fn get_playing_now(
+    pool: &Pool<Postgres>,
+    cache: &Cache,
+    user_name: &str,
+) -> Result<HttpResponse, Error> {
+    let user = match repo::user::get_user_by_name(pool, user_name).await? {
+        Some(user) => user,
+        None => return Ok(user_not_found(user_name)),
+    };
+
+    // Prefer what the client explicitly reported as playing now, then fall
+    // back to the latest scrobble whose track hasn't finished yet.
+    let metadata = match cache.get(&playing_now_key(&user.did))? {
+        Some(cached) => Some(serde_json::from_str::<Value>(&cached)?),
+        None => repo::listen::get_current_listen(pool, &user.xata_id)
+            .await?
+            .map(|listen| track_metadata(&listen)),
+    };
+
+    let listens = metadata
+        .map(|metadata| {
+            vec![json!({
+              "playing_now": true,
+              "track_metadata": metadata,
+            })]
+        })
+        .unwrap_or_default();
+
+    Ok(HttpResponse::Ok().json(json!({
+      "payload": {
+        "count": listens.len(),
+        "listens": listens,
+        "playing_now": true,
+        "user_id": user.handle,
+      }
+    })))
 }
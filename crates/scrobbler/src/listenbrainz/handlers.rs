@@ -9,14 +9,29 @@ use crate::{
     events::Events,
     listenbrainz::{
         core::{
-            listen_count::get_listen_count, listens::get_listens, playing_now::get_playing_now,
-            search_users::search_users, submit::submit_listens,
+            listen_count::get_listen_count,
+            listens::get_listens,
+            playing_now::{get_playing_now, playing_now_key, PLAYING_NOW_DEFAULT_TTL},
+            search_users::search_users,
+            submit::submit_listens,
         },
         statistics::{
-            artists::get_top_artists, recordings::get_top_recordings,
-            release_groups::get_top_release_groups, releases::get_top_releases,
+            activity::{
+                get_artist_activity, get_daily_activity, get_listening_activity,
+                get_sitewide_listening_activity,
+            },
+            artists::{
+                get_artist_listeners, get_artist_map, get_sitewide_artist_map,
+                get_sitewide_artists, get_top_artists,
+            },
+            recordings::{get_sitewide_recordings, get_top_recordings},
+            release_groups::{
+                get_release_group_listeners, get_sitewide_release_groups, get_top_release_groups,
+            },
+            releases::{get_sitewide_releases, get_top_releases},
+            year_in_music::get_year_in_music,
         },
-        types::SubmitListensRequest,
+        types::{GetListensParams, StatsParams, SubmitListensRequest},
     },
     musicbrainz::client::MusicbrainzClient,
     repo,
@@ -127,6 +142,22 @@ pub async fn handle_submit_listens(
                 });
 
                 events.emit_song_changed(&did, track).await;
+
+                // Keep the reported track around until it should have ended so
+                // `GET /1/user/{user_name}/playing-now` can serve it.
+                let ttl = if duration_ms > 0 {
+                    (duration_ms / 1000) as usize
+                } else {
+                    PLAYING_NOW_DEFAULT_TTL
+                };
+                match serde_json::to_string(meta) {
+                    Ok(value) => {
+                        if let Err(e) = cache.setex(&playing_now_key(&did), &value, ttl) {
+                            tracing::error!(error = %e, "Failed to cache playing now");
+                        }
+                    }
+                    Err(e) => tracing::error!(error = %e, "Failed to serialize playing now"),
+                }
                 // Only schedule stop timer when duration is known; if unknown we
                 // rely on the next playing_now submission (or never fire).
                 if duration_ms > 0 {
@@ -217,10 +248,14 @@ pub async fn handle_search_users(
 }
 
 #[get("/1/user/{user_name}/listens")]
-pub async fn handle_get_listens(user_name: web::Path<String>) -> impl Responder {
+pub async fn handle_get_listens(
+    user_name: web::Path<String>,
+    params: web::Query<GetListensParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
     let user_name = user_name.into_inner();
-    match get_listens(&user_name).await {
-        Ok(listens) => HttpResponse::Ok().json(listens),
+    match get_listens(data.get_ref(), &user_name, &params).await {
+        Ok(response) => response,
         Err(e) => {
             tracing::error!(error = %e, "Error getting listens for user {}", user_name);
             HttpResponse::InternalServerError().finish()
@@ -229,10 +264,13 @@ pub async fn handle_get_listens(user_name: web::Path<String>) -> impl Responder
 }
 
 #[get("/1/user/{user_name}/listen-count")]
-pub async fn handle_get_listen_count(user_name: web::Path<String>) -> impl Responder {
+pub async fn handle_get_listen_count(
+    user_name: web::Path<String>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
     let user_name = user_name.into_inner();
-    match get_listen_count(&user_name).await {
-        Ok(count) => HttpResponse::Ok().json(count),
+    match get_listen_count(data.get_ref(), &user_name).await {
+        Ok(response) => response,
         Err(e) => {
             tracing::error!(error = %e, "Error getting listen count for user {}", user_name);
             HttpResponse::InternalServerError().finish()
@@ -241,10 +279,14 @@ pub async fn handle_get_listen_count(user_name: web::Path<String>) -> impl Respo
 }
 
 #[get("/1/user/{user_name}/playing-now")]
-pub async fn handle_get_playing_now(user_name: web::Path<String>) -> impl Responder {
+pub async fn handle_get_playing_now(
+    user_name: web::Path<String>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+    cache: web::Data<Cache>,
+) -> impl Responder {
     let user_name = user_name.into_inner();
-    match get_playing_now(&user_name).await {
-        Ok(playing_now) => HttpResponse::Ok().json(playing_now),
+    match get_playing_now(data.get_ref(), cache.get_ref(), &user_name).await {
+        Ok(response) => response,
         Err(e) => {
             tracing::error!(error = %e, "Error getting playing now for user {}", user_name);
             HttpResponse::InternalServerError().finish()
@@ -253,10 +295,14 @@ pub async fn handle_get_playing_now(user_name: web::Path<String>) -> impl Respon
 }
 
 #[get("/1/stats/user/{user_name}/artists")]
-pub async fn handle_get_artists(user_name: web::Path<String>) -> impl Responder {
+pub async fn handle_get_artists(
+    user_name: web::Path<String>,
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
     let user_name = user_name.into_inner();
-    match get_top_artists(&user_name).await {
-        Ok(artists) => HttpResponse::Ok().json(artists),
+    match get_top_artists(data.get_ref(), &user_name, &params).await {
+        Ok(response) => response,
         Err(e) => {
             tracing::error!(error = %e, "Error getting top artists");
             HttpResponse::InternalServerError().finish()
@@ -265,10 +311,14 @@ pub async fn handle_get_artists(user_name: web::Path<String>) -> impl Responder
 }
 
 #[get("/1/stats/user/{user_name}/releases")]
-pub async fn handle_get_releases(user_name: web::Path<String>) -> impl Responder {
+pub async fn handle_get_releases(
+    user_name: web::Path<String>,
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
     let user_name = user_name.into_inner();
-    match get_top_releases(&user_name).await {
-        Ok(releases) => HttpResponse::Ok().json(releases),
+    match get_top_releases(data.get_ref(), &user_name, &params).await {
+        Ok(response) => response,
         Err(e) => {
             tracing::error!(error = %e, "Error getting top releases");
             HttpResponse::InternalServerError().finish()
@@ -277,10 +327,14 @@ pub async fn handle_get_releases(user_name: web::Path<String>) -> impl Responder
 }
 
 #[get("/1/stats/user/{user_name}/recordings")]
-pub async fn handle_get_recordings(user_name: web::Path<String>) -> impl Responder {
+pub async fn handle_get_recordings(
+    user_name: web::Path<String>,
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
     let user_name = user_name.into_inner();
-    match get_top_recordings(&user_name).await {
-        Ok(recordings) => HttpResponse::Ok().json(recordings),
+    match get_top_recordings(data.get_ref(), &user_name, &params).await {
+        Ok(response) => response,
         Err(e) => {
             tracing::error!(error = %e, "Error getting top recordings");
             HttpResponse::InternalServerError().finish()
@@ -289,10 +343,14 @@ pub async fn handle_get_recordings(user_name: web::Path<String>) -> impl Respond
 }
 
 #[get("/1/stats/user/{user_name}/release-groups")]
-pub async fn handle_get_release_groups(user_name: web::Path<String>) -> impl Responder {
+pub async fn handle_get_release_groups(
+    user_name: web::Path<String>,
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
     let user_name = user_name.into_inner();
-    match get_top_release_groups(&user_name).await {
-        Ok(release_groups) => HttpResponse::Ok().json(release_groups),
+    match get_top_release_groups(data.get_ref(), &user_name, &params).await {
+        Ok(response) => response,
         Err(e) => {
             tracing::error!(error = %e, "Error getting top release groups");
             HttpResponse::InternalServerError().finish()
@@ -300,13 +358,196 @@ pub async fn handle_get_release_groups(user_name: web::Path<String>) -> impl Res
     }
 }
 
-#[get("/1/stats/user/{user_name}/recordings")]
-pub async fn handle_get_recording_activity(user_name: web::Path<String>) -> impl Responder {
+#[get("/1/stats/user/{user_name}/listening-activity")]
+pub async fn handle_get_listening_activity(
+    user_name: web::Path<String>,
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
     let user_name = user_name.into_inner();
-    match get_top_recordings(&user_name).await {
-        Ok(recordings) => HttpResponse::Ok().json(recordings),
+    match get_listening_activity(data.get_ref(), &user_name, &params).await {
+        Ok(response) => response,
         Err(e) => {
-            tracing::error!(error = %e, "Error getting top recordings");
+            tracing::error!(error = %e, "Error getting listening activity");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/1/stats/user/{user_name}/daily-activity")]
+pub async fn handle_get_daily_activity(
+    user_name: web::Path<String>,
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
+    let user_name = user_name.into_inner();
+    match get_daily_activity(data.get_ref(), &user_name, &params).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(error = %e, "Error getting daily activity");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/1/stats/user/{user_name}/artist-activity")]
+pub async fn handle_get_artist_activity(
+    user_name: web::Path<String>,
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
+    let user_name = user_name.into_inner();
+    match get_artist_activity(data.get_ref(), &user_name, &params).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(error = %e, "Error getting artist activity");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/1/stats/user/{user_name}/artist-map")]
+pub async fn handle_get_artist_map(
+    user_name: web::Path<String>,
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
+    let user_name = user_name.into_inner();
+    match get_artist_map(data.get_ref(), &user_name, &params).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(error = %e, "Error getting artist map");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/1/stats/user/{user_name}/year-in-music/{year}")]
+pub async fn handle_get_year_in_music(
+    path: web::Path<(String, i32)>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
+    let (user_name, year) = path.into_inner();
+    match get_year_in_music(data.get_ref(), &user_name, year).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(error = %e, "Error getting year in music");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/1/stats/artist/{artist_id}/listeners")]
+pub async fn handle_get_artist_listeners(
+    artist_id: web::Path<String>,
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
+    let artist_id = artist_id.into_inner();
+    match get_artist_listeners(data.get_ref(), &artist_id, &params).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(error = %e, "Error getting artist listeners");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/1/stats/release-group/{release_group_id}/listeners")]
+pub async fn handle_get_release_group_listeners(
+    release_group_id: web::Path<String>,
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
+    let release_group_id = release_group_id.into_inner();
+    match get_release_group_listeners(data.get_ref(), &release_group_id, &params).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(error = %e, "Error getting release group listeners");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/1/stats/sitewide/artists")]
+pub async fn handle_get_sitewide_artists(
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
+    match get_sitewide_artists(data.get_ref(), &params).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(error = %e, "Error getting sitewide artists");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/1/stats/sitewide/releases")]
+pub async fn handle_get_sitewide_releases(
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
+    match get_sitewide_releases(data.get_ref(), &params).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(error = %e, "Error getting sitewide releases");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/1/stats/sitewide/recordings")]
+pub async fn handle_get_sitewide_recordings(
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
+    match get_sitewide_recordings(data.get_ref(), &params).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(error = %e, "Error getting sitewide recordings");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/1/stats/sitewide/release-groups")]
+pub async fn handle_get_sitewide_release_groups(
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
+    match get_sitewide_release_groups(data.get_ref(), &params).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(error = %e, "Error getting sitewide release groups");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/1/stats/sitewide/listening-activity")]
+pub async fn handle_get_sitewide_listening_activity(
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
+    match get_sitewide_listening_activity(data.get_ref(), &params).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(error = %e, "Error getting sitewide listening activity");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[get("/1/stats/sitewide/artist-map")]
+pub async fn handle_get_sitewide_artist_map(
+    params: web::Query<StatsParams>,
+    data: web::Data<Arc<Pool<Postgres>>>,
+) -> impl Responder {
+    match get_sitewide_artist_map(data.get_ref(), &params).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(error = %e, "Error getting sitewide artist map");
             HttpResponse::InternalServerError().finish()
         }
     }
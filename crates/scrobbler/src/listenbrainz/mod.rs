@@ -1,4 +1,5 @@
 pub mod core;
 pub mod handlers;
+pub mod response;
 pub mod statistics;
 pub mod types;
@@ -0,0 +1,42 @@
+use actix_web::{http::StatusCode, HttpResponse};
+use serde_json::json;
+
+pub const DEFAULT_ITEMS_PER_GET: i64 = 25;
+pub const MAX_ITEMS_PER_GET: i64 = 1000;
+pub const MAX_STATS_ITEMS_PER_GET: i64 = 100;
+
+/// Error body in the shape ListenBrainz clients expect: `{"code", "error"}`.
+pub fn error(status: StatusCode, message: &str) -> HttpResponse {
+    HttpResponse::build(status).json(json!({
+      "code": status.as_u16(),
+      "error": message,
+    }))
+}
+
+pub fn bad_request(message: &str) -> HttpResponse {
+    error(StatusCode::BAD_REQUEST, message)
+}
+
+pub fn user_not_found(user_name: &str) -> HttpResponse {
+    error(
+        StatusCode::NOT_FOUND,
+        &format!("Cannot find user: {}", user_name),
+    )
+}
+
+/// Validates an optional `count` query parameter, clamping it to `max`.
+pub fn parse_count(count: Option<i64>, max: i64) -> Result<i64, HttpResponse> {
+    match count {
+        None => Ok(DEFAULT_ITEMS_PER_GET),
+        Some(c) if c < 0 => Err(bad_request("Number of items to fetch must be positive")),
+        Some(c) => Ok(c.min(max)),
+    }
+}
+
+pub fn parse_offset(offset: Option<i64>) -> Result<i64, HttpResponse> {
+    match offset {
+        None => Ok(0),
+        Some(o) if o < 0 => Err(bad_request("Offset must be positive")),
+        Some(o) => Ok(o),
+    }
+}
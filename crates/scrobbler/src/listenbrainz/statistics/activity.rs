@@ -1,17 +1,233 @@
+use std::collections::HashMap;
+
+use actix_web::HttpResponse;
 use anyhow::Error;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    listenbrainz::{
+        response::{bad_request, user_not_found},
+        statistics::range::{Bucket, StatsRange},
+        types::StatsParams,
+    },
+    repo,
+    xata::user::UserWithoutSecret,
+};
+
+// Number of artists reported by `artist-activity`.
+const ARTIST_ACTIVITY_LIMIT: i64 = 15;
+
+pub const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+pub async fn get_listening_activity(
+    pool: &Pool<Postgres>,
+    user_name: &str,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    match repo::user::get_user_by_name(pool, user_name).await? {
+        Some(user) => listening_activity(pool, Some(&user), params).await,
+        None => Ok(user_not_found(user_name)),
+    }
+}
+
+pub async fn get_sitewide_listening_activity(
+    pool: &Pool<Postgres>,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    listening_activity(pool, None, params).await
+}
+
+async fn listening_activity(
+    pool: &Pool<Postgres>,
+    user: Option<&UserWithoutSecret>,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    let range = match StatsRange::parse(params.range.as_deref()) {
+        Ok(range) => range,
+        Err(e) => return Ok(bad_request(&e.to_string())),
+    };
+    let (from, to, bucket) = range.activity_bounds(Utc::now());
+
+    let rows = repo::stats::get_activity(
+        pool,
+        user.map(|u| u.xata_id.as_str()),
+        from.timestamp(),
+        to.timestamp(),
+        bucket.as_sql(),
+    )
+    .await?;
+    if rows.is_empty() {
+        return Ok(HttpResponse::NoContent().finish());
+    }
 
-pub async fn get_listening_activity(_user_name: &str) -> Result<(), Error> {
-    Ok(())
+    // all_time starts at the epoch; begin at the first bucket with listens
+    // instead of emitting decades of empty years.
+    let from = match range {
+        StatsRange::AllTime => DateTime::from_timestamp(rows[0].bucket, 0).unwrap_or(from),
+        _ => from,
+    };
+    let counts: HashMap<i64, i64> = rows.iter().map(|r| (r.bucket, r.listen_count)).collect();
+    let listening_activity = fill_buckets(from, to, bucket, &counts);
+
+    let mut payload = json!({
+      "listening_activity": listening_activity,
+      "range": range.as_str(),
+      "from_ts": from.timestamp(),
+      "to_ts": to.timestamp(),
+      "last_updated": Utc::now().timestamp(),
+    });
+    if let Some(user) = user {
+        payload["user_id"] = json!(user.handle);
+    }
+
+    Ok(HttpResponse::Ok().json(json!({ "payload": payload })))
 }
 
-pub async fn get_artist_activity(_user_name: &str) -> Result<(), Error> {
-    Ok(())
+/// Emits one entry per bucket between `from` and `to`, including buckets
+/// without any listen.
+pub fn fill_buckets(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    bucket: Bucket,
+    counts: &HashMap<i64, i64>,
+) -> Vec<Value> {
+    let mut entries = vec![];
+    let mut start = from;
+    while start < to {
+        let end = bucket.next(start);
+        entries.push(json!({
+          "from_ts": start.timestamp(),
+          "to_ts": end.timestamp() - 1,
+          "time_range": bucket.label(start),
+          "listen_count": counts.get(&start.timestamp()).copied().unwrap_or(0),
+        }));
+        start = end;
+    }
+    entries
 }
 
-pub async fn get_daily_activity(_user_name: &str) -> Result<(), Error> {
-    Ok(())
+pub async fn get_daily_activity(
+    pool: &Pool<Postgres>,
+    user_name: &str,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    let user = match repo::user::get_user_by_name(pool, user_name).await? {
+        Some(user) => user,
+        None => return Ok(user_not_found(user_name)),
+    };
+    let range = match StatsRange::parse(params.range.as_deref()) {
+        Ok(range) => range,
+        Err(e) => return Ok(bad_request(&e.to_string())),
+    };
+    let (from, to) = range.bounds(Utc::now());
+
+    let rows = repo::stats::get_hourly_activity(
+        pool,
+        Some(&user.xata_id),
+        from.timestamp(),
+        to.timestamp(),
+    )
+    .await?;
+    if rows.is_empty() {
+        return Ok(HttpResponse::NoContent().finish());
+    }
+
+    let counts: HashMap<(i32, i32), i64> = rows
+        .iter()
+        .map(|r| ((r.day_of_week, r.hour), r.listen_count))
+        .collect();
+
+    let mut daily_activity = serde_json::Map::new();
+    for (index, day) in WEEKDAYS.iter().enumerate() {
+        let hours = (0..24)
+            .map(|hour| {
+                json!({
+                  "hour": hour,
+                  "listen_count": counts.get(&(index as i32 + 1, hour)).copied().unwrap_or(0),
+                })
+            })
+            .collect::<Vec<_>>();
+        daily_activity.insert(day.to_string(), json!(hours));
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+      "payload": {
+        "daily_activity": daily_activity,
+        "range": range.as_str(),
+        "from_ts": from.timestamp(),
+        "to_ts": to.timestamp(),
+        "last_updated": Utc::now().timestamp(),
+        "user_id": user.handle,
+      }
+    })))
 }
 
-pub async fn get_sitewide_listening_activity() -> Result<(), Error> {
-    Ok(())
+pub async fn get_artist_activity(
+    pool: &Pool<Postgres>,
+    user_name: &str,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    let user = match repo::user::get_user_by_name(pool, user_name).await? {
+        Some(user) => user,
+        None => return Ok(user_not_found(user_name)),
+    };
+    let range = match StatsRange::parse(params.range.as_deref()) {
+        Ok(range) => range,
+        Err(e) => return Ok(bad_request(&e.to_string())),
+    };
+    let (from, to) = range.bounds(Utc::now());
+
+    let artists = repo::stats::get_top_artists(
+        pool,
+        Some(&user.xata_id),
+        from.timestamp(),
+        to.timestamp(),
+        ARTIST_ACTIVITY_LIMIT,
+        0,
+    )
+    .await?;
+    if artists.is_empty() {
+        return Ok(HttpResponse::NoContent().finish());
+    }
+
+    let artist_ids = artists
+        .iter()
+        .map(|a| a.xata_id.clone())
+        .collect::<Vec<_>>();
+    let albums = repo::stats::get_artist_albums(
+        pool,
+        Some(&user.xata_id),
+        from.timestamp(),
+        to.timestamp(),
+        &artist_ids,
+    )
+    .await?;
+
+    let result = artists
+        .iter()
+        .map(|artist| {
+            json!({
+              "name": artist.name,
+              "artist_mbid": null,
+              "listen_count": artist.listen_count,
+              "albums": albums
+                  .iter()
+                  .filter(|album| album.artist_id == artist.xata_id)
+                  .map(|album| json!({ "name": album.title, "listen_count": album.listen_count }))
+                  .collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(json!({ "result": result })))
 }
@@ -1,21 +1,244 @@
+use std::collections::BTreeMap;
+
+use actix_web::{http::StatusCode, HttpResponse};
 use anyhow::Error;
+use chrono::Utc;
+use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    listenbrainz::{
+        response::{error, parse_count, parse_offset, user_not_found, MAX_STATS_ITEMS_PER_GET},
+        statistics::window,
+        types::StatsParams,
+    },
+    repo::{self, stats::ArtistStat},
+    xata::user::UserWithoutSecret,
+};
+
+// Upper bound on the number of artists considered when building artist maps.
+const ARTIST_MAP_LIMIT: i64 = 1000;
+
+pub async fn get_top_artists(
+    pool: &Pool<Postgres>,
+    user_name: &str,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    match repo::user::get_user_by_name(pool, user_name).await? {
+        Some(user) => top_artists(pool, Some(&user), params).await,
+        None => Ok(user_not_found(user_name)),
+    }
+}
+
+pub async fn get_sitewide_artists(
+    pool: &Pool<Postgres>,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    top_artists(pool, None, params).await
+}
+
+async fn top_artists(
+    pool: &Pool<Postgres>,
+    user: Option<&UserWithoutSecret>,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    let window = match window(params.range.as_deref()) {
+        Ok(window) => window,
+        Err(response) => return Ok(response),
+    };
+    let count = match parse_count(params.count, MAX_STATS_ITEMS_PER_GET) {
+        Ok(count) => count,
+        Err(response) => return Ok(response),
+    };
+    let offset = match parse_offset(params.offset) {
+        Ok(offset) => offset,
+        Err(response) => return Ok(response),
+    };
+    let user_id = user.map(|u| u.xata_id.as_str());
+
+    let total =
+        repo::stats::count_distinct(pool, user_id, "artist_id", window.from_ts, window.to_ts)
+            .await?;
+    if total == 0 {
+        return Ok(HttpResponse::NoContent().finish());
+    }
+
+    let artists =
+        repo::stats::get_top_artists(pool, user_id, window.from_ts, window.to_ts, count, offset)
+            .await?;
+
+    let mut payload = json!({
+      "artists": artists.iter().map(artist_to_json).collect::<Vec<_>>(),
+      "count": artists.len(),
+      "offset": offset,
+      "total_artist_count": total,
+      "range": window.range.as_str(),
+      "from_ts": window.from_ts,
+      "to_ts": window.to_ts,
+      "last_updated": Utc::now().timestamp(),
+    });
+    if let Some(user) = user {
+        payload["user_id"] = json!(user.handle);
+    }
 
-pub async fn get_top_artists(_user_name: &str) -> Result<(), Error> {
-    Ok(())
+    Ok(HttpResponse::Ok().json(json!({ "payload": payload })))
 }
 
-pub async fn get_artist_map(_user_name: &str) -> Result<(), Error> {
-    Ok(())
+pub async fn get_artist_map(
+    pool: &Pool<Postgres>,
+    user_name: &str,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    match repo::user::get_user_by_name(pool, user_name).await? {
+        Some(user) => artist_map(pool, Some(&user), params).await,
+        None => Ok(user_not_found(user_name)),
+    }
 }
 
-pub async fn get_artist_listeners(_user_name: &str) -> Result<(), Error> {
-    Ok(())
+pub async fn get_sitewide_artist_map(
+    pool: &Pool<Postgres>,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    artist_map(pool, None, params).await
 }
 
-pub async fn get_sitewide_artists() -> Result<(), Error> {
-    Ok(())
+/// Groups the most listened artists by the area they come from. Rocksky only
+/// knows the free-form `born_in` of an artist, which is reported as-is in the
+/// `country` field; artists without one are left out of the map.
+async fn artist_map(
+    pool: &Pool<Postgres>,
+    user: Option<&UserWithoutSecret>,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    let window = match window(params.range.as_deref()) {
+        Ok(window) => window,
+        Err(response) => return Ok(response),
+    };
+
+    let artists = repo::stats::get_top_artists(
+        pool,
+        user.map(|u| u.xata_id.as_str()),
+        window.from_ts,
+        window.to_ts,
+        ARTIST_MAP_LIMIT,
+        0,
+    )
+    .await?;
+    if artists.is_empty() {
+        return Ok(HttpResponse::NoContent().finish());
+    }
+
+    let mut countries: BTreeMap<&str, Vec<&ArtistStat>> = BTreeMap::new();
+    for artist in &artists {
+        if let Some(country) = artist.born_in.as_deref().filter(|c| !c.is_empty()) {
+            countries.entry(country).or_default().push(artist);
+        }
+    }
+
+    let mut artist_map = countries
+        .into_iter()
+        .map(|(country, artists)| {
+            let listen_count: i64 = artists.iter().map(|a| a.listen_count).sum();
+            (
+                listen_count,
+                json!({
+                  "country": country,
+                  "artist_count": artists.len(),
+                  "listen_count": listen_count,
+                  "artists": artists.iter().map(|a| artist_to_json(a)).collect::<Vec<_>>(),
+                }),
+            )
+        })
+        .collect::<Vec<_>>();
+    artist_map.sort_by_key(|c| std::cmp::Reverse(c.0));
+
+    let mut payload = json!({
+      "artist_map": artist_map.into_iter().map(|(_, c)| c).collect::<Vec<_>>(),
+      "range": window.range.as_str(),
+      "from_ts": window.from_ts,
+      "to_ts": window.to_ts,
+      "last_updated": Utc::now().timestamp(),
+    });
+    if let Some(user) = user {
+        payload["user_id"] = json!(user.handle);
+    }
+
+    Ok(HttpResponse::Ok().json(json!({ "payload": payload })))
+}
+
+/// Top listeners of an artist. Rocksky doesn't store artist MBIDs, so the
+/// artist is identified by its Rocksky id or AT-URI.
+pub async fn get_artist_listeners(
+    pool: &Pool<Postgres>,
+    artist_id: &str,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    let window = match window(params.range.as_deref()) {
+        Ok(window) => window,
+        Err(response) => return Ok(response),
+    };
+    let count = match parse_count(params.count, MAX_STATS_ITEMS_PER_GET) {
+        Ok(count) => count,
+        Err(response) => return Ok(response),
+    };
+
+    let artist = match repo::artist::get_artist(pool, artist_id).await? {
+        Some(artist) => artist,
+        None => {
+            return Ok(error(
+                StatusCode::NOT_FOUND,
+                &format!("Cannot find artist: {}", artist_id),
+            ))
+        }
+    };
+
+    let (total_listen_count, total_user_count) = repo::stats::count_listeners(
+        pool,
+        "artist_id",
+        &artist.xata_id,
+        window.from_ts,
+        window.to_ts,
+    )
+    .await?;
+    if total_listen_count == 0 {
+        return Ok(HttpResponse::NoContent().finish());
+    }
+
+    let listeners = repo::stats::get_listeners(
+        pool,
+        "artist_id",
+        &artist.xata_id,
+        window.from_ts,
+        window.to_ts,
+        count,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+      "payload": {
+        "artist_mbid": null,
+        "artist_id": artist.xata_id,
+        "artist_name": artist.name,
+        "listeners": listeners
+            .iter()
+            .map(|l| json!({ "user_name": l.handle, "listen_count": l.listen_count }))
+            .collect::<Vec<_>>(),
+        "total_listen_count": total_listen_count,
+        "total_user_count": total_user_count,
+        "stats_range": window.range.as_str(),
+        "from_ts": window.from_ts,
+        "to_ts": window.to_ts,
+        "last_updated": Utc::now().timestamp(),
+      }
+    })))
 }
 
-pub async fn get_sitewide_artist_map() -> Result<(), Error> {
-    Ok(())
+pub fn artist_to_json(artist: &ArtistStat) -> Value {
+    json!({
+      "artist_mbids": [],
+      "artist_name": artist.name,
+      "artist_id": artist.xata_id,
+      "artist_uri": artist.uri,
+      "listen_count": artist.listen_count,
+    })
 }
@@ -1,6 +1,31 @@
 pub mod activity;
 pub mod artists;
+pub mod range;
 pub mod recordings;
 pub mod release_groups;
 pub mod releases;
 pub mod year_in_music;
+
+use actix_web::HttpResponse;
+use chrono::Utc;
+
+use crate::listenbrainz::response::bad_request;
+
+use self::range::StatsRange;
+
+/// The `[from_ts, to_ts)` window a statistics request covers.
+pub struct Window {
+    pub range: StatsRange,
+    pub from_ts: i64,
+    pub to_ts: i64,
+}
+
+pub fn window(range: Option<&str>) -> Result<Window, HttpResponse> {
+    let range = StatsRange::parse(range).map_err(|e| bad_request(&e.to_string()))?;
+    let (from, to) = range.bounds(Utc::now());
+    Ok(Window {
+        range,
+        from_ts: from.timestamp(),
+        to_ts: to.timestamp(),
+    })
+}
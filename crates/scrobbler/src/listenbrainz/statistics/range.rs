@@ -0,0 +1,210 @@
+use anyhow::Error;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+
+/// Time ranges accepted by the ListenBrainz statistics endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsRange {
+    ThisWeek,
+    ThisMonth,
+    ThisYear,
+    Week,
+    Month,
+    Quarter,
+    HalfYearly,
+    Year,
+    AllTime,
+}
+
+/// Granularity of the buckets returned by `listening-activity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bucket {
+    Day,
+    Month,
+    Year,
+}
+
+impl Bucket {
+    /// Unit name understood by PostgreSQL's `date_trunc`.
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Bucket::Day => "day",
+            Bucket::Month => "month",
+            Bucket::Year => "year",
+        }
+    }
+
+    pub fn next(&self, start: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Bucket::Day => start + Duration::days(1),
+            Bucket::Month => add_months(start, 1),
+            Bucket::Year => add_months(start, 12),
+        }
+    }
+
+    pub fn label(&self, start: DateTime<Utc>) -> String {
+        match self {
+            Bucket::Day => start.format("%A %d %B %Y").to_string(),
+            Bucket::Month => start.format("%B %Y").to_string(),
+            Bucket::Year => start.format("%Y").to_string(),
+        }
+    }
+}
+
+impl StatsRange {
+    pub fn parse(range: Option<&str>) -> Result<Self, Error> {
+        match range.unwrap_or("all_time") {
+            "this_week" => Ok(StatsRange::ThisWeek),
+            "this_month" => Ok(StatsRange::ThisMonth),
+            "this_year" => Ok(StatsRange::ThisYear),
+            "week" => Ok(StatsRange::Week),
+            "month" => Ok(StatsRange::Month),
+            "quarter" => Ok(StatsRange::Quarter),
+            "half_yearly" => Ok(StatsRange::HalfYearly),
+            "year" => Ok(StatsRange::Year),
+            "all_time" => Ok(StatsRange::AllTime),
+            other => Err(Error::msg(format!("Invalid range: {}", other))),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatsRange::ThisWeek => "this_week",
+            StatsRange::ThisMonth => "this_month",
+            StatsRange::ThisYear => "this_year",
+            StatsRange::Week => "week",
+            StatsRange::Month => "month",
+            StatsRange::Quarter => "quarter",
+            StatsRange::HalfYearly => "half_yearly",
+            StatsRange::Year => "year",
+            StatsRange::AllTime => "all_time",
+        }
+    }
+
+    /// Returns the `[from, to)` window covered by this range. `week`, `month`,
+    /// `quarter`, `half_yearly` and `year` refer to the last *complete*
+    /// period, `this_*` to the period in progress, like upstream ListenBrainz.
+    pub fn bounds(&self, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let today = day_start(now);
+        let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+        let month_start = Utc
+            .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+            .unwrap();
+        let year_start = Utc.with_ymd_and_hms(now.year(), 1, 1, 0, 0, 0).unwrap();
+
+        match self {
+            StatsRange::ThisWeek => (week_start, now),
+            StatsRange::ThisMonth => (month_start, now),
+            StatsRange::ThisYear => (year_start, now),
+            StatsRange::Week => (week_start - Duration::days(7), week_start),
+            StatsRange::Month => (add_months(month_start, -1), month_start),
+            StatsRange::Quarter => {
+                let quarter_start = Utc
+                    .with_ymd_and_hms(now.year(), (now.month0() / 3) * 3 + 1, 1, 0, 0, 0)
+                    .unwrap();
+                (add_months(quarter_start, -3), quarter_start)
+            }
+            StatsRange::HalfYearly => {
+                let half_start = Utc
+                    .with_ymd_and_hms(now.year(), (now.month0() / 6) * 6 + 1, 1, 0, 0, 0)
+                    .unwrap();
+                (add_months(half_start, -6), half_start)
+            }
+            StatsRange::Year => (add_months(year_start, -12), year_start),
+            StatsRange::AllTime => (DateTime::<Utc>::UNIX_EPOCH, now),
+        }
+    }
+
+    /// Window and bucket size used by `listening-activity`. For week, month
+    /// and year ranges the previous period is included as well so clients can
+    /// draw the "this period vs. last period" comparison.
+    pub fn activity_bounds(&self, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>, Bucket) {
+        let (from, to) = self.bounds(now);
+        match self {
+            StatsRange::ThisWeek | StatsRange::Week => (from - Duration::days(7), to, Bucket::Day),
+            StatsRange::ThisMonth | StatsRange::Month => (add_months(from, -1), to, Bucket::Day),
+            StatsRange::Quarter => (from, to, Bucket::Day),
+            StatsRange::HalfYearly => (from, to, Bucket::Month),
+            StatsRange::ThisYear | StatsRange::Year => (add_months(from, -12), to, Bucket::Month),
+            StatsRange::AllTime => (from, to, Bucket::Year),
+        }
+    }
+}
+
+fn day_start(dt: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(dt.year(), dt.month(), dt.day(), 0, 0, 0)
+        .unwrap()
+}
+
+/// Shifts a first-of-month timestamp by `months`, keeping it on the 1st.
+fn add_months(dt: DateTime<Utc>, months: i32) -> DateTime<Utc> {
+    let total = dt.year() * 12 + dt.month0() as i32 + months;
+    let date =
+        NaiveDate::from_ymd_opt(total.div_euclid(12), total.rem_euclid(12) as u32 + 1, 1).unwrap();
+    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        // Wednesday
+        Utc.with_ymd_and_hms(2025, 5, 14, 15, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(StatsRange::parse(None).unwrap(), StatsRange::AllTime);
+        assert_eq!(StatsRange::parse(Some("week")).unwrap(), StatsRange::Week);
+        assert_eq!(
+            StatsRange::parse(Some("half_yearly")).unwrap().as_str(),
+            "half_yearly"
+        );
+        assert!(StatsRange::parse(Some("decade")).is_err());
+    }
+
+    #[test]
+    fn test_week_bounds() {
+        let (from, to) = StatsRange::Week.bounds(now());
+        assert_eq!(from, Utc.with_ymd_and_hms(2025, 5, 5, 0, 0, 0).unwrap());
+        assert_eq!(to, Utc.with_ymd_and_hms(2025, 5, 12, 0, 0, 0).unwrap());
+
+        let (from, to) = StatsRange::ThisWeek.bounds(now());
+        assert_eq!(from, Utc.with_ymd_and_hms(2025, 5, 12, 0, 0, 0).unwrap());
+        assert_eq!(to, now());
+    }
+
+    #[test]
+    fn test_month_and_year_bounds() {
+        let (from, to) = StatsRange::Month.bounds(now());
+        assert_eq!(from, Utc.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap());
+        assert_eq!(to, Utc.with_ymd_and_hms(2025, 5, 1, 0, 0, 0).unwrap());
+
+        let (from, to) = StatsRange::Quarter.bounds(now());
+        assert_eq!(from, Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(to, Utc.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap());
+
+        let (from, to) = StatsRange::Year.bounds(now());
+        assert_eq!(from, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(to, Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_month_bounds_wrap_year() {
+        let january = Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap();
+        let (from, to) = StatsRange::Month.bounds(january);
+        assert_eq!(from, Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap());
+        assert_eq!(to, Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_activity_bounds() {
+        let (from, _, bucket) = StatsRange::Week.activity_bounds(now());
+        assert_eq!(from, Utc.with_ymd_and_hms(2025, 4, 28, 0, 0, 0).unwrap());
+        assert_eq!(bucket, Bucket::Day);
+
+        let (from, _, bucket) = StatsRange::ThisYear.activity_bounds(now());
+        assert_eq!(from, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(bucket, Bucket::Month);
+    }
+}
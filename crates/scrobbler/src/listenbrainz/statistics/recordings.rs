@@ -1,9 +1,94 @@
+use actix_web::HttpResponse;
 use anyhow::Error;
+use chrono::Utc;
+use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
 
-pub async fn get_top_recordings(_user_name: &str) -> Result<(), Error> {
-    Ok(())
+use crate::{
+    listenbrainz::{
+        response::{parse_count, parse_offset, user_not_found, MAX_STATS_ITEMS_PER_GET},
+        statistics::window,
+        types::StatsParams,
+    },
+    repo::{self, stats::TrackStat},
+    xata::user::UserWithoutSecret,
+};
+
+pub async fn get_top_recordings(
+    pool: &Pool<Postgres>,
+    user_name: &str,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    match repo::user::get_user_by_name(pool, user_name).await? {
+        Some(user) => top_recordings(pool, Some(&user), params).await,
+        None => Ok(user_not_found(user_name)),
+    }
+}
+
+pub async fn get_sitewide_recordings(
+    pool: &Pool<Postgres>,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    top_recordings(pool, None, params).await
+}
+
+async fn top_recordings(
+    pool: &Pool<Postgres>,
+    user: Option<&UserWithoutSecret>,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    let window = match window(params.range.as_deref()) {
+        Ok(window) => window,
+        Err(response) => return Ok(response),
+    };
+    let count = match parse_count(params.count, MAX_STATS_ITEMS_PER_GET) {
+        Ok(count) => count,
+        Err(response) => return Ok(response),
+    };
+    let offset = match parse_offset(params.offset) {
+        Ok(offset) => offset,
+        Err(response) => return Ok(response),
+    };
+    let user_id = user.map(|u| u.xata_id.as_str());
+
+    let total =
+        repo::stats::count_distinct(pool, user_id, "track_id", window.from_ts, window.to_ts)
+            .await?;
+    if total == 0 {
+        return Ok(HttpResponse::NoContent().finish());
+    }
+
+    let recordings =
+        repo::stats::get_top_tracks(pool, user_id, window.from_ts, window.to_ts, count, offset)
+            .await?;
+
+    let mut payload = json!({
+      "recordings": recordings.iter().map(recording_to_json).collect::<Vec<_>>(),
+      "count": recordings.len(),
+      "offset": offset,
+      "total_recording_count": total,
+      "range": window.range.as_str(),
+      "from_ts": window.from_ts,
+      "to_ts": window.to_ts,
+      "last_updated": Utc::now().timestamp(),
+    });
+    if let Some(user) = user {
+        payload["user_id"] = json!(user.handle);
+    }
+
+    Ok(HttpResponse::Ok().json(json!({ "payload": payload })))
 }
 
-pub async fn get_sitewide_recordings() -> Result<(), Error> {
-    Ok(())
+pub fn recording_to_json(recording: &TrackStat) -> Value {
+    json!({
+      "artist_mbids": [],
+      "artist_name": recording.artist,
+      "recording_mbid": recording.mb_id,
+      "release_mbid": null,
+      "release_name": recording.album,
+      "track_name": recording.title,
+      "recording_id": recording.xata_id,
+      "recording_uri": recording.uri,
+      "listen_count": recording.listen_count,
+    })
 }
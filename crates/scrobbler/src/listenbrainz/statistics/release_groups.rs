@@ -1,12 +1,166 @@
+use actix_web::{http::StatusCode, HttpResponse};
 use anyhow::Error;
+use chrono::Utc;
+use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
 
-pub async fn get_top_release_groups(_user_name: &str) -> Result<(), Error> {
-    Ok(())
+use crate::{
+    listenbrainz::{
+        response::{error, parse_count, parse_offset, user_not_found, MAX_STATS_ITEMS_PER_GET},
+        statistics::window,
+        types::StatsParams,
+    },
+    repo::{self, stats::AlbumStat},
+    xata::user::UserWithoutSecret,
+};
+
+// Rocksky has a single album entity, so release groups are backed by the same
+// `albums` rows as releases.
+
+pub async fn get_top_release_groups(
+    pool: &Pool<Postgres>,
+    user_name: &str,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    match repo::user::get_user_by_name(pool, user_name).await? {
+        Some(user) => top_release_groups(pool, Some(&user), params).await,
+        None => Ok(user_not_found(user_name)),
+    }
+}
+
+pub async fn get_sitewide_release_groups(
+    pool: &Pool<Postgres>,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    top_release_groups(pool, None, params).await
 }
-pub async fn get_release_group_listeners(_user_name: &str) -> Result<(), Error> {
-    Ok(())
+
+async fn top_release_groups(
+    pool: &Pool<Postgres>,
+    user: Option<&UserWithoutSecret>,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    let window = match window(params.range.as_deref()) {
+        Ok(window) => window,
+        Err(response) => return Ok(response),
+    };
+    let count = match parse_count(params.count, MAX_STATS_ITEMS_PER_GET) {
+        Ok(count) => count,
+        Err(response) => return Ok(response),
+    };
+    let offset = match parse_offset(params.offset) {
+        Ok(offset) => offset,
+        Err(response) => return Ok(response),
+    };
+    let user_id = user.map(|u| u.xata_id.as_str());
+
+    let total =
+        repo::stats::count_distinct(pool, user_id, "album_id", window.from_ts, window.to_ts)
+            .await?;
+    if total == 0 {
+        return Ok(HttpResponse::NoContent().finish());
+    }
+
+    let release_groups =
+        repo::stats::get_top_albums(pool, user_id, window.from_ts, window.to_ts, count, offset)
+            .await?;
+
+    let mut payload = json!({
+      "release_groups": release_groups.iter().map(release_group_to_json).collect::<Vec<_>>(),
+      "count": release_groups.len(),
+      "offset": offset,
+      "total_release_group_count": total,
+      "range": window.range.as_str(),
+      "from_ts": window.from_ts,
+      "to_ts": window.to_ts,
+      "last_updated": Utc::now().timestamp(),
+    });
+    if let Some(user) = user {
+        payload["user_id"] = json!(user.handle);
+    }
+
+    Ok(HttpResponse::Ok().json(json!({ "payload": payload })))
+}
+
+/// Top listeners of an album, identified by its Rocksky id or AT-URI.
+pub async fn get_release_group_listeners(
+    pool: &Pool<Postgres>,
+    release_group_id: &str,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    let window = match window(params.range.as_deref()) {
+        Ok(window) => window,
+        Err(response) => return Ok(response),
+    };
+    let count = match parse_count(params.count, MAX_STATS_ITEMS_PER_GET) {
+        Ok(count) => count,
+        Err(response) => return Ok(response),
+    };
+
+    let album = match repo::album::get_album(pool, release_group_id).await? {
+        Some(album) => album,
+        None => {
+            return Ok(error(
+                StatusCode::NOT_FOUND,
+                &format!("Cannot find release group: {}", release_group_id),
+            ))
+        }
+    };
+
+    let (total_listen_count, total_user_count) = repo::stats::count_listeners(
+        pool,
+        "album_id",
+        &album.xata_id,
+        window.from_ts,
+        window.to_ts,
+    )
+    .await?;
+    if total_listen_count == 0 {
+        return Ok(HttpResponse::NoContent().finish());
+    }
+
+    let listeners = repo::stats::get_listeners(
+        pool,
+        "album_id",
+        &album.xata_id,
+        window.from_ts,
+        window.to_ts,
+        count,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+      "payload": {
+        "release_group_mbid": null,
+        "release_group_id": album.xata_id,
+        "release_group_name": album.title,
+        "artist_mbids": [],
+        "artist_name": album.artist,
+        "caa_id": null,
+        "caa_release_mbid": null,
+        "listeners": listeners
+            .iter()
+            .map(|l| json!({ "user_name": l.handle, "listen_count": l.listen_count }))
+            .collect::<Vec<_>>(),
+        "total_listen_count": total_listen_count,
+        "total_user_count": total_user_count,
+        "stats_range": window.range.as_str(),
+        "from_ts": window.from_ts,
+        "to_ts": window.to_ts,
+        "last_updated": Utc::now().timestamp(),
+      }
+    })))
 }
 
-pub async fn get_sitewide_release_groups() -> Result<(), Error> {
-    Ok(())
+fn release_group_to_json(release_group: &AlbumStat) -> Value {
+    json!({
+      "artist_mbids": [],
+      "artist_name": release_group.artist,
+      "release_group_mbid": null,
+      "release_group_name": release_group.title,
+      "release_group_id": release_group.xata_id,
+      "release_group_uri": release_group.uri,
+      "album_art": release_group.album_art,
+      "listen_count": release_group.listen_count,
+    })
 }
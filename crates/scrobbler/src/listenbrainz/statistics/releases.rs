@@ -1,9 +1,93 @@
+use actix_web::HttpResponse;
 use anyhow::Error;
+use chrono::Utc;
+use serde_json::{json, Value};
+use sqlx::{Pool, Postgres};
 
-pub async fn get_top_releases(_user_name: &str) -> Result<(), Error> {
-    Ok(())
+use crate::{
+    listenbrainz::{
+        response::{parse_count, parse_offset, user_not_found, MAX_STATS_ITEMS_PER_GET},
+        statistics::window,
+        types::StatsParams,
+    },
+    repo::{self, stats::AlbumStat},
+    xata::user::UserWithoutSecret,
+};
+
+pub async fn get_top_releases(
+    pool: &Pool<Postgres>,
+    user_name: &str,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    match repo::user::get_user_by_name(pool, user_name).await? {
+        Some(user) => top_releases(pool, Some(&user), params).await,
+        None => Ok(user_not_found(user_name)),
+    }
+}
+
+pub async fn get_sitewide_releases(
+    pool: &Pool<Postgres>,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    top_releases(pool, None, params).await
+}
+
+async fn top_releases(
+    pool: &Pool<Postgres>,
+    user: Option<&UserWithoutSecret>,
+    params: &StatsParams,
+) -> Result<HttpResponse, Error> {
+    let window = match window(params.range.as_deref()) {
+        Ok(window) => window,
+        Err(response) => return Ok(response),
+    };
+    let count = match parse_count(params.count, MAX_STATS_ITEMS_PER_GET) {
+        Ok(count) => count,
+        Err(response) => return Ok(response),
+    };
+    let offset = match parse_offset(params.offset) {
+        Ok(offset) => offset,
+        Err(response) => return Ok(response),
+    };
+    let user_id = user.map(|u| u.xata_id.as_str());
+
+    let total =
+        repo::stats::count_distinct(pool, user_id, "album_id", window.from_ts, window.to_ts)
+            .await?;
+    if total == 0 {
+        return Ok(HttpResponse::NoContent().finish());
+    }
+
+    let releases =
+        repo::stats::get_top_albums(pool, user_id, window.from_ts, window.to_ts, count, offset)
+            .await?;
+
+    let mut payload = json!({
+      "releases": releases.iter().map(release_to_json).collect::<Vec<_>>(),
+      "count": releases.len(),
+      "offset": offset,
+      "total_release_count": total,
+      "range": window.range.as_str(),
+      "from_ts": window.from_ts,
+      "to_ts": window.to_ts,
+      "last_updated": Utc::now().timestamp(),
+    });
+    if let Some(user) = user {
+        payload["user_id"] = json!(user.handle);
+    }
+
+    Ok(HttpResponse::Ok().json(json!({ "payload": payload })))
 }
 
-pub async fn get_sitewide_releases() -> Result<(), Error> {
-    Ok(())
+pub fn release_to_json(release: &AlbumStat) -> Value {
+    json!({
+      "artist_mbids": [],
+      "artist_name": release.artist,
+      "release_mbid": null,
+      "release_name": release.title,
+      "release_id": release.xata_id,
+      "release_uri": release.uri,
+      "album_art": release.album_art,
+      "listen_count": release.listen_count,
+    })
 }
@@ -1,5 +1,96 @@
+use std::collections::HashMap;
+
+use actix_web::HttpResponse;
 use anyhow::Error;
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    listenbrainz::{
+        response::{bad_request, user_not_found},
+        statistics::{
+            activity::{fill_buckets, WEEKDAYS},
+            artists::artist_to_json,
+            range::Bucket,
+            recordings::recording_to_json,
+            releases::release_to_json,
+        },
+    },
+    repo,
+};
+
+// Number of entries in each top list of the report.
+const TOP_LIMIT: i64 = 50;
+
+pub async fn get_year_in_music(
+    pool: &Pool<Postgres>,
+    user_name: &str,
+    year: i32,
+) -> Result<HttpResponse, Error> {
+    let user = match repo::user::get_user_by_name(pool, user_name).await? {
+        Some(user) => user,
+        None => return Ok(user_not_found(user_name)),
+    };
+
+    let (from, to) = match (
+        Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).single(),
+        Utc.with_ymd_and_hms(year + 1, 1, 1, 0, 0, 0).single(),
+    ) {
+        (Some(from), Some(to)) => (from, to),
+        _ => return Ok(bad_request(&format!("Invalid year: {}", year))),
+    };
+    let (from_ts, to_ts) = (from.timestamp(), to.timestamp());
+    let user_id = Some(user.xata_id.as_str());
+
+    let total_listen_count = repo::stats::count_listens(pool, user_id, from_ts, to_ts).await?;
+    if total_listen_count == 0 {
+        return Ok(HttpResponse::NoContent().finish());
+    }
+
+    let total_artists_count =
+        repo::stats::count_distinct(pool, user_id, "artist_id", from_ts, to_ts).await?;
+    let total_new_artists_discovered =
+        repo::stats::count_new_artists(pool, &user.xata_id, from_ts, to_ts).await?;
+    let top_artists =
+        repo::stats::get_top_artists(pool, user_id, from_ts, to_ts, TOP_LIMIT, 0).await?;
+    let top_releases =
+        repo::stats::get_top_albums(pool, user_id, from_ts, to_ts, TOP_LIMIT, 0).await?;
+    let top_recordings =
+        repo::stats::get_top_tracks(pool, user_id, from_ts, to_ts, TOP_LIMIT, 0).await?;
+
+    let days = repo::stats::get_activity(pool, user_id, from_ts, to_ts, Bucket::Day.as_sql())
+        .await?
+        .into_iter()
+        .map(|r| (r.bucket, r.listen_count))
+        .collect::<HashMap<_, _>>();
+    let listens_per_day = fill_buckets(from, to, Bucket::Day, &days);
+
+    let mut weekdays = [0i64; 7];
+    for row in repo::stats::get_hourly_activity(pool, user_id, from_ts, to_ts).await? {
+        if let Some(count) = weekdays.get_mut(row.day_of_week as usize - 1) {
+            *count += row.listen_count;
+        }
+    }
+    let day_of_week = weekdays
+        .iter()
+        .enumerate()
+        .max_by_key(|(index, count)| (**count, std::cmp::Reverse(*index)))
+        .map(|(index, _)| WEEKDAYS[index]);
 
-pub async fn get_year_in_music(_user_name: &str) -> Result<(), Error> {
-    Ok(())
+    Ok(HttpResponse::Ok().json(json!({
+      "payload": {
+        "user_name": user.handle,
+        "data": {
+          "day_of_week": day_of_week,
+          "total_listen_count": total_listen_count,
+          "total_artists_count": total_artists_count,
+          "total_new_artists_discovered": total_new_artists_discovered,
+          "top_artists": top_artists.iter().map(artist_to_json).collect::<Vec<_>>(),
+          "top_releases": top_releases.iter().map(release_to_json).collect::<Vec<_>>(),
+          "top_recordings": top_recordings.iter().map(recording_to_json).collect::<Vec<_>>(),
+          "listens_per_day": listens_per_day,
+        }
+      }
+    })))
 }
@@ -1,9 +1,9 @@
 use std::collections::HashMap;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AdditionalInfo {
     pub release_name: Option<String>,
     pub musicbrainz_artist_id: Option<String>,
@@ -15,7 +15,7 @@ pub struct AdditionalInfo {
     pub extra: HashMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TrackMetadata {
     pub artist_name: String,
     pub track_name: String,
@@ -34,3 +34,17 @@ pub struct SubmitListensRequest {
     pub listen_type: String,
     pub payload: Vec<ListenPayload>,
 }
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GetListensParams {
+    pub min_ts: Option<i64>,
+    pub max_ts: Option<i64>,
+    pub count: Option<i64>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct StatsParams {
+    pub count: Option<i64>,
+    pub offset: Option<i64>,
+    pub range: Option<String>,
+}
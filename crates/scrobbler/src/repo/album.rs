@@ -32,3 +32,17 @@ pub async fn get_album_by_uri(pool: &Pool<Postgres>, uri: &str) -> Result<Album,
 
     Ok(results[0].clone())
 }
+
+pub async fn get_album(pool: &Pool<Postgres>, id: &str) -> Result<Option<Album>, Error> {
+    let results: Vec<Album> = sqlx::query_as(
+        r#"
+    SELECT * FROM albums
+    WHERE xata_id = $1 OR uri = $1
+    "#,
+    )
+    .bind(id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(results.into_iter().next())
+}
@@ -26,3 +26,20 @@ pub async fn get_apikey(
 
     Ok(Some(results[0].clone()))
 }
+
+pub async fn get_apikey_by_key(
+    pool: &Pool<Postgres>,
+    apikey: &str,
+) -> Result<Option<ApiKey>, Error> {
+    let results: Vec<ApiKey> = sqlx::query_as(
+        r#"
+    SELECT * FROM api_keys
+    WHERE api_key = $1 AND enabled = true
+  "#,
+    )
+    .bind(apikey)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(results.into_iter().next())
+}
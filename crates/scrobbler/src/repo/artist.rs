@@ -35,3 +35,17 @@ pub async fn get_artist_by_uri(pool: &Pool<Postgres>, uri: &str) -> Result<Artis
 
     Ok(results[0].clone())
 }
+
+pub async fn get_artist(pool: &Pool<Postgres>, id: &str) -> Result<Option<Artist>, Error> {
+    let results: Vec<Artist> = sqlx::query_as(
+        r#"
+    SELECT * FROM artists
+    WHERE xata_id = $1 OR uri = $1
+    "#,
+    )
+    .bind(id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(results.into_iter().next())
+}
@@ -0,0 +1,205 @@
+use anyhow::Error;
+use sqlx::{Pool, Postgres};
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct Listen {
+    pub xata_id: String,
+    pub uri: Option<String>,
+    pub listened_at: i64,
+    pub inserted_at: i64,
+    pub track_id: String,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub album_artist: String,
+    pub album_art: Option<String>,
+    pub duration: i32,
+    pub mb_id: Option<String>,
+    pub isrc: Option<String>,
+    pub track_number: Option<i32>,
+    pub spotify_link: Option<String>,
+}
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct ListenBounds {
+    pub oldest_listen_ts: Option<i64>,
+    pub latest_listen_ts: Option<i64>,
+}
+
+const LISTEN_COLUMNS: &str = r#"
+    scrobbles.xata_id,
+    scrobbles.uri,
+    EXTRACT(EPOCH FROM scrobbles.timestamp)::bigint AS listened_at,
+    EXTRACT(EPOCH FROM scrobbles.xata_createdat)::bigint AS inserted_at,
+    tracks.xata_id AS track_id,
+    tracks.title,
+    tracks.artist,
+    tracks.album,
+    tracks.album_artist,
+    tracks.album_art,
+    tracks.duration,
+    tracks.mb_id,
+    tracks.isrc,
+    tracks.track_number,
+    tracks.spotify_link
+"#;
+
+/// Fetches a page of listens strictly between `min_ts` and `max_ts`.
+///
+/// Listens are always returned newest first. When only `min_ts` is given the
+/// page closest to `min_ts` is selected (oldest first) and then reversed, so
+/// clients can walk forward through the history the same way they walk
+/// backward with `max_ts`.
+pub async fn get_listens(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    min_ts: Option<i64>,
+    max_ts: Option<i64>,
+    count: i64,
+) -> Result<Vec<Listen>, Error> {
+    let ascending = min_ts.is_some() && max_ts.is_none();
+    let query = format!(
+        r#"
+    SELECT {}
+    FROM scrobbles
+    JOIN tracks ON scrobbles.track_id = tracks.xata_id
+    WHERE scrobbles.user_id = $1
+      AND ($2::bigint IS NULL OR scrobbles.timestamp > to_timestamp($2) AT TIME ZONE 'UTC')
+      AND ($3::bigint IS NULL OR scrobbles.timestamp < to_timestamp($3) AT TIME ZONE 'UTC')
+    ORDER BY scrobbles.timestamp {}
+    LIMIT $4
+    "#,
+        LISTEN_COLUMNS,
+        if ascending { "ASC" } else { "DESC" }
+    );
+
+    let mut results: Vec<Listen> = sqlx::query_as(&query)
+        .bind(user_id)
+        .bind(min_ts)
+        .bind(max_ts)
+        .bind(count)
+        .fetch_all(pool)
+        .await?;
+
+    if ascending {
+        results.reverse();
+    }
+
+    Ok(results)
+}
+
+/// Fetches a page of listens between `from` and `to` (inclusive), newest
+/// first, for offset-based clients such as Last.fm's `user.getRecentTracks`.
+pub async fn get_recent_listens(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    from: Option<i64>,
+    to: Option<i64>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Listen>, Error> {
+    let query = format!(
+        r#"
+    SELECT {}
+    FROM scrobbles
+    JOIN tracks ON scrobbles.track_id = tracks.xata_id
+    WHERE scrobbles.user_id = $1
+      AND ($2::bigint IS NULL OR scrobbles.timestamp >= to_timestamp($2) AT TIME ZONE 'UTC')
+      AND ($3::bigint IS NULL OR scrobbles.timestamp <= to_timestamp($3) AT TIME ZONE 'UTC')
+    ORDER BY scrobbles.timestamp DESC
+    LIMIT $4 OFFSET $5
+    "#,
+        LISTEN_COLUMNS
+    );
+
+    let results: Vec<Listen> = sqlx::query_as(&query)
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(results)
+}
+
+pub async fn count_recent_listens(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<i64, Error> {
+    let count: i64 = sqlx::query_scalar(
+        r#"
+    SELECT COUNT(*) FROM scrobbles
+    WHERE user_id = $1
+      AND ($2::bigint IS NULL OR timestamp >= to_timestamp($2) AT TIME ZONE 'UTC')
+      AND ($3::bigint IS NULL OR timestamp <= to_timestamp($3) AT TIME ZONE 'UTC')
+    "#,
+    )
+    .bind(user_id)
+    .bind(from)
+    .bind(to)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+pub async fn get_listen_count(pool: &Pool<Postgres>, user_id: &str) -> Result<i64, Error> {
+    let count: i64 = sqlx::query_scalar(
+        r#"
+    SELECT COUNT(*) FROM scrobbles WHERE user_id = $1
+    "#,
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+pub async fn get_listen_bounds(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+) -> Result<ListenBounds, Error> {
+    let bounds: ListenBounds = sqlx::query_as(
+        r#"
+    SELECT
+      EXTRACT(EPOCH FROM MIN(timestamp))::bigint AS oldest_listen_ts,
+      EXTRACT(EPOCH FROM MAX(timestamp))::bigint AS latest_listen_ts
+    FROM scrobbles
+    WHERE user_id = $1
+    "#,
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(bounds)
+}
+
+/// Returns the user's latest listen if its track is still playing, i.e. the
+/// scrobble timestamp plus the track duration hasn't elapsed yet.
+pub async fn get_current_listen(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+) -> Result<Option<Listen>, Error> {
+    let query = format!(
+        r#"
+    SELECT {}
+    FROM scrobbles
+    JOIN tracks ON scrobbles.track_id = tracks.xata_id
+    WHERE scrobbles.user_id = $1
+      AND scrobbles.timestamp + (tracks.duration * INTERVAL '1 millisecond') >= NOW() AT TIME ZONE 'UTC'
+    ORDER BY scrobbles.timestamp DESC
+    LIMIT 1
+    "#,
+        LISTEN_COLUMNS
+    );
+
+    let results: Vec<Listen> = sqlx::query_as(&query).bind(user_id).fetch_all(pool).await?;
+
+    Ok(results.into_iter().next())
+}
@@ -1,7 +1,9 @@
 pub mod album;
 pub mod api_key;
 pub mod artist;
+pub mod listen;
 pub mod spotify_account;
 pub mod spotify_token;
+pub mod stats;
 pub mod track;
 pub mod user;
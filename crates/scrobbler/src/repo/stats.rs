@@ -0,0 +1,415 @@
+use anyhow::Error;
+use sqlx::{Pool, Postgres};
+
+// All queries below take an optional user id (None for sitewide statistics)
+// and a `[from, to)` window expressed in unix seconds.
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct ArtistStat {
+    pub xata_id: String,
+    pub name: String,
+    pub uri: Option<String>,
+    pub born_in: Option<String>,
+    pub listen_count: i64,
+}
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct AlbumStat {
+    pub xata_id: String,
+    pub title: String,
+    pub artist: String,
+    pub uri: Option<String>,
+    pub album_art: Option<String>,
+    pub listen_count: i64,
+}
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct TrackStat {
+    pub xata_id: String,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub mb_id: Option<String>,
+    pub uri: Option<String>,
+    pub listen_count: i64,
+}
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct ActivityStat {
+    pub bucket: i64,
+    pub listen_count: i64,
+}
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct HourlyStat {
+    pub day_of_week: i32,
+    pub hour: i32,
+    pub listen_count: i64,
+}
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct ArtistAlbumStat {
+    pub artist_id: String,
+    pub title: String,
+    pub listen_count: i64,
+}
+
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct ListenerStat {
+    pub handle: String,
+    pub listen_count: i64,
+}
+
+const WINDOW: &str = r#"
+    ($1::text IS NULL OR scrobbles.user_id = $1)
+    AND scrobbles.timestamp >= to_timestamp($2) AT TIME ZONE 'UTC'
+    AND scrobbles.timestamp < to_timestamp($3) AT TIME ZONE 'UTC'
+"#;
+
+pub async fn get_top_artists(
+    pool: &Pool<Postgres>,
+    user_id: Option<&str>,
+    from: i64,
+    to: i64,
+    count: i64,
+    offset: i64,
+) -> Result<Vec<ArtistStat>, Error> {
+    let query = format!(
+        r#"
+    SELECT artists.xata_id, artists.name, artists.uri, artists.born_in, COUNT(*) AS listen_count
+    FROM scrobbles
+    JOIN artists ON scrobbles.artist_id = artists.xata_id
+    WHERE {}
+    GROUP BY artists.xata_id, artists.name, artists.uri, artists.born_in
+    ORDER BY listen_count DESC, artists.name ASC
+    LIMIT $4 OFFSET $5
+    "#,
+        WINDOW
+    );
+
+    let results: Vec<ArtistStat> = sqlx::query_as(&query)
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .bind(count)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(results)
+}
+
+pub async fn get_top_albums(
+    pool: &Pool<Postgres>,
+    user_id: Option<&str>,
+    from: i64,
+    to: i64,
+    count: i64,
+    offset: i64,
+) -> Result<Vec<AlbumStat>, Error> {
+    let query = format!(
+        r#"
+    SELECT albums.xata_id, albums.title, albums.artist, albums.uri, albums.album_art, COUNT(*) AS listen_count
+    FROM scrobbles
+    JOIN albums ON scrobbles.album_id = albums.xata_id
+    WHERE {}
+    GROUP BY albums.xata_id, albums.title, albums.artist, albums.uri, albums.album_art
+    ORDER BY listen_count DESC, albums.title ASC
+    LIMIT $4 OFFSET $5
+    "#,
+        WINDOW
+    );
+
+    let results: Vec<AlbumStat> = sqlx::query_as(&query)
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .bind(count)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(results)
+}
+
+pub async fn get_top_tracks(
+    pool: &Pool<Postgres>,
+    user_id: Option<&str>,
+    from: i64,
+    to: i64,
+    count: i64,
+    offset: i64,
+) -> Result<Vec<TrackStat>, Error> {
+    let query = format!(
+        r#"
+    SELECT tracks.xata_id, tracks.title, tracks.artist, tracks.album, tracks.mb_id, tracks.uri, COUNT(*) AS listen_count
+    FROM scrobbles
+    JOIN tracks ON scrobbles.track_id = tracks.xata_id
+    WHERE {}
+    GROUP BY tracks.xata_id, tracks.title, tracks.artist, tracks.album, tracks.mb_id, tracks.uri
+    ORDER BY listen_count DESC, tracks.title ASC
+    LIMIT $4 OFFSET $5
+    "#,
+        WINDOW
+    );
+
+    let results: Vec<TrackStat> = sqlx::query_as(&query)
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .bind(count)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(results)
+}
+
+/// Counts distinct values of `column` (`artist_id`, `album_id` or
+/// `track_id`) listened to within the window.
+pub async fn count_distinct(
+    pool: &Pool<Postgres>,
+    user_id: Option<&str>,
+    column: &str,
+    from: i64,
+    to: i64,
+) -> Result<i64, Error> {
+    let column = match column {
+        "artist_id" | "album_id" | "track_id" => column,
+        _ => return Err(Error::msg(format!("Invalid column: {}", column))),
+    };
+    let query = format!(
+        r#"
+    SELECT COUNT(DISTINCT scrobbles.{}) FROM scrobbles WHERE {}
+    "#,
+        column, WINDOW
+    );
+
+    let count: i64 = sqlx::query_scalar(&query)
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(count)
+}
+
+pub async fn count_listens(
+    pool: &Pool<Postgres>,
+    user_id: Option<&str>,
+    from: i64,
+    to: i64,
+) -> Result<i64, Error> {
+    let query = format!(
+        r#"
+    SELECT COUNT(*) FROM scrobbles WHERE {}
+    "#,
+        WINDOW
+    );
+
+    let count: i64 = sqlx::query_scalar(&query)
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(count)
+}
+
+/// Listen counts grouped by `date_trunc(unit, timestamp)`; `bucket` is the
+/// unix timestamp of the start of each bucket.
+pub async fn get_activity(
+    pool: &Pool<Postgres>,
+    user_id: Option<&str>,
+    from: i64,
+    to: i64,
+    unit: &str,
+) -> Result<Vec<ActivityStat>, Error> {
+    let query = format!(
+        r#"
+    SELECT
+      EXTRACT(EPOCH FROM date_trunc($4, scrobbles.timestamp))::bigint AS bucket,
+      COUNT(*) AS listen_count
+    FROM scrobbles
+    WHERE {}
+    GROUP BY bucket
+    ORDER BY bucket ASC
+    "#,
+        WINDOW
+    );
+
+    let results: Vec<ActivityStat> = sqlx::query_as(&query)
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .bind(unit)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(results)
+}
+
+/// Listen counts per ISO day of week (1 = Monday) and hour of day (UTC).
+pub async fn get_hourly_activity(
+    pool: &Pool<Postgres>,
+    user_id: Option<&str>,
+    from: i64,
+    to: i64,
+) -> Result<Vec<HourlyStat>, Error> {
+    let query = format!(
+        r#"
+    SELECT
+      EXTRACT(ISODOW FROM scrobbles.timestamp)::int AS day_of_week,
+      EXTRACT(HOUR FROM scrobbles.timestamp)::int AS hour,
+      COUNT(*) AS listen_count
+    FROM scrobbles
+    WHERE {}
+    GROUP BY day_of_week, hour
+    ORDER BY day_of_week ASC, hour ASC
+    "#,
+        WINDOW
+    );
+
+    let results: Vec<HourlyStat> = sqlx::query_as(&query)
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(results)
+}
+
+/// Per-album listen counts for the given artists, used by `artist-activity`.
+pub async fn get_artist_albums(
+    pool: &Pool<Postgres>,
+    user_id: Option<&str>,
+    from: i64,
+    to: i64,
+    artist_ids: &[String],
+) -> Result<Vec<ArtistAlbumStat>, Error> {
+    let query = format!(
+        r#"
+    SELECT scrobbles.artist_id, albums.title, COUNT(*) AS listen_count
+    FROM scrobbles
+    JOIN albums ON scrobbles.album_id = albums.xata_id
+    WHERE {}
+      AND scrobbles.artist_id = ANY($4)
+    GROUP BY scrobbles.artist_id, albums.title
+    ORDER BY listen_count DESC, albums.title ASC
+    "#,
+        WINDOW
+    );
+
+    let results: Vec<ArtistAlbumStat> = sqlx::query_as(&query)
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .bind(artist_ids)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(results)
+}
+
+/// Number of artists first listened to by the user within the window.
+pub async fn count_new_artists(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+    from: i64,
+    to: i64,
+) -> Result<i64, Error> {
+    let count: i64 = sqlx::query_scalar(
+        r#"
+    SELECT COUNT(*) FROM (
+      SELECT artist_id, MIN(timestamp) AS first_listen
+      FROM scrobbles
+      WHERE user_id = $1 AND artist_id IS NOT NULL
+      GROUP BY artist_id
+    ) first_listens
+    WHERE first_listen >= to_timestamp($2) AT TIME ZONE 'UTC'
+      AND first_listen < to_timestamp($3) AT TIME ZONE 'UTC'
+    "#,
+    )
+    .bind(user_id)
+    .bind(from)
+    .bind(to)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+/// Top listeners of an artist, album or track. `column` is the scrobbles
+/// column the id refers to.
+pub async fn get_listeners(
+    pool: &Pool<Postgres>,
+    column: &str,
+    id: &str,
+    from: i64,
+    to: i64,
+    count: i64,
+) -> Result<Vec<ListenerStat>, Error> {
+    let column = match column {
+        "artist_id" | "album_id" | "track_id" => column,
+        _ => return Err(Error::msg(format!("Invalid column: {}", column))),
+    };
+    let query = format!(
+        r#"
+    SELECT users.handle, COUNT(*) AS listen_count
+    FROM scrobbles
+    JOIN users ON scrobbles.user_id = users.xata_id
+    WHERE scrobbles.{} = $1
+      AND scrobbles.timestamp >= to_timestamp($2) AT TIME ZONE 'UTC'
+      AND scrobbles.timestamp < to_timestamp($3) AT TIME ZONE 'UTC'
+    GROUP BY users.handle
+    ORDER BY listen_count DESC, users.handle ASC
+    LIMIT $4
+    "#,
+        column
+    );
+
+    let results: Vec<ListenerStat> = sqlx::query_as(&query)
+        .bind(id)
+        .bind(from)
+        .bind(to)
+        .bind(count)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(results)
+}
+
+pub async fn count_listeners(
+    pool: &Pool<Postgres>,
+    column: &str,
+    id: &str,
+    from: i64,
+    to: i64,
+) -> Result<(i64, i64), Error> {
+    let column = match column {
+        "artist_id" | "album_id" | "track_id" => column,
+        _ => return Err(Error::msg(format!("Invalid column: {}", column))),
+    };
+    let query = format!(
+        r#"
+    SELECT COUNT(*), COUNT(DISTINCT scrobbles.user_id)
+    FROM scrobbles
+    WHERE scrobbles.{} = $1
+      AND scrobbles.timestamp >= to_timestamp($2) AT TIME ZONE 'UTC'
+      AND scrobbles.timestamp < to_timestamp($3) AT TIME ZONE 'UTC'
+    "#,
+        column
+    );
+
+    let counts: (i64, i64) = sqlx::query_as(&query)
+        .bind(id)
+        .bind(from)
+        .bind(to)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(counts)
+}
@@ -45,3 +45,43 @@ pub async fn get_user_by_did(
 
     Ok(Some(results[0].clone()))
 }
+
+/// Resolves a ListenBrainz-style `user_name`, which is either the user's
+/// handle or their DID.
+pub async fn get_user_by_name(
+    pool: &Pool<Postgres>,
+    user_name: &str,
+) -> Result<Option<UserWithoutSecret>, Error> {
+    let results: Vec<UserWithoutSecret> = sqlx::query_as(
+        r#"
+    SELECT * FROM users
+    WHERE handle = $1 OR did = $1
+  "#,
+    )
+    .bind(user_name)
+    .fetch_all(pool)
+    .await?;
+
+    if results.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(results[0].clone()))
+}
+
+pub async fn get_user_by_id(
+    pool: &Pool<Postgres>,
+    user_id: &str,
+) -> Result<Option<UserWithoutSecret>, Error> {
+    let results: Vec<UserWithoutSecret> = sqlx::query_as(
+        r#"
+    SELECT * FROM users
+    WHERE xata_id = $1
+  "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(results.into_iter().next())
+}
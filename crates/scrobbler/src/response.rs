@@ -26,3 +26,10 @@ pub fn build_response(scrobbles: Vec<Scrobble>) -> Value {
         }
     })
 }
+
+pub fn build_error(code: u32, message: &str) -> Value {
+    json!({
+        "error": code,
+        "message": message
+    })
+}
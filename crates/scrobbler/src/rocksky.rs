@@ -47,3 +47,52 @@ pub async fn scrobble(cache: &Cache, did: &str, track: Track, timestamp: u64) ->
 
     Ok(())
 }
+
+pub async fn like(did: &str, track: Track) -> Result<(), Error> {
+    let token = generate_token(did)?;
+    let client = Client::new();
+
+    tracing::info!(did = %did, artist = %track.artist, track = %track.title, "Loving track");
+
+    let response = client
+        .post(format!("{}/likes", ROCKSKY_API))
+        .bearer_auth(token)
+        .json(&track)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        let response_text = response.text().await?;
+        tracing::error!(did = %did, response = %response_text, "Failed to love track");
+        return Err(Error::msg(format!(
+            "Failed to love track: {}",
+            response_text
+        )));
+    }
+
+    Ok(())
+}
+
+pub async fn unlike(did: &str, sha256: &str) -> Result<(), Error> {
+    let token = generate_token(did)?;
+    let client = Client::new();
+
+    tracing::info!(did = %did, sha256 = %sha256, "Unloving track");
+
+    let response = client
+        .delete(format!("{}/likes/{}", ROCKSKY_API, sha256))
+        .bearer_auth(token)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        let response_text = response.text().await?;
+        tracing::error!(did = %did, response = %response_text, "Failed to unlove track");
+        return Err(Error::msg(format!(
+            "Failed to unlove track: {}",
+            response_text
+        )));
+    }
+
+    Ok(())
+}
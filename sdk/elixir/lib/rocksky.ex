@@ -8,7 +8,8 @@ defmodule Rocksky do
   SDK.
 
   Reads/writes return `{:ok, value}` | `{:error, message}` with binary-keyed
-  maps (the wire shape). Records passed to the write verbs are maps with
+  maps (the wire shape: the AppView's response verbatim, so `neighbours` is
+  `%{"neighbours" => [...]}`). Records passed to the write verbs are maps with
   camelCase binary keys — `"title"`, `"artist"`, `"album"`, `"albumArtist"`,
   `"durationMs"`, …
 
@@ -177,12 +178,67 @@ defmodule Rocksky do
   @doc "Keep the local dedup index hydrated from Jetstream in the background."
   def hydrate_from_jetstream(agent), do: :rocksky.agent_hydrate_from_jetstream(agent)
 
+  @doc """
+  Scrobble a play, or keep it in the offline queue (needs a dedup_path at login)
+  when the PDS is unreachable. Returns `%{"sent" => uris}` or
+  `%{"queued" => %{"depth" => n}}`.
+  """
+  def scrobble_or_queue(agent, track), do: :rocksky.agent_scrobble_or_queue(agent, track)
+
+  @doc "How many plays are waiting in the offline queue."
+  def queue_depth(agent), do: :rocksky.agent_queue_depth(agent)
+
+  @doc "Replay the offline queue once, oldest play first."
+  def flush_queue(agent), do: :rocksky.agent_flush_queue(agent)
+
+  @doc "Replay the offline queue in the background, backing off while offline."
+  def replay_queue(agent), do: :rocksky.agent_replay_queue(agent)
+
   @doc "Like a record by strong reference."
   def like(agent, uri, cid), do: :rocksky.agent_like(agent, to_bin(uri), to_bin(cid))
 
   @doc "Follow an account by DID."
   def follow(agent, did), do: :rocksky.agent_follow(agent, to_bin(did))
 
+  @doc """
+  Write many records in `applyWrites` transactions of up to 200 writes.
+  `writes` is a list of maps like `%{scrobble: track}`,
+  `%{like: %{uri: uri, cid: cid}}` or `%{follow: did}`.
+  """
+  def apply_batch(agent, writes), do: :rocksky.agent_apply_batch(agent, writes)
+
+  @doc """
+  Create a playlist. `playlist` is a map with camelCase string keys: required
+  `"name"`; optional `"description"`, `"pictureUrl"` and streaming links.
+  Returns its `"uri"` and `"cid"`.
+  """
+  def create_playlist(agent, playlist) when is_map(playlist),
+    do: :rocksky.agent_create_playlist(agent, playlist)
+
+  @doc """
+  Append a song (a track map, like `scrobble/2`'s) to the end of a playlist. A
+  song already in the playlist is left where it is. Returns the item URI.
+  """
+  def add_to_playlist(agent, playlist_uri, song) when is_map(song),
+    do: :rocksky.agent_add_to_playlist(agent, to_bin(playlist_uri), song)
+
+  @doc "Remove a song, by its song URI, from a playlist."
+  def remove_from_playlist(agent, playlist_uri, song_uri),
+    do: :rocksky.agent_remove_from_playlist(agent, to_bin(playlist_uri), to_bin(song_uri))
+
+  @doc "Move the songs of `song_uris` to the front of a playlist, in that order."
+  def reorder_playlist(agent, playlist_uri, song_uris) when is_list(song_uris),
+    do:
+      :rocksky.agent_reorder_playlist(
+        agent,
+        to_bin(playlist_uri),
+        Enum.map(song_uris, &to_bin/1)
+      )
+
+  @doc "Delete a playlist and its items."
+  def delete_playlist(agent, playlist_uri),
+    do: :rocksky.agent_delete_playlist(agent, to_bin(playlist_uri))
+
   @doc "Post a shout on a subject."
   def shout(agent, subject_uri, subject_cid, message),
     do: :rocksky.agent_shout(agent, to_bin(subject_uri), to_bin(subject_cid), to_bin(message))
@@ -20,8 +20,11 @@
          song_hash/3, album_hash/2, artist_hash/1,
          agent_login/3, agent_login/4, agent_login/5, agent_scrobble/2,
          agent_scrobble_match/2, agent_scrobble_match/7, agent_sync_repo/1,
-         agent_hydrate_from_jetstream/1, agent_like/3,
-         agent_follow/2, agent_shout/4, agent_shout_with_gif/5,
+         agent_hydrate_from_jetstream/1, agent_scrobble_or_queue/2,
+         agent_queue_depth/1, agent_flush_queue/1, agent_replay_queue/1, agent_like/3,
+         agent_follow/2, agent_apply_batch/2, agent_create_playlist/2,
+         agent_add_to_playlist/3, agent_remove_from_playlist/3, agent_reorder_playlist/3,
+         agent_delete_playlist/2, agent_shout/4, agent_shout_with_gif/5,
          agent_reply_shout_with_gif/7, agent_refresh_session/1,
          unread_count/1, unread_count/2, notifications/1, notifications/2,
          notifications/3, update_seen/2, update_seen/3, update_seen_raw/3,
@@ -249,8 +252,54 @@ agent_sync_repo(Agent) -> unwrap(rocksky_nif:agent_sync_repo(Agent)).
 agent_hydrate_from_jetstream(Agent) ->
     unwrap(rocksky_nif:agent_hydrate_from_jetstream(Agent)).
 
+%% Scrobble a play, or keep it in the offline queue (in the dedup store) when the
+%% PDS is unreachable. Returns {ok, #{<<"sent">> := Uris}} or
+%% {ok, #{<<"queued">> := #{<<"depth">> := N}}}.
+agent_scrobble_or_queue(Agent, Track) ->
+    unwrap(rocksky_nif:agent_scrobble_or_queue(Agent, iolist_to_binary(json:encode(Track)))).
+
+%% How many plays are waiting in the offline queue.
+agent_queue_depth(Agent) -> unwrap(rocksky_nif:agent_queue_depth(Agent)).
+
+%% Replay the offline queue once, oldest play first. Returns the counts of plays
+%% sent, duplicates, dropped and remaining.
+agent_flush_queue(Agent) -> unwrap(rocksky_nif:agent_flush_queue(Agent)).
+
+%% Replay the offline queue in the background, backing off while offline.
+agent_replay_queue(Agent) -> unwrap(rocksky_nif:agent_replay_queue(Agent)).
+
 agent_like(Agent, Uri, Cid) -> unwrap(rocksky_nif:agent_like(Agent, b(Uri), b(Cid))).
 agent_follow(Agent, Did) -> unwrap(rocksky_nif:agent_follow(Agent, b(Did))).
+
+%% Write many records in applyWrites transactions of up to 200 writes. `Writes`
+%% is a list of maps like #{scrobble => Track}, #{like => #{uri => U, cid => C}}
+%% or #{follow => Did}. Returns the per-write outcomes and commit counts.
+agent_apply_batch(Agent, Writes) ->
+    unwrap(rocksky_nif:agent_apply_batch(Agent, iolist_to_binary(json:encode(Writes)))).
+
+%% Create a playlist from a map (<<"name">> required, plus <<"description">>,
+%% <<"pictureUrl">> and streaming links). Returns its <<"uri">> and <<"cid">>.
+agent_create_playlist(Agent, Playlist) ->
+    unwrap(rocksky_nif:agent_create_playlist(Agent, iolist_to_binary(json:encode(Playlist)))).
+
+%% Append a song (a track map, like agent_scrobble's) to the end of a playlist.
+%% A song already in the playlist is left where it is. Returns the item URI.
+agent_add_to_playlist(Agent, PlaylistUri, Song) ->
+    unwrap(rocksky_nif:agent_add_to_playlist(
+        Agent, b(PlaylistUri), iolist_to_binary(json:encode(Song)))).
+
+%% Remove a song, by its song URI, from a playlist.
+agent_remove_from_playlist(Agent, PlaylistUri, SongUri) ->
+    unwrap(rocksky_nif:agent_remove_from_playlist(Agent, b(PlaylistUri), b(SongUri))).
+
+%% Move the songs of `SongUris` to the front of a playlist, in that order.
+agent_reorder_playlist(Agent, PlaylistUri, SongUris) ->
+    unwrap(rocksky_nif:agent_reorder_playlist(
+        Agent, b(PlaylistUri), iolist_to_binary(json:encode([b(U) || U <- SongUris])))).
+
+%% Delete a playlist and its items.
+agent_delete_playlist(Agent, PlaylistUri) ->
+    unwrap(rocksky_nif:agent_delete_playlist(Agent, b(PlaylistUri))).
 agent_shout(Agent, SubjectUri, SubjectCid, Message) ->
     unwrap(rocksky_nif:agent_shout(Agent, b(SubjectUri), b(SubjectCid), b(Message))).
 
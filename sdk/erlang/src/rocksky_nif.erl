@@ -1,7 +1,8 @@
 %% Raw NIF module: loads the Rustler-built native library and declares the NIF
 %% stubs. Each function is replaced by the native implementation on load; the
 %% Erlang bodies only run if loading failed. Reads/writes return JSON binaries
-%% ({"ok"|"error"} envelopes); the identity hashes return the hex binary directly.
+%% ({"ok"|"error"} envelopes around the AppView's response verbatim, e.g.
+%% {"ok":{"neighbours":[...]}}); the identity hashes return the hex binary directly.
 %%
 %% Prefer the friendly `rocksky` module over calling these directly.
 -module(rocksky_nif).
@@ -24,11 +25,15 @@
          song_hash/3, album_hash/2, artist_hash/1,
          agent_login/5, agent_did/1, agent_refresh_session/1, agent_scrobble/2,
          agent_scrobble_match/2, agent_sync_repo/1, agent_hydrate_from_jetstream/1,
+         agent_scrobble_or_queue/2, agent_queue_depth/1, agent_flush_queue/1,
+         agent_replay_queue/1,
          agent_create_song/2, agent_create_album/2, agent_create_artist/2,
          agent_like/3, agent_unlike/2, agent_follow/2, agent_unfollow/2,
+         agent_apply_batch/2,
          agent_shout/4, agent_reply_shout/6, agent_shout_with_gif/5,
          agent_reply_shout_with_gif/7, update_seen/3, agent_set_now_playing/2,
-         agent_clear_now_playing/1,
+         agent_clear_now_playing/1, agent_create_playlist/2, agent_add_to_playlist/3,
+         agent_remove_from_playlist/3, agent_reorder_playlist/3, agent_delete_playlist/2,
          remote_player_connect/3, remote_player_next_command/1,
          remote_player_set_now_playing/2, remote_player_set_status/2,
          remote_player_set_queue/3, remote_player_disconnect/1,
@@ -279,6 +284,10 @@ agent_scrobble(_Agent, _Json) -> ?NOT_LOADED.
 agent_scrobble_match(_Agent, _InputJson) -> ?NOT_LOADED.
 agent_sync_repo(_Agent) -> ?NOT_LOADED.
 agent_hydrate_from_jetstream(_Agent) -> ?NOT_LOADED.
+agent_scrobble_or_queue(_Agent, _Json) -> ?NOT_LOADED.
+agent_queue_depth(_Agent) -> ?NOT_LOADED.
+agent_flush_queue(_Agent) -> ?NOT_LOADED.
+agent_replay_queue(_Agent) -> ?NOT_LOADED.
 agent_create_song(_Agent, _Json) -> ?NOT_LOADED.
 agent_create_album(_Agent, _Json) -> ?NOT_LOADED.
 agent_create_artist(_Agent, _Json) -> ?NOT_LOADED.
@@ -286,12 +295,18 @@ agent_like(_Agent, _Uri, _Cid) -> ?NOT_LOADED.
 agent_unlike(_Agent, _Uri) -> ?NOT_LOADED.
 agent_follow(_Agent, _Did) -> ?NOT_LOADED.
 agent_unfollow(_Agent, _Did) -> ?NOT_LOADED.
+agent_apply_batch(_Agent, _Json) -> ?NOT_LOADED.
 agent_shout(_Agent, _SubjectUri, _SubjectCid, _Message) -> ?NOT_LOADED.
 agent_reply_shout(_Agent, _SubjectUri, _SubjectCid, _ParentUri, _ParentCid, _Message) -> ?NOT_LOADED.
 agent_shout_with_gif(_Agent, _SubjectUri, _SubjectCid, _Message, _GifJson) -> ?NOT_LOADED.
 agent_reply_shout_with_gif(_Agent, _SubjectUri, _SubjectCid, _ParentUri, _ParentCid, _Message, _GifJson) -> ?NOT_LOADED.
 agent_set_now_playing(_Agent, _Json) -> ?NOT_LOADED.
 agent_clear_now_playing(_Agent) -> ?NOT_LOADED.
+agent_create_playlist(_Agent, _Json) -> ?NOT_LOADED.
+agent_add_to_playlist(_Agent, _PlaylistUri, _SongJson) -> ?NOT_LOADED.
+agent_remove_from_playlist(_Agent, _PlaylistUri, _SongUri) -> ?NOT_LOADED.
+agent_reorder_playlist(_Agent, _PlaylistUri, _SongUrisJson) -> ?NOT_LOADED.
+agent_delete_playlist(_Agent, _PlaylistUri) -> ?NOT_LOADED.
 
 %% --- remote control (remote-player feature) -----------------------------------
 %% RemotePlayer (a controllable player) + RemoteController (a remote UI). connect